@@ -6,7 +6,7 @@
 /**
  * Defines the current ABI version
  */
-#define MUN_ABI_VERSION 300
+#define MUN_ABI_VERSION 400
 
 /**
  * Represents the kind of memory management a struct uses.
@@ -91,12 +91,32 @@ public:
      * Returns `true` if memory was reclaimed, `false` otherwise. This behavior
      * will likely change in the future.
      */
-    bool gc_collect() const noexcept {
+    bool gc_collect() noexcept {
         bool reclaimed;
         MUN_ASSERT(mun_gc_collect(m_handle, &reclaimed));
         return reclaimed;
     }
 
+    /** Performs a step of an incremental garbage collection cycle, starting a
+     * new cycle if none is in progress.
+     *
+     * \param budget the number of objects the step processes, roughly
+     * \return whether the step completed the cycle
+     */
+    bool gc_step(size_t budget) noexcept {
+        bool completed;
+        MUN_ASSERT(mun_gc_step(m_handle, budget, &completed));
+        return completed;
+    }
+
+    /** Informs the garbage collector that references were stored in `obj`.
+     *
+     * \param obj a garbage collection type_handle
+     */
+    void gc_write_barrier(MunGcPtr obj) const noexcept {
+        MUN_ASSERT(mun_gc_write_barrier(m_handle, obj));
+    }
+
     /**
      * Roots the specified `obj`, which keeps it and objects it references
      * alive.
@@ -167,6 +187,36 @@ struct RuntimeOptions {
      * functions.
      */
     std::vector<RuntimeFunction> functions;
+
+    /**
+     * When more than this number of bytes is allocated, garbage is collected during the next
+     * update. `0` means no limit.
+     */
+    size_t heap_soft_limit = 0;
+
+    /**
+     * The maximum number of bytes that Mun code can allocate. Only applies to
+     * code that was compiled with heap limit checks. `0` means no limit.
+     */
+    size_t heap_hard_limit = 0;
+
+    /**
+     * A callback that is invoked with a report of the changes of every hot reload, from within
+     * `Runtime::update`. The report is only valid for the duration of the call.
+     */
+    MunReloadCallback reload_callback = nullptr;
+
+    /**
+     * The data that is passed to `reload_callback`.
+     */
+    void* reload_callback_data = nullptr;
+
+    /**
+     * The registration function of a statically linked Mun library, e.g. `mun_register_mod`. If
+     * set, the assembly is loaded from the entry points that it returns and `library_path` only
+     * identifies the assembly.
+     */
+    const void* (*static_registration)() = nullptr;
 };
 
 /** Construct a new runtime that loads the library at `library_path` and its dependencies.
@@ -200,6 +250,11 @@ inline std::optional<Runtime> make_runtime(std::string_view library_path,
     runtime_options.functions =
         function_definitions.empty() ? nullptr : function_definitions.data();
     runtime_options.num_functions = static_cast<uint32_t>(function_definitions.size());
+    runtime_options.heap_soft_limit = options.heap_soft_limit;
+    runtime_options.heap_hard_limit = options.heap_hard_limit;
+    runtime_options.reload_callback = options.reload_callback;
+    runtime_options.reload_callback_data = options.reload_callback_data;
+    runtime_options.static_registration = options.static_registration;
 
     MunRuntime handle;
     if (auto error = Error(mun_runtime_create(library_path.data(), runtime_options, &handle));
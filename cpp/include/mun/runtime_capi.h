@@ -129,6 +129,88 @@ typedef struct MunExternalFunctionDefinition {
     const void *fn_ptr;
 } MunExternalFunctionDefinition;
 
+/**
+ * Describes how the objects of a struct whose layout changed were converted.
+ */
+typedef struct MunMappedTypeReport {
+    /**
+     * The name of the struct
+     */
+    const char *name;
+    /**
+     * The number of objects that were converted. Garbage is collected before
+     * objects are converted, so only reachable objects are counted.
+     */
+    uintptr_t object_count;
+    /**
+     * The number of objects that were passed to a migration defined by the
+     * struct
+     */
+    uintptr_t migrated_count;
+    /**
+     * The fields of the old struct whose values were dropped
+     */
+    const char *const *dropped_fields;
+    /**
+     * The number of names in [`dropped_fields`]
+     */
+    uintptr_t num_dropped_fields;
+    /**
+     * The fields of the new struct that were zero-initialized
+     */
+    const char *const *zero_initialized_fields;
+    /**
+     * The number of names in [`zero_initialized_fields`]
+     */
+    uintptr_t num_zero_initialized_fields;
+} MunMappedTypeReport;
+
+/**
+ * A report of the changes that were made when assemblies were hot reloaded.
+ */
+typedef struct MunReloadReport {
+    /**
+     * The paths of the assemblies that were reloaded
+     */
+    const char *const *assemblies;
+    /**
+     * The number of paths in [`assemblies`]
+     */
+    uintptr_t num_assemblies;
+    /**
+     * The names of the functions that were added
+     */
+    const char *const *added_functions;
+    /**
+     * The number of names in [`added_functions`]
+     */
+    uintptr_t num_added_functions;
+    /**
+     * The names of the functions that were removed
+     */
+    const char *const *removed_functions;
+    /**
+     * The number of names in [`removed_functions`]
+     */
+    uintptr_t num_removed_functions;
+    /**
+     * The names of the functions whose signature changed
+     */
+    const char *const *changed_functions;
+    /**
+     * The number of names in [`changed_functions`]
+     */
+    uintptr_t num_changed_functions;
+    /**
+     * The structs whose layout changed
+     */
+    const struct MunMappedTypeReport *mapped_types;
+    /**
+     * The number of structs in [`mapped_types`]
+     */
+    uintptr_t num_mapped_types;
+} MunReloadReport;
+
 /**
  * Options required to construct a [`RuntimeHandle`] through
  * [`mun_runtime_create`]
@@ -152,6 +234,35 @@ typedef struct MunRuntimeOptions {
      * The number of functions in the [`functions`] array.
      */
     uint32_t num_functions;
+    /**
+     * When more than this number of bytes is allocated, garbage is collected
+     * during the next [`mun_runtime_update`]. The limit is not checked when
+     * memory is allocated. `0` means no limit.
+     */
+    uintptr_t heap_soft_limit;
+    /**
+     * The maximum number of bytes that Mun code can allocate. An allocation
+     * that would exceed this limit fails and interrupts the Mun code, see
+     * [`mun_runtime_take_heap_limit_exceeded`]. Only applies to code that was
+     * compiled with heap limit checks. `0` means no limit.
+     */
+    uintptr_t heap_hard_limit;
+    /**
+     * A callback that is invoked with a report of the changes of every hot
+     * reload, from within [`mun_runtime_update`]. Can be null.
+     */
+    void (*reload_callback)(const struct MunReloadReport *report, void *data);
+    /**
+     * The data that is passed to [`reload_callback`].
+     */
+    void *reload_callback_data;
+    /**
+     * The registration function of a statically linked Mun library, e.g.
+     * `mun_register_mod`. If set, the assembly is loaded from the entry points
+     * that it returns and `library_path` only identifies the assembly.
+     * Statically linked assemblies are never reloaded. Can be null.
+     */
+    const void *(*static_registration)(void);
 } MunRuntimeOptions;
 
 /**
@@ -356,6 +467,13 @@ typedef struct MunFields {
     uintptr_t count;
 } MunFields;
 
+/**
+ * A callback that is invoked with a report of the changes of a hot reload.
+ * The report and all strings it references are only valid for the duration
+ * of the call.
+ */
+typedef void (*MunReloadCallback)(const struct MunReloadReport *report, void *data);
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -433,6 +551,9 @@ struct MunErrorHandle mun_gc_unroot(struct MunRuntime runtime, MunGcPtr obj);
  * returned. If `reclaimed` is `true`, memory was reclaimed, otherwise nothing
  * happend. This behavior will likely change in the future.
  *
+ * This must not be called concurrently with any other function that uses the
+ * runtime.
+ *
  * If a non-zero error handle is returned, it must be manually destructed using
  * [`mun_error_destroy`].
  *
@@ -444,6 +565,43 @@ struct MunErrorHandle mun_gc_unroot(struct MunRuntime runtime, MunGcPtr obj);
  */
 struct MunErrorHandle mun_gc_collect(struct MunRuntime runtime, bool *reclaimed);
 
+/**
+ * Performs a step of an incremental garbage collection cycle, starting a new
+ * cycle if none is in progress. Every step processes roughly `budget` objects.
+ * If successful, `completed` is set, otherwise a non-zero error handle is
+ * returned. If `completed` is `true`, the step completed the cycle.
+ *
+ * This must not be called while Mun functions are executing, nor concurrently
+ * with any other function that uses the runtime.
+ *
+ * If a non-zero error handle is returned, it must be manually destructed using
+ * [`mun_error_destroy`].
+ *
+ * # Safety
+ *
+ * This function receives raw pointers as parameters. If any of the arguments
+ * is a null pointer, an error will be returned. Passing pointers to invalid
+ * data, will lead to undefined behavior.
+ */
+struct MunErrorHandle mun_gc_step(struct MunRuntime runtime, uintptr_t budget, bool *completed);
+
+/**
+ * Informs the garbage collector that references were stored in `obj`. This
+ * must be called after storing references in an object from outside of Mun
+ * code, so an incremental collection cycle does not miss them. If successful,
+ * a zero error handle is returned.
+ *
+ * If a non-zero error handle is returned, it must be manually destructed using
+ * [`mun_error_destroy`].
+ *
+ * # Safety
+ *
+ * This function receives raw pointers as parameters. If any of the arguments
+ * is a null pointer, an error will be returned. Passing pointers to invalid
+ * data, will lead to undefined behavior.
+ */
+struct MunErrorHandle mun_gc_write_barrier(struct MunRuntime runtime, MunGcPtr obj);
+
 /**
  * Constructs a new runtime that loads the library at `library_path` and its
  * dependencies. If successful, the runtime `handle` is set, otherwise a
@@ -542,6 +700,103 @@ struct MunErrorHandle mun_runtime_get_type_info_by_id(struct MunRuntime runtime,
  */
 struct MunErrorHandle mun_runtime_update(struct MunRuntime runtime, bool *updated);
 
+/**
+ * Limits the amount of work that Mun code on the calling thread may perform
+ * to `budget`, and clears any interrupt. Every function call and loop
+ * iteration consumes one unit of budget. When Mun code tries to consume more
+ * budget than is left, all Mun functions on the stack return immediately.
+ *
+ * Only assemblies that were compiled with budget checks consume budget. The
+ * value returned by a function that ran out of budget is meaningless and must
+ * be discarded. Use [`mun_runtime_is_budget_exhausted`] after a call to check
+ * whether this is the case, and [`mun_runtime_clear_budget`] to remove the
+ * limit again.
+ *
+ * If a non-zero error handle is returned, it must be manually destructed using
+ * [`mun_error_destroy`].
+ *
+ * # Safety
+ *
+ * This function receives raw pointers as parameters. If any of the arguments
+ * is a null pointer, an error will be returned. Passing pointers to invalid
+ * data, will lead to undefined behavior.
+ */
+struct MunErrorHandle mun_runtime_set_budget(struct MunRuntime runtime, uint64_t budget);
+
+/**
+ * Removes the limit on the amount of work that Mun code on the calling thread
+ * may perform, and clears any interrupt.
+ *
+ * If a non-zero error handle is returned, it must be manually destructed using
+ * [`mun_error_destroy`].
+ *
+ * # Safety
+ *
+ * This function receives raw pointers as parameters. If any of the arguments
+ * is a null pointer, an error will be returned. Passing pointers to invalid
+ * data, will lead to undefined behavior.
+ */
+struct MunErrorHandle mun_runtime_clear_budget(struct MunRuntime runtime);
+
+/**
+ * Checks whether Mun code on the calling thread was interrupted because it ran
+ * out of budget. If successful, `exhausted` is set, otherwise a non-zero error
+ * handle is returned.
+ *
+ * If a non-zero error handle is returned, it must be manually destructed using
+ * [`mun_error_destroy`].
+ *
+ * # Safety
+ *
+ * This function receives raw pointers as parameters. If any of the arguments
+ * is a null pointer, an error will be returned. Passing pointers to invalid
+ * data, will lead to undefined behavior.
+ */
+struct MunErrorHandle mun_runtime_is_budget_exhausted(struct MunRuntime runtime, bool *exhausted);
+
+/**
+ * Checks whether Mun code on the calling thread was interrupted because an
+ * allocation exceeded the hard heap limit of the runtime corresponding to
+ * `handle`, and clears the interrupt. If successful, `exceeded` is set,
+ * otherwise a non-zero error handle is returned.
+ *
+ * While Mun code is interrupted, all Mun functions on the stack return
+ * immediately. The value returned by the interrupted function is meaningless
+ * and must be discarded.
+ *
+ * If a non-zero error handle is returned, it must be manually destructed using
+ * [`mun_error_destroy`].
+ *
+ * # Safety
+ *
+ * This function receives raw pointers as parameters. If any of the arguments
+ * is a null pointer, an error will be returned. Passing pointers to invalid
+ * data, will lead to undefined behavior.
+ */
+struct MunErrorHandle mun_runtime_take_heap_limit_exceeded(struct MunRuntime runtime,
+                                                           bool *exceeded);
+
+/**
+ * Checks whether Mun code on the calling thread was interrupted because an
+ * integer operation overflowed, and clears the interrupt. If successful,
+ * `overflowed` is set, otherwise a non-zero error handle is returned.
+ *
+ * Overflows are only detected in assemblies that were compiled with overflow
+ * checks. While Mun code is interrupted, all Mun functions on the stack return
+ * immediately. The value returned by the interrupted function is meaningless
+ * and must be discarded.
+ *
+ * If a non-zero error handle is returned, it must be manually destructed using
+ * [`mun_error_destroy`].
+ *
+ * # Safety
+ *
+ * This function receives raw pointers as parameters. If any of the arguments
+ * is a null pointer, an error will be returned. Passing pointers to invalid
+ * data, will lead to undefined behavior.
+ */
+struct MunErrorHandle mun_runtime_take_overflow(struct MunRuntime runtime, bool *overflowed);
+
 /**
  * Notifies the runtime an additional references exists to the function. This
  * ensures that the data is kept alive even if [`mun_function_release`] is
@@ -234,10 +234,12 @@ std::optional<T> StructRef::replace(std::string_view field_name, T value) noexce
         }
 
         auto byte_ptr = reinterpret_cast<std::byte*>(*raw());
-        return std::make_optional(Marshal<T>::swap_at(
+        auto old = Marshal<T>::swap_at(
             Marshal<T>::to(std::move(value)),
             reinterpret_cast<typename Marshal<T>::type*>(byte_ptr + field_info->offset()),
-            *m_runtime, field_info->type()));
+            *m_runtime, field_info->type());
+        m_runtime->gc_write_barrier(raw());
+        return std::make_optional(std::move(old));
     } else {
         return std::nullopt;
     }
@@ -262,6 +264,7 @@ bool StructRef::set(std::string_view field_name, T value) noexcept {
             Marshal<T>::to(std::move(value)),
             reinterpret_cast<typename Marshal<T>::type*>(byte_ptr + field_info->offset()),
             field_info->type());
+        m_runtime->gc_write_barrier(raw());
         return true;
     } else {
         return false;
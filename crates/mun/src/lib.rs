@@ -3,7 +3,7 @@ mod ops;
 use std::ffi::OsString;
 
 use clap::{Parser, Subcommand};
-use ops::{build, init, language_server, new, start};
+use ops::{bindgen, build, explain, init, language_server, new, repl, run, start};
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -29,6 +29,18 @@ enum Command {
 
     /// Invoke a function from a munlib
     Start(start::Args),
+
+    /// Compile a Mun project in memory and invoke a function
+    Run(run::Args),
+
+    /// Start an interactive session that evaluates Mun code line by line
+    Repl(repl::Args),
+
+    /// Generate Rust bindings for a munlib
+    Bindgen(bindgen::Args),
+
+    /// Print a detailed explanation of a diagnostic code, e.g. `E0012`
+    Explain(explain::Args),
 }
 
 #[derive(Copy, Debug, Clone, PartialEq, Eq)]
@@ -59,5 +71,9 @@ where
         Command::New(args) => new::new(args),
         Command::Init(args) => init::init(args),
         Command::Start(args) => start::start(args),
+        Command::Run(args) => run::run(args),
+        Command::Repl(args) => repl::repl(args),
+        Command::Bindgen(args) => bindgen::bindgen(args),
+        Command::Explain(args) => explain::explain(args),
     }
 }
@@ -1,5 +1,9 @@
+pub mod bindgen;
 pub mod build;
+pub mod explain;
 pub mod init;
 pub mod language_server;
 pub mod new;
+pub mod repl;
+pub mod run;
 pub mod start;
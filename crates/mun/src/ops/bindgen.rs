@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use mun_bindgen::Bindings;
+
+use crate::ExitStatus;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The library to generate bindings for
+    library: PathBuf,
+
+    /// The file to write the bindings to. Writes to stdout if omitted.
+    #[clap(long, short = 'o')]
+    output: Option<PathBuf>,
+}
+
+/// Generates Rust bindings for the structs and functions of the specified
+/// library and its dependencies.
+pub fn bindgen(args: Args) -> anyhow::Result<ExitStatus> {
+    // Safety: we assume that the passed in library is safe
+    let bindings = unsafe { Bindings::from_munlib(&args.library) }?;
+    let source = bindings.generate();
+
+    match args.output {
+        Some(output) => std::fs::write(output, source)?,
+        None => print!("{source}"),
+    }
+
+    Ok(ExitStatus::Success)
+}
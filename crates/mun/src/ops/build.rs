@@ -16,6 +16,12 @@ pub enum UseColor {
     Auto,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum MessageFormat {
+    Human,
+    Json,
+}
+
 #[derive(clap::Args)]
 pub struct Args {
     /// Path to the manifest of the project
@@ -30,10 +36,35 @@ pub struct Args {
     #[clap(long, value_enum)]
     color: Option<UseColor>,
 
+    /// The format in which diagnostics are emitted. With `json`, every
+    /// diagnostic is emitted to stdout as a JSON object on a separate line.
+    #[clap(long, value_enum)]
+    message_format: Option<MessageFormat>,
+
     /// Emits IR instead of a *.munlib
     #[clap(long)]
     emit_ir: bool,
 
+    /// Instruments the generated code so the runtime can limit how long it
+    /// runs
+    #[clap(long)]
+    budget_checks: bool,
+
+    /// Instruments the generated code so the runtime can limit how much memory
+    /// it allocates
+    #[clap(long)]
+    heap_limit_checks: bool,
+
+    /// Emits DWARF debug information, so the generated code can be debugged
+    /// with gdb or lldb
+    #[clap(long)]
+    debug: bool,
+
+    /// Emits a static library that is linked into the host, instead of
+    /// munlibs that are loaded by the runtime. Not supported for MSVC targets
+    #[clap(long = "static")]
+    static_library: bool,
+
     /// Run the compiler in watch mode. Watch input files and trigger
     /// recompilation on changes.
     #[clap(long)]
@@ -63,8 +94,45 @@ pub fn build(args: Args) -> Result<ExitStatus, anyhow::Error> {
         _ => return Err(anyhow!("Only optimization levels 0-3 are supported")),
     };
 
-    let display_colors = args
-        .color
+    let message_format = match args.message_format {
+        Some(MessageFormat::Json) => mun_compiler::MessageFormat::Json,
+        Some(MessageFormat::Human) | None => {
+            mun_compiler::MessageFormat::Human(display_color(args.color))
+        }
+    };
+    let manifest_path = locate_manifest(args.manifest_path.as_deref())?;
+
+    log::info!("located build manifest at: {}", manifest_path.display());
+
+    let compiler_options = Config {
+        target: args
+            .target
+            .unwrap_or_else(|| Target::host_target().expect("unable to determine host target")),
+        optimization_lvl,
+        out_dir: None,
+        emit_ir: args.emit_ir,
+        budget_checks: args.budget_checks,
+        heap_limit_checks: args.heap_limit_checks,
+        debug_info: args.debug,
+        static_library: args.static_library,
+    };
+
+    if args.watch {
+        mun_compiler_daemon::compile_and_watch_manifest(
+            &manifest_path,
+            compiler_options,
+            message_format,
+        )
+    } else {
+        mun_compiler::compile_manifest(&manifest_path, compiler_options, message_format)
+    }
+    .map(Into::into)
+}
+
+/// Returns how colors are used in the output, either as specified by `color`
+/// or by the `MUN_TERMINAL_COLOR` environment variable.
+pub fn display_color(color: Option<UseColor>) -> DisplayColor {
+    color
         .map(|clr| match clr {
             UseColor::Disable => DisplayColor::Disable,
             UseColor::Enable => DisplayColor::Enable,
@@ -79,10 +147,13 @@ pub fn build(args: Args) -> Result<ExitStatus, anyhow::Error> {
                 })
                 .ok()
         })
-        .unwrap_or(DisplayColor::Auto);
+        .unwrap_or(DisplayColor::Auto)
+}
 
-    // Locate the manifest
-    let manifest_path = match &args.manifest_path {
+/// Returns the path to the manifest at `manifest_path`, or the manifest in the
+/// current directory or one of its parents.
+pub fn locate_manifest(manifest_path: Option<&Path>) -> anyhow::Result<PathBuf> {
+    match manifest_path {
         None => {
             let current_dir =
                 std::env::current_dir().expect("could not determine current working directory");
@@ -92,37 +163,15 @@ pub fn build(args: Args) -> Result<ExitStatus, anyhow::Error> {
                     MANIFEST_FILENAME,
                     current_dir.display()
                 )
-            })?
+            })
         }
-        Some(path) => std::fs::canonicalize(Path::new(&path)).map_err(|_error| {
+        Some(path) => std::fs::canonicalize(path).map_err(|_error| {
             anyhow::anyhow!(
                 "'{}' does not refer to a valid manifest path",
                 path.display()
             )
-        })?,
-    };
-
-    log::info!("located build manifest at: {}", manifest_path.display());
-
-    let compiler_options = Config {
-        target: args
-            .target
-            .unwrap_or_else(|| Target::host_target().expect("unable to determine host target")),
-        optimization_lvl,
-        out_dir: None,
-        emit_ir: args.emit_ir,
-    };
-
-    if args.watch {
-        mun_compiler_daemon::compile_and_watch_manifest(
-            &manifest_path,
-            compiler_options,
-            display_colors,
-        )
-    } else {
-        mun_compiler::compile_manifest(&manifest_path, compiler_options, display_colors)
+        }),
     }
-    .map(Into::into)
 }
 
 /// Find a Mun manifest file in the specified directory or one of its parents.
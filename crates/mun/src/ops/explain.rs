@@ -0,0 +1,17 @@
+use anyhow::anyhow;
+
+use crate::ExitStatus;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The code of the diagnostic to explain, e.g. `E0012`
+    code: String,
+}
+
+/// Prints the long-form explanation of the diagnostic with the specified code.
+pub fn explain(args: Args) -> anyhow::Result<ExitStatus> {
+    let explanation = mun_diagnostics::explanation(&args.code.to_ascii_uppercase())
+        .ok_or_else(|| anyhow!("`{}` is not a valid diagnostic code", args.code))?;
+    print!("{explanation}");
+    Ok(ExitStatus::Success)
+}
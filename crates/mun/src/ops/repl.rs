@@ -0,0 +1,731 @@
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    ptr::NonNull,
+};
+
+use anyhow::anyhow;
+use mun_compiler::{
+    Config, DisplayColor, Driver, OptimizationLevel, PathOrInline, RelativePathBuf,
+};
+use mun_hir::{Expr, HirDatabase, HirDisplay, ModuleDef, Package, Pat, Statement, Ty, TyKind};
+use mun_memory::gc::{Array, GcPtr, HasIndirectionPtr};
+use mun_runtime::{RootedStruct, Runtime, StructRef, Type};
+use mun_syntax::{
+    ast::{FunctionDefOwner, ModuleItemKind, ModuleItemOwner, NameOwner},
+    AstNode, SourceFile, SyntaxKind,
+};
+
+use crate::{
+    ops::build::{display_color, locate_manifest, UseColor},
+    ExitStatus,
+};
+
+/// The name of the function that evaluates the last expression
+const EVAL_FN: &str = "__repl_eval";
+
+/// The name of the struct in which the value of an expression is returned
+const VALUE_STRUCT: &str = "__ReplValue";
+
+/// The name of the variable that holds the value of an expression
+const VALUE_VAR: &str = "__repl_value";
+
+/// The name of the struct in which the values of variables are stored
+const STATE_STRUCT: &str = "__ReplState";
+
+/// The name of the parameter of the evaluation function that holds the state
+const STATE_PARAM: &str = "__repl_state";
+
+const HELP: &str = "\
+Enter Mun items, statements or expressions. Items are added to the REPL module,
+replacing items with the same name. Statements are executed once, and the
+variables that they bind can be used by all following statements. The value of
+every expression is printed.
+
+Commands:
+  :help   Show this message
+  :quit   Exit the REPL";
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to the manifest of a project whose items can be used
+    #[clap(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Use color in output
+    #[clap(long, value_enum)]
+    color: Option<UseColor>,
+}
+
+/// Starts an interactive session that evaluates Mun code line by line.
+pub fn repl(args: Args) -> anyhow::Result<ExitStatus> {
+    let display_color = display_color(args.color);
+    let Some(mut repl) = Repl::new(args.manifest_path.as_deref(), display_color)? else {
+        return Ok(ExitStatus::Error);
+    };
+
+    let stdin = io::stdin();
+    let mut input = String::new();
+    loop {
+        print!("{}", if input.is_empty() { "> " } else { "| " });
+        io::stdout().flush()?;
+
+        if stdin.lock().read_line(&mut input)? == 0 {
+            break;
+        }
+        if !is_complete(&input) {
+            continue;
+        }
+
+        let snippet = std::mem::take(&mut input);
+        match snippet.trim() {
+            "" => {}
+            ":quit" | ":q" => break,
+            ":help" => println!("{HELP}"),
+            code => match repl.eval(code) {
+                Ok(Some(value)) => println!("{value}"),
+                Ok(None) => {}
+                Err(error) => eprintln!("error: {error}"),
+            },
+        }
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+/// A kind of snippet that is entered in the REPL.
+#[derive(Debug, PartialEq, Eq)]
+enum Snippet {
+    /// Item definitions, e.g. functions and structs
+    Items,
+
+    /// Statements that don't produce a value
+    Statements(String),
+
+    /// An expression whose value is printed, optionally preceded by statements
+    Expression { statements: String, expr: String },
+}
+
+/// Determines the kind of snippet of `input`. Snippets that cannot be parsed
+/// are considered items, so the compiler reports their syntax errors.
+fn classify(input: &str) -> Snippet {
+    if SourceFile::parse(input).errors().is_empty() {
+        return Snippet::Items;
+    }
+
+    let prefix = "fn f() {\n";
+    let parse = SourceFile::parse(&format!("{prefix}{input}\n}}"));
+    if !parse.errors().is_empty() {
+        return Snippet::Items;
+    }
+
+    let Some(body) = parse.tree().functions().next().and_then(|f| f.body()) else {
+        return Snippet::Items;
+    };
+    match body.expr() {
+        Some(expr) => {
+            let start = usize::from(expr.syntax().text_range().start()) - prefix.len();
+            Snippet::Expression {
+                statements: input[..start].to_owned(),
+                expr: expr.syntax().text().to_string(),
+            }
+        }
+        // Statements like `let a = 1` are also accepted without a trailing semicolon, which
+        // is required to execute other statements after them
+        None if !input.ends_with([';', '}']) => Snippet::Statements(format!("{input};")),
+        None => Snippet::Statements(input.to_owned()),
+    }
+}
+
+/// Returns true if all delimiters in `input` are closed, which means that no
+/// more lines are needed to complete the snippet. Delimiters in comments are
+/// ignored.
+fn is_complete(input: &str) -> bool {
+    let mut depth = 0isize;
+    for token in mun_syntax::tokenize(input) {
+        match token.kind {
+            SyntaxKind::L_CURLY | SyntaxKind::L_PAREN | SyntaxKind::L_BRACKET => depth += 1,
+            SyntaxKind::R_CURLY | SyntaxKind::R_PAREN | SyntaxKind::R_BRACKET => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+/// An item that was entered in the REPL.
+#[derive(Clone)]
+struct Item {
+    /// The name of the item, if it has one. An item replaces an earlier item
+    /// with the same name.
+    name: Option<String>,
+    source: String,
+}
+
+/// Splits `input` into the items that it defines. Input that cannot be parsed
+/// is kept as a single item, so the compiler reports its syntax errors.
+fn split_items(input: &str) -> Vec<Item> {
+    let parse = SourceFile::parse(input);
+    if !parse.errors().is_empty() {
+        return vec![Item {
+            name: None,
+            source: input.to_owned(),
+        }];
+    }
+
+    parse
+        .tree()
+        .items()
+        .map(|item| {
+            let name = match item.kind() {
+                ModuleItemKind::FunctionDef(def) => def.name(),
+                ModuleItemKind::StructDef(def) => def.name(),
+                ModuleItemKind::TypeAliasDef(def) => def.name(),
+                ModuleItemKind::ConstDef(def) => def.name(),
+                ModuleItemKind::Use(_) | ModuleItemKind::Impl(_) => None,
+            };
+            Item {
+                name: name.map(|name| name.text().to_string()),
+                source: item.syntax().text().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// A variable that was bound by a statement that was entered in the REPL.
+#[derive(Clone)]
+struct Variable {
+    name: String,
+
+    /// The field of the state struct in which the value is stored
+    field: String,
+
+    /// The path with which the type of the variable is referred to
+    type_path: String,
+}
+
+/// The state of an interactive session. The entered code is stored in a
+/// module of the driver of a runtime, which hot reloads the module after
+/// every change.
+///
+/// The values of variables are stored in a rooted state struct. Hot reloading
+/// migrates the struct to its new fields, so every statement is only executed
+/// once.
+struct Repl {
+    runtime: Runtime,
+    display_color: DisplayColor,
+
+    /// The path of the REPL module, relative to the source directory
+    path: RelativePathBuf,
+
+    /// The fully qualified name of the evaluation function
+    eval_fn: String,
+
+    /// Code that precedes the entered items, e.g. imports of the project
+    prelude: &'static str,
+
+    items: Vec<Item>,
+    variables: Vec<Variable>,
+
+    /// The struct in which the values of the variables are stored
+    state: RootedStruct,
+}
+
+impl Repl {
+    /// Constructs a session, optionally attached to the project with the
+    /// manifest at `manifest_path`. Returns `None` if the project contains
+    /// errors.
+    fn new(
+        manifest_path: Option<&std::path::Path>,
+        display_color: DisplayColor,
+    ) -> anyhow::Result<Option<Self>> {
+        // Optimizing the code isn't worth the time it takes to compile every line
+        let config = Config {
+            optimization_lvl: OptimizationLevel::None,
+            ..Config::default()
+        };
+
+        let (driver, path, module_path, prelude) = if let Some(manifest_path) = manifest_path {
+            let manifest_path = locate_manifest(Some(manifest_path))?;
+            let manifest_dir = manifest_path
+                .parent()
+                .expect("a manifest is always located in a directory");
+            let (_package, mut driver) = Driver::with_package_path(manifest_dir, config)?;
+
+            let path = RelativePathBuf::from("repl.mun");
+            if driver.get_file_id_for_path(&path).is_some() {
+                return Err(anyhow!("the project already contains a `repl` module"));
+            }
+            let prelude = "use package::*;\n";
+            driver.add_file(&path, initial_source(prelude));
+            (driver, path, "repl::", prelude)
+        } else {
+            let path = RelativePathBuf::from("mod.mun");
+            let (driver, _file_id) = Driver::with_file(
+                config,
+                PathOrInline::Inline {
+                    rel_path: path.clone(),
+                    contents: initial_source(""),
+                },
+            )?;
+            (driver, path, "", "")
+        };
+
+        if driver.emit_diagnostics(&mut io::stderr(), display_color)? {
+            return Ok(None);
+        }
+
+        // The runtime only loads the root assembly and its dependencies, which don't
+        // include the REPL module of a project.
+        let file_id = driver
+            .get_file_id_for_path(&path)
+            .expect("the REPL module was added to the driver");
+        let assembly_path = driver.assembly_output_path_from_file(file_id);
+        let root_assembly_path = driver.root_assembly_path();
+
+        // Safety: the assemblies are compiled from the entered code
+        let mut runtime = unsafe { Runtime::jit_builder(driver).finish() }?;
+        if assembly_path != root_assembly_path {
+            unsafe { runtime.add_assembly(&assembly_path) }?;
+        }
+
+        // The initial evaluation function constructs the empty state
+        let eval_fn = format!("{module_path}{EVAL_FN}");
+        let state: StructRef<'_> = runtime.invoke(&eval_fn, ()).map_err(|e| anyhow!("{}", e))?;
+        let state = state.root();
+
+        Ok(Some(Self {
+            runtime,
+            display_color,
+            path,
+            eval_fn,
+            prelude,
+            items: Vec::new(),
+            variables: Vec::new(),
+            state,
+        }))
+    }
+
+    /// Evaluates `input` and returns its value, if it is an expression that
+    /// produces one.
+    fn eval(&mut self, input: &str) -> anyhow::Result<Option<String>> {
+        match classify(input) {
+            Snippet::Items => {
+                self.add_items(input)?;
+                Ok(None)
+            }
+            Snippet::Statements(statements) => self.execute(&statements, None),
+            Snippet::Expression { statements, expr } => self.execute(&statements, Some(&expr)),
+        }
+    }
+
+    /// Adds the items of `input` to the REPL module, replacing earlier items
+    /// with the same names.
+    fn add_items(&mut self, input: &str) -> anyhow::Result<()> {
+        let new_items = split_items(input);
+        let mut items = self
+            .items
+            .iter()
+            .filter(|item| {
+                item.name.is_none() || !new_items.iter().any(|new| new.name == item.name)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        items.extend(new_items);
+
+        let source = self.source(&items, &self.variables, &format!("pub fn {EVAL_FN}() {{}}"));
+        if self.compile(source)? {
+            self.items = items;
+            self.reload();
+        }
+        Ok(())
+    }
+
+    /// Executes `statements` followed by `expr`, and returns the value of the
+    /// expression, if it produces one. The variables that are bound by the
+    /// statements are stored in the state.
+    fn execute(&mut self, statements: &str, expr: Option<&str>) -> anyhow::Result<Option<String>> {
+        let loads = self.variables.iter().fold(String::new(), |mut loads, v| {
+            let _ = writeln!(loads, "let {} = {STATE_PARAM}.{};", v.name, v.field);
+            loads
+        });
+        let value = expr
+            .map(|expr| format!("let {VALUE_VAR} = {{ {expr} }};\n"))
+            .unwrap_or_default();
+
+        // Determine the types of the variables that are bound by the statements, and
+        // the type of the expression
+        let source = self.source(
+            &self.items,
+            &self.variables,
+            &format!("pub fn {EVAL_FN}({STATE_PARAM}: {STATE_STRUCT}) {{\n{loads}{statements}\n{value}}}"),
+        );
+        if !self.compile(source)? {
+            return Ok(None);
+        }
+        let mut bindings = self
+            .bindings()
+            .expect("the evaluation function was compiled");
+        let value_ty = if expr.is_some() {
+            bindings.pop().map(|(_, ty)| ty)
+        } else {
+            None
+        };
+
+        let db = self
+            .runtime
+            .driver_mut()
+            .expect("the runtime is constructed from a driver")
+            .database();
+        let mut variables = self.variables.clone();
+        let mut fields = self.variables.clone();
+        for (name, ty) in bindings.into_iter().skip(self.variables.len()) {
+            let type_path = type_path(db, &ty).ok_or_else(|| {
+                anyhow!("variables of type `{}` cannot be stored", ty.display(db))
+            })?;
+            let existing = variables.iter().position(|v| v.name == name);
+            if matches!(existing, Some(index) if variables[index].type_path == type_path) {
+                continue;
+            }
+
+            // The previous value of a variable whose type changes is loaded from its old
+            // field, so the new value is stored in another field
+            let mut field = name.clone();
+            for suffix in 1.. {
+                if !fields.iter().any(|f| f.field == field) {
+                    break;
+                }
+                field = format!("{name}_{suffix}");
+            }
+
+            let variable = Variable {
+                name,
+                field,
+                type_path,
+            };
+            fields.push(variable.clone());
+            match existing {
+                Some(index) => variables[index] = variable,
+                None => variables.push(variable),
+            }
+        }
+
+        let value_path = match value_ty {
+            Some(ty) if !ty.is_empty() && !ty.is_never() => {
+                Some(type_path(db, &ty).ok_or_else(|| {
+                    anyhow!("values of type `{}` cannot be printed", ty.display(db))
+                })?)
+            }
+            _ => None,
+        };
+
+        // The value is wrapped in a struct, which can be returned as a `StructRef`
+        // regardless of its type
+        let mut items = self.items.clone();
+        let (return_ty, return_value) = match &value_path {
+            Some(value_path) => {
+                items.push(Item {
+                    name: None,
+                    source: format!("pub struct {VALUE_STRUCT} {{ value: {value_path} }}"),
+                });
+                (
+                    format!(" -> {VALUE_STRUCT}"),
+                    format!("{VALUE_STRUCT} {{ value: {VALUE_VAR} }}\n"),
+                )
+            }
+            None => (String::new(), String::new()),
+        };
+        let stores = variables.iter().fold(String::new(), |mut stores, v| {
+            let _ = writeln!(stores, "{STATE_PARAM}.{} = {};", v.field, v.name);
+            stores
+        });
+        let source = self.source(
+            &items,
+            &fields,
+            &format!(
+                "pub fn {EVAL_FN}({STATE_PARAM}: {STATE_STRUCT}){return_ty} {{\n{loads}{statements}\n{value}{stores}{return_value}}}"
+            ),
+        );
+        if !self.compile(source)? {
+            return Ok(None);
+        }
+        self.reload();
+
+        let state = self.state.as_ref(&self.runtime);
+        let value = if value_path.is_some() {
+            let value: StructRef<'_> = self
+                .runtime
+                .invoke(&self.eval_fn, (state,))
+                .map_err(|e| anyhow!("{}", e))?;
+            let ty = value.type_info();
+            let field = ty
+                .as_struct()
+                .and_then(|s| s.fields().get(0))
+                .expect("the value struct has a single field");
+
+            // Safety: the struct was just returned by the runtime, and its field contains a
+            // value of the field's type
+            Some(unsafe {
+                let ptr = NonNull::new_unchecked(value.into_raw().get_ptr().cast_mut());
+                format_value(&self.runtime, offset(ptr, field.offset()), &field.ty())
+            })
+        } else {
+            self.runtime
+                .invoke(&self.eval_fn, (state,))
+                .map_err(|e| anyhow!("{}", e))?;
+            None
+        };
+
+        self.variables = variables;
+        Ok(value)
+    }
+
+    /// Returns the source of the REPL module, with `items`, the state struct
+    /// with the fields of `variables`, and the evaluation function `eval_fn`.
+    fn source(&self, items: &[Item], variables: &[Variable], eval_fn: &str) -> String {
+        let mut source = String::from(self.prelude);
+        for item in items {
+            source.push_str(&item.source);
+            source.push('\n');
+        }
+
+        let fields = variables
+            .iter()
+            .map(|v| format!("{}: {}", v.field, v.type_path))
+            .collect::<Vec<_>>();
+        source.push_str(&format!(
+            "pub struct {STATE_STRUCT} {{ {} }}\n",
+            fields.join(", ")
+        ));
+        source.push_str(eval_fn);
+        source
+    }
+
+    /// Replaces the REPL module by `source` and emits its diagnostics. Returns
+    /// true if it doesn't contain errors.
+    fn compile(&mut self, source: String) -> anyhow::Result<bool> {
+        let display_color = self.display_color;
+        let path = self.path.clone();
+        let driver = self.driver_mut();
+        driver.update_file(path, source);
+        let has_errors = driver.emit_diagnostics(&mut io::stderr(), display_color)?;
+        Ok(!has_errors)
+    }
+
+    /// Hot reloads the assemblies that changed since the last compilation.
+    fn reload(&mut self) {
+        // Safety: the assemblies are compiled from the entered code
+        unsafe { self.runtime.update() };
+    }
+
+    /// Returns the names and types of the variables that are bound by the
+    /// statements of the evaluation function, in order.
+    fn bindings(&mut self) -> Option<Vec<(String, Ty)>> {
+        let path = self.path.clone();
+        let driver = self.driver_mut();
+        let db = driver.database();
+        let file_id = driver.get_file_id_for_path(path)?;
+        let function = Package::all(db)
+            .into_iter()
+            .flat_map(|package| package.modules(db))
+            .filter(|module| module.file_id(db) == Some(file_id))
+            .flat_map(|module| module.declarations(db))
+            .find_map(|def| match def {
+                ModuleDef::Function(function) if function.name(db).to_string() == EVAL_FN => {
+                    Some(function)
+                }
+                _ => None,
+            })?;
+
+        let body = function.body(db);
+        let infer = function.infer(db);
+        let Expr::Block { statements, .. } = &body[body.body_expr()] else {
+            return None;
+        };
+        let bindings = statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Let { pat, .. } => match &body[*pat] {
+                    Pat::Bind { name } => Some((name.to_string(), infer[*pat].clone())),
+                    _ => None,
+                },
+                Statement::Expr(_) => None,
+            })
+            .collect();
+        Some(bindings)
+    }
+
+    fn driver_mut(&mut self) -> &mut Driver {
+        self.runtime
+            .driver_mut()
+            .expect("the runtime is constructed from a driver")
+    }
+}
+
+/// Returns the initial source of the REPL module, whose evaluation function
+/// constructs the empty state.
+fn initial_source(prelude: &str) -> String {
+    format!(
+        "{prelude}pub struct {STATE_STRUCT} {{}}\npub fn {EVAL_FN}() -> {STATE_STRUCT} {{ {STATE_STRUCT} {{}} }}"
+    )
+}
+
+/// Returns the path with which `ty` can be referred to from the REPL module,
+/// if values of the type can be printed.
+fn type_path(db: &dyn HirDatabase, ty: &Ty) -> Option<String> {
+    match ty.interned() {
+        TyKind::Bool | TyKind::Int(_) | TyKind::Float(_) => Some(ty.display(db).to_string()),
+        TyKind::Struct(s) => Some(format!("package::{}", s.full_name(db))),
+        TyKind::Array(element_ty) => Some(format!("[{}]", type_path(db, element_ty)?)),
+        _ => None,
+    }
+}
+
+macro_rules! format_primitive {
+    ($ptr:ident, $ty:ident, $($primitive:ty),+) => {
+        $(
+            if $ty.equals::<$primitive>() {
+                return format!("{:?}", $ptr.cast::<$primitive>().as_ptr().read_unaligned());
+            }
+        )+
+    };
+}
+
+/// Formats the value of type `ty` at `ptr`, using the type information of
+/// the runtime.
+///
+/// # Safety
+///
+/// `ptr` must point to an initialized value of type `ty`.
+unsafe fn format_value(runtime: &Runtime, ptr: NonNull<u8>, ty: &Type) -> String {
+    if ty.is_reference_type() {
+        let object = ptr.cast::<GcPtr>().as_ptr().read_unaligned();
+        if let Some(array) = runtime.gc_array(object) {
+            let element_ty = array.element_type();
+            let elements = array
+                .elements()
+                .map(|element| format_value(runtime, element, &element_ty))
+                .collect::<Vec<_>>();
+            return format!("[{}]", elements.join(", "));
+        }
+
+        let data = NonNull::new_unchecked(object.deref::<u8>().cast_mut());
+        return format_struct(runtime, data, ty);
+    }
+
+    if ty.is_struct() {
+        return format_struct(runtime, ptr, ty);
+    }
+
+    format_primitive!(ptr, ty, bool, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+    format!("<{}>", ty.name())
+}
+
+/// Formats the struct of type `ty` whose fields are stored at `ptr`.
+///
+/// # Safety
+///
+/// `ptr` must point to the initialized fields of a struct of type `ty`.
+unsafe fn format_struct(runtime: &Runtime, ptr: NonNull<u8>, ty: &Type) -> String {
+    let s = ty.as_struct().expect("the type must be a struct");
+    let fields = s
+        .fields()
+        .iter()
+        .map(|field| {
+            let value = format_value(runtime, offset(ptr, field.offset()), &field.ty());
+            format!("{}: {value}", field.name())
+        })
+        .collect::<Vec<_>>();
+
+    if fields.is_empty() {
+        ty.name().to_owned()
+    } else {
+        format!("{} {{ {} }}", ty.name(), fields.join(", "))
+    }
+}
+
+/// Offsets `ptr` by `count` bytes.
+///
+/// # Safety
+///
+/// The resulting pointer must be in bounds of the object that `ptr` points
+/// into.
+unsafe fn offset(ptr: NonNull<u8>, count: usize) -> NonNull<u8> {
+    NonNull::new_unchecked(ptr.as_ptr().add(count))
+}
+
+#[cfg(test)]
+mod test {
+    use mun_compiler::DisplayColor;
+
+    use super::{classify, is_complete, Repl, Snippet};
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("fn foo() -> i32 { 5 }"), Snippet::Items);
+        assert_eq!(classify("struct Foo { a: i32 }"), Snippet::Items);
+        assert_eq!(
+            classify("let a = 5;"),
+            Snippet::Statements(String::from("let a = 5;"))
+        );
+        assert_eq!(
+            classify("let a = 5"),
+            Snippet::Statements(String::from("let a = 5;"))
+        );
+        assert_eq!(
+            classify("a + 1"),
+            Snippet::Expression {
+                statements: String::new(),
+                expr: String::from("a + 1"),
+            }
+        );
+        assert_eq!(
+            classify("let b = a * 2; b + 1"),
+            Snippet::Expression {
+                statements: String::from("let b = a * 2; "),
+                expr: String::from("b + 1"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_complete() {
+        assert!(is_complete("1 + 2"));
+        assert!(!is_complete("fn foo() {"));
+        assert!(is_complete("fn foo() {\n}"));
+        assert!(is_complete("fn foo() {} // }"));
+        assert!(is_complete("fn foo() { /* { */ }"));
+        assert!(!is_complete("fn foo() { // }"));
+    }
+
+    #[test]
+    fn test_eval() {
+        let mut repl = Repl::new(None, DisplayColor::Disable)
+            .unwrap()
+            .expect("the REPL module contains errors");
+        let mut eval = |input: &str| repl.eval(input).unwrap();
+
+        assert_eq!(eval("struct Foo { a: i32 }"), None);
+        assert_eq!(eval("let foo = Foo { a: 1 }"), None);
+        assert_eq!(eval("let values = [1, 2, 3];"), None);
+        assert_eq!(eval("foo.a += values[2]"), None);
+        assert_eq!(eval("foo").as_deref(), Some("Foo { a: 4 }"));
+        assert_eq!(eval("let b = foo.a * 2; b + 1").as_deref(), Some("9"));
+        assert_eq!(eval("b").as_deref(), Some("8"));
+        assert_eq!(eval("values").as_deref(), Some("[1, 2, 3]"));
+
+        // Redefining a struct migrates the existing values
+        assert_eq!(eval("struct Foo { a: i32, b: bool }"), None);
+        assert_eq!(eval("foo").as_deref(), Some("Foo { a: 4, b: false }"));
+
+        // Redefining a variable changes its type
+        assert_eq!(eval("let values = [values[0] == 1]"), None);
+        assert_eq!(eval("values").as_deref(), Some("[true]"));
+
+        // Redefining a function replaces it
+        assert_eq!(eval("fn double(a: i32) -> i32 { a * 2 }"), None);
+        assert_eq!(eval("double(b)").as_deref(), Some("16"));
+        assert_eq!(eval("fn double(a: i32) -> i32 { a + a + 1 }"), None);
+        assert_eq!(eval("double(b)").as_deref(), Some("17"));
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use mun_compiler::{Config, Driver};
+use mun_interpreter::{Interpreter, Value};
+use mun_runtime::Runtime;
+
+use crate::{
+    ops::{
+        build::{display_color, locate_manifest, UseColor},
+        start::invoke_entry,
+    },
+    ExitStatus,
+};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to the manifest of the project
+    #[clap(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Use color in output
+    #[clap(long, value_enum)]
+    color: Option<UseColor>,
+
+    /// Interprets the code instead of compiling it to machine code
+    #[clap(long)]
+    interpret: bool,
+
+    /// The function entry point to call
+    #[clap(default_value_t = String::from("main"))]
+    entry: String,
+}
+
+/// Compiles the project in the current directory, or one of its parent
+/// directories, in memory and invokes function `entry`. Nothing is written to
+/// the filesystem.
+pub fn run(args: Args) -> anyhow::Result<ExitStatus> {
+    let manifest_path = locate_manifest(args.manifest_path.as_deref())?;
+    let manifest_dir = manifest_path
+        .parent()
+        .expect("a manifest is always located in a directory");
+    let (_package, driver) = Driver::with_package_path(manifest_dir, Config::default())?;
+
+    if driver.emit_diagnostics(&mut std::io::stderr(), display_color(args.color))? {
+        return Ok(ExitStatus::Error);
+    }
+
+    if args.interpret {
+        let mut interpreter = Interpreter::new(driver.database());
+        match interpreter.invoke(&args.entry, Vec::new())? {
+            Value::Unit => {}
+            Value::Bool(value) => println!("{value}"),
+            Value::Int(value) => println!("{value}"),
+            Value::Float(value) => println!("{value}"),
+            Value::Struct { ty, .. } => {
+                return Err(anyhow!(
+                    "Only native Mun return types are supported for entry points. Found: {}",
+                    ty.name()
+                ))
+            }
+            Value::Gc(_) => {
+                return Err(anyhow!(
+                    "Only native Mun return types are supported for entry points"
+                ))
+            }
+        }
+        Ok(ExitStatus::Success)
+    } else {
+        // Safety: the assemblies are compiled from the source code of the project
+        let runtime = unsafe { Runtime::jit_builder(driver).finish() }?;
+        invoke_entry(&runtime, &args.entry)
+    }
+}
@@ -21,39 +21,35 @@ pub fn start(args: Args) -> anyhow::Result<ExitStatus> {
 
     // Safety: we assume that the passed in library is safe
     let runtime = unsafe { builder.finish() }?;
+    invoke_entry(&runtime, &args.entry)
+}
 
-    let fn_definition = runtime
-        .get_function_definition(&args.entry)
-        .ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                format!("Failed to obtain entry point '{}'", &args.entry),
-            )
-        })?;
+/// Invokes the function `entry` of `runtime` and prints its result.
+pub fn invoke_entry(runtime: &Runtime, entry: &str) -> anyhow::Result<ExitStatus> {
+    let fn_definition = runtime.get_function_definition(entry).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Failed to obtain entry point '{entry}'"),
+        )
+    })?;
 
     let return_type = &fn_definition.prototype.signature.return_type;
     if return_type.equals::<bool>() {
-        let result: bool = runtime
-            .invoke(&args.entry, ())
-            .map_err(|e| anyhow!("{}", e))?;
+        let result: bool = runtime.invoke(entry, ()).map_err(|e| anyhow!("{}", e))?;
 
         println!("{result}");
     } else if return_type.equals::<f64>() {
-        let result: f64 = runtime
-            .invoke(&args.entry, ())
-            .map_err(|e| anyhow!("{}", e))?;
+        let result: f64 = runtime.invoke(entry, ()).map_err(|e| anyhow!("{}", e))?;
 
         println!("{result}");
     } else if return_type.equals::<i64>() {
-        let result: i64 = runtime
-            .invoke(&args.entry, ())
-            .map_err(|e| anyhow!("{}", e))?;
+        let result: i64 = runtime.invoke(entry, ()).map_err(|e| anyhow!("{}", e))?;
 
         println!("{result}");
     } else if return_type.equals::<()>() {
         #[allow(clippy::unit_arg)]
         runtime
-            .invoke(&args.entry, ())
+            .invoke(entry, ())
             .map(|_: ()| ExitStatus::Success)
             .map_err(|e| anyhow!("{}", e))?;
     } else {
@@ -53,6 +53,17 @@ fn mun_emit_ir() {
     assert!(ir_path.is_file());
 }
 
+/// Verifies that `mun explain` accepts known diagnostic codes and rejects
+/// unknown ones.
+#[test]
+fn mun_explain() {
+    let args: Vec<OsString> = vec!["mun".into(), "explain".into(), "e0013".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let args: Vec<OsString> = vec!["mun".into(), "explain".into(), "E9999".into()];
+    assert!(run_with_args(args).is_err());
+}
+
 fn build(project: &Path, args: &[&str]) {
     let args: Vec<OsString> = vec![
         OsString::from("mun"),
@@ -0,0 +1,21 @@
+use std::ffi::c_void;
+
+use crate::AssemblyInfo;
+
+/// Represents the functions that an assembly exposes to the runtime.
+///
+/// A munlib exports these functions as individual symbols. A static library
+/// instead bundles them in a single struct that is returned by its
+/// registration function (see [`static_registration_fn_name`]).
+///
+/// [`static_registration_fn_name`]: crate::static_registration_fn_name
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EntryPoints {
+    /// Returns the ABI version of the assembly
+    pub get_version: extern "C" fn() -> u32,
+    /// Returns the information of the assembly
+    pub get_info: extern "C" fn() -> AssemblyInfo<'static>,
+    /// Sets the handle of the allocator used by the assembly
+    pub set_allocator_handle: extern "C" fn(*mut c_void),
+}
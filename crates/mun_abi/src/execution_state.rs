@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// Describes why the Mun code that executes on a thread was interrupted.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Interrupt {
+    /// The execution budget was exhausted
+    BudgetExhausted = 1,
+    /// An integer operation overflowed while overflow checks were enabled
+    Overflow = 2,
+    /// An allocation exceeded the hard heap limit
+    HeapLimitExceeded = 3,
+}
+
+impl Interrupt {
+    /// Converts the value stored in [`ExecutionState::interrupt`] back into an
+    /// `Interrupt`. Returns `None` if execution was not interrupted.
+    pub fn from_raw(value: u32) -> Option<Interrupt> {
+        match value {
+            1 => Some(Interrupt::BudgetExhausted),
+            2 => Some(Interrupt::Overflow),
+            3 => Some(Interrupt::HeapLimitExceeded),
+            _ => None,
+        }
+    }
+}
+
+/// Represents the state of the Mun code that executes on a thread.
+///
+/// Generated code obtains the state of the current thread through the
+/// `execution_state` intrinsic. Once the state is interrupted, every Mun
+/// function on the stack returns immediately.
+#[repr(C)]
+pub struct ExecutionState {
+    /// The number of units of budget that may still be consumed. Generated code
+    /// atomically subtracts a unit for every function call and loop iteration,
+    /// and interrupts execution if the budget was already zero.
+    pub budget: AtomicU64,
+    /// The reason why execution was interrupted as an [`Interrupt`], or zero if
+    /// it was not interrupted.
+    pub interrupt: AtomicU32,
+}
+
+impl ExecutionState {
+    /// Constructs a new state that may consume `budget` units.
+    pub const fn new(budget: u64) -> Self {
+        Self {
+            budget: AtomicU64::new(budget),
+            interrupt: AtomicU32::new(0),
+        }
+    }
+
+    /// Sets the remaining budget to `budget` and clears the interrupt.
+    pub fn reset(&self, budget: u64) {
+        self.budget.store(budget, Ordering::Relaxed);
+        self.interrupt.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns the number of units of budget that may still be consumed.
+    pub fn remaining_budget(&self) -> u64 {
+        // Generated code wraps the budget around when it runs out
+        if self.interrupted() == Some(Interrupt::BudgetExhausted) {
+            0
+        } else {
+            self.budget.load(Ordering::Relaxed)
+        }
+    }
+
+    /// Consumes `units` of budget, e.g. the units that a nested invocation
+    /// consumed. The budget does not drop below zero; generated code
+    /// interrupts execution as soon as it tries to consume more.
+    pub fn consume(&self, units: u64) {
+        // The closure always returns `Some`, so this cannot fail
+        let _ = self
+            .budget
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |budget| {
+                Some(budget.saturating_sub(units))
+            });
+    }
+
+    /// Interrupts execution because of `reason`, unless it was already
+    /// interrupted for another reason. Returns true if execution is
+    /// interrupted because of `reason`.
+    pub fn interrupt(&self, reason: Interrupt) -> bool {
+        self.interrupt
+            .compare_exchange(0, reason as u32, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Returns the reason why execution was interrupted, if it was.
+    pub fn interrupted(&self) -> Option<Interrupt> {
+        Interrupt::from_raw(self.interrupt.load(Ordering::Relaxed))
+    }
+
+    /// Clears the interrupt and returns the reason why execution was
+    /// interrupted, if it was.
+    pub fn take_interrupt(&self) -> Option<Interrupt> {
+        Interrupt::from_raw(self.interrupt.swap(0, Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::{ExecutionState, Interrupt};
+
+    #[test]
+    fn test_execution_state_interrupt() {
+        let state = ExecutionState::new(10);
+        assert_eq!(state.interrupted(), None);
+
+        assert!(state.interrupt(Interrupt::HeapLimitExceeded));
+        assert!(!state.interrupt(Interrupt::Overflow));
+        assert_eq!(state.interrupted(), Some(Interrupt::HeapLimitExceeded));
+        assert_eq!(state.remaining_budget(), 10);
+
+        assert_eq!(state.take_interrupt(), Some(Interrupt::HeapLimitExceeded));
+        assert_eq!(state.interrupted(), None);
+
+        assert!(state.interrupt(Interrupt::Overflow));
+        assert_eq!(state.take_interrupt(), Some(Interrupt::Overflow));
+    }
+
+    #[test]
+    fn test_execution_state_budget() {
+        let state = ExecutionState::new(10);
+        state.consume(4);
+        assert_eq!(state.remaining_budget(), 6);
+        state.consume(100);
+        assert_eq!(state.remaining_budget(), 0);
+
+        // Mimic generated code that tries to consume a unit of an exhausted budget
+        assert_eq!(state.budget.fetch_sub(1, Ordering::Relaxed), 0);
+        state.interrupt(Interrupt::BudgetExhausted);
+        assert_eq!(state.remaining_budget(), 0);
+
+        state.reset(3);
+        assert_eq!(state.interrupted(), None);
+        assert_eq!(state.remaining_budget(), 3);
+    }
+}
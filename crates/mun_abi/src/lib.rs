@@ -8,6 +8,8 @@ use std::{ffi::CStr, fmt};
 
 pub use assembly_info::AssemblyInfo;
 pub use dispatch_table::DispatchTable;
+pub use entry_points::EntryPoints;
+pub use execution_state::{ExecutionState, Interrupt};
 pub use function_info::{FunctionDefinition, FunctionPrototype, FunctionSignature};
 pub use module_info::ModuleInfo;
 pub use primitive::PrimitiveType;
@@ -19,6 +21,8 @@ pub use type_lut::TypeLut;
 // C bindings can be manually generated by running `cargo gen-abi`.
 mod assembly_info;
 mod dispatch_table;
+mod entry_points;
+mod execution_state;
 mod function_info;
 mod module_info;
 mod primitive;
@@ -33,13 +37,31 @@ mod test_utils;
 
 /// Defines the current ABI version
 #[allow(clippy::zero_prefixed_literal)]
-pub const ABI_VERSION: u32 = 00_03_00;
+pub const ABI_VERSION: u32 = 00_04_00;
 /// Defines the name for the `get_info` function
 pub const GET_INFO_FN_NAME: &str = "get_info";
 /// Defines the name for the `get_version` function
 pub const GET_VERSION_FN_NAME: &str = "get_version";
 /// Defines the name for the `set_allocator_handle` function
 pub const SET_ALLOCATOR_HANDLE_FN_NAME: &str = "set_allocator_handle";
+/// Defines the name of the module from which WebAssembly munlibs import the
+/// intrinsics of the runtime
+pub const INTRINSICS_WASM_MODULE: &str = "mun";
+/// Defines the prefix for the registration function of a static library
+pub const STATIC_REGISTRATION_FN_PREFIX: &str = "mun_register_";
+
+/// Returns the name of the function that a static library called
+/// `library_name` exports to register its [`EntryPoints`] with the host.
+///
+/// Characters that are not valid in a C identifier are replaced by
+/// underscores, e.g. `my-game` results in `mun_register_my_game`.
+pub fn static_registration_fn_name(library_name: &str) -> String {
+    let sanitized: String = library_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{STATIC_REGISTRATION_FN_PREFIX}{sanitized}")
+}
 
 /// Represents a globally unique identifier (GUID).
 #[repr(C)]
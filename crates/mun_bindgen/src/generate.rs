@@ -0,0 +1,452 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use mun_abi::StructMemoryKind;
+
+use crate::{Bindings, Function, Struct, TypeRef};
+
+/// The header of every generated file.
+const HEADER: &str = "\
+// This file is generated by `mun bindgen`. Do not edit it manually; regenerate it
+// when the Mun code changes instead.
+";
+
+/// The functions that are used by the generated `verify` function.
+const VERIFY_HELPERS: &str = r#"
+fn verify_struct(
+    runtime: &::mun_runtime::Runtime,
+    name: &str,
+    fields: &[(&str, &str)],
+    errors: &mut Vec<String>,
+) {
+    let Some(ty) = runtime.get_type_info_by_name(name) else {
+        errors.push(format!("struct `{name}` does not exist"));
+        return;
+    };
+    let Some(s) = ty.as_struct() else {
+        errors.push(format!("`{name}` is not a struct"));
+        return;
+    };
+    for (field_name, field_type) in fields {
+        match s.fields().find_by_name(field_name) {
+            None => errors.push(format!("struct `{name}` has no field `{field_name}`")),
+            Some(field) if field.ty().name() != *field_type => errors.push(format!(
+                "field `{name}::{field_name}` is of type `{}` instead of `{field_type}`",
+                field.ty().name()
+            )),
+            Some(_) => {}
+        }
+    }
+}
+
+fn verify_function(
+    runtime: &::mun_runtime::Runtime,
+    name: &str,
+    arg_types: &[&str],
+    return_type: &str,
+    errors: &mut Vec<String>,
+) {
+    let Some(definition) = runtime.get_function_definition(name) else {
+        errors.push(format!("function `{name}` does not exist"));
+        return;
+    };
+    let signature = &definition.prototype.signature;
+    let actual_arg_types = signature.arg_types.iter().map(::mun_runtime::Type::name).collect::<Vec<_>>();
+    let actual_return_type = signature.return_type.name();
+    if actual_arg_types != arg_types || actual_return_type != return_type {
+        errors.push(format!(
+            "function `{name}` has signature `fn({}) -> {actual_return_type}`",
+            actual_arg_types.join(", ")
+        ));
+    }
+}
+"#;
+
+/// A Rust module in the generated bindings, which corresponds to a Mun module.
+#[derive(Default)]
+struct Module<'b> {
+    structs: Vec<&'b Struct>,
+    functions: Vec<&'b Function>,
+    /// Functions that are defined in an `impl` block of a struct in this module,
+    /// by the name of the struct
+    methods: BTreeMap<&'b str, Vec<&'b Function>>,
+    children: BTreeMap<&'b str, Module<'b>>,
+}
+
+impl<'b> Module<'b> {
+    /// Returns the module with the specified path relative to this module.
+    fn child(&mut self, path: &[&'b str]) -> &mut Module<'b> {
+        path.iter().fold(self, |module, name| {
+            module.children.entry(name).or_default()
+        })
+    }
+}
+
+/// Generates the Rust source of `bindings`.
+pub(crate) fn generate(bindings: &Bindings) -> String {
+    let mut root = Module::default();
+    for s in &bindings.structs {
+        let path = s.name.split("::").collect::<Vec<_>>();
+        root.child(&path[..path.len() - 1]).structs.push(s);
+    }
+    for function in &bindings.functions {
+        let path = function.name.split("::").collect::<Vec<_>>();
+        let (module_path, method_of) = match path.len() {
+            1 => (&path[..0], None),
+            len => {
+                let parent = path[..len - 1].join("::");
+                if bindings.structs.iter().any(|s| s.name == parent) {
+                    (&path[..len - 2], Some(path[len - 2]))
+                } else {
+                    (&path[..len - 1], None)
+                }
+            }
+        };
+        let module = root.child(module_path);
+        match method_of {
+            Some(struct_name) => module
+                .methods
+                .entry(struct_name)
+                .or_default()
+                .push(function),
+            None => module.functions.push(function),
+        }
+    }
+
+    let mut out = String::from(HEADER);
+    write_module(&mut out, &root, 0);
+    write_verify(&mut out, bindings);
+    out
+}
+
+/// Writes the items of `module` at nesting depth `depth`.
+fn write_module(out: &mut String, module: &Module<'_>, depth: usize) {
+    let indent = "    ".repeat(depth);
+    let root = "super::".repeat(depth);
+    let mut separator = if depth == 0 { "\n" } else { "" };
+
+    for s in &module.structs {
+        let local_name = s.name.rsplit("::").next().unwrap();
+        let methods = module
+            .methods
+            .get(local_name)
+            .map_or(&[][..], Vec::as_slice);
+        let code = struct_code(s, local_name, methods, &root);
+        out.push_str(std::mem::replace(&mut separator, "\n"));
+        write_indented(out, &code, &indent);
+    }
+
+    for function in &module.functions {
+        let local_name = function.name.rsplit("::").next().unwrap();
+        out.push_str(std::mem::replace(&mut separator, "\n"));
+        write_indented(
+            out,
+            &function_code(function, local_name, &root, false),
+            &indent,
+        );
+    }
+
+    for (name, child) in &module.children {
+        out.push_str(std::mem::replace(&mut separator, "\n"));
+        writeln!(out, "{indent}pub mod {} {{", ident(name)).unwrap();
+        write_module(out, child, depth + 1);
+        writeln!(out, "{indent}}}").unwrap();
+    }
+}
+
+/// Writes every line of `code` prefixed with `indent`.
+fn write_indented(out: &mut String, code: &str, indent: &str) {
+    for line in code.lines() {
+        if line.is_empty() {
+            writeln!(out).unwrap();
+        } else {
+            writeln!(out, "{indent}{line}").unwrap();
+        }
+    }
+}
+
+/// Returns the Rust source of a newtype for the struct `s`, which is named
+/// `local_name` in its module. `methods` are the functions defined in an `impl`
+/// block of the struct. Other types are referred to relative to `root`.
+fn struct_code(s: &Struct, local_name: &str, methods: &[&Function], root: &str) -> String {
+    let name = ident(local_name);
+    let mut code = String::new();
+
+    match s.memory_kind {
+        StructMemoryKind::Gc => {
+            writeln!(code, "/// A handle to a Mun `{}` struct.", s.name).unwrap();
+        }
+        StructMemoryKind::Value => {
+            writeln!(
+                code,
+                "/// A handle to a copy of a Mun `{}` value struct.",
+                s.name
+            )
+            .unwrap();
+        }
+    }
+    writeln!(
+        code,
+        "///
+/// The field accessors panic if the struct no longer matches the bindings,
+/// e.g. after a hot reload.
+#[derive(Clone)]
+pub struct {name}<'r>(pub ::mun_runtime::StructRef<'r>);
+
+impl<'r> {name}<'r> {{
+    /// The name of the struct in the Mun runtime
+    pub const NAME: &'static str = \"{}\";",
+        s.name
+    )
+    .unwrap();
+
+    for field in &s.fields {
+        let Some(ty) = rust_type(&field.ty, root) else {
+            writeln!(
+                code,
+                "\n    // The field `{}` is of the unsupported type `{}`",
+                field.name,
+                field.ty.mun_name()
+            )
+            .unwrap();
+            continue;
+        };
+        let getter = ident(&field.name);
+        let setter = format!("set_{}", field.name);
+        writeln!(
+            code,
+            "
+    /// Returns the value of the `{field_name}` field.
+    pub fn {getter}(&self) -> {ty} {{
+        self.0.get(\"{field_name}\").unwrap_or_else(|e| panic!(\"{{e}}\"))
+    }}
+
+    /// Sets the value of the `{field_name}` field.
+    pub fn {setter}(&mut self, value: {ty}) {{
+        self.0.set(\"{field_name}\", value).unwrap_or_else(|e| panic!(\"{{e}}\"));
+    }}",
+            field_name = field.name,
+        )
+        .unwrap();
+    }
+
+    for function in methods {
+        let local_name = function.name.rsplit("::").next().unwrap();
+        writeln!(code).unwrap();
+        for line in function_code(function, local_name, root, true).lines() {
+            writeln!(code, "    {line}").unwrap();
+        }
+    }
+
+    writeln!(
+        code,
+        "}}
+
+impl<'r> ::mun_runtime::Marshal<'r> for {name}<'r> {{
+    type MunType = <::mun_runtime::StructRef<'r> as ::mun_runtime::Marshal<'r>>::MunType;
+
+    fn marshal_from<'rt>(value: Self::MunType, runtime: &'rt ::mun_runtime::Runtime) -> Self
+    where
+        'rt: 'r,
+    {{
+        Self(::mun_runtime::Marshal::marshal_from(value, runtime))
+    }}
+
+    fn marshal_into(self) -> Self::MunType {{
+        ::mun_runtime::Marshal::marshal_into(self.0)
+    }}
+
+    fn marshal_from_ptr<'rt>(
+        ptr: ::std::ptr::NonNull<Self::MunType>,
+        runtime: &'rt ::mun_runtime::Runtime,
+        type_info: &::mun_runtime::Type,
+    ) -> Self
+    where
+        'rt: 'r,
+    {{
+        Self(::mun_runtime::Marshal::marshal_from_ptr(ptr, runtime, type_info))
+    }}
+
+    fn marshal_to_ptr(
+        value: Self,
+        ptr: ::std::ptr::NonNull<Self::MunType>,
+        type_info: &::mun_runtime::Type,
+    ) {{
+        ::mun_runtime::StructRef::marshal_to_ptr(value.0, ptr, type_info);
+    }}
+}}
+
+impl<'r> ::mun_runtime::ArgumentReflection for {name}<'r> {{
+    fn type_info(&self, runtime: &::mun_runtime::Runtime) -> ::mun_runtime::Type {{
+        ::mun_runtime::ArgumentReflection::type_info(&self.0, runtime)
+    }}
+}}
+
+impl<'r> ::mun_runtime::ReturnTypeReflection for {name}<'r> {{
+    fn accepts_type(ty: &::mun_runtime::Type) -> bool {{
+        ty.is_struct() && ty.name() == Self::NAME
+    }}
+
+    fn type_hint() -> &'static str {{
+        Self::NAME
+    }}
+}}"
+    )
+    .unwrap();
+
+    code
+}
+
+/// Returns the Rust source of a function that invokes `function`, named
+/// `local_name`. Types are referred to relative to `root`. A method uses the
+/// lifetime of its `impl` block instead of declaring its own, and a function
+/// without structs or arrays needs no lifetime at all.
+fn function_code(function: &Function, local_name: &str, root: &str, is_method: bool) -> String {
+    let arg_types = function
+        .arg_types
+        .iter()
+        .map(|ty| rust_type(ty, root))
+        .collect::<Option<Vec<_>>>();
+    let (Some(arg_types), Some(return_type)) = (arg_types, rust_type(&function.return_type, root))
+    else {
+        return format!(
+            "// The function `{}` uses unsupported types\n",
+            function.name
+        );
+    };
+
+    let (generics, runtime_lifetime) = if is_method {
+        ("", "'r ")
+    } else if arg_types
+        .iter()
+        .chain([&return_type])
+        .any(|ty| ty.contains("'r"))
+    {
+        ("<'r>", "'r ")
+    } else {
+        ("", "")
+    };
+
+    let args = arg_types
+        .iter()
+        .enumerate()
+        .fold(String::new(), |mut args, (idx, ty)| {
+            write!(args, "\n    arg{idx}: {ty},").unwrap();
+            args
+        });
+    let arg_names = tuple((0..arg_types.len()).map(|idx| format!("arg{idx}")));
+    let arg_tuple = tuple(arg_types.iter().cloned());
+
+    format!(
+        "/// Invokes the Mun function `{name}`.
+pub fn {fn_name}{generics}(
+    runtime: &{runtime_lifetime}::mun_runtime::Runtime,{args}
+) -> Result<{return_type}, ::mun_runtime::InvokeErr<'static, {arg_tuple}>> {{
+    runtime.invoke(\"{name}\", {arg_names})
+}}
+",
+        name = function.name,
+        fn_name = ident(local_name),
+    )
+}
+
+/// Returns the Rust source of a tuple of `elements`.
+fn tuple(elements: impl Iterator<Item = String>) -> String {
+    let elements = elements.collect::<Vec<_>>();
+    if let [element] = elements.as_slice() {
+        format!("({element},)")
+    } else {
+        format!("({})", elements.join(", "))
+    }
+}
+
+/// Writes a `verify` function that checks that the structs and functions of
+/// the runtime match the bindings.
+fn write_verify(out: &mut String, bindings: &Bindings) {
+    writeln!(
+        out,
+        "
+/// Verifies that the structs and functions of `runtime` match these bindings.
+/// Returns a description of every mismatch.
+pub fn verify(runtime: &::mun_runtime::Runtime) -> Result<(), Vec<String>> {{
+    let mut errors = Vec::new();"
+    )
+    .unwrap();
+
+    for s in &bindings.structs {
+        let fields = s
+            .fields
+            .iter()
+            .map(|field| format!("(\"{}\", \"{}\")", field.name, field.ty.mun_name()))
+            .collect::<Vec<_>>();
+        writeln!(
+            out,
+            "    verify_struct(runtime, \"{}\", &[{}], &mut errors);",
+            s.name,
+            fields.join(", ")
+        )
+        .unwrap();
+    }
+
+    for function in &bindings.functions {
+        let arg_types = function
+            .arg_types
+            .iter()
+            .map(|ty| format!("\"{}\"", ty.mun_name()))
+            .collect::<Vec<_>>();
+        writeln!(
+            out,
+            "    verify_function(runtime, \"{}\", &[{}], \"{}\", &mut errors);",
+            function.name,
+            arg_types.join(", "),
+            function.return_type.mun_name()
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        out,
+        "    if errors.is_empty() {{
+        Ok(())
+    }} else {{
+        Err(errors)
+    }}
+}}"
+    )
+    .unwrap();
+    out.push_str(VERIFY_HELPERS);
+}
+
+/// Returns the Rust type that represents `ty`, referring to structs relative to
+/// `root`, or `None` if the type is not supported.
+fn rust_type(ty: &TypeRef, root: &str) -> Option<String> {
+    match ty {
+        TypeRef::Primitive(primitive) => Some(primitive.rust_name.to_owned()),
+        TypeRef::Struct(name) => {
+            let path = name.split("::").map(ident).collect::<Vec<_>>().join("::");
+            Some(format!("{root}{path}<'r>"))
+        }
+        TypeRef::Array(element) => Some(format!(
+            "::mun_runtime::ArrayRef<'r, {}>",
+            rust_type(element, root)?
+        )),
+        TypeRef::Unsupported(_) => None,
+    }
+}
+
+/// Returns a Rust identifier for the Mun identifier `name`, escaping Rust
+/// keywords.
+fn ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let",
+        "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+        "static", "struct", "trait", "true", "try", "type", "typeof", "union", "unsafe", "unsized",
+        "use", "virtual", "where", "while", "yield",
+    ];
+
+    match name {
+        "crate" | "self" | "super" | "Self" => format!("{name}_"),
+        _ if KEYWORDS.contains(&name) => format!("r#{name}"),
+        _ => name.to_owned(),
+    }
+}
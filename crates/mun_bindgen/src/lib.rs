@@ -0,0 +1,293 @@
+//! Generates strongly typed Rust bindings for the structs and functions of
+//! compiled Mun libraries.
+//!
+//! The bindings wrap the dynamically typed API of the `mun_runtime` crate:
+//! every struct becomes a newtype over a `StructRef` with typed field
+//! accessors, and every function becomes a typed function that invokes it.
+//! When a script changes, regenerating the bindings turns mismatches in the
+//! host code into compile errors. The generated `verify` function reports
+//! mismatches between the bindings and a running runtime, e.g. after a hot
+//! reload.
+//!
+//! The bindings can be generated by the `mun bindgen` command, or from a build
+//! script:
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Safety: the munlib was compiled by the Mun compiler
+//! let bindings = unsafe { mun_bindgen::Bindings::from_munlib("target/mod.munlib".as_ref()) }?;
+//! std::fs::write("src/mun_bindings.rs", bindings.generate())?;
+//! # Ok(())
+//! # }
+//! ```
+#![warn(missing_docs)]
+
+mod generate;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use mun_abi::{self as abi, Guid, StructMemoryKind, TypeId};
+use mun_libloader::MunLibrary;
+
+/// An error that occurs while loading the type information of a munlib.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Failed to load the munlib
+    #[error("Failed to load `{0}`: {1}")]
+    LoadLibrary(PathBuf, mun_libloader::InitError),
+    /// The munlib was compiled for a different version of the ABI
+    #[error("ABI version mismatch. `{path}` is `{actual}` but bindgen is `{expected}`")]
+    MismatchedAbiVersions {
+        /// The path of the munlib
+        path: PathBuf,
+        /// The ABI version supported by bindgen
+        expected: u32,
+        /// The ABI version of the munlib
+        actual: u32,
+    },
+}
+
+/// The structs and functions of one or more Mun assemblies, for which Rust
+/// bindings can be generated.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bindings {
+    /// The structs, sorted by name
+    pub structs: Vec<Struct>,
+    /// The functions, sorted by name
+    pub functions: Vec<Function>,
+}
+
+/// A Mun struct.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Struct {
+    /// The full path of the struct, e.g. `foo::Bar`
+    pub name: String,
+    /// Whether the struct is garbage collected or a value struct
+    pub memory_kind: StructMemoryKind,
+    /// The fields of the struct
+    pub fields: Vec<Field>,
+}
+
+/// A field of a Mun struct.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    /// The name of the field
+    pub name: String,
+    /// The type of the field
+    pub ty: TypeRef,
+}
+
+/// A Mun function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Function {
+    /// The full path of the function, e.g. `foo::bar`
+    pub name: String,
+    /// The types of the arguments
+    pub arg_types: Vec<TypeRef>,
+    /// The return type
+    pub return_type: TypeRef,
+}
+
+/// A reference to a type that is used by a struct or function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeRef {
+    /// A primitive type, e.g. `core::i32`
+    Primitive(&'static PrimitiveInfo),
+    /// A struct, referred to by its full path
+    Struct(String),
+    /// An array of elements of a type
+    Array(Box<TypeRef>),
+    /// A type that cannot be used from Rust, e.g. a pointer
+    Unsupported(String),
+}
+
+/// Describes how a primitive type is named in Mun and in Rust.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PrimitiveInfo {
+    guid: Guid,
+    /// The name of the type in Mun, e.g. `core::i32`
+    pub mun_name: &'static str,
+    /// The name of the type in Rust, e.g. `i32`
+    pub rust_name: &'static str,
+}
+
+macro_rules! primitives {
+    ($($mun_name:literal => $rust_name:literal),*) => {
+        [$(
+            PrimitiveInfo {
+                guid: Guid::from_str($mun_name),
+                mun_name: $mun_name,
+                rust_name: $rust_name,
+            },
+        )*]
+    };
+}
+
+/// The primitive types that can be used from Rust. The names must match
+/// those of [`mun_abi::PrimitiveType`].
+static PRIMITIVES: [PrimitiveInfo; 14] = primitives! {
+    "core::i8" => "i8",
+    "core::i16" => "i16",
+    "core::i32" => "i32",
+    "core::i64" => "i64",
+    "core::i128" => "i128",
+    "core::u8" => "u8",
+    "core::u16" => "u16",
+    "core::u32" => "u32",
+    "core::u64" => "u64",
+    "core::u128" => "u128",
+    "core::f32" => "f32",
+    "core::f64" => "f64",
+    "core::bool" => "bool",
+    "core::empty" => "()"
+};
+
+impl TypeRef {
+    /// Returns the primitive type with the name `mun_name`, e.g. `core::i32`.
+    pub fn primitive(mun_name: &str) -> Option<TypeRef> {
+        PRIMITIVES
+            .iter()
+            .find(|primitive| primitive.mun_name == mun_name)
+            .map(TypeRef::Primitive)
+    }
+
+    /// Returns the name of the type in Mun.
+    pub fn mun_name(&self) -> String {
+        match self {
+            TypeRef::Primitive(primitive) => primitive.mun_name.to_owned(),
+            TypeRef::Struct(name) | TypeRef::Unsupported(name) => name.clone(),
+            TypeRef::Array(element) => format!("[{}]", element.mun_name()),
+        }
+    }
+
+    /// Resolves a type ID, using `structs` to look up the names of structs.
+    fn from_type_id(type_id: &TypeId<'_>, structs: &HashMap<Guid, String>) -> TypeRef {
+        match type_id {
+            TypeId::Concrete(guid) => PRIMITIVES
+                .iter()
+                .find(|primitive| primitive.guid == *guid)
+                .map(TypeRef::Primitive)
+                .or_else(|| structs.get(guid).cloned().map(TypeRef::Struct))
+                .unwrap_or_else(|| TypeRef::Unsupported(guid.to_string())),
+            TypeId::Pointer(_) => TypeRef::Unsupported(type_id.to_string()),
+            TypeId::Array(array) => {
+                TypeRef::Array(Box::new(TypeRef::from_type_id(array.element, structs)))
+            }
+        }
+    }
+}
+
+impl Bindings {
+    /// Collects the structs and functions of the assembly at `library_path`
+    /// and of all assemblies that it depends on.
+    ///
+    /// # Safety
+    ///
+    /// The munlibs are loaded to retrieve their type information. A munlib is
+    /// simply a shared object, so see [`MunLibrary::new`] for the
+    /// requirements to load it soundly.
+    pub unsafe fn from_munlib(library_path: &Path) -> Result<Self, Error> {
+        let mut libraries = Vec::new();
+        let mut loaded = HashSet::new();
+        let mut to_load = VecDeque::from([library_path.to_path_buf()]);
+        while let Some(path) = to_load.pop_front() {
+            if !loaded.insert(path.clone()) {
+                continue;
+            }
+
+            let library =
+                MunLibrary::new(&path).map_err(|e| Error::LoadLibrary(path.clone(), e))?;
+            let version = library.get_abi_version();
+            if version != abi::ABI_VERSION {
+                return Err(Error::MismatchedAbiVersions {
+                    path,
+                    expected: abi::ABI_VERSION,
+                    actual: version,
+                });
+            }
+
+            // Dependencies are stored next to the assembly, with the same extension
+            let info = library.get_info();
+            let parent = path.parent().unwrap_or_else(|| Path::new(""));
+            for dependency in info.dependencies() {
+                let mut dependency_path = parent.join(dependency);
+                if let Some(extension) = path.extension() {
+                    dependency_path = dependency_path.with_extension(extension);
+                }
+                to_load.push_back(dependency_path);
+            }
+
+            libraries.push(library);
+        }
+
+        let infos = libraries
+            .iter()
+            .map(|library| library.get_info())
+            .collect::<Vec<_>>();
+        Ok(Self::from_assembly_infos(&infos))
+    }
+
+    /// Collects the structs and functions of the specified assemblies.
+    pub fn from_assembly_infos(infos: &[abi::AssemblyInfo<'_>]) -> Self {
+        let struct_names = infos
+            .iter()
+            .flat_map(|info| info.symbols.types())
+            .filter(|ty| ty.data.is_struct())
+            .map(|ty| (*ty.as_concrete(), ty.name().to_owned()))
+            .collect::<HashMap<_, _>>();
+
+        let mut structs = infos
+            .iter()
+            .flat_map(|info| info.symbols.types())
+            .filter_map(|ty| {
+                let definition = ty.as_struct()?;
+                Some(Struct {
+                    name: ty.name().to_owned(),
+                    memory_kind: definition.memory_kind,
+                    fields: definition
+                        .field_names()
+                        .zip(definition.field_types())
+                        .map(|(name, ty)| Field {
+                            name: name.to_owned(),
+                            ty: TypeRef::from_type_id(ty, &struct_names),
+                        })
+                        .collect(),
+                })
+            })
+            .collect::<Vec<_>>();
+        structs.sort_by(|a, b| a.name.cmp(&b.name));
+        structs.dedup_by(|a, b| a.name == b.name);
+
+        let mut functions = infos
+            .iter()
+            .flat_map(|info| info.symbols.functions())
+            .map(|function| {
+                let signature = &function.prototype.signature;
+                Function {
+                    name: function.prototype.name().to_owned(),
+                    arg_types: signature
+                        .arg_types()
+                        .iter()
+                        .map(|ty| TypeRef::from_type_id(ty, &struct_names))
+                        .collect(),
+                    return_type: TypeRef::from_type_id(&signature.return_type, &struct_names),
+                }
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        functions.dedup_by(|a, b| a.name == b.name);
+
+        Bindings { structs, functions }
+    }
+
+    /// Generates the Rust source of the bindings. The source can be included
+    /// into any module of a crate that depends on `mun_runtime`.
+    pub fn generate(&self) -> String {
+        generate::generate(self)
+    }
+}
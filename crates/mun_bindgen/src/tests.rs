@@ -0,0 +1,89 @@
+use mun_abi::StructMemoryKind;
+
+use crate::{Bindings, Field, Function, Struct, TypeRef};
+
+fn primitive(mun_name: &str) -> TypeRef {
+    TypeRef::primitive(mun_name).expect("unknown primitive type")
+}
+
+fn field(name: &str, ty: TypeRef) -> Field {
+    Field {
+        name: name.to_owned(),
+        ty,
+    }
+}
+
+fn function(name: &str, arg_types: Vec<TypeRef>, return_type: TypeRef) -> Function {
+    Function {
+        name: name.to_owned(),
+        arg_types,
+        return_type,
+    }
+}
+
+#[test]
+fn functions() {
+    let bindings = Bindings {
+        structs: Vec::new(),
+        functions: vec![
+            function(
+                "add",
+                vec![primitive("core::i32"), primitive("core::i32")],
+                primitive("core::i32"),
+            ),
+            function("tick", Vec::new(), primitive("core::empty")),
+            function(
+                "type",
+                vec![primitive("core::bool")],
+                primitive("core::f64"),
+            ),
+        ],
+    };
+    insta::assert_snapshot!(bindings.generate());
+}
+
+#[test]
+fn structs() {
+    let bindings = Bindings {
+        structs: vec![
+            Struct {
+                name: "Enemy".to_owned(),
+                memory_kind: StructMemoryKind::Gc,
+                fields: vec![
+                    field("position", TypeRef::Struct("math::Vec2".to_owned())),
+                    field(
+                        "waypoints",
+                        TypeRef::Array(Box::new(TypeRef::Struct("math::Vec2".to_owned()))),
+                    ),
+                    field("target", TypeRef::Unsupported("*const Enemy".to_owned())),
+                ],
+            },
+            Struct {
+                name: "math::Vec2".to_owned(),
+                memory_kind: StructMemoryKind::Value,
+                fields: vec![
+                    field("x", primitive("core::f32")),
+                    field("y", primitive("core::f32")),
+                ],
+            },
+        ],
+        functions: vec![
+            function(
+                "Enemy::new",
+                vec![TypeRef::Struct("math::Vec2".to_owned())],
+                TypeRef::Struct("Enemy".to_owned()),
+            ),
+            function(
+                "math::length",
+                vec![TypeRef::Struct("math::Vec2".to_owned())],
+                primitive("core::f32"),
+            ),
+            function(
+                "math::self::unsupported",
+                vec![TypeRef::Unsupported("*mut core::u8".to_owned())],
+                primitive("core::empty"),
+            ),
+        ],
+    };
+    insta::assert_snapshot!(bindings.generate());
+}
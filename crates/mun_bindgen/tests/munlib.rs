@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use mun_bindgen::Bindings;
+use mun_test::{CompileAndRunTestDriver, CompileTestDriver};
+
+// The generated bindings are checked in as they are generated
+#[allow(dead_code, clippy::needless_lifetimes)]
+#[rustfmt::skip]
+#[path = "munlib/bindings.rs"]
+mod bindings;
+
+const SOURCE: &str = r"
+    pub struct(value) Vec2 { x: f32, y: f32 }
+
+    pub struct Enemy {
+        position: Vec2,
+        health: i32,
+        waypoints: [Vec2],
+    }
+
+    pub fn new_enemy(x: f32, y: f32) -> Enemy {
+        Enemy {
+            position: Vec2 { x, y },
+            health: 100,
+            waypoints: [Vec2 { x: 0.0, y: 0.0 }, Vec2 { x, y }],
+        }
+    }
+
+    pub fn damage(enemy: Enemy, amount: i32) {
+        enemy.health -= amount;
+    }
+
+    pub fn length_squared(v: Vec2) -> f32 {
+        v.x * v.x + v.y * v.y
+    }
+";
+
+#[test]
+fn bindings_are_fresh() {
+    let driver = CompileTestDriver::from_file(SOURCE);
+
+    // Safety: We compiled the munlib ourselves, therefore loading it is safe.
+    let bindings = unsafe { Bindings::from_munlib(driver.lib_path()) }.unwrap();
+    let generated = bindings.generate();
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/munlib/bindings.rs");
+    if std::env::var_os("UPDATE_BINDINGS").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    } else {
+        assert!(
+            generated == std::fs::read_to_string(&path).unwrap(),
+            "`{}` is out of date, regenerate it by running this test with `UPDATE_BINDINGS=1`",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn bindings_invoke_functions() {
+    let driver = CompileAndRunTestDriver::new(SOURCE, |builder| builder)
+        .expect("Failed to build test driver");
+    let runtime = &driver.runtime;
+    assert_eq!(bindings::verify(runtime), Ok(()));
+
+    let mut enemy = bindings::new_enemy(runtime, 3.0, 4.0).unwrap();
+    assert_eq!(enemy.health(), 100);
+    bindings::damage(runtime, enemy.clone(), 30).unwrap();
+    assert_eq!(enemy.health(), 70);
+    enemy.set_health(5);
+    assert_eq!(enemy.health(), 5);
+
+    let position = enemy.position();
+    assert_eq!(position.x(), 3.0);
+    assert_eq!(bindings::length_squared(runtime, position).unwrap(), 25.0);
+
+    let waypoints = enemy.waypoints();
+    assert_eq!(waypoints.len(), 2);
+    let last = waypoints.iter().last().unwrap();
+    assert_eq!(last.y(), 4.0);
+}
@@ -0,0 +1,266 @@
+// This file is generated by `mun bindgen`. Do not edit it manually; regenerate it
+// when the Mun code changes instead.
+
+/// A handle to a Mun `Enemy` struct.
+///
+/// The field accessors panic if the struct no longer matches the bindings,
+/// e.g. after a hot reload.
+#[derive(Clone)]
+pub struct Enemy<'r>(pub ::mun_runtime::StructRef<'r>);
+
+impl<'r> Enemy<'r> {
+    /// The name of the struct in the Mun runtime
+    pub const NAME: &'static str = "Enemy";
+
+    /// Returns the value of the `position` field.
+    pub fn position(&self) -> Vec2<'r> {
+        self.0.get("position").unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Sets the value of the `position` field.
+    pub fn set_position(&mut self, value: Vec2<'r>) {
+        self.0.set("position", value).unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Returns the value of the `health` field.
+    pub fn health(&self) -> i32 {
+        self.0.get("health").unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Sets the value of the `health` field.
+    pub fn set_health(&mut self, value: i32) {
+        self.0.set("health", value).unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Returns the value of the `waypoints` field.
+    pub fn waypoints(&self) -> ::mun_runtime::ArrayRef<'r, Vec2<'r>> {
+        self.0.get("waypoints").unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Sets the value of the `waypoints` field.
+    pub fn set_waypoints(&mut self, value: ::mun_runtime::ArrayRef<'r, Vec2<'r>>) {
+        self.0.set("waypoints", value).unwrap_or_else(|e| panic!("{e}"));
+    }
+}
+
+impl<'r> ::mun_runtime::Marshal<'r> for Enemy<'r> {
+    type MunType = <::mun_runtime::StructRef<'r> as ::mun_runtime::Marshal<'r>>::MunType;
+
+    fn marshal_from<'rt>(value: Self::MunType, runtime: &'rt ::mun_runtime::Runtime) -> Self
+    where
+        'rt: 'r,
+    {
+        Self(::mun_runtime::Marshal::marshal_from(value, runtime))
+    }
+
+    fn marshal_into(self) -> Self::MunType {
+        ::mun_runtime::Marshal::marshal_into(self.0)
+    }
+
+    fn marshal_from_ptr<'rt>(
+        ptr: ::std::ptr::NonNull<Self::MunType>,
+        runtime: &'rt ::mun_runtime::Runtime,
+        type_info: &::mun_runtime::Type,
+    ) -> Self
+    where
+        'rt: 'r,
+    {
+        Self(::mun_runtime::Marshal::marshal_from_ptr(ptr, runtime, type_info))
+    }
+
+    fn marshal_to_ptr(
+        value: Self,
+        ptr: ::std::ptr::NonNull<Self::MunType>,
+        type_info: &::mun_runtime::Type,
+    ) {
+        ::mun_runtime::StructRef::marshal_to_ptr(value.0, ptr, type_info);
+    }
+}
+
+impl<'r> ::mun_runtime::ArgumentReflection for Enemy<'r> {
+    fn type_info(&self, runtime: &::mun_runtime::Runtime) -> ::mun_runtime::Type {
+        ::mun_runtime::ArgumentReflection::type_info(&self.0, runtime)
+    }
+}
+
+impl<'r> ::mun_runtime::ReturnTypeReflection for Enemy<'r> {
+    fn accepts_type(ty: &::mun_runtime::Type) -> bool {
+        ty.is_struct() && ty.name() == Self::NAME
+    }
+
+    fn type_hint() -> &'static str {
+        Self::NAME
+    }
+}
+
+/// A handle to a copy of a Mun `Vec2` value struct.
+///
+/// The field accessors panic if the struct no longer matches the bindings,
+/// e.g. after a hot reload.
+#[derive(Clone)]
+pub struct Vec2<'r>(pub ::mun_runtime::StructRef<'r>);
+
+impl<'r> Vec2<'r> {
+    /// The name of the struct in the Mun runtime
+    pub const NAME: &'static str = "Vec2";
+
+    /// Returns the value of the `x` field.
+    pub fn x(&self) -> f32 {
+        self.0.get("x").unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Sets the value of the `x` field.
+    pub fn set_x(&mut self, value: f32) {
+        self.0.set("x", value).unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Returns the value of the `y` field.
+    pub fn y(&self) -> f32 {
+        self.0.get("y").unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Sets the value of the `y` field.
+    pub fn set_y(&mut self, value: f32) {
+        self.0.set("y", value).unwrap_or_else(|e| panic!("{e}"));
+    }
+}
+
+impl<'r> ::mun_runtime::Marshal<'r> for Vec2<'r> {
+    type MunType = <::mun_runtime::StructRef<'r> as ::mun_runtime::Marshal<'r>>::MunType;
+
+    fn marshal_from<'rt>(value: Self::MunType, runtime: &'rt ::mun_runtime::Runtime) -> Self
+    where
+        'rt: 'r,
+    {
+        Self(::mun_runtime::Marshal::marshal_from(value, runtime))
+    }
+
+    fn marshal_into(self) -> Self::MunType {
+        ::mun_runtime::Marshal::marshal_into(self.0)
+    }
+
+    fn marshal_from_ptr<'rt>(
+        ptr: ::std::ptr::NonNull<Self::MunType>,
+        runtime: &'rt ::mun_runtime::Runtime,
+        type_info: &::mun_runtime::Type,
+    ) -> Self
+    where
+        'rt: 'r,
+    {
+        Self(::mun_runtime::Marshal::marshal_from_ptr(ptr, runtime, type_info))
+    }
+
+    fn marshal_to_ptr(
+        value: Self,
+        ptr: ::std::ptr::NonNull<Self::MunType>,
+        type_info: &::mun_runtime::Type,
+    ) {
+        ::mun_runtime::StructRef::marshal_to_ptr(value.0, ptr, type_info);
+    }
+}
+
+impl<'r> ::mun_runtime::ArgumentReflection for Vec2<'r> {
+    fn type_info(&self, runtime: &::mun_runtime::Runtime) -> ::mun_runtime::Type {
+        ::mun_runtime::ArgumentReflection::type_info(&self.0, runtime)
+    }
+}
+
+impl<'r> ::mun_runtime::ReturnTypeReflection for Vec2<'r> {
+    fn accepts_type(ty: &::mun_runtime::Type) -> bool {
+        ty.is_struct() && ty.name() == Self::NAME
+    }
+
+    fn type_hint() -> &'static str {
+        Self::NAME
+    }
+}
+
+/// Invokes the Mun function `damage`.
+pub fn damage<'r>(
+    runtime: &'r ::mun_runtime::Runtime,
+    arg0: Enemy<'r>,
+    arg1: i32,
+) -> Result<(), ::mun_runtime::InvokeErr<'static, (Enemy<'r>, i32)>> {
+    runtime.invoke("damage", (arg0, arg1))
+}
+
+/// Invokes the Mun function `length_squared`.
+pub fn length_squared<'r>(
+    runtime: &'r ::mun_runtime::Runtime,
+    arg0: Vec2<'r>,
+) -> Result<f32, ::mun_runtime::InvokeErr<'static, (Vec2<'r>,)>> {
+    runtime.invoke("length_squared", (arg0,))
+}
+
+/// Invokes the Mun function `new_enemy`.
+pub fn new_enemy<'r>(
+    runtime: &'r ::mun_runtime::Runtime,
+    arg0: f32,
+    arg1: f32,
+) -> Result<Enemy<'r>, ::mun_runtime::InvokeErr<'static, (f32, f32)>> {
+    runtime.invoke("new_enemy", (arg0, arg1))
+}
+
+/// Verifies that the structs and functions of `runtime` match these bindings.
+/// Returns a description of every mismatch.
+pub fn verify(runtime: &::mun_runtime::Runtime) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    verify_struct(runtime, "Enemy", &[("position", "Vec2"), ("health", "core::i32"), ("waypoints", "[Vec2]")], &mut errors);
+    verify_struct(runtime, "Vec2", &[("x", "core::f32"), ("y", "core::f32")], &mut errors);
+    verify_function(runtime, "damage", &["Enemy", "core::i32"], "core::empty", &mut errors);
+    verify_function(runtime, "length_squared", &["Vec2"], "core::f32", &mut errors);
+    verify_function(runtime, "new_enemy", &["core::f32", "core::f32"], "Enemy", &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn verify_struct(
+    runtime: &::mun_runtime::Runtime,
+    name: &str,
+    fields: &[(&str, &str)],
+    errors: &mut Vec<String>,
+) {
+    let Some(ty) = runtime.get_type_info_by_name(name) else {
+        errors.push(format!("struct `{name}` does not exist"));
+        return;
+    };
+    let Some(s) = ty.as_struct() else {
+        errors.push(format!("`{name}` is not a struct"));
+        return;
+    };
+    for (field_name, field_type) in fields {
+        match s.fields().find_by_name(field_name) {
+            None => errors.push(format!("struct `{name}` has no field `{field_name}`")),
+            Some(field) if field.ty().name() != *field_type => errors.push(format!(
+                "field `{name}::{field_name}` is of type `{}` instead of `{field_type}`",
+                field.ty().name()
+            )),
+            Some(_) => {}
+        }
+    }
+}
+
+fn verify_function(
+    runtime: &::mun_runtime::Runtime,
+    name: &str,
+    arg_types: &[&str],
+    return_type: &str,
+    errors: &mut Vec<String>,
+) {
+    let Some(definition) = runtime.get_function_definition(name) else {
+        errors.push(format!("function `{name}` does not exist"));
+        return;
+    };
+    let signature = &definition.prototype.signature;
+    let actual_arg_types = signature.arg_types.iter().map(::mun_runtime::Type::name).collect::<Vec<_>>();
+    let actual_return_type = signature.return_type.name();
+    if actual_arg_types != arg_types || actual_return_type != return_type {
+        errors.push(format!(
+            "function `{name}` has signature `fn({}) -> {actual_return_type}`",
+            actual_arg_types.join(", ")
+        ));
+    }
+}
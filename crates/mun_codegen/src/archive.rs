@@ -0,0 +1,242 @@
+//! Writes the static archives (`*.a` or `*.lib`) that bundle the object files
+//! of a static library.
+//!
+//! An archive consists of a global header, followed by a symbol table and the
+//! archive's members. The symbol table maps every symbol that the host links
+//! against to the member that defines it. Its layout differs between the GNU
+//! variant, which is used for ELF objects, and the BSD variant, which is used for
+//! Mach-O objects. The COFF variant that the MSVC linker expects is not
+//! supported.
+
+use std::io::{self, Write};
+
+/// The global header of an archive.
+const MAGIC: &[u8] = b"!<arch>\n";
+
+/// The size of the header that precedes every member.
+const MEMBER_HEADER_SIZE: usize = 60;
+
+/// The variant of the archive's symbol table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// A GNU archive, as expected by the GNU and LLVM linkers
+    Gnu,
+    /// A BSD archive, as expected by the Apple linker
+    Bsd,
+}
+
+/// A member of an archive, with the symbols that it defines.
+pub struct ArchiveMember<'a> {
+    /// The file name of the member, which must not exceed 15 characters
+    pub name: &'a str,
+    /// The contents of the member
+    pub data: &'a [u8],
+    /// The (mangled) names of the symbols that the member exports
+    pub symbols: &'a [String],
+}
+
+/// Writes an archive of `kind` that contains `members` to `writer`.
+pub fn write_archive<W: Write>(
+    writer: &mut W,
+    kind: ArchiveKind,
+    members: &[ArchiveMember<'_>],
+) -> io::Result<()> {
+    let symbols: Vec<(&str, usize)> = members
+        .iter()
+        .enumerate()
+        .flat_map(|(index, member)| member.symbols.iter().map(move |s| (s.as_str(), index)))
+        .collect();
+
+    // The symbol table refers to members by the offset of their headers, which
+    // depends on the size of the symbol table itself.
+    let symbol_table_size = match kind {
+        ArchiveKind::Gnu => gnu_symbol_table(&symbols, &[]).len(),
+        ArchiveKind::Bsd => bsd_symbol_table(&symbols, &[]).len(),
+    };
+    let mut offset = MAGIC.len() + MEMBER_HEADER_SIZE + padded_size(symbol_table_size, kind);
+    let member_offsets: Vec<usize> = members
+        .iter()
+        .map(|member| {
+            let member_offset = offset;
+            offset += MEMBER_HEADER_SIZE + padded_size(member.data.len(), kind);
+            member_offset
+        })
+        .collect();
+
+    writer.write_all(MAGIC)?;
+    match kind {
+        ArchiveKind::Gnu => write_member(
+            writer,
+            "/",
+            &gnu_symbol_table(&symbols, &member_offsets),
+            kind,
+        )?,
+        ArchiveKind::Bsd => write_member(
+            writer,
+            "__.SYMDEF",
+            &bsd_symbol_table(&symbols, &member_offsets),
+            kind,
+        )?,
+    }
+    for member in members {
+        let name = match kind {
+            // GNU member names are terminated by a slash, so they can contain spaces.
+            ArchiveKind::Gnu => format!("{}/", member.name),
+            ArchiveKind::Bsd => member.name.to_owned(),
+        };
+        write_member(writer, &name, member.data, kind)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the size of a member's data including its padding. Members are
+/// aligned to two bytes, but the Apple linker expects objects to be aligned to
+/// eight bytes.
+fn padded_size(size: usize, kind: ArchiveKind) -> usize {
+    let alignment = match kind {
+        ArchiveKind::Gnu => 2,
+        ArchiveKind::Bsd => 8,
+    };
+    (size + alignment - 1) / alignment * alignment
+}
+
+/// Writes the header and the padded data of a member.
+fn write_member<W: Write>(
+    writer: &mut W,
+    name: &str,
+    data: &[u8],
+    kind: ArchiveKind,
+) -> io::Result<()> {
+    assert!(name.len() <= 16, "archive member name `{name}` is too long");
+
+    // BSD archives include the padding in the size of a member, whereas GNU
+    // archives skip it implicitly.
+    let padded_size = padded_size(data.len(), kind);
+    let size = match kind {
+        ArchiveKind::Gnu => data.len(),
+        ArchiveKind::Bsd => padded_size,
+    };
+    writeln!(
+        writer,
+        "{name:<16}{mtime:<12}{uid:<6}{gid:<6}{mode:<8}{size:<10}`",
+        mtime = 0,
+        uid = 0,
+        gid = 0,
+        mode = 644,
+    )?;
+    writer.write_all(data)?;
+
+    let padding = match kind {
+        ArchiveKind::Gnu => b'\n',
+        ArchiveKind::Bsd => 0,
+    };
+    writer.write_all(&vec![padding; padded_size - data.len()])
+}
+
+/// Constructs a GNU symbol table: the number of symbols and the offset of
+/// each symbol's member as big-endian integers, followed by the
+/// null-terminated names of the symbols.
+fn gnu_symbol_table(symbols: &[(&str, usize)], member_offsets: &[usize]) -> Vec<u8> {
+    let mut table = Vec::new();
+    table.extend_from_slice(&(symbols.len() as u32).to_be_bytes());
+    for (_, member) in symbols {
+        let offset = member_offsets.get(*member).copied().unwrap_or_default();
+        table.extend_from_slice(&(offset as u32).to_be_bytes());
+    }
+    for (name, _) in symbols {
+        table.extend_from_slice(name.as_bytes());
+        table.push(0);
+    }
+    table
+}
+
+/// Constructs a BSD symbol table: the size and the contents of an array of
+/// pairs of a symbol's name offset and its member's offset, followed by the
+/// size and the contents of the string table. All integers are
+/// little-endian, as Apple platforms are.
+fn bsd_symbol_table(symbols: &[(&str, usize)], member_offsets: &[usize]) -> Vec<u8> {
+    let mut strings = Vec::new();
+    let mut ranlibs = Vec::new();
+    for (name, member) in symbols {
+        let offset = member_offsets.get(*member).copied().unwrap_or_default();
+        ranlibs.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+        ranlibs.extend_from_slice(&(offset as u32).to_le_bytes());
+        strings.extend_from_slice(name.as_bytes());
+        strings.push(0);
+    }
+
+    // Pad the string table so the members that follow remain aligned
+    while (strings.len() + ranlibs.len()) % 8 != 0 {
+        strings.push(0);
+    }
+
+    let mut table = Vec::new();
+    table.extend_from_slice(&(ranlibs.len() as u32).to_le_bytes());
+    table.extend_from_slice(&ranlibs);
+    table.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+    table.extend_from_slice(&strings);
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_archive, ArchiveKind, ArchiveMember};
+
+    fn archive(kind: ArchiveKind) -> Vec<u8> {
+        let symbols = [String::from("mun_register_foo")];
+        let mut archive = Vec::new();
+        write_archive(
+            &mut archive,
+            kind,
+            &[ArchiveMember {
+                name: "foo.o",
+                data: b"object",
+                symbols: &symbols,
+            }],
+        )
+        .unwrap();
+        archive
+    }
+
+    fn member_offset(archive: &[u8], name: &[u8]) -> usize {
+        archive
+            .windows(name.len())
+            .position(|window| window == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn gnu_archive() {
+        let archive = archive(ArchiveKind::Gnu);
+        assert!(archive.starts_with(b"!<arch>\n/               0"));
+
+        // The symbol table refers to the header of the object
+        let symbol_table = &archive[68..];
+        assert_eq!(symbol_table[..4], 1u32.to_be_bytes());
+        let offset = u32::from_be_bytes(symbol_table[4..8].try_into().unwrap()) as usize;
+        assert_eq!(offset, member_offset(&archive, b"foo.o/"));
+        assert_eq!(&symbol_table[8..25], b"mun_register_foo\0");
+
+        assert!(archive.ends_with(b"6         `\nobject"));
+        assert_eq!(archive.len() % 2, 0);
+    }
+
+    #[test]
+    fn bsd_archive() {
+        let archive = archive(ArchiveKind::Bsd);
+        assert!(archive.starts_with(b"!<arch>\n__.SYMDEF       0"));
+
+        let symbol_table = &archive[68..];
+        assert_eq!(symbol_table[..4], 8u32.to_le_bytes());
+        assert_eq!(symbol_table[4..8], 0u32.to_le_bytes());
+        let offset = u32::from_le_bytes(symbol_table[8..12].try_into().unwrap()) as usize;
+        assert_eq!(offset, member_offset(&archive, b"foo.o "));
+        assert_eq!(&symbol_table[16..33], b"mun_register_foo\0");
+
+        // Objects are aligned to eight bytes
+        let data_offset = member_offset(&archive, b"object");
+        assert_eq!(data_offset % 8, 0);
+        assert_eq!(&archive[data_offset - 2..data_offset], b"`\n");
+    }
+}
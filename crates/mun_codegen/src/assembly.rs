@@ -2,7 +2,9 @@ use std::{path::Path, sync::Arc};
 
 use anyhow::anyhow;
 use apple_codesign::{SigningSettings, UnifiedSigner};
-use inkwell::context::Context;
+use inkwell::{context::Context, targets::FileType};
+use mun_abi as abi;
+use mun_target::spec;
 use tempfile::NamedTempFile;
 
 use crate::{
@@ -36,6 +38,17 @@ impl<'db, 'ink, 'ctx> Assembly<'db, 'ink, 'ctx> {
         )
     }
 
+    /// Returns the machine code of the `Assembly`'s module as an object file
+    /// in memory.
+    pub fn into_object_bytes(self) -> Result<Vec<u8>, anyhow::Error> {
+        let obj = self
+            .code_gen
+            .target_machine
+            .write_to_memory_buffer(&self.module, FileType::Object)
+            .map_err(|e| anyhow!("{}", e))?;
+        Ok(obj.as_slice().to_vec())
+    }
+
     /// Tries to write the `Assembly`'s IR to file.
     pub fn write_ir_to_file(self, output_path: &Path) -> Result<(), anyhow::Error> {
         self.module
@@ -121,6 +134,99 @@ pub(crate) fn build_target_assembly(
     Arc::new(TargetAssembly { file })
 }
 
+/// A `StaticLibrary` is a reference to a static archive stored on disk, which
+/// contains all modules and is linked into the host.
+#[derive(Debug)]
+pub struct StaticLibrary {
+    file: NamedTempFile,
+}
+
+impl PartialEq for StaticLibrary {
+    fn eq(&self, other: &Self) -> bool {
+        self.path().eq(other.path())
+    }
+}
+
+impl Eq for StaticLibrary {}
+
+impl StaticLibrary {
+    /// Returns the file name of the static library called `library_name` on
+    /// `target`, e.g. `libfoo.a` or `foo.lib`.
+    pub fn file_name(target: &spec::Target, library_name: &str) -> String {
+        if target.options.is_like_windows {
+            format!("{library_name}.lib")
+        } else {
+            format!("lib{library_name}.a")
+        }
+    }
+
+    /// Returns the current location of the static library
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Copies the static library to the specified location
+    pub fn copy_to<P: AsRef<Path>>(&self, destination: P) -> Result<(), std::io::Error> {
+        std::fs::copy(self.path(), destination).map(|_| ())
+    }
+}
+
+/// Builds a static library that contains all modules.
+pub(crate) fn build_static_library(db: &dyn CodeGenDatabase) -> Arc<StaticLibrary> {
+    let library_name = db
+        .static_library_name()
+        .expect("the static library name must be set");
+
+    // Setup the code generation context
+    let inkwell_context = Context::create();
+    let code_gen_context = CodeGenContext::new(&inkwell_context, db);
+
+    // All modules are grouped into a single assembly
+    let module_partition = db.module_partition();
+    let (module_group_id, _) = module_partition
+        .iter()
+        .next()
+        .expect("a static library requires at least one module");
+    let assembly = build_assembly(db, &code_gen_context, module_group_id);
+
+    // Convert the assembly into an object file
+    let obj_file = assembly
+        .into_object_file()
+        .expect("unable to create object file");
+
+    // Construct a temporary file for the static library
+    let file = NamedTempFile::new().expect("could not create temp file for static library");
+
+    // Bundle the object file into a static library
+    let registration_fn_name = abi::static_registration_fn_name(&library_name);
+    obj_file
+        .into_static_library(file.path(), &[&registration_fn_name])
+        .expect("could not create static library");
+
+    Arc::new(StaticLibrary { file })
+}
+
+/// An error that prevents building a static library.
+#[derive(Debug, thiserror::Error)]
+pub enum StaticLibraryError {
+    /// The linker of the target expects an archive format that cannot be written
+    #[error("static libraries are not supported for target '{0}'")]
+    UnsupportedTarget(String),
+}
+
+/// Checks that a static library can be built for the target of the database.
+/// The MSVC linker expects COFF archives, which contain a second linker member
+/// that is not written, so MSVC targets are rejected.
+pub fn check_static_library(db: &dyn CodeGenDatabase) -> Result<(), StaticLibraryError> {
+    let target = db.target();
+    if target.options.is_like_msvc {
+        return Err(StaticLibraryError::UnsupportedTarget(
+            target.llvm_target.to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// An `AssemblyIr` is a reference to an IR file stored on disk.
 #[derive(Debug)]
 pub struct AssemblyIr {
@@ -171,3 +277,36 @@ pub(crate) fn build_assembly_ir(
 
     Arc::new(AssemblyIr { file })
 }
+
+/// An `AssemblyObject` holds the machine code of an assembly as an object
+/// file in memory, so it can be compiled into the running process by a
+/// [`crate::JitEngine`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct AssemblyObject {
+    bytes: Vec<u8>,
+}
+
+impl AssemblyObject {
+    /// Returns the contents of the object file
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Builds an object file in memory for the specified module.
+pub(crate) fn build_assembly_object(
+    db: &dyn CodeGenDatabase,
+    module_group: ModuleGroupId,
+) -> Arc<AssemblyObject> {
+    // Setup the code generation context
+    let inkwell_context = Context::create();
+    let code_gen_context = CodeGenContext::new(&inkwell_context, db);
+
+    // Build an assembly for the module
+    let assembly = build_assembly(db, &code_gen_context, module_group);
+
+    let bytes = assembly
+        .into_object_bytes()
+        .expect("unable to create object file");
+    Arc::new(AssemblyObject { bytes })
+}
@@ -52,9 +52,12 @@ impl<'db, 'ink, 'ctx, 't> AssemblyBuilder<'db, 'ink, 'ctx, 't> {
             .link_in_module(file.llvm_module.clone())
             .map_err(|e| CodeGenerationError::ModuleLinkerError(e.to_string()))?;
 
-        if self.code_gen.db.target().options.is_like_windows {
+        if self.code_gen.db.target().options.is_like_windows
+            && self.code_gen.static_library_name.is_none()
+        {
             // Add the useless `_fltused` symbol to indicate that the object file supports
-            // floating-point values. This is required for Windows.
+            // floating-point values. This is required for Windows. A static library relies
+            // on the host to define it.
             let fltused =
                 self.assembly_module
                     .add_global(self.code_gen.context.i32_type(), None, "_fltused");
@@ -84,6 +87,8 @@ impl<'db, 'ink, 'ctx, 't> AssemblyBuilder<'db, 'ink, 'ctx, 't> {
             .referenced_modules
             .union(&direct_children)
             .filter_map(|&module| self.module_group_partition.group_for_module(module))
+            // Modules within the same group do not depend on another assembly
+            .filter(|&group_id| group_id != self.module_group_id)
             .collect::<FxHashSet<_>>()
             .into_iter()
             .map(|group_id| {
@@ -107,6 +112,11 @@ impl<'db, 'ink, 'ctx, 't> AssemblyBuilder<'db, 'ink, 'ctx, 't> {
             dependencies,
         );
 
+        // Generate the registration function of a static library
+        if let Some(library_name) = &self.code_gen.static_library_name {
+            symbols::gen_static_registration_fn(&value_context, library_name);
+        }
+
         // Optimize the assembly module
         optimize_module(&self.assembly_module, self.code_gen.optimization_level);
 
@@ -1,8 +1,13 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf};
 
-use inkwell::{context::Context, module::Module, targets::TargetMachine, types::StructType};
+use inkwell::{
+    context::Context,
+    module::{FlagBehavior, Module},
+    targets::TargetMachine,
+    types::StructType,
+};
 
-use crate::{ir::ty::HirTypeCache, CodeGenDatabase};
+use crate::{db::create_target_machine, ir::ty::HirTypeCache, CodeGenDatabase};
 
 pub struct CodeGenContext<'db, 'ink> {
     /// The current LLVM context
@@ -20,20 +25,42 @@ pub struct CodeGenContext<'db, 'ink> {
     /// The optimization level
     pub optimization_level: inkwell::OptimizationLevel,
 
+    /// Whether generated code consumes the execution budget of the runtime
+    pub budget_checks: bool,
+
+    /// Whether allocations of generated code fail when they exceed the hard
+    /// heap limit of the runtime
+    pub heap_limit_checks: bool,
+
+    /// Whether DWARF debug information is emitted
+    pub debug_info: bool,
+
+    /// The directory that contains the source files, which debug information
+    /// refers to
+    pub source_directory: PathBuf,
+
+    /// The name of the static library that is generated, if any
+    pub static_library_name: Option<String>,
+
     /// The target to generate code for
-    pub target_machine: Rc<TargetMachine>,
+    pub target_machine: TargetMachine,
 }
 
 impl<'db, 'ink> CodeGenContext<'db, 'ink> {
     /// Constructs a new `CodeGenContext` from an LLVM context and a
     /// `CodeGenDatabase`.
     pub fn new(context: &'ink Context, db: &'db dyn CodeGenDatabase) -> Self {
-        let target_machine = db.target_machine().0;
+        let target_machine = create_target_machine(db);
         Self {
             context,
             rust_types: RefCell::new(HashMap::default()),
             hir_types: HirTypeCache::new(context, db.upcast(), target_machine.get_target_data()),
             optimization_level: db.optimization_level(),
+            budget_checks: db.budget_checks(),
+            heap_limit_checks: db.heap_limit_checks(),
+            debug_info: db.debug_info(),
+            source_directory: db.source_directory(),
+            static_library_name: db.static_library_name(),
             target_machine,
             db: db.upcast(),
         }
@@ -45,6 +72,30 @@ impl<'db, 'ink> CodeGenContext<'db, 'ink> {
         let module = self.context.create_module(name.as_ref());
         module.set_data_layout(&self.target_machine.get_target_data().get_data_layout());
         module.set_triple(&self.target_machine.get_triple());
+
+        // The target machine cannot be configured with an ABI, so it is specified on
+        // every module instead.
+        let abi_name = &self.db.target().options.llvm_abiname;
+        if !abi_name.is_empty() {
+            module.add_metadata_flag(
+                "target-abi",
+                FlagBehavior::Error,
+                self.context.metadata_string(abi_name),
+            );
+        }
+
         module
     }
+
+    /// Returns true if integer arithmetic should trap into the runtime when its
+    /// result overflows. This is only the case for unoptimized builds.
+    pub fn overflow_checks(&self) -> bool {
+        self.optimization_level == inkwell::OptimizationLevel::None
+    }
+
+    /// Returns true if generated code can be interrupted by the runtime, in
+    /// which case every call has to check whether the callee was interrupted.
+    pub fn interrupt_checks(&self) -> bool {
+        self.overflow_checks() || self.budget_checks || self.heap_limit_checks
+    }
 }
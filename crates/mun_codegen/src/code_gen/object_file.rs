@@ -46,4 +46,21 @@ impl ObjectFile {
 
         Ok(())
     }
+
+    /// Bundles the object file into a static library that exports
+    /// `exported_symbols`.
+    pub fn into_static_library(
+        self,
+        output_path: &Path,
+        exported_symbols: &[&str],
+    ) -> Result<(), anyhow::Error> {
+        linker::build_static_library(
+            &self.target,
+            self.obj_file.path(),
+            exported_symbols,
+            output_path,
+        )?;
+
+        Ok(())
+    }
 }
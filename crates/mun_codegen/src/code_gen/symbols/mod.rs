@@ -1,6 +1,8 @@
 use std::{collections::HashSet, convert::TryFrom, ffi::CString};
 
-use inkwell::{attributes::Attribute, module::Linkage, types::AnyType};
+use inkwell::{
+    attributes::Attribute, module::Linkage, types::AnyType, values::BasicValueEnum, AddressSpace,
+};
 use ir_type_builder::TypeIdBuilder;
 use itertools::Itertools;
 use mun_abi as abi;
@@ -226,7 +228,7 @@ fn get_function_definition_array<'ink, 'a>(
     functions
         .sorted_by_cached_key(|f| f.full_name(db))
         .map(|f| {
-            let name = f.name(db).to_string();
+            let name = f.local_name(db);
 
             // Get the function from the cloned module and modify the linkage of the
             // function.
@@ -426,9 +428,9 @@ fn gen_get_info_fn<'ink>(
     let target = db.target();
 
     // Construct the return type of the `get_info` method. Depending on the C ABI
-    // this is either the `MunAssemblyInfo` struct or void. On windows the
-    // return argument is passed back to the caller through a pointer to the
-    // return type as the first argument. e.g.: On Windows:
+    // this is either the `MunAssemblyInfo` struct or void. On Windows and
+    // WebAssembly the return argument is passed back to the caller through a
+    // pointer to the return type as the first argument. e.g.: On Windows:
     // ```c
     // void get_info(MunModuleInfo* result) {...}
     // ```
@@ -436,7 +438,8 @@ fn gen_get_info_fn<'ink>(
     // ```c
     // MunModuleInfo get_info() { ... }
     // ```
-    let get_symbols_type = if target.options.is_like_windows {
+    let return_indirectly = target.options.is_like_windows || target.options.is_like_wasm;
+    let get_symbols_type = if return_indirectly {
         Value::<'ink, fn(*mut ir::AssemblyInfo<'ink>)>::get_ir_type(context.type_context)
     } else {
         Value::<'ink, fn() -> ir::AssemblyInfo<'ink>>::get_ir_type(context.type_context)
@@ -447,7 +450,7 @@ fn gen_get_info_fn<'ink>(
             .module
             .add_function("get_info", get_symbols_type, Some(Linkage::DLLExport));
 
-    if target.options.is_like_windows {
+    if return_indirectly {
         let type_attribute = context.context.create_type_attribute(
             Attribute::get_named_enum_kind_id("sret"),
             ir::AssemblyInfo::get_ir_type(context.type_context).as_any_type_enum(),
@@ -462,7 +465,7 @@ fn gen_get_info_fn<'ink>(
 
     // Get a pointer to the IR value that will hold the return value. Again this
     // differs depending on the C ABI.
-    let result_ptr = if target.options.is_like_windows {
+    let result_ptr = if return_indirectly {
         get_symbols_fn
             .get_nth_param(0)
             .unwrap()
@@ -520,7 +523,7 @@ fn gen_get_info_fn<'ink>(
     );
 
     // Construct the return statement of the function.
-    if target.options.is_like_windows {
+    if return_indirectly {
         builder.build_return(None);
     } else {
         builder.build_return(Some(&builder.build_load(result_ptr, "")));
@@ -571,3 +574,67 @@ fn gen_get_version_fn(context: &IrValueContext<'_, '_, '_>) {
 
     builder.build_return(Some(&abi::ABI_VERSION.as_value(context).value));
 }
+
+/// Generates the registration function of a static library, which returns a
+/// pointer to the [`abi::EntryPoints`] of the assembly. All other symbols that
+/// the assembly defines are internalized, so they don't clash with the symbols
+/// of the host or of other static libraries.
+///
+/// Intrinsics are not resolved by the host's linker. Like in a munlib, they are
+/// called through the dispatch table, which the runtime fills when the
+/// assembly is registered.
+pub(crate) fn gen_static_registration_fn(context: &IrValueContext<'_, '_, '_>, library_name: &str) {
+    let module = context.module;
+    for function in module.get_functions() {
+        if function.count_basic_blocks() > 0 {
+            function.set_linkage(Linkage::Internal);
+        }
+    }
+    for global in module.get_globals() {
+        // Skip intrinsic globals such as `llvm.used`, whose linkage is fixed
+        let is_intrinsic = global.get_name().to_bytes().starts_with(b"llvm.");
+        if !global.is_declaration() && !is_intrinsic {
+            global.set_linkage(Linkage::Internal);
+        }
+    }
+
+    // The fields must be in the same order as the fields of `abi::EntryPoints`
+    let entry_points: Vec<BasicValueEnum<'_>> = [
+        abi::GET_VERSION_FN_NAME,
+        abi::GET_INFO_FN_NAME,
+        abi::SET_ALLOCATOR_HANDLE_FN_NAME,
+    ]
+    .into_iter()
+    .map(|name| {
+        module
+            .get_function(name)
+            .unwrap_or_else(|| panic!("missing entry point `{name}`"))
+            .as_global_value()
+            .as_pointer_value()
+            .into()
+    })
+    .collect();
+    let entry_points = context.context.const_struct(&entry_points, false);
+    let entry_points_global = module.add_global(entry_points.get_type(), None, "entryPoints");
+    entry_points_global.set_initializer(&entry_points);
+    entry_points_global.set_constant(true);
+    entry_points_global.set_linkage(Linkage::Private);
+
+    let entry_points_ptr_type = context.context.i8_type().ptr_type(AddressSpace::default());
+    let registration_fn = module.add_function(
+        &abi::static_registration_fn_name(library_name),
+        entry_points_ptr_type.fn_type(&[], false),
+        Some(Linkage::External),
+    );
+
+    let builder = context.context.create_builder();
+    let body_ir = context.context.append_basic_block(registration_fn, "body");
+    builder.position_at_end(body_ir);
+
+    let entry_points_ptr = builder.build_pointer_cast(
+        entry_points_global.as_pointer_value(),
+        entry_points_ptr_type,
+        "entry_points",
+    );
+    builder.build_return(Some(&entry_points_ptr));
+}
@@ -1,9 +1,13 @@
-use std::{rc::Rc, sync::Arc};
+use std::{path::PathBuf, sync::Arc};
 
-use by_address::ByAddress;
-use inkwell::targets::{CodeModel, InitializationConfig, RelocMode, Target, TargetTriple};
+use inkwell::targets::{
+    CodeModel, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+};
 
-use crate::{AssemblyIr, ModuleGroupId, ModulePartition, TargetAssembly};
+use crate::{
+    AssemblyIr, AssemblyObject, ModuleGroupId, ModuleGrouping, ModulePartition, StaticLibrary,
+    TargetAssembly,
+};
 
 /// The `CodeGenDatabase` enables caching of code generation stages.
 /// Inkwell/LLVM objects are not stored in the cache because they are not
@@ -19,34 +23,78 @@ pub trait CodeGenDatabase: mun_hir::HirDatabase + mun_db::Upcast<dyn mun_hir::Hi
     #[salsa::input]
     fn optimization_level(&self) -> inkwell::OptimizationLevel;
 
+    /// Set whether generated code should consume the execution budget provided
+    /// by the runtime
+    #[salsa::input]
+    fn budget_checks(&self) -> bool;
+
+    /// Set whether allocations of generated code should fail when they exceed
+    /// the hard heap limit of the runtime
+    #[salsa::input]
+    fn heap_limit_checks(&self) -> bool;
+
+    /// Set whether DWARF debug information should be emitted, so a debugger
+    /// can step through the generated code
+    #[salsa::input]
+    fn debug_info(&self) -> bool;
+
+    /// Set the directory that contains the source files, which debug
+    /// information refers to
+    #[salsa::input]
+    fn source_directory(&self) -> PathBuf;
+
+    /// Set how the modules of a package are grouped into assemblies
+    #[salsa::input]
+    fn module_grouping(&self) -> ModuleGrouping;
+
+    /// Set the name of the static library to build, if any. A static library
+    /// bundles all modules into a single assembly that is linked into the host,
+    /// instead of being loaded by the runtime.
+    #[salsa::input]
+    fn static_library_name(&self) -> Option<String>;
+
     /// Returns the current module partition
     #[salsa::invoke(crate::module_partition::build_partition)]
     fn module_partition(&self) -> Arc<ModulePartition>;
 
-    /// Returns the inkwell target machine that completely describes the code
-    /// generation target. All target-specific information should be
-    /// accessible through this interface.
-    fn target_machine(&self) -> ByAddress<Rc<inkwell::targets::TargetMachine>>;
-
     /// Returns a file containing the IR for the specified module.
     #[salsa::invoke(crate::assembly::build_assembly_ir)]
     fn assembly_ir(&self, module_group: ModuleGroupId) -> Arc<AssemblyIr>;
 
+    /// Returns an object file in memory for the specified module, which can be
+    /// compiled into the running process by a [`crate::JitEngine`].
+    #[salsa::invoke(crate::assembly::build_assembly_object)]
+    fn assembly_object(&self, module_group: ModuleGroupId) -> Arc<AssemblyObject>;
+
     /// Returns a fully linked shared object for the specified module.
     #[salsa::invoke(crate::assembly::build_target_assembly)]
     fn target_assembly(&self, module_group: ModuleGroupId) -> Arc<TargetAssembly>;
+
+    /// Returns a static library that contains all modules. This requires the
+    /// static library name to be set.
+    #[salsa::invoke(crate::assembly::build_static_library)]
+    fn static_library(&self) -> Arc<StaticLibrary>;
 }
 
 /// Constructs the primary interface to the complete machine description for the
 /// target machine. All target-specific information should be accessible through
 /// this interface.
-fn target_machine(db: &dyn CodeGenDatabase) -> ByAddress<Rc<inkwell::targets::TargetMachine>> {
+///
+/// The target machine is not cached by the database. A runtime that compiles
+/// its assemblies with the JIT owns a compiler driver, so the database has to be
+/// `Send`. An `Rc` can't be stored in it, and a `TargetMachine` can't be shared
+/// through an `Arc` either, because LLVM doesn't allow it to emit code from
+/// multiple threads at the same time. Instead every `CodeGenContext` creates its
+/// own, which is cheap compared to generating the code of an assembly.
+pub(crate) fn create_target_machine(db: &dyn CodeGenDatabase) -> TargetMachine {
     // Get the HIR target
     let target = db.target();
 
-    // Initialize the x86 target
+    // Initialize the supported targets
     Target::initialize_x86(&InitializationConfig::default());
     Target::initialize_aarch64(&InitializationConfig::default());
+    Target::initialize_riscv(&InitializationConfig::default());
+    Target::initialize_webassembly(&InitializationConfig::default());
 
     // Retrieve the LLVM target using the specified target.
     let target_triple = TargetTriple::create(&db.target().llvm_target);
@@ -54,7 +102,7 @@ fn target_machine(db: &dyn CodeGenDatabase) -> ByAddress<Rc<inkwell::targets::Ta
         .expect("could not find llvm target tripple for Mun target");
 
     // Construct target machine for machine code generation
-    let target_machine = llvm_target
+    llvm_target
         .create_target_machine(
             &target_triple,
             &target.options.cpu,
@@ -63,7 +111,5 @@ fn target_machine(db: &dyn CodeGenDatabase) -> ByAddress<Rc<inkwell::targets::Ta
             RelocMode::PIC,
             CodeModel::Default,
         )
-        .expect("could not create llvm target machine");
-
-    ByAddress(Rc::new(target_machine))
+        .expect("could not create llvm target machine")
 }
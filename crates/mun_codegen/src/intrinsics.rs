@@ -26,4 +26,28 @@ intrinsics! {
     ///
     /// Note that the elements in the array are left uninitialized.
     pub fn new_array(type_handle: *const ffi::c_void, length: usize, alloc_handle: *mut ffi::c_void) -> *const *mut ffi::c_void;
+
+    /// Allocates memory like `new`, unless the allocation would exceed the hard heap limit. In that
+    /// case null is returned and execution is interrupted; the calling function must return
+    /// immediately.
+    pub fn try_new(type_handle: *const ffi::c_void, alloc_handle: *mut ffi::c_void) -> *const *mut ffi::c_void;
+
+    /// Allocates memory like `new_array`, unless the allocation would exceed the hard heap limit.
+    /// In that case null is returned and execution is interrupted; the calling function must
+    /// return immediately.
+    pub fn try_new_array(type_handle: *const ffi::c_void, length: usize, alloc_handle: *mut ffi::c_void) -> *const *mut ffi::c_void;
+
+    /// Reports that an integer operation overflowed at the specified location in the source file
+    /// and interrupts execution; the calling function must return immediately. `file` is a
+    /// null-terminated string and both `line` and `column` are one-based.
+    pub fn overflow_trap(file: *const u8, line: u32, column: u32) -> ();
+
+    /// Returns a pointer to the `ExecutionState` of the current thread, which holds the execution
+    /// budget and records why execution was interrupted. The pointer is never null and remains
+    /// valid until the calling function returns.
+    pub fn execution_state() -> *mut ffi::c_void;
+
+    /// Informs the garbage collector referred to by `alloc_handle` that a reference to `object`
+    /// was stored in another object on the heap.
+    pub fn gc_write_barrier(object: *const *mut ffi::c_void, alloc_handle: *mut ffi::c_void) -> ();
 }
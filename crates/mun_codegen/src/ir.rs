@@ -10,6 +10,7 @@ use inkwell::{
 
 mod array;
 pub mod body;
+pub(crate) mod debug_info;
 #[macro_use]
 pub(crate) mod dispatch_table;
 pub mod file;
@@ -4,24 +4,33 @@ use inkwell::{
     basic_block::BasicBlock,
     builder::Builder,
     context::Context,
+    debug_info::{DILocation, DIScope},
+    intrinsics::Intrinsic,
+    module::{Linkage, Module},
+    types::IntType,
     values::{
-        AggregateValueEnum, BasicMetadataValueEnum, BasicValueEnum, CallSiteValue, FloatValue,
-        FunctionValue, GlobalValue, IntValue, PointerValue, StructValue,
+        AggregateValueEnum, BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue,
+        FloatValue, FunctionValue, GlobalValue, IntValue, PointerValue, StructValue,
+        UnnamedAddress,
     },
-    AddressSpace, FloatPredicate, IntPredicate,
+    AddressSpace, AtomicOrdering, AtomicRMWBinOp, FloatPredicate, IntPredicate,
 };
 use mun_abi as abi;
 use mun_hir::{
-    ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, HirDatabase, HirDisplay, InferenceResult,
-    Literal, LogicOp, Name, Ordering, Pat, PatId, Path, ResolveBitness, Resolver, Statement,
-    TyKind, UnaryOp, ValueNs,
+    ArithOp, BinaryOp, Body, CmpOp, ConstValue, Expr, ExprId, HirDatabase, HirDisplay,
+    InferenceResult, IntMethod, IntTy, Literal, LogicOp, Name, Ordering, OverflowBehavior, Pat,
+    PatId, Path, ResolveBitness, Resolver, Statement, Ty, TyKind, UnaryOp, ValueNs,
 };
 
 use crate::{
     intrinsics,
     ir::{
-        dispatch_table::DispatchTable, ty::HirTypeCache, type_table::TypeTable, RuntimeArrayValue,
-        RuntimeReferenceValue,
+        debug_info::DebugInfoBuilder,
+        dispatch_table::DispatchTable,
+        intrinsics::{contains_references, new_array_intrinsic, new_intrinsic},
+        ty::HirTypeCache,
+        type_table::TypeTable,
+        RuntimeArrayValue, RuntimeReferenceValue,
     },
     module_group::ModuleGroup,
     value::Global,
@@ -44,6 +53,7 @@ pub(crate) struct ExternalGlobals<'ink> {
 pub(crate) struct BodyIrGenerator<'db, 'ink, 't> {
     context: &'ink Context,
     db: &'db dyn HirDatabase,
+    module: &'t Module<'ink>,
     body: Arc<Body>,
     infer: Arc<InferenceResult>,
     builder: Builder<'ink>,
@@ -59,6 +69,12 @@ pub(crate) struct BodyIrGenerator<'db, 'ink, 't> {
     hir_function: mun_hir::Function,
     external_globals: ExternalGlobals<'ink>,
     module_group: &'t ModuleGroup,
+    overflow_checks: bool,
+    budget_checks: bool,
+    heap_limit_checks: bool,
+    execution_state: Option<PointerValue<'ink>>,
+    debug_info: Option<&'t DebugInfoBuilder<'db, 'ink, 't>>,
+    debug_scope: Option<DIScope<'ink>>,
 }
 
 impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
@@ -66,6 +82,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
     pub fn new(
         context: &'ink Context,
         db: &'db dyn HirDatabase,
+        module: &'t Module<'ink>,
         function: (mun_hir::Function, FunctionValue<'ink>),
         function_map: &'t HashMap<mun_hir::Function, FunctionValue<'ink>>,
         dispatch_table: &'t DispatchTable<'ink>,
@@ -73,6 +90,10 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         external_globals: ExternalGlobals<'ink>,
         hir_types: &'t HirTypeCache<'db, 'ink>,
         module_group: &'t ModuleGroup,
+        overflow_checks: bool,
+        budget_checks: bool,
+        heap_limit_checks: bool,
+        debug_info: Option<&'t DebugInfoBuilder<'db, 'ink, 't>>,
     ) -> Self {
         let (hir_function, ir_function) = function;
 
@@ -88,6 +109,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         BodyIrGenerator {
             context,
             db,
+            module,
             body,
             infer,
             builder,
@@ -103,16 +125,36 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             external_globals,
             hir_types,
             module_group,
+            overflow_checks,
+            budget_checks,
+            heap_limit_checks,
+            execution_state: None,
+            debug_info,
+            debug_scope: None,
         }
     }
 
     /// Generates IR for the body of the function.
     pub fn gen_fn_body(&mut self) {
-        // Iterate over all parameters and their type and store them so we can reference
-        // them later in code.
-        for (i, (pat, _ty)) in self.body.params().iter().enumerate() {
-            let body = self.body.clone(); // Avoid borrow issues
+        // Describe the function to the debugger. Everything up to the body itself is
+        // attributed to the declaration of the function.
+        if let Some(debug_info) = self.debug_info {
+            let scope = debug_info.create_function(self.hir_function, self.fn_value);
+            let position = self.hir_function.position(self.db);
+            self.builder
+                .set_current_debug_location(debug_info.location(scope, position));
+            self.debug_scope = Some(scope);
+        }
 
+        // Iterate over all parameters and their type and store them so we can reference
+        // them later in code. The `self` parameter, if any, is the first parameter.
+        let body = self.body.clone(); // Avoid borrow issues
+        let params = body
+            .self_param()
+            .into_iter()
+            .chain(body.params().iter())
+            .map(|(pat, _ty)| pat);
+        for (i, pat) in params.enumerate() {
             match &body[*pat] {
                 Pat::Bind { name } => {
                     let name = name.to_string();
@@ -120,6 +162,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                     let builder = self.new_alloca_builder();
                     let param_ptr = builder.build_alloca(param.get_type(), &name);
                     builder.build_store(param_ptr, param);
+                    self.gen_debug_variable(*pat, &name, Some(i as u32 + 1), param_ptr);
                     self.pat_to_local.insert(*pat, param_ptr);
                     self.pat_to_name.insert(*pat, name);
                 }
@@ -136,6 +179,8 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             }
         }
 
+        self.gen_budget_check();
+
         // Generate code for the body of the function
         let ret_value = self.gen_expr(self.body.body_expr());
 
@@ -202,7 +247,10 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                     if hir_struct.data(self.db.upcast()).memory_kind
                         == mun_hir::StructMemoryKind::Value
                     {
-                        self.gen_struct_alloc_on_heap(hir_struct, value.into_struct_value())
+                        let reference =
+                            self.gen_struct_alloc_on_heap(hir_struct, value.into_struct_value());
+                        self.gen_write_barrier(value, &fn_ret_type);
+                        reference
                     } else {
                         value
                     }
@@ -217,6 +265,35 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
     /// Generates IR for the specified expression. Dependending on the type of
     /// expression an IR value is returned.
     fn gen_expr(&mut self, expr: ExprId) -> Option<inkwell::values::BasicValueEnum<'ink>> {
+        let parent_debug_location = self.enter_debug_location(expr);
+        let value = self.gen_expr_kind(expr);
+        if let Some((scope, location)) = parent_debug_location {
+            self.debug_scope = Some(scope);
+            self.builder.set_current_debug_location(location);
+        }
+        value
+    }
+
+    /// Attributes the code generated for `expr` to its position in the source,
+    /// if debug information is generated. A block also opens a new scope for
+    /// the variables it declares. Returns the scope and location to restore
+    /// afterwards.
+    fn enter_debug_location(&mut self, expr: ExprId) -> Option<(DIScope<'ink>, DILocation<'ink>)> {
+        let (debug_info, scope) = (self.debug_info?, self.debug_scope?);
+        let parent_location = self.builder.get_current_debug_location()?;
+        let position = self.hir_function.expr_position(self.db, expr)?;
+        if matches!(self.body[expr], Expr::Block { .. }) {
+            let file_id = self.hir_function.file_id(self.db);
+            self.debug_scope = Some(debug_info.create_lexical_block(scope, file_id, position));
+        }
+        self.builder.set_current_debug_location(
+            debug_info.location(self.debug_scope.unwrap_or(scope), position),
+        );
+        Some((scope, parent_location))
+    }
+
+    /// Generates IR for the kind of the specified expression.
+    fn gen_expr_kind(&mut self, expr: ExprId) -> Option<inkwell::values::BasicValueEnum<'ink>> {
         let body = self.body.clone();
         match &body[expr] {
             Expr::Block {
@@ -233,35 +310,22 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             Expr::BinaryOp { lhs, rhs, op } => {
                 self.gen_binary_op(expr, *lhs, *rhs, op.expect("missing op"))
             }
-            Expr::UnaryOp { expr, op } => self.gen_unary_op(*expr, *op),
+            Expr::UnaryOp { expr: operand, op } => match self.infer.method_resolution(expr) {
+                // The operator is overloaded by a method (e.g. `fn neg(self) -> Self`)
+                Some(method) => self.gen_fn_call(expr, method, &[*operand]),
+                None => self.gen_unary_op(expr, *operand, *op),
+            },
             Expr::Call {
                 ref callee,
                 ref args,
             } => {
+                if let Some(method) = self.infer.int_method(expr) {
+                    return Some(self.gen_int_method_call(expr, method, args).into());
+                }
+
                 // Get the callable definition from the map
                 match self.infer[*callee].as_callable_def() {
-                    Some(mun_hir::CallableDef::Function(def)) => {
-                        // Get all the arguments
-                        let args: Vec<BasicMetadataValueEnum<'_>> = args
-                            .iter()
-                            .map(|expr| self.gen_expr(*expr).expect("expected a value").into())
-                            .collect();
-
-                        self.gen_call(def, &args)
-                            .try_as_basic_value()
-                            .left()
-                            // If the called function is a void function it doesn't return anything.
-                            // If this method (`gen_expr`) returns None we assume the return value
-                            // is `never`. We return a const unit struct here to ensure that at
-                            // least something is returned. This matches with the mun_hir where a
-                            // `nothing` is returned instead of a `never`.
-                            //
-                            // This unit value will also be optimized out.
-                            .or_else(|| match self.infer[expr].interned() {
-                                TyKind::Never => None,
-                                _ => Some(self.context.const_struct(&[], false).into()),
-                            })
-                    }
+                    Some(mun_hir::CallableDef::Function(def)) => self.gen_fn_call(expr, def, args),
                     Some(mun_hir::CallableDef::Struct(_)) => {
                         Some(self.gen_named_tuple_lit(expr, args))
                     }
@@ -361,10 +425,10 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         // Construct the struct literal
         let struct_ty = self.hir_types.get_struct_type(hir_struct);
         let mut value: AggregateValueEnum<'_> = struct_ty.get_undef().into();
-        for (i, arg) in args.into_iter().enumerate() {
+        for (i, arg) in args.iter().enumerate() {
             value = self
                 .builder
-                .build_insert_value(value, arg, i as u32, "init")
+                .build_insert_value(value, *arg, i as u32, "init")
                 .expect("Failed to initialize struct field.");
         }
         let struct_lit = value.into_struct_value();
@@ -373,11 +437,124 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             mun_hir::StructMemoryKind::Value => struct_lit.into(),
             mun_hir::StructMemoryKind::Gc => {
                 // TODO: Root memory in GC
-                self.gen_struct_alloc_on_heap(hir_struct, struct_lit)
+                let reference = self.gen_struct_alloc_on_heap(hir_struct, struct_lit);
+
+                // The new object is stored on the heap, so it may contain references that the
+                // garbage collector has not seen yet.
+                for (field, arg) in hir_struct.fields(self.db).into_iter().zip(args) {
+                    self.gen_write_barrier(arg, &field.ty(self.db));
+                }
+                reference
             }
         }
     }
 
+    /// Generates IR that informs the garbage collector about every reference
+    /// contained in `value` of type `ty`, which was stored in an object on the
+    /// heap. This lets an incremental collection cycle find objects that only
+    /// become reachable through an object that it already traced.
+    fn gen_write_barrier(&mut self, value: BasicValueEnum<'ink>, ty: &Ty) {
+        match ty.interned() {
+            TyKind::Struct(s)
+                if s.data(self.db.upcast()).memory_kind == mun_hir::StructMemoryKind::Value =>
+            {
+                let value = value.into_struct_value();
+                for (idx, field) in s.fields(self.db).into_iter().enumerate() {
+                    let field_ty = field.ty(self.db);
+                    if !contains_references(self.db, &field_ty) {
+                        continue;
+                    }
+
+                    let field_value = self
+                        .builder
+                        .build_extract_value(
+                            value,
+                            idx as u32,
+                            &format!("{}.{}", s.name(self.db), field.name(self.db)),
+                        )
+                        .expect("Failed to extract struct field.");
+                    self.gen_write_barrier(field_value, &field_ty);
+                }
+            }
+            TyKind::Struct(_) | TyKind::Array(_) => {
+                // The write barrier is only required while an incremental cycle is marking
+                // objects. The allocator handle points to a pointer to the flag that indicates
+                // this.
+                let allocator_handle = self.get_allocator_handle_ptr();
+                let marking_ptr_ptr = self.builder.build_bitcast(
+                    allocator_handle,
+                    self.context
+                        .i8_type()
+                        .ptr_type(AddressSpace::default())
+                        .ptr_type(AddressSpace::default()),
+                    "marking_ptr_ptr",
+                );
+                let marking_ptr = self
+                    .builder
+                    .build_load(marking_ptr_ptr.into_pointer_value(), "marking_ptr")
+                    .into_pointer_value();
+                let marking = self.builder.build_load(marking_ptr, "marking");
+                let load = marking
+                    .as_instruction_value()
+                    .expect("a load is an instruction");
+                load.set_atomic_ordering(AtomicOrdering::Monotonic)
+                    .expect("could not make the load atomic");
+                let is_marking = self.builder.build_int_compare(
+                    IntPredicate::NE,
+                    marking.into_int_value(),
+                    self.context.i8_type().const_zero(),
+                    "is_marking",
+                );
+
+                let barrier_block = self
+                    .context
+                    .append_basic_block(self.fn_value, "write_barrier");
+                let continue_block = self
+                    .context
+                    .append_basic_block(self.fn_value, "after_write_barrier");
+                self.builder
+                    .build_conditional_branch(is_marking, barrier_block, continue_block);
+
+                self.builder.position_at_end(barrier_block);
+                let write_barrier = self.dispatch_table.gen_intrinsic_lookup(
+                    self.external_globals.dispatch_table,
+                    &self.builder,
+                    self.module,
+                    &intrinsics::gc_write_barrier,
+                );
+
+                // The intrinsic is agnostic of the type of the object
+                let object = self.builder.build_bitcast(
+                    value,
+                    self.context
+                        .i8_type()
+                        .ptr_type(AddressSpace::default())
+                        .ptr_type(AddressSpace::default()),
+                    "object",
+                );
+
+                self.builder.build_call(
+                    write_barrier,
+                    &[object.into(), allocator_handle.into()],
+                    "",
+                );
+                self.builder.build_unconditional_branch(continue_block);
+
+                self.builder.position_at_end(continue_block);
+            }
+            _ => {}
+        }
+    }
+
+    /// Generates a write barrier for `value` that was assigned to the place
+    /// `lhs_expr`, unless that place is a local variable.
+    fn gen_assignment_write_barrier(&mut self, lhs_expr: ExprId, value: BasicValueEnum<'ink>) {
+        if !matches!(self.body[lhs_expr], Expr::Path(_)) {
+            let ty = self.infer[lhs_expr].clone();
+            self.gen_write_barrier(value, &ty);
+        }
+    }
+
     fn gen_struct_alloc_on_heap(
         &mut self,
         hir_struct: mun_hir::Struct,
@@ -387,7 +564,8 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         let new_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
             self.external_globals.dispatch_table,
             &self.builder,
-            &intrinsics::new,
+            self.module,
+            new_intrinsic(self.heap_limit_checks),
         );
 
         let type_info_ptr = self.type_table.gen_type_info_lookup(
@@ -419,6 +597,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             .left()
             .unwrap()
             .into_pointer_value();
+        self.gen_allocation_check(untyped_reference);
 
         // Cast the object pointer to the struct type
         let typed_reference = self
@@ -509,6 +688,34 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         }
     }
 
+    /// Describes the variable bound by `pat`, which is stored at `storage`, if
+    /// debug information is generated. Parameters are numbered by `arg_no`,
+    /// starting at 1.
+    fn gen_debug_variable(
+        &self,
+        pat: PatId,
+        name: &str,
+        arg_no: Option<u32>,
+        storage: PointerValue<'ink>,
+    ) {
+        let (Some(debug_info), Some(scope)) = (self.debug_info, self.debug_scope) else {
+            return;
+        };
+        let position = self
+            .hir_function
+            .pat_position(self.db, pat)
+            .unwrap_or_else(|| self.hir_function.position(self.db));
+        let location = debug_info.location(scope, position);
+        let file_id = self.hir_function.file_id(self.db);
+        let ty = &self.infer[pat];
+        match arg_no {
+            Some(arg_no) => {
+                debug_info.declare_parameter(file_id, name, arg_no, ty, storage, location);
+            }
+            None => debug_info.declare_local(file_id, name, ty, storage, location),
+        }
+    }
+
     /// Constructs a builder that should be used to emit an `alloca`
     /// instruction. These instructions should be at the start of the IR.
     fn new_alloca_builder(&self) -> Builder<'ink> {
@@ -548,6 +755,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                     .get_basic_type(&pat_ty)
                     .expect("expected basic type");
                 let ptr = builder.build_alloca(ty, &name.to_string());
+                self.gen_debug_variable(pat, &name.to_string(), None, ptr);
                 self.pat_to_local.insert(pat, ptr);
                 self.pat_to_name.insert(pat, name.to_string());
                 if !(pat_ty.is_empty() || pat_ty.is_never()) {
@@ -586,10 +794,65 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                 }
             }
             ValueNs::StructId(_) => self.gen_unit_struct_lit(expr),
+            ValueNs::ConstId(id) => match mun_hir::Const::from(id).eval(self.db) {
+                Ok(value) => self.gen_const_value(value, expr),
+                // Constants that cannot be evaluated are reported as diagnostics of the
+                // constant, and code is never generated for a package with diagnostics.
+                Err(err) => unreachable!("constant could not be evaluated: {err:?}"),
+            },
             ValueNs::FunctionId(_) => panic!("unable to generate path expression from a function"),
         }
     }
 
+    /// Generates an IR integer constant from the bit pattern of a value. Signed
+    /// values are passed as their two's complement.
+    fn gen_const_int(&self, int_ty: IntTy, value: u128) -> BasicValueEnum<'ink> {
+        let context = self.context;
+        let value = match int_ty.resolve(&self.db.target_data_layout()).bitness {
+            mun_hir::IntBitness::X8 => context.i8_type().const_int(value as u64, false),
+            mun_hir::IntBitness::X16 => context.i16_type().const_int(value as u64, false),
+            mun_hir::IntBitness::X32 => context.i32_type().const_int(value as u64, false),
+            mun_hir::IntBitness::X64 => context.i64_type().const_int(value as u64, false),
+            mun_hir::IntBitness::X128 => context
+                .i128_type()
+                .const_int_arbitrary_precision(&[value as u64, (value >> 64) as u64]),
+            mun_hir::IntBitness::Xsize => unreachable!("unresolved bitness in code generation"),
+        };
+        value.into()
+    }
+
+    /// Generates an IR constant from the compile-time value of a constant.
+    /// Constants are always inlined at the location where they are used.
+    fn gen_const_value(&self, value: ConstValue, expr: ExprId) -> BasicValueEnum<'ink> {
+        let ty = &self.infer[expr];
+        let context = self.context;
+        match (value, ty.interned()) {
+            (ConstValue::Int(v), TyKind::Int(int_ty)) => self.gen_const_int(*int_ty, v as u128),
+            (ConstValue::UInt(v), TyKind::Int(int_ty)) => self.gen_const_int(*int_ty, v),
+            (ConstValue::Float(v), TyKind::Float(float_ty)) => {
+                let value = match float_ty.bitness.resolve(&self.db.target_data_layout()) {
+                    mun_hir::FloatBitness::X32 => context.f32_type().const_float(v),
+                    mun_hir::FloatBitness::X64 => context.f64_type().const_float(v),
+                };
+                value.into()
+            }
+            (ConstValue::Bool(v), TyKind::Bool) => {
+                let ty = context.bool_type();
+                if v {
+                    ty.const_all_ones().into()
+                } else {
+                    ty.const_zero().into()
+                }
+            }
+            // A value only differs from its type if the constant has a type error, which is
+            // reported as a diagnostic.
+            _ => unreachable!(
+                "constant value `{value}` does not match its type `{}`",
+                ty.display(self.db)
+            ),
+        }
+    }
+
     /// Given an expression and its value optionally dereference the value to
     /// get to the actual value. This is useful if we need to do an
     /// indirection to get to the actual value.
@@ -624,7 +887,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                 .pat_to_local
                 .get(&pat)
                 .expect("unresolved local binding"),
-            ValueNs::FunctionId(_) | ValueNs::StructId(_) => {
+            ValueNs::FunctionId(_) | ValueNs::StructId(_) | ValueNs::ConstId(_) => {
                 panic!("no support for module definitions")
             }
         }
@@ -633,23 +896,24 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
     /// Generates IR to calculate a binary operation between two expressions.
     fn gen_binary_op(
         &mut self,
-        _tgt_expr: ExprId,
+        tgt_expr: ExprId,
         lhs: ExprId,
         rhs: ExprId,
         op: BinaryOp,
     ) -> Option<BasicValueEnum<'ink>> {
+        if let BinaryOp::Assignment { op } = op {
+            return self.gen_assignment(tgt_expr, lhs, rhs, op);
+        }
+
+        if let Some(method) = self.infer.method_resolution(tgt_expr) {
+            return self.gen_overloaded_binary_op(tgt_expr, method, lhs, rhs, op);
+        }
+
         let lhs_type = self.infer[lhs].clone();
         match lhs_type.interned() {
             TyKind::Bool => self.gen_binary_op_bool(lhs, rhs, op),
             TyKind::Float(_) => self.gen_binary_op_float(lhs, rhs, op),
-            TyKind::Int(ty) => self.gen_binary_op_int(lhs, rhs, op, ty.signedness),
-            TyKind::Struct(s) => {
-                if s.data(self.db.upcast()).memory_kind == mun_hir::StructMemoryKind::Value {
-                    self.gen_binary_op_value_struct(lhs, rhs, op)
-                } else {
-                    self.gen_binary_op_heap_struct(lhs, rhs, op)
-                }
-            }
+            TyKind::Int(ty) => self.gen_binary_op_int(tgt_expr, lhs, rhs, op, ty.signedness),
             _ => {
                 let rhs_type = self.infer[rhs].clone();
                 unimplemented!(
@@ -661,12 +925,110 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         }
     }
 
+    /// Generates IR to calculate a binary operation that is overloaded by a
+    /// method of the type on the left hand side, e.g. `fn add(self, rhs: Self)
+    /// -> Self`.
+    fn gen_overloaded_binary_op(
+        &mut self,
+        tgt_expr: ExprId,
+        method: mun_hir::Function,
+        lhs: ExprId,
+        rhs: ExprId,
+        op: BinaryOp,
+    ) -> Option<BasicValueEnum<'ink>> {
+        let value = self.gen_fn_call(tgt_expr, method, &[lhs, rhs])?;
+        match op {
+            // `a != b` is implemented as `!a.eq(b)`
+            BinaryOp::CmpOp(CmpOp::Eq { negated: true }) => {
+                Some(self.builder.build_not(value.into_int_value(), "not").into())
+            }
+            _ => Some(value),
+        }
+    }
+
+    /// Generates IR for an assignment (e.g. `a = b`) or a compound assignment
+    /// (e.g. `a += b`). The place of the left hand side is computed only once,
+    /// so side effects in it (e.g. the call in `a[f()] += b`) happen once.
+    fn gen_assignment(
+        &mut self,
+        tgt_expr: ExprId,
+        lhs_expr: ExprId,
+        rhs_expr: ExprId,
+        op: Option<ArithOp>,
+    ) -> Option<BasicValueEnum<'ink>> {
+        let value = match op {
+            None => {
+                let value = self.gen_expr(rhs_expr)?;
+                let place = self.gen_place_expr(lhs_expr)?;
+                self.builder.build_store(place, value);
+                value
+            }
+            Some(op) => {
+                let place = self.gen_place_expr(lhs_expr)?;
+                let lhs = self.builder.build_load(place, "lhs");
+                let rhs = self.gen_expr(rhs_expr)?;
+                let value = if let Some(method) = self.infer.method_resolution(tgt_expr) {
+                    self.gen_call(method, &[lhs.into(), rhs.into()])
+                        .try_as_basic_value()
+                        .left()
+                        .expect("operator methods return a value")
+                } else {
+                    self.gen_compound_assignment_op(tgt_expr, lhs_expr, lhs, rhs, op)
+                };
+                self.builder.build_store(place, value);
+                value
+            }
+        };
+        self.gen_assignment_write_barrier(lhs_expr, value);
+        Some(self.gen_empty())
+    }
+
+    /// Generates IR to calculate the new value of the left hand side of a
+    /// compound assignment of primitive values (e.g. `a += b`).
+    fn gen_compound_assignment_op(
+        &mut self,
+        tgt_expr: ExprId,
+        lhs_expr: ExprId,
+        lhs: BasicValueEnum<'ink>,
+        rhs: BasicValueEnum<'ink>,
+        op: ArithOp,
+    ) -> BasicValueEnum<'ink> {
+        let lhs_type = self.infer[lhs_expr].clone();
+        match lhs_type.interned() {
+            TyKind::Bool => self
+                .gen_arith_bin_op_bool(lhs.into_int_value(), rhs.into_int_value(), op)
+                .into(),
+            TyKind::Float(_) => self
+                .gen_arith_bin_op_float(lhs.into_float_value(), rhs.into_float_value(), op)
+                .into(),
+            TyKind::Int(ty) => self
+                .gen_arith_bin_op_int(
+                    tgt_expr,
+                    lhs.into_int_value(),
+                    rhs.into_int_value(),
+                    op,
+                    ty.signedness,
+                )
+                .into(),
+            _ => unimplemented!(
+                "Assignment with {:?} operator is not implemented for {}",
+                op,
+                lhs_type.display(self.db)
+            ),
+        }
+    }
+
     /// Generates IR to calculate a unary operation on an expression.
-    fn gen_unary_op(&mut self, expr: ExprId, op: UnaryOp) -> Option<BasicValueEnum<'ink>> {
+    fn gen_unary_op(
+        &mut self,
+        tgt_expr: ExprId,
+        expr: ExprId,
+        op: UnaryOp,
+    ) -> Option<BasicValueEnum<'ink>> {
         let ty = &self.infer[expr];
         match ty.interned() {
             TyKind::Float(_) => self.gen_unary_op_float(expr, op),
-            &TyKind::Int(int_ty) => self.gen_unary_op_int(expr, op, int_ty.signedness),
+            &TyKind::Int(int_ty) => self.gen_unary_op_int(tgt_expr, expr, op, int_ty.signedness),
             TyKind::Bool => self.gen_unary_op_bool(expr, op),
             _ => unimplemented!("unimplemented operation op{0}", ty.display(self.db)),
         }
@@ -688,6 +1050,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
     /// Generates IR to calculate a unary operation on an integer value.
     fn gen_unary_op_int(
         &mut self,
+        tgt_expr: ExprId,
         expr: ExprId,
         op: UnaryOp,
         signedness: mun_hir::Signedness,
@@ -698,13 +1061,18 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             .expect("no value")
             .into_int_value();
         match op {
-            UnaryOp::Neg => {
-                if signedness == mun_hir::Signedness::Signed {
-                    Some(self.builder.build_int_neg(value, "neg").into())
-                } else {
-                    unimplemented!("Operator {:?} is not implemented for unsigned integer", op)
-                }
+            UnaryOp::Neg if signedness == mun_hir::Signedness::Unsigned => {
+                unimplemented!("Operator {:?} is not implemented for unsigned integer", op)
             }
+            UnaryOp::Neg if self.overflow_checks => {
+                // Negating the minimum value of a signed integer overflows
+                let zero = value.get_type().const_zero();
+                Some(
+                    self.gen_trapping_int_op(tgt_expr, zero, value, ArithOp::Subtract, signedness)
+                        .into(),
+                )
+            }
+            UnaryOp::Neg => Some(self.builder.build_int_neg(value, "neg").into()),
             UnaryOp::Not => Some(self.builder.build_not(value, "not").into()),
             //_ => unimplemented!("Operator {:?} is not implemented for integer", op),
         }
@@ -740,15 +1108,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             .into_int_value();
         match op {
             BinaryOp::ArithOp(op) => Some(self.gen_arith_bin_op_bool(lhs, rhs, op).into()),
-            BinaryOp::Assignment { op } => {
-                let rhs = match op {
-                    Some(op) => self.gen_arith_bin_op_bool(lhs, rhs, op),
-                    None => rhs,
-                };
-                let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
-                Some(self.gen_empty())
-            }
+            BinaryOp::Assignment { .. } => unreachable!("assignments are generated separately"),
             BinaryOp::LogicOp(op) => Some(self.gen_logic_bin_op(lhs, rhs, op).into()),
             BinaryOp::CmpOp(op) => Some(
                 self.gen_cmp_bin_op_int(lhs, rhs, op, mun_hir::Signedness::Unsigned)
@@ -804,15 +1164,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                         .into(),
                 )
             }
-            BinaryOp::Assignment { op } => {
-                let rhs = match op {
-                    Some(op) => self.gen_arith_bin_op_float(lhs, rhs, op),
-                    None => rhs,
-                };
-                let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
-                Some(self.gen_empty())
-            }
+            BinaryOp::Assignment { .. } => unreachable!("assignments are generated separately"),
             BinaryOp::LogicOp(_) => {
                 unimplemented!("Operator {:?} is not implemented for float", op)
             }
@@ -822,6 +1174,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
     /// Generates IR to calculate a binary operation between two integer values.
     fn gen_binary_op_int(
         &mut self,
+        tgt_expr: ExprId,
         lhs_expr: ExprId,
         rhs_expr: ExprId,
         op: BinaryOp,
@@ -838,83 +1191,18 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             .expect("no rhs value")
             .into_int_value();
         match op {
-            BinaryOp::ArithOp(op) => {
-                Some(self.gen_arith_bin_op_int(lhs, rhs, op, signedness).into())
-            }
+            BinaryOp::ArithOp(op) => Some(
+                self.gen_arith_bin_op_int(tgt_expr, lhs, rhs, op, signedness)
+                    .into(),
+            ),
             BinaryOp::CmpOp(op) => Some(self.gen_cmp_bin_op_int(lhs, rhs, op, signedness).into()),
-            BinaryOp::Assignment { op } => {
-                let rhs = match op {
-                    Some(op) => self.gen_arith_bin_op_int(lhs, rhs, op, signedness),
-                    None => rhs,
-                };
-                let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
-                Some(self.gen_empty())
-            }
+            BinaryOp::Assignment { .. } => unreachable!("assignments are generated separately"),
             BinaryOp::LogicOp(_) => {
                 unreachable!("Operator {:?} is not implemented for integer", op)
             }
         }
     }
 
-    /// Generates IR to calculate a binary operation between two heap struct
-    /// values (e.g. a Mun `struct(gc)`).
-    fn gen_binary_op_heap_struct(
-        &mut self,
-        lhs_expr: ExprId,
-        rhs_expr: ExprId,
-        op: BinaryOp,
-    ) -> Option<BasicValueEnum<'ink>> {
-        let rhs = self
-            .gen_expr(rhs_expr)
-            .expect("no rhs value")
-            .into_pointer_value();
-        match op {
-            BinaryOp::Assignment { op } => {
-                let rhs = match op {
-                    Some(op) => unimplemented!(
-                        "Assignment with {:?} operator is not implemented for struct",
-                        op
-                    ),
-                    None => rhs,
-                };
-                let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
-                Some(self.gen_empty())
-            }
-            _ => unimplemented!("Operator {:?} is not implemented for struct", op),
-        }
-    }
-
-    /// Generates IR to calculate a binary operation between two value struct
-    /// values, denoted in Mun as `struct(value)`.
-    fn gen_binary_op_value_struct(
-        &mut self,
-        lhs_expr: ExprId,
-        rhs_expr: ExprId,
-        op: BinaryOp,
-    ) -> Option<BasicValueEnum<'ink>> {
-        let rhs = self
-            .gen_expr(rhs_expr)
-            .expect("no rhs value")
-            .into_struct_value();
-        match op {
-            BinaryOp::Assignment { op } => {
-                let rhs = match op {
-                    Some(op) => unimplemented!(
-                        "Assignment with {:?} operator is not implemented for struct",
-                        op
-                    ),
-                    None => rhs,
-                };
-                let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
-                Some(self.gen_empty())
-            }
-            _ => unimplemented!("Operator {:?} is not implemented for struct", op),
-        }
-    }
-
     fn gen_arith_bin_op_bool(
         &mut self,
         lhs: IntValue<'ink>,
@@ -987,7 +1275,71 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         self.builder.build_int_compare(predicate, lhs, rhs, name)
     }
 
+    /// Generates IR for an arithmetic operation between two integer values. If
+    /// overflow checks are enabled, additions, subtractions, multiplications
+    /// and signed divisions trap into the runtime when their result overflows.
     fn gen_arith_bin_op_int(
+        &mut self,
+        tgt_expr: ExprId,
+        lhs: IntValue<'ink>,
+        rhs: IntValue<'ink>,
+        op: ArithOp,
+        signedness: mun_hir::Signedness,
+    ) -> IntValue<'ink> {
+        match op {
+            ArithOp::Add | ArithOp::Subtract | ArithOp::Multiply if self.overflow_checks => {
+                self.gen_trapping_int_op(tgt_expr, lhs, rhs, op, signedness)
+            }
+            ArithOp::Divide | ArithOp::Remainder if signedness.is_signed() => {
+                self.gen_signed_div_int(tgt_expr, lhs, rhs, op)
+            }
+            _ => self.gen_wrapping_arith_bin_op_int(lhs, rhs, op, signedness),
+        }
+    }
+
+    /// Generates IR for a signed division or remainder. Dividing the minimum
+    /// value of the type by `-1` overflows, which traps into the runtime if
+    /// overflow checks are enabled. Otherwise the result wraps around, like
+    /// `wrapping_div` and `wrapping_rem`; LLVM leaves it undefined.
+    fn gen_signed_div_int(
+        &mut self,
+        tgt_expr: ExprId,
+        lhs: IntValue<'ink>,
+        rhs: IntValue<'ink>,
+        op: ArithOp,
+    ) -> IntValue<'ink> {
+        let ty = lhs.get_type();
+        let bits = ty.get_bit_width();
+        let mut min_words = vec![0u64; ((bits + 63) / 64) as usize];
+        min_words[((bits - 1) / 64) as usize] = 1 << ((bits - 1) % 64);
+        let min = ty.const_int_arbitrary_precision(&min_words);
+
+        let is_min = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, lhs, min, "is_min");
+        let is_minus_one = self.builder.build_int_compare(
+            IntPredicate::EQ,
+            rhs,
+            ty.const_all_ones(),
+            "is_minus_one",
+        );
+        let overflowed = self.builder.build_and(is_min, is_minus_one, "overflowed");
+
+        let rhs = if self.overflow_checks {
+            self.gen_overflow_trap(tgt_expr, overflowed);
+            rhs
+        } else {
+            // Dividing by one instead results in the wrapped values, `MIN` and `0`
+            self.builder
+                .build_select(overflowed, ty.const_int(1, false), rhs, "rhs")
+                .into_int_value()
+        };
+        self.gen_wrapping_arith_bin_op_int(lhs, rhs, op, mun_hir::Signedness::Signed)
+    }
+
+    /// Generates IR for an arithmetic operation between two integer values
+    /// without any overflow checks; results that overflow wrap around.
+    fn gen_wrapping_arith_bin_op_int(
         &mut self,
         lhs: IntValue<'ink>,
         rhs: IntValue<'ink>,
@@ -1021,6 +1373,227 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         }
     }
 
+    /// Generates IR for a call to a built-in integer method, e.g.
+    /// `i32::saturating_add(a, b)`.
+    fn gen_int_method_call(
+        &mut self,
+        expr: ExprId,
+        method: IntMethod,
+        args: &[ExprId],
+    ) -> IntValue<'ink> {
+        let signedness = match self.infer[expr].interned() {
+            TyKind::Int(ty) => ty.signedness,
+            _ => unreachable!("built-in integer methods always return an integer"),
+        };
+        let [lhs, rhs] = [args[0], args[1]].map(|arg| {
+            self.gen_expr(arg)
+                .map(|value| self.opt_deref_value(arg, value))
+                .expect("no argument value")
+                .into_int_value()
+        });
+        match method.overflow {
+            OverflowBehavior::Wrapping => {
+                self.gen_wrapping_arith_bin_op_int(lhs, rhs, method.op, signedness)
+            }
+            OverflowBehavior::Saturating => {
+                self.gen_saturating_int_op(lhs, rhs, method.op, signedness)
+            }
+            OverflowBehavior::Strict => {
+                self.gen_trapping_int_op(expr, lhs, rhs, method.op, signedness)
+            }
+        }
+    }
+
+    /// Generates IR for an addition, subtraction or multiplication that traps
+    /// into the runtime, reporting the location of `tgt_expr`, if its result
+    /// overflows.
+    fn gen_trapping_int_op(
+        &mut self,
+        tgt_expr: ExprId,
+        lhs: IntValue<'ink>,
+        rhs: IntValue<'ink>,
+        op: ArithOp,
+        signedness: mun_hir::Signedness,
+    ) -> IntValue<'ink> {
+        let (value, overflowed) = self.gen_int_op_with_overflow(lhs, rhs, op, signedness);
+        self.gen_overflow_trap(tgt_expr, overflowed);
+        value
+    }
+
+    /// Generates IR that traps into the runtime, reporting the location of
+    /// `tgt_expr`, if `overflowed` is true. The runtime interrupts execution,
+    /// so the function returns immediately after the trap.
+    fn gen_overflow_trap(&mut self, tgt_expr: ExprId, overflowed: IntValue<'ink>) {
+        let trap_block = self.context.append_basic_block(self.fn_value, "overflow");
+        let continue_block = self
+            .context
+            .append_basic_block(self.fn_value, "no_overflow");
+        self.builder
+            .build_conditional_branch(overflowed, trap_block, continue_block);
+
+        self.builder.position_at_end(trap_block);
+        let (line, column) = self
+            .hir_function
+            .expr_position(self.db, tgt_expr)
+            .map_or((0, 0), |position| {
+                (position.line + 1, position.col_utf16 + 1)
+            });
+        let file = self.gen_source_file_path();
+        let overflow_trap = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            self.module,
+            &intrinsics::overflow_trap,
+        );
+        let i32_type = self.context.i32_type();
+        self.builder.build_call(
+            overflow_trap,
+            &[
+                file.into(),
+                i32_type.const_int(line.into(), false).into(),
+                i32_type.const_int(column.into(), false).into(),
+            ],
+            "",
+        );
+        self.gen_interrupted_return();
+
+        self.builder.position_at_end(continue_block);
+    }
+
+    /// Generates IR for an addition, subtraction or multiplication whose
+    /// result is clamped to the bounds of the integer type.
+    fn gen_saturating_int_op(
+        &mut self,
+        lhs: IntValue<'ink>,
+        rhs: IntValue<'ink>,
+        op: ArithOp,
+        signedness: mun_hir::Signedness,
+    ) -> IntValue<'ink> {
+        let (name, intrinsic) = match (op, signedness) {
+            (ArithOp::Add, mun_hir::Signedness::Signed) => ("saturating_add", "llvm.sadd.sat"),
+            (ArithOp::Add, mun_hir::Signedness::Unsigned) => ("saturating_add", "llvm.uadd.sat"),
+            (ArithOp::Subtract, mun_hir::Signedness::Signed) => ("saturating_sub", "llvm.ssub.sat"),
+            (ArithOp::Subtract, mun_hir::Signedness::Unsigned) => {
+                ("saturating_sub", "llvm.usub.sat")
+            }
+            (ArithOp::Multiply, _) => {
+                // LLVM has no saturating multiplication for integers, so select the bound
+                // manually when the multiplication overflows.
+                let (value, overflowed) = self.gen_int_op_with_overflow(lhs, rhs, op, signedness);
+                let ty = lhs.get_type();
+                let bound = match signedness {
+                    mun_hir::Signedness::Unsigned => ty.const_all_ones(),
+                    mun_hir::Signedness::Signed => {
+                        // The result is negative if exactly one of the operands is negative
+                        let sign = self.builder.build_xor(lhs, rhs, "sign");
+                        let is_negative = self.builder.build_int_compare(
+                            IntPredicate::SLT,
+                            sign,
+                            ty.const_zero(),
+                            "is_negative",
+                        );
+                        let max = ty.const_all_ones().const_rshr(ty.const_int(1, false));
+                        self.builder
+                            .build_select(is_negative, max.const_not(), max, "bound")
+                            .into_int_value()
+                    }
+                };
+                return self
+                    .builder
+                    .build_select(overflowed, bound, value, "saturating_mul")
+                    .into_int_value();
+            }
+            _ => unreachable!("operator {op:?} has no saturating variant"),
+        };
+
+        let function = self.llvm_int_intrinsic(intrinsic, lhs.get_type());
+        self.builder
+            .build_call(function, &[lhs.into(), rhs.into()], name)
+            .try_as_basic_value()
+            .left()
+            .expect("saturating intrinsics always return a value")
+            .into_int_value()
+    }
+
+    /// Generates a call to one of LLVM's `*.with.overflow` intrinsics. Returns
+    /// the wrapped result of the operation and a flag that indicates whether
+    /// the operation overflowed.
+    fn gen_int_op_with_overflow(
+        &mut self,
+        lhs: IntValue<'ink>,
+        rhs: IntValue<'ink>,
+        op: ArithOp,
+        signedness: mun_hir::Signedness,
+    ) -> (IntValue<'ink>, IntValue<'ink>) {
+        let (name, intrinsic) = match (op, signedness) {
+            (ArithOp::Add, mun_hir::Signedness::Signed) => ("add", "llvm.sadd.with.overflow"),
+            (ArithOp::Add, mun_hir::Signedness::Unsigned) => ("add", "llvm.uadd.with.overflow"),
+            (ArithOp::Subtract, mun_hir::Signedness::Signed) => ("sub", "llvm.ssub.with.overflow"),
+            (ArithOp::Subtract, mun_hir::Signedness::Unsigned) => {
+                ("sub", "llvm.usub.with.overflow")
+            }
+            (ArithOp::Multiply, mun_hir::Signedness::Signed) => ("mul", "llvm.smul.with.overflow"),
+            (ArithOp::Multiply, mun_hir::Signedness::Unsigned) => {
+                ("mul", "llvm.umul.with.overflow")
+            }
+            _ => unreachable!("operator {op:?} cannot be checked for overflow"),
+        };
+
+        let function = self.llvm_int_intrinsic(intrinsic, lhs.get_type());
+        let result = self
+            .builder
+            .build_call(
+                function,
+                &[lhs.into(), rhs.into()],
+                &format!("{name}_with_overflow"),
+            )
+            .try_as_basic_value()
+            .left()
+            .expect("overflow intrinsics always return a value")
+            .into_struct_value();
+        let value = self
+            .builder
+            .build_extract_value(result, 0, name)
+            .expect("missing result")
+            .into_int_value();
+        let overflowed = self
+            .builder
+            .build_extract_value(result, 1, &format!("{name}_overflowed"))
+            .expect("missing overflow flag")
+            .into_int_value();
+        (value, overflowed)
+    }
+
+    /// Returns the declaration of the overloaded LLVM intrinsic with the
+    /// specified name for an integer type, e.g. `llvm.sadd.with.overflow.i32`.
+    fn llvm_int_intrinsic(&self, name: &str, ty: IntType<'ink>) -> FunctionValue<'ink> {
+        Intrinsic::find(name)
+            .and_then(|intrinsic| intrinsic.get_declaration(self.module, &[ty.into()]))
+            .unwrap_or_else(|| panic!("unknown LLVM intrinsic '{name}'"))
+    }
+
+    /// Returns a pointer to a null-terminated string that contains the path of
+    /// the function's source file, relative to its source root.
+    fn gen_source_file_path(&self) -> PointerValue<'ink> {
+        let path = self
+            .db
+            .file_relative_path(self.hir_function.file_id(self.db))
+            .to_string();
+        let global = self.module.get_global(&path).unwrap_or_else(|| {
+            let value = self.context.const_string(path.as_bytes(), true);
+            let global = self.module.add_global(value.get_type(), None, &path);
+            global.set_initializer(&value);
+            global.set_constant(true);
+            global.set_linkage(Linkage::Private);
+            global.set_unnamed_address(UnnamedAddress::Global);
+            global
+        });
+
+        let zero = self.context.i32_type().const_zero();
+        // SAFETY: The global is a non-empty array, so its first element always exists
+        unsafe { global.as_pointer_value().const_in_bounds_gep(&[zero, zero]) }
+    }
+
     fn gen_arith_bin_op_float(
         &mut self,
         lhs: FloatValue<'ink>,
@@ -1094,13 +1667,43 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         self.module_group.should_runtime_link_fn(self.db, function)
     }
 
+    /// Generates IR to call the specified function with the values of the
+    /// specified argument expressions.
+    fn gen_fn_call(
+        &mut self,
+        expr: ExprId,
+        function: mun_hir::Function,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum<'ink>> {
+        // Get all the arguments
+        let args: Vec<BasicMetadataValueEnum<'_>> = args
+            .iter()
+            .map(|expr| self.gen_expr(*expr).expect("expected a value").into())
+            .collect();
+
+        self.gen_call(function, &args)
+            .try_as_basic_value()
+            .left()
+            // If the called function is a void function it doesn't return anything.
+            // If this method (`gen_expr`) returns None we assume the return value
+            // is `never`. We return a const unit struct here to ensure that at
+            // least something is returned. This matches with the mun_hir where a
+            // `nothing` is returned instead of a `never`.
+            //
+            // This unit value will also be optimized out.
+            .or_else(|| match self.infer[expr].interned() {
+                TyKind::Never => None,
+                _ => Some(self.context.const_struct(&[], false).into()),
+            })
+    }
+
     /// Generates IR for a function call.
     fn gen_call(
         &mut self,
         function: mun_hir::Function,
         args: &[BasicMetadataValueEnum<'ink>],
     ) -> CallSiteValue<'ink> {
-        if self.should_use_dispatch_table(function) {
+        let call = if self.should_use_dispatch_table(function) {
             let ptr_value = self.dispatch_table.gen_function_lookup(
                 self.db,
                 self.external_globals.dispatch_table,
@@ -1118,7 +1721,199 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             });
             self.builder
                 .build_call(*llvm_function, args, &function.name(self.db).to_string())
+        };
+
+        // If the callee was interrupted its result is meaningless, so unwind
+        // this function as well.
+        if self.interrupt_checks() && !function.is_extern(self.db) {
+            self.gen_interrupt_check();
         }
+
+        call
+    }
+
+    /// Consumes a unit of the execution budget of the current thread. If the
+    /// budget was already exhausted, execution is interrupted and the function
+    /// returns immediately. Does nothing if budget checks are disabled.
+    fn gen_budget_check(&mut self) {
+        if !self.budget_checks {
+            return;
+        }
+
+        let execution_state = self.get_execution_state();
+        let budget_ptr = self
+            .builder
+            .build_struct_gep(execution_state, 0, "budget_ptr")
+            .expect("could not retrieve `budget` from the execution state");
+        let i64_type = self.context.i64_type();
+        let previous_budget = self
+            .builder
+            .build_atomicrmw(
+                AtomicRMWBinOp::Sub,
+                budget_ptr,
+                i64_type.const_int(1, false),
+                AtomicOrdering::Monotonic,
+            )
+            .expect("could not consume the execution budget");
+
+        // Consuming a unit of an exhausted budget wraps it around, but the runtime
+        // disregards the budget once execution has been interrupted.
+        let out_of_budget = self.builder.build_int_compare(
+            IntPredicate::EQ,
+            previous_budget,
+            i64_type.const_zero(),
+            "is_out_of_budget",
+        );
+        let exhausted_block = self
+            .context
+            .append_basic_block(self.fn_value, "budget_exhausted");
+        let within_budget_block = self
+            .context
+            .append_basic_block(self.fn_value, "within_budget");
+        self.builder
+            .build_conditional_branch(out_of_budget, exhausted_block, within_budget_block);
+
+        self.builder.position_at_end(exhausted_block);
+        let interrupt_ptr = self
+            .builder
+            .build_struct_gep(execution_state, 1, "interrupt_ptr")
+            .expect("could not retrieve `interrupt` from the execution state");
+        let store = self.builder.build_store(
+            interrupt_ptr,
+            self.context
+                .i32_type()
+                .const_int(abi::Interrupt::BudgetExhausted as u64, false),
+        );
+        store
+            .set_atomic_ordering(AtomicOrdering::Monotonic)
+            .and_then(|()| store.set_alignment(4))
+            .expect("could not make the store atomic");
+        self.gen_interrupted_return();
+
+        self.builder.position_at_end(within_budget_block);
+    }
+
+    /// Returns true if the generated code can be interrupted, in which case
+    /// every call has to check whether the callee was interrupted. Without any
+    /// runtime checks, functions always run to completion.
+    fn interrupt_checks(&self) -> bool {
+        self.overflow_checks || self.budget_checks || self.heap_limit_checks
+    }
+
+    /// Returns from the function if execution on the current thread was
+    /// interrupted, e.g. because a callee exhausted the execution budget.
+    fn gen_interrupt_check(&mut self) {
+        let execution_state = self.get_execution_state();
+        let interrupt_ptr = self
+            .builder
+            .build_struct_gep(execution_state, 1, "interrupt_ptr")
+            .expect("could not retrieve `interrupt` from the execution state");
+        let interrupt = self.builder.build_load(interrupt_ptr, "interrupt");
+        let load = interrupt
+            .as_instruction_value()
+            .expect("a load is an instruction");
+        load.set_atomic_ordering(AtomicOrdering::Monotonic)
+            .and_then(|()| load.set_alignment(4))
+            .expect("could not make the load atomic");
+
+        let interrupted = self.builder.build_int_compare(
+            IntPredicate::NE,
+            interrupt.into_int_value(),
+            self.context.i32_type().const_zero(),
+            "is_interrupted",
+        );
+        let interrupted_block = self
+            .context
+            .append_basic_block(self.fn_value, "interrupted");
+        let continue_block = self
+            .context
+            .append_basic_block(self.fn_value, "not_interrupted");
+        self.builder
+            .build_conditional_branch(interrupted, interrupted_block, continue_block);
+
+        self.builder.position_at_end(interrupted_block);
+        self.gen_interrupted_return();
+
+        self.builder.position_at_end(continue_block);
+    }
+
+    /// Returns from the function if an allocation failed, which happens when it
+    /// would exceed the hard heap limit. The runtime has already interrupted
+    /// execution on the current thread. Does nothing if heap limit checks are
+    /// disabled, in which case allocations never fail.
+    fn gen_allocation_check(&mut self, reference: PointerValue<'ink>) {
+        if !self.heap_limit_checks {
+            return;
+        }
+
+        let failed = self
+            .builder
+            .build_is_null(reference, "is_allocation_failed");
+        let failed_block = self
+            .context
+            .append_basic_block(self.fn_value, "allocation_failed");
+        let allocated_block = self.context.append_basic_block(self.fn_value, "allocated");
+        self.builder
+            .build_conditional_branch(failed, failed_block, allocated_block);
+
+        self.builder.position_at_end(failed_block);
+        self.gen_interrupted_return();
+
+        self.builder.position_at_end(allocated_block);
+    }
+
+    /// Generates a return from a function whose execution was interrupted. The
+    /// returned value is meaningless, the runtime discards it.
+    fn gen_interrupted_return(&mut self) {
+        match self.fn_value.get_type().get_return_type() {
+            Some(ty) => self.builder.build_return(Some(&ty.const_zero())),
+            None => self.builder.build_return(None),
+        };
+    }
+
+    /// Returns a pointer to the `ExecutionState` of the current thread. It is
+    /// obtained from the runtime once, at the start of the function.
+    fn get_execution_state(&mut self) -> PointerValue<'ink> {
+        if let Some(execution_state) = self.execution_state {
+            return execution_state;
+        }
+
+        // Calls in a function with debug information must have a location
+        let builder = self.new_alloca_builder();
+        if self.debug_info.is_some() {
+            if let Some(location) = self.builder.get_current_debug_location() {
+                builder.set_current_debug_location(location);
+            }
+        }
+        let execution_state_fn = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &builder,
+            self.module,
+            &intrinsics::execution_state,
+        );
+        let untyped_execution_state = builder
+            .build_call(execution_state_fn, &[], "untyped_execution_state")
+            .try_as_basic_value()
+            .left()
+            .expect("the execution state intrinsic must return a pointer")
+            .into_pointer_value();
+
+        // The fields must match those of `abi::ExecutionState`
+        let execution_state_type = self.context.struct_type(
+            &[
+                self.context.i64_type().into(),
+                self.context.i32_type().into(),
+            ],
+            false,
+        );
+        let execution_state = builder.build_pointer_cast(
+            untyped_execution_state,
+            execution_state_type.ptr_type(AddressSpace::default()),
+            "execution_state",
+        );
+
+        self.execution_state = Some(execution_state);
+        execution_state
     }
 
     /// Generates IR for an if statement.
@@ -1317,6 +2112,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         self.builder.position_at_end(loop_block);
         let (exit_block, _, value) = self.gen_loop_block_expr(body_expr, exit_block);
         if value.is_some() {
+            self.gen_budget_check();
             self.builder.build_unconditional_branch(cond_block);
         }
 
@@ -1338,6 +2134,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         self.builder.position_at_end(loop_block);
         let (exit_block, break_values, value) = self.gen_loop_block_expr(body_expr, exit_block);
         if value.is_some() {
+            self.gen_budget_check();
             self.builder.build_unconditional_branch(loop_block);
         }
 
@@ -1465,12 +2262,14 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         let array_ty = &self.infer[expr];
         let element_ty = array_ty
             .as_array()
-            .expect("the type of an array literal expression must be an Array");
+            .expect("the type of an array literal expression must be an Array")
+            .clone();
 
         let new_array_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
             self.external_globals.dispatch_table,
             &self.builder,
-            &intrinsics::new_array,
+            self.module,
+            new_array_intrinsic(self.heap_limit_checks),
         );
 
         let type_info_ptr = self.type_table.gen_type_info_lookup(
@@ -1513,9 +2312,10 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             .left()
             .unwrap()
             .into_pointer_value();
+        self.gen_allocation_check(untyped_array_ptr);
 
         // Cast the object pointer to the array struct type
-        let array_ty = self.hir_types.get_array_type(element_ty);
+        let array_ty = self.hir_types.get_array_type(&element_ty);
         let array_ptr = self
             .builder
             .build_bitcast(
@@ -1541,6 +2341,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
 
             let expr_value = self.gen_expr(*expr)?;
             self.builder.build_store(element_ptr, expr_value);
+            self.gen_write_barrier(expr_value, &element_ty);
         }
 
         // Once all values have been stored in the array, update the length of the array
@@ -0,0 +1,518 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use inkwell::{
+    context::Context,
+    debug_info::{
+        debug_metadata_version, AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants,
+        DILocalVariable, DILocation, DIScope, DISubprogram, DIType, DWARFEmissionKind,
+        DWARFSourceLanguage, DebugInfoBuilder as DIBuilder,
+    },
+    module::{FlagBehavior, Module},
+    targets::TargetData,
+    types::StructType,
+    values::{FunctionValue, PointerValue},
+    AddressSpace, OptimizationLevel,
+};
+use mun_hir::{HasVisibility, HirDatabase, HirDisplay, Signedness, StructMemoryKind, Ty, TyKind};
+use mun_hir_input::{FileId, LineCol};
+
+use crate::{code_gen::CodeGenContext, ir::ty::HirTypeCache, module_group::ModuleGroup};
+
+/// DWARF base type encodings, see section 7.8 of the DWARF 4 specification.
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x07;
+
+/// Generates the DWARF debug information of a single LLVM module, which maps
+/// the generated code back to the Mun source it was generated from.
+pub(crate) struct DebugInfoBuilder<'db, 'ink, 't> {
+    context: &'ink Context,
+    db: &'db dyn HirDatabase,
+    hir_types: &'t HirTypeCache<'db, 'ink>,
+    target_data: TargetData,
+    builder: DIBuilder<'ink>,
+    compile_unit: DICompileUnit<'ink>,
+    directory: String,
+    is_optimized: bool,
+    files: RefCell<HashMap<FileId, DIFile<'ink>>>,
+    types: RefCell<HashMap<Ty, DIType<'ink>>>,
+}
+
+impl<'db, 'ink, 't> DebugInfoBuilder<'db, 'ink, 't> {
+    /// Constructs a new `DebugInfoBuilder` that emits debug information into
+    /// `module`.
+    pub fn new(
+        code_gen: &'t CodeGenContext<'db, 'ink>,
+        module: &Module<'ink>,
+        module_group: &ModuleGroup,
+    ) -> Self {
+        let context = code_gen.context;
+        let db = code_gen.db;
+
+        module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            context
+                .i32_type()
+                .const_int(debug_metadata_version().into(), false),
+        );
+        module.add_basic_value_flag(
+            "Dwarf Version",
+            FlagBehavior::Warning,
+            context.i32_type().const_int(4, false),
+        );
+
+        let directory = code_gen.source_directory.to_string_lossy().into_owned();
+        let file_name = module_group.files(db).next().map_or_else(
+            || module_group.name.clone(),
+            |file_id| db.file_relative_path(file_id).to_string(),
+        );
+        let is_optimized = code_gen.optimization_level != OptimizationLevel::None;
+        let (builder, compile_unit) = module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &file_name,
+            &directory,
+            concat!("mun ", env!("CARGO_PKG_VERSION")),
+            is_optimized,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+
+        Self {
+            context,
+            db,
+            hir_types: &code_gen.hir_types,
+            target_data: code_gen.target_machine.get_target_data(),
+            builder,
+            compile_unit,
+            directory,
+            is_optimized,
+            files: RefCell::default(),
+            types: RefCell::default(),
+        }
+    }
+
+    /// Resolves all temporary debug information. This must be called after
+    /// all function bodies have been generated and before the module is
+    /// verified.
+    pub fn finalize(&self) {
+        self.builder.finalize();
+    }
+
+    /// Creates the debug information of `hir_function`, which is generated
+    /// as `fn_value`, and returns its scope.
+    pub fn create_function(
+        &self,
+        hir_function: mun_hir::Function,
+        fn_value: FunctionValue<'ink>,
+    ) -> DIScope<'ink> {
+        let file = self.file(hir_function.file_id(self.db));
+        let line = hir_function.position(self.db).line + 1;
+
+        let fn_sig = hir_function
+            .ty(self.db)
+            .callable_sig(self.db)
+            .expect("a function must have a signature");
+        let return_type = if fn_sig.ret().is_empty() {
+            None
+        } else {
+            self.ty(fn_sig.ret())
+        };
+        let param_types = fn_sig
+            .params()
+            .iter()
+            .filter_map(|ty| self.ty(ty))
+            .collect::<Vec<_>>();
+        let subroutine_type =
+            self.builder
+                .create_subroutine_type(file, return_type, &param_types, DIFlags::ZERO);
+
+        let subprogram: DISubprogram<'ink> = self.builder.create_function(
+            self.compile_unit.as_debug_info_scope(),
+            &hir_function.full_name(self.db),
+            fn_value.get_name().to_str().ok(),
+            file,
+            line,
+            subroutine_type,
+            !hir_function.visibility(self.db).is_externally_visible(),
+            true,
+            line,
+            DIFlags::PROTOTYPED,
+            self.is_optimized,
+        );
+        fn_value.set_subprogram(subprogram);
+        subprogram.as_debug_info_scope()
+    }
+
+    /// Creates a nested scope for a block that starts at `position`.
+    pub fn create_lexical_block(
+        &self,
+        scope: DIScope<'ink>,
+        file_id: FileId,
+        position: LineCol,
+    ) -> DIScope<'ink> {
+        self.builder
+            .create_lexical_block(
+                scope,
+                self.file(file_id),
+                position.line + 1,
+                position.col_utf16 + 1,
+            )
+            .as_debug_info_scope()
+    }
+
+    /// Returns the location of `position` within `scope`.
+    pub fn location(&self, scope: DIScope<'ink>, position: LineCol) -> DILocation<'ink> {
+        self.builder.create_debug_location(
+            self.context,
+            position.line + 1,
+            position.col_utf16 + 1,
+            scope,
+            None,
+        )
+    }
+
+    /// Describes the parameter `name` of type `ty`, which is stored at
+    /// `storage`. Parameters are numbered starting at 1.
+    pub fn declare_parameter(
+        &self,
+        file_id: FileId,
+        name: &str,
+        arg_no: u32,
+        ty: &Ty,
+        storage: PointerValue<'ink>,
+        location: DILocation<'ink>,
+    ) {
+        let Some(di_ty) = self.ty(ty) else {
+            return;
+        };
+        let variable = self.builder.create_parameter_variable(
+            location.get_scope(),
+            name,
+            arg_no,
+            self.file(file_id),
+            location.get_line(),
+            di_ty,
+            true,
+            DIFlags::ZERO,
+        );
+        self.insert_declare(variable, storage, location);
+    }
+
+    /// Describes the local variable `name` of type `ty`, which is stored at
+    /// `storage`.
+    pub fn declare_local(
+        &self,
+        file_id: FileId,
+        name: &str,
+        ty: &Ty,
+        storage: PointerValue<'ink>,
+        location: DILocation<'ink>,
+    ) {
+        let Some(di_ty) = self.ty(ty) else {
+            return;
+        };
+        let variable = self.builder.create_auto_variable(
+            location.get_scope(),
+            name,
+            self.file(file_id),
+            location.get_line(),
+            di_ty,
+            true,
+            DIFlags::ZERO,
+            0,
+        );
+        self.insert_declare(variable, storage, location);
+    }
+
+    /// Declares that `variable` lives at `storage`, directly after the
+    /// `alloca` that allocated it.
+    fn insert_declare(
+        &self,
+        variable: DILocalVariable<'ink>,
+        storage: PointerValue<'ink>,
+        location: DILocation<'ink>,
+    ) {
+        let alloca = storage
+            .as_instruction()
+            .expect("variables must be allocated by an alloca");
+        match alloca.get_next_instruction() {
+            Some(next) => {
+                self.builder.insert_declare_before_instruction(
+                    storage,
+                    Some(variable),
+                    None,
+                    location,
+                    next,
+                );
+            }
+            None => {
+                self.builder.insert_declare_at_end(
+                    storage,
+                    Some(variable),
+                    None,
+                    location,
+                    alloca.get_parent().expect("an alloca must be in a block"),
+                );
+            }
+        }
+    }
+
+    /// Returns the file with the specified `file_id`.
+    fn file(&self, file_id: FileId) -> DIFile<'ink> {
+        *self.files.borrow_mut().entry(file_id).or_insert_with(|| {
+            let path = self.db.file_relative_path(file_id);
+            self.builder.create_file(path.as_str(), &self.directory)
+        })
+    }
+
+    /// Returns the debug type of a variable of type `ty`, or `None` if the
+    /// type cannot be described.
+    fn ty(&self, ty: &Ty) -> Option<DIType<'ink>> {
+        if let Some(di_ty) = self.types.borrow().get(ty) {
+            return Some(*di_ty);
+        }
+
+        let di_ty = match ty.interned() {
+            TyKind::Bool => self.basic_type(ty, DW_ATE_BOOLEAN),
+            TyKind::Int(int_ty) => {
+                let encoding = match int_ty.signedness {
+                    Signedness::Signed => DW_ATE_SIGNED,
+                    Signedness::Unsigned => DW_ATE_UNSIGNED,
+                };
+                self.basic_type(ty, encoding)
+            }
+            TyKind::Float(_) => self.basic_type(ty, DW_ATE_FLOAT),
+            TyKind::Tuple(_, substs) => {
+                let ir_ty = self.hir_types.get_tuple_type(substs);
+                let members = substs
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, ty)| (idx.to_string(), ty.clone()))
+                    .collect::<Vec<_>>();
+                self.composite_type(&ty.display(self.db).to_string(), None, ir_ty, &members)
+            }
+            TyKind::Struct(s) => {
+                let ir_ty = self.hir_types.get_struct_type(*s);
+                let members = s
+                    .fields(self.db)
+                    .into_iter()
+                    .map(|field| (field.name(self.db).to_string(), field.ty(self.db)))
+                    .collect::<Vec<_>>();
+                let location = Some((s.file_id(self.db), s.position(self.db)));
+                if s.data(self.db.upcast()).memory_kind == StructMemoryKind::Value {
+                    self.composite_type(&s.full_name(self.db), location, ir_ty, &members)
+                } else {
+                    // Garbage collected structs are referenced through a handle, which may
+                    // (indirectly) contain a field of its own type.
+                    self.reference_type(ty, |this| {
+                        this.composite_type(&s.full_name(this.db), location, ir_ty, &members)
+                    })
+                }
+            }
+            TyKind::Array(element_ty) => {
+                self.reference_type(ty, |this| this.array_type(ty, element_ty))
+            }
+            _ => return None,
+        };
+
+        self.types.borrow_mut().insert(ty.clone(), di_ty);
+        Some(di_ty)
+    }
+
+    /// Returns a primitive type with the specified DWARF `encoding`.
+    fn basic_type(&self, ty: &Ty, encoding: u32) -> DIType<'ink> {
+        let ir_ty = self
+            .hir_types
+            .get_basic_type(ty)
+            .expect("primitive types must be basic types");
+        self.builder
+            .create_basic_type(
+                &ty.display(self.db).to_string(),
+                self.target_data.get_abi_size(&ir_ty) * 8,
+                encoding,
+                DIFlags::ZERO,
+            )
+            .expect("primitive types have a name")
+            .as_type()
+    }
+
+    /// Returns a struct type that is laid out as `ir_ty` and whose members
+    /// are named and typed by `members`.
+    fn composite_type(
+        &self,
+        name: &str,
+        location: Option<(FileId, LineCol)>,
+        ir_ty: StructType<'ink>,
+        members: &[(String, Ty)],
+    ) -> DIType<'ink> {
+        let (file, line) = match location {
+            Some((file_id, position)) => (self.file(file_id), position.line + 1),
+            None => (self.compile_unit.get_file(), 0),
+        };
+        let members = members
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, (member_name, member_ty))| {
+                let di_ty = self.ty(member_ty)?;
+                let ir_member_ty = ir_ty.get_field_type_at_index(idx as u32)?;
+                let offset = self.target_data.offset_of_element(&ir_ty, idx as u32)?;
+                Some(
+                    self.builder
+                        .create_member_type(
+                            file.as_debug_info_scope(),
+                            member_name,
+                            file,
+                            line,
+                            self.target_data.get_abi_size(&ir_member_ty) * 8,
+                            self.target_data.get_abi_alignment(&ir_member_ty) * 8,
+                            offset * 8,
+                            DIFlags::PUBLIC,
+                            di_ty,
+                        )
+                        .as_type(),
+                )
+            })
+            .collect::<Vec<_>>();
+        self.builder
+            .create_struct_type(
+                file.as_debug_info_scope(),
+                name,
+                file,
+                line,
+                self.target_data.get_abi_size(&ir_ty) * 8,
+                self.target_data.get_abi_alignment(&ir_ty) * 8,
+                DIFlags::PUBLIC,
+                None,
+                &members,
+                0,
+                None,
+                name,
+            )
+            .as_type()
+    }
+
+    /// Returns the type of the heap allocated value of an array, which holds
+    /// its length, its capacity and its elements.
+    fn array_type(&self, ty: &Ty, element_ty: &Ty) -> DIType<'ink> {
+        let ir_ty = self.hir_types.get_array_type(element_ty);
+        let file = self.compile_unit.get_file();
+        let usize_bits = u64::from(self.target_data.get_pointer_byte_size(None)) * 8;
+        let usize_ty = self
+            .builder
+            .create_basic_type("usize", usize_bits, DW_ATE_UNSIGNED, DIFlags::ZERO)
+            .expect("usize has a name")
+            .as_type();
+        let element_ir_ty = self
+            .hir_types
+            .get_basic_type(element_ty)
+            .expect("array elements must be basic types");
+        let element_align = self.target_data.get_abi_alignment(&element_ir_ty) * 8;
+        // The number of elements is only known at runtime, so the elements are
+        // described as an array without a length. The subscript is a DWARF
+        // subrange rather than the contents of the array.
+        #[allow(clippy::single_range_in_vec_init)]
+        let elements_ty = self
+            .builder
+            .create_array_type(
+                self.ty(element_ty)
+                    .expect("array elements must be describable"),
+                0,
+                element_align,
+                &[0..0],
+            )
+            .as_type();
+
+        let member = |name: &str, idx: u32, di_ty: DIType<'ink>, size: u64, align: u32| {
+            self.builder
+                .create_member_type(
+                    file.as_debug_info_scope(),
+                    name,
+                    file,
+                    0,
+                    size,
+                    align,
+                    self.target_data
+                        .offset_of_element(&ir_ty, idx)
+                        .expect("array fields must exist")
+                        * 8,
+                    DIFlags::PUBLIC,
+                    di_ty,
+                )
+                .as_type()
+        };
+        let usize_align = usize_bits as u32;
+        let members = [
+            member("length", 0, usize_ty, usize_bits, usize_align),
+            member("capacity", 1, usize_ty, usize_bits, usize_align),
+            member("elements", 2, elements_ty, 0, element_align),
+        ];
+
+        let name = ty.display(self.db).to_string();
+        self.builder
+            .create_struct_type(
+                file.as_debug_info_scope(),
+                &name,
+                file,
+                0,
+                self.target_data.get_abi_size(&ir_ty) * 8,
+                self.target_data.get_abi_alignment(&ir_ty) * 8,
+                DIFlags::PUBLIC,
+                None,
+                &members,
+                0,
+                None,
+                &name,
+            )
+            .as_type()
+    }
+
+    /// Returns the type of a reference to a heap allocated object: a pointer
+    /// to the pointer to the object. A placeholder is registered for `ty`
+    /// while `object_type` is constructed, so the object can refer to `ty`
+    /// itself.
+    fn reference_type(
+        &self,
+        ty: &Ty,
+        object_type: impl FnOnce(&Self) -> DIType<'ink>,
+    ) -> DIType<'ink> {
+        // SAFETY: The placeholder is replaced before it is used by the module
+        let placeholder = unsafe { self.builder.create_placeholder_derived_type(self.context) };
+        self.types
+            .borrow_mut()
+            .insert(ty.clone(), placeholder.as_type());
+
+        let pointer_bits = u64::from(self.target_data.get_pointer_byte_size(None)) * 8;
+        let object_pointer = self.builder.create_pointer_type(
+            "",
+            object_type(self),
+            pointer_bits,
+            pointer_bits as u32,
+            AddressSpace::default(),
+        );
+        let reference = self.builder.create_pointer_type(
+            "",
+            object_pointer.as_type(),
+            pointer_bits,
+            pointer_bits as u32,
+            AddressSpace::default(),
+        );
+
+        // SAFETY: The placeholder was created by this builder and is no longer
+        // referenced after being replaced
+        unsafe {
+            self.builder
+                .replace_placeholder_derived_type(placeholder, reference);
+        }
+        reference.as_type()
+    }
+}
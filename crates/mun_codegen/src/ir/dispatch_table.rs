@@ -4,12 +4,14 @@ use std::{
 };
 
 use inkwell::{
+    attributes::AttributeLoc,
     context::Context,
-    module::Module,
+    module::{Linkage, Module},
     targets::TargetData,
     types::{BasicTypeEnum, FunctionType},
-    values::{BasicValueEnum, CallableValue},
+    values::{BasicValueEnum, CallableValue, FunctionValue},
 };
+use mun_abi as abi;
 use mun_hir::{Body, Expr, ExprId, HirDatabase, InferenceResult};
 use rustc_hash::FxHashSet;
 
@@ -33,6 +35,10 @@ use crate::{
 /// function from IR. The `DispatchTable` is exposed to the Runtime which fills
 /// the structure with valid pointers to functions. This basically enables all
 /// hot reloading within Mun.
+///
+/// WebAssembly modules cannot be given pointers to functions of the host.
+/// Instead, they import the intrinsics of the runtime, which are therefore
+/// not part of their dispatch table.
 #[derive(Debug, Eq, PartialEq)]
 pub struct DispatchTable<'ink> {
     // The LLVM context in which all LLVM types live
@@ -43,6 +49,8 @@ pub struct DispatchTable<'ink> {
     function_to_idx: HashMap<mun_hir::Function, usize>,
     // Prototype to function index
     prototype_to_idx: HashMap<FunctionPrototype, usize>,
+    // Whether intrinsics are imported instead of looked up in the table
+    import_intrinsics: bool,
     // This contains an ordered list of all the function in the dispatch table
     entries: Vec<DispatchableFunction>,
     // Contains a reference to the global value containing the DispatchTable
@@ -102,13 +110,19 @@ impl<'ink> DispatchTable<'ink> {
 
     /// Generates a function lookup through the `DispatchTable`, equivalent to
     /// something along the lines of: `dispatchTable[i]`, where i is the
-    /// index of the intrinsic and `dispatchTable` is a struct
+    /// index of the intrinsic and `dispatchTable` is a struct. If intrinsics
+    /// are imported, the imported function in `module` is returned instead.
     pub fn gen_intrinsic_lookup(
         &self,
         table_ref: Option<inkwell::values::GlobalValue<'ink>>,
         builder: &inkwell::builder::Builder<'ink>,
-        intrinsic: &impl Intrinsic,
+        module: &Module<'ink>,
+        intrinsic: &(impl Intrinsic + ?Sized),
     ) -> CallableValue<'ink> {
+        if self.import_intrinsics {
+            return self.gen_intrinsic_import(module, intrinsic).into();
+        }
+
         let prototype = intrinsic.prototype();
 
         // Get the index of the intrinsic
@@ -120,6 +134,37 @@ impl<'ink> DispatchTable<'ink> {
         Self::gen_function_lookup_by_index(table_ref, builder, &prototype.name, index)
     }
 
+    /// Declares the function through which `intrinsic` is imported from the
+    /// runtime by a WebAssembly module.
+    fn gen_intrinsic_import(
+        &self,
+        module: &Module<'ink>,
+        intrinsic: &(impl Intrinsic + ?Sized),
+    ) -> FunctionValue<'ink> {
+        let name = intrinsic.prototype().name;
+
+        // Mun identifiers cannot contain a period, so the symbol doesn't clash with
+        // a Mun function
+        let symbol = format!("{}.{name}", abi::INTRINSICS_WASM_MODULE);
+        module.get_function(&symbol).unwrap_or_else(|| {
+            let function = module.add_function(
+                &symbol,
+                intrinsic.ir_type(self.context, &self.target),
+                Some(Linkage::External),
+            );
+            for (key, value) in [
+                ("wasm-import-module", abi::INTRINSICS_WASM_MODULE),
+                ("wasm-import-name", &name),
+            ] {
+                function.add_attribute(
+                    AttributeLoc::Function,
+                    self.context.create_string_attribute(key, value),
+                );
+            }
+            function
+        })
+    }
+
     /// Generates a function lookup through the `DispatchTable`, equivalent to
     /// something along the lines of: `dispatchTable[i]`, where i is the
     /// index and `dispatchTable` is a struct
@@ -178,6 +223,8 @@ pub(crate) struct DispatchTableBuilder<'db, 'ink, 't> {
     function_to_idx: HashMap<mun_hir::Function, usize>,
     // Prototype to function index
     prototype_to_idx: HashMap<FunctionPrototype, usize>,
+    // Whether intrinsics are imported instead of added to the table
+    import_intrinsics: bool,
     // These are *all* called functions in the modules
     entries: Vec<TypedDispatchableFunction<'ink>>,
     // Contains a reference to the global value containing the DispatchTable
@@ -196,13 +243,16 @@ struct TypedDispatchableFunction<'ink> {
 }
 
 impl<'db, 'ink, 't> DispatchTableBuilder<'db, 'ink, 't> {
-    /// Creates a new builder that can generate a dispatch function.
+    /// Creates a new builder that can generate a dispatch function. Unless
+    /// `import_intrinsics` is set, the `intrinsics` are added to the table.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         context: &'ink Context,
         target_data: TargetData,
         db: &'db dyn HirDatabase,
         module: &'t Module<'ink>,
         intrinsics: &BTreeMap<FunctionPrototype, FunctionType<'ink>>,
+        import_intrinsics: bool,
         hir_types: &'t HirTypeCache<'db, 'ink>,
         module_group: &'t ModuleGroup,
     ) -> Self {
@@ -213,6 +263,7 @@ impl<'db, 'ink, 't> DispatchTableBuilder<'db, 'ink, 't> {
             target_data,
             function_to_idx: HashMap::default(),
             prototype_to_idx: HashMap::default(),
+            import_intrinsics,
             entries: Vec::default(),
             table_ref: None,
             table_type: context.opaque_struct_type("DispatchTable"),
@@ -221,7 +272,7 @@ impl<'db, 'ink, 't> DispatchTableBuilder<'db, 'ink, 't> {
             referenced_modules: FxHashSet::default(),
         };
 
-        if !intrinsics.is_empty() {
+        if !intrinsics.is_empty() && !import_intrinsics {
             table.ensure_table_ref();
 
             // Use a `BTreeMap` to guarantee deterministically ordered output
@@ -258,24 +309,35 @@ impl<'db, 'ink, 't> DispatchTableBuilder<'db, 'ink, 't> {
         // If this expression is a call, store it in the dispatch table
         if let Expr::Call { callee, .. } = expr {
             match infer[*callee].as_callable_def() {
-                Some(mun_hir::CallableDef::Function(def)) => {
-                    if self.module_group.should_runtime_link_fn(self.db, def) {
-                        let fn_module = def.module(self.db);
-                        if !def.is_extern(self.db) && !self.module_group.contains(fn_module) {
-                            self.referenced_modules.insert(fn_module);
-                        }
-                        self.collect_fn_def(def);
-                    }
-                }
+                Some(mun_hir::CallableDef::Function(def)) => self.collect_fn_call(def),
                 Some(mun_hir::CallableDef::Struct(_)) => (),
+                // Built-in integer methods are generated inline
+                None if infer.int_method(expr_id).is_some() => (),
                 None => panic!("expected a callable expression"),
             }
         }
 
+        // Overloaded operators are calls to the method that implements them
+        if let Some(method) = infer.method_resolution(expr_id) {
+            self.collect_fn_call(method);
+        }
+
         // Recurse further
         expr.walk_child_exprs(|expr_id| self.collect_expr(expr_id, body, infer));
     }
 
+    /// Collects a call to the specified function, adding the function to the
+    /// dispatch table if it must be linked at runtime.
+    fn collect_fn_call(&mut self, function: mun_hir::Function) {
+        if self.module_group.should_runtime_link_fn(self.db, function) {
+            let fn_module = function.module(self.db);
+            if !function.is_extern(self.db) && !self.module_group.contains(fn_module) {
+                self.referenced_modules.insert(fn_module);
+            }
+            self.collect_fn_def(function);
+        }
+    }
+
     /// Collects function call expression from the given expression.
     #[allow(clippy::map_entry)]
     pub fn collect_fn_def(&mut self, function: mun_hir::Function) {
@@ -383,6 +445,7 @@ impl<'db, 'ink, 't> DispatchTableBuilder<'db, 'ink, 't> {
                 target: self.target_data,
                 function_to_idx: self.function_to_idx,
                 prototype_to_idx: self.prototype_to_idx,
+                import_intrinsics: self.import_intrinsics,
                 table_ref: self.table_ref,
                 table_type,
                 entries: self
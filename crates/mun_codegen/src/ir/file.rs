@@ -6,7 +6,10 @@ use mun_hir::{HasVisibility, ModuleDef};
 use super::body::ExternalGlobals;
 use crate::{
     code_gen::CodeGenContext,
-    ir::{body::BodyIrGenerator, file_group::FileGroupIr, function, type_table::TypeTable},
+    ir::{
+        body::BodyIrGenerator, debug_info::DebugInfoBuilder, file_group::FileGroupIr, function,
+        type_table::TypeTable,
+    },
     module_group::ModuleGroup,
     value::Global,
 };
@@ -37,29 +40,29 @@ pub(crate) fn gen_file_ir<'ink>(
     let mut functions = HashMap::new();
     let mut type_definitions = HashSet::new();
     let mut wrapper_functions = BTreeMap::new();
+    for f in module_group.functions(code_gen.db) {
+        if !f.is_extern(code_gen.db) {
+            let fun = function::gen_prototype(code_gen.db, hir_types, f, &llvm_module);
+            functions.insert(f, fun);
+
+            let fn_sig = f.ty(code_gen.db).callable_sig(code_gen.db).unwrap();
+            if f.visibility(code_gen.db).is_externally_visible()
+                && !fn_sig.marshallable(code_gen.db)
+            {
+                let wrapper_fun = function::gen_public_prototype(
+                    code_gen.db,
+                    &code_gen.hir_types,
+                    f,
+                    &llvm_module,
+                );
+                wrapper_functions.insert(f, wrapper_fun);
+            }
+        }
+    }
     for def in module_group
         .iter()
         .flat_map(|module| module.declarations(code_gen.db))
     {
-        if let ModuleDef::Function(f) = def {
-            if !f.is_extern(code_gen.db) {
-                let fun = function::gen_prototype(code_gen.db, hir_types, f, &llvm_module);
-                functions.insert(f, fun);
-
-                let fn_sig = f.ty(code_gen.db).callable_sig(code_gen.db).unwrap();
-                if f.visibility(code_gen.db).is_externally_visible()
-                    && !fn_sig.marshallable(code_gen.db)
-                {
-                    let wrapper_fun = function::gen_public_prototype(
-                        code_gen.db,
-                        &code_gen.hir_types,
-                        f,
-                        &llvm_module,
-                    );
-                    wrapper_functions.insert(f, wrapper_fun);
-                }
-            }
-        }
         if let ModuleDef::Struct(s) = def {
             type_definitions.insert(s.ty(code_gen.db));
         }
@@ -87,12 +90,16 @@ pub(crate) fn gen_file_ir<'ink>(
 
     // Construct requirements for generating the bodies
     let fn_pass_manager = function::create_pass_manager(&llvm_module, code_gen.optimization_level);
+    let debug_info = code_gen
+        .debug_info
+        .then(|| DebugInfoBuilder::new(code_gen, &llvm_module, module_group));
 
     // Generate the function bodies
     for (hir_function, llvm_function) in functions.iter() {
         let mut code_gen = BodyIrGenerator::new(
             code_gen.context,
             code_gen.db,
+            &llvm_module,
             (*hir_function, *llvm_function),
             &functions,
             &group_ir.dispatch_table,
@@ -100,16 +107,20 @@ pub(crate) fn gen_file_ir<'ink>(
             external_globals.clone(),
             &code_gen.hir_types,
             module_group,
+            code_gen.overflow_checks(),
+            code_gen.budget_checks,
+            code_gen.heap_limit_checks,
+            debug_info.as_ref(),
         );
 
         code_gen.gen_fn_body();
-        fn_pass_manager.run_on(llvm_function);
     }
 
     for (hir_function, llvm_function) in wrapper_functions.iter() {
         let mut code_gen = BodyIrGenerator::new(
             code_gen.context,
             code_gen.db,
+            &llvm_module,
             (*hir_function, *llvm_function),
             &functions,
             &group_ir.dispatch_table,
@@ -117,9 +128,20 @@ pub(crate) fn gen_file_ir<'ink>(
             external_globals.clone(),
             &code_gen.hir_types,
             module_group,
+            code_gen.overflow_checks(),
+            code_gen.budget_checks,
+            code_gen.heap_limit_checks,
+            debug_info.as_ref(),
         );
 
         code_gen.gen_fn_wrapper();
+    }
+
+    // The debug information must be complete before the functions are optimized
+    if let Some(debug_info) = &debug_info {
+        debug_info.finalize();
+    }
+    for llvm_function in functions.values().chain(wrapper_functions.values()) {
         fn_pass_manager.run_on(llvm_function);
     }
 
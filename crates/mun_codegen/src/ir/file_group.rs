@@ -45,40 +45,37 @@ pub(crate) fn gen_file_group_ir<'ink>(
 
     // Collect all intrinsic functions, wrapper function, and generate struct
     // declarations.
-    for def in module_group
-        .iter()
-        .flat_map(|module| module.declarations(code_gen.db))
-    {
-        match def {
-            ModuleDef::Function(f) if !f.is_extern(code_gen.db) => {
-                intrinsics::collect_fn_body(
+    // TODO: Extern types for functions?
+    for f in module_group.functions(code_gen.db) {
+        if !f.is_extern(code_gen.db) {
+            intrinsics::collect_fn_body(
+                code_gen.context,
+                code_gen.target_machine.get_target_data(),
+                code_gen.db,
+                &mut intrinsics_map,
+                &mut needs_alloc,
+                code_gen.overflow_checks(),
+                code_gen.budget_checks,
+                code_gen.heap_limit_checks,
+                &f.body(code_gen.db),
+                &f.infer(code_gen.db),
+            );
+
+            let fn_sig = f.ty(code_gen.db).callable_sig(code_gen.db).unwrap();
+            if f.visibility(code_gen.db).is_externally_visible()
+                && !fn_sig.marshallable(code_gen.db)
+            {
+                intrinsics::collect_wrapper_body(
                     code_gen.context,
                     code_gen.target_machine.get_target_data(),
                     code_gen.db,
+                    fn_sig.ret(),
                     &mut intrinsics_map,
                     &mut needs_alloc,
-                    &f.body(code_gen.db),
-                    &f.infer(code_gen.db),
+                    code_gen.heap_limit_checks,
+                    code_gen.interrupt_checks(),
                 );
-
-                let fn_sig = f.ty(code_gen.db).callable_sig(code_gen.db).unwrap();
-                if f.visibility(code_gen.db).is_externally_visible()
-                    && !fn_sig.marshallable(code_gen.db)
-                {
-                    intrinsics::collect_wrapper_body(
-                        code_gen.context,
-                        code_gen.target_machine.get_target_data(),
-                        &mut intrinsics_map,
-                        &mut needs_alloc,
-                    );
-                }
             }
-            // TODO: Extern types for functions?
-            ModuleDef::Module(_)
-            | ModuleDef::Struct(_)
-            | ModuleDef::PrimitiveType(_)
-            | ModuleDef::TypeAlias(_)
-            | ModuleDef::Function(_) => (),
         }
     }
 
@@ -89,20 +86,16 @@ pub(crate) fn gen_file_group_ir<'ink>(
         code_gen.db,
         &llvm_module,
         &intrinsics_map,
+        code_gen.db.target().options.is_like_wasm,
         &code_gen.hir_types,
         module_group,
     );
-    for def in module_group
-        .iter()
-        .flat_map(|module| module.declarations(code_gen.db))
-    {
-        if let ModuleDef::Function(f) = def {
-            // Find all functions that must be present in the dispatch table
-            if !f.is_extern(code_gen.db) {
-                let body = f.body(code_gen.db);
-                let infer = f.infer(code_gen.db);
-                dispatch_table_builder.collect_body(&body, &infer);
-            }
+    for f in module_group.functions(code_gen.db) {
+        // Find all functions that must be present in the dispatch table
+        if !f.is_extern(code_gen.db) {
+            let body = f.body(code_gen.db);
+            let infer = f.infer(code_gen.db);
+            dispatch_table_builder.collect_body(&body, &infer);
         }
     }
 
@@ -133,16 +126,13 @@ pub(crate) fn gen_file_group_ir<'ink>(
         .iter()
         .flat_map(|module| module.declarations(code_gen.db))
     {
-        match def {
-            ModuleDef::Struct(s) => {
-                type_table_builder.collect_struct(s);
-            }
-            ModuleDef::Function(f) => {
-                type_table_builder.collect_fn(f);
-            }
-            ModuleDef::PrimitiveType(_) | ModuleDef::TypeAlias(_) | ModuleDef::Module(_) => (),
+        if let ModuleDef::Struct(s) = def {
+            type_table_builder.collect_struct(s);
         }
     }
+    for f in module_group.functions(code_gen.db) {
+        type_table_builder.collect_fn(f);
+    }
 
     let type_table = type_table_builder.build();
 
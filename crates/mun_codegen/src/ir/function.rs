@@ -33,7 +33,7 @@ pub(crate) fn gen_prototype<'db, 'ink>(
     func: mun_hir::Function,
     module: &Module<'ink>,
 ) -> FunctionValue<'ink> {
-    let name = func.name(db).to_string();
+    let name = func.local_name(db);
     let ir_ty = types.get_function_type(func);
     module.add_function(&name, ir_ty, None)
 }
@@ -50,7 +50,7 @@ pub(crate) fn gen_public_prototype<'db, 'ink>(
     func: mun_hir::Function,
     module: &Module<'ink>,
 ) -> FunctionValue<'ink> {
-    let name = format!("{}_wrapper", func.name(db));
+    let name = format!("{}_wrapper", func.local_name(db));
     let ir_ty = types.get_public_function_type(func);
     module.add_function(&name, ir_ty, None)
 }
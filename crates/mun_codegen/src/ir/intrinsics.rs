@@ -1,7 +1,10 @@
 use std::{collections::BTreeMap, sync::Arc};
 
 use inkwell::{context::Context, targets::TargetData, types::FunctionType};
-use mun_hir::{Body, Expr, ExprId, HirDatabase, InferenceResult, ValueNs};
+use mun_hir::{
+    ArithOp, BinaryOp, Body, Expr, ExprId, HirDatabase, InferenceResult, OverflowBehavior,
+    StructMemoryKind, Ty, TyKind, UnaryOp, ValueNs,
+};
 
 use crate::{
     intrinsics::{self, Intrinsic},
@@ -15,7 +18,7 @@ pub type IntrinsicsMap<'ink> = BTreeMap<FunctionPrototype, FunctionType<'ink>>;
 fn collect_intrinsic<'ink>(
     context: &'ink Context,
     target: &TargetData,
-    intrinsic: &impl Intrinsic,
+    intrinsic: &(impl Intrinsic + ?Sized),
     entries: &mut IntrinsicsMap<'ink>,
 ) {
     let prototype = intrinsic.prototype();
@@ -24,6 +27,101 @@ fn collect_intrinsic<'ink>(
         .or_insert_with(|| intrinsic.ir_type(context, target));
 }
 
+/// Returns true if the specified expression traps into the runtime when an
+/// integer overflow occurs.
+fn may_trap_on_overflow(
+    expr_id: ExprId,
+    expr: &Expr,
+    infer: &InferenceResult,
+    overflow_checks: bool,
+) -> bool {
+    if let Some(method) = infer.int_method(expr_id) {
+        return method.overflow == OverflowBehavior::Strict;
+    }
+
+    if !overflow_checks {
+        return false;
+    }
+
+    match expr {
+        Expr::BinaryOp {
+            lhs,
+            op: Some(BinaryOp::ArithOp(op) | BinaryOp::Assignment { op: Some(op) }),
+            ..
+        } => {
+            let TyKind::Int(ty) = infer[*lhs].interned() else {
+                return false;
+            };
+            match op {
+                ArithOp::Add | ArithOp::Subtract | ArithOp::Multiply => true,
+                // Dividing the minimum value of a signed type by `-1` overflows
+                ArithOp::Divide | ArithOp::Remainder => ty.signedness.is_signed(),
+                _ => false,
+            }
+        }
+        Expr::UnaryOp {
+            expr,
+            op: UnaryOp::Neg,
+        } => matches!(infer[*expr].interned(), TyKind::Int(_)),
+        _ => false,
+    }
+}
+
+/// Returns true if a value of type `ty` contains references to garbage collected
+/// objects. Storing such a value in an object on the heap requires a write
+/// barrier.
+pub(crate) fn contains_references(db: &dyn HirDatabase, ty: &Ty) -> bool {
+    match ty.interned() {
+        TyKind::Struct(s) => match s.data(db.upcast()).memory_kind {
+            StructMemoryKind::Gc => true,
+            StructMemoryKind::Value => s
+                .fields(db)
+                .into_iter()
+                .any(|field| contains_references(db, &field.ty(db))),
+        },
+        TyKind::Array(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns true if `s` is a gc struct with fields that contain references.
+fn gc_struct_contains_references(db: &dyn HirDatabase, s: mun_hir::Struct) -> bool {
+    s.data(db.upcast()).memory_kind == StructMemoryKind::Gc
+        && s.fields(db)
+            .into_iter()
+            .any(|field| contains_references(db, &field.ty(db)))
+}
+
+/// Returns true if the specified expression stores references in an object on
+/// the heap, which requires a write barrier.
+fn needs_write_barrier(
+    db: &dyn HirDatabase,
+    expr_id: ExprId,
+    body: &Body,
+    infer: &InferenceResult,
+) -> bool {
+    match &body[expr_id] {
+        // Assigning to a local variable doesn't store anything on the heap
+        Expr::BinaryOp {
+            lhs,
+            op: Some(BinaryOp::Assignment { .. }),
+            ..
+        } => !matches!(body[*lhs], Expr::Path(_)) && contains_references(db, &infer[*lhs]),
+        // Struct literals of gc structs store their fields in a new object on the heap
+        Expr::Call { callee, .. } => match infer[*callee].as_callable_def() {
+            Some(mun_hir::CallableDef::Struct(s)) => gc_struct_contains_references(db, s),
+            _ => false,
+        },
+        Expr::RecordLit { .. } => infer[expr_id]
+            .as_struct()
+            .map_or(false, |s| gc_struct_contains_references(db, s)),
+        Expr::Array(_) => infer[expr_id]
+            .as_array()
+            .map_or(false, |element_ty| contains_references(db, element_ty)),
+        _ => false,
+    }
+}
+
 /// Iterates over all expressions and stores information on which intrinsics
 /// they use in `entries`.
 #[allow(clippy::too_many_arguments)]
@@ -33,27 +131,48 @@ fn collect_expr<'ink>(
     db: &'_ dyn HirDatabase,
     intrinsics: &mut IntrinsicsMap<'ink>,
     needs_alloc: &mut bool,
+    overflow_checks: bool,
+    heap_limit_checks: bool,
+    interrupt_checks: bool,
     expr_id: ExprId,
     body: &Arc<Body>,
     infer: &InferenceResult,
 ) {
     let expr = &body[expr_id];
+    let new = new_intrinsic(heap_limit_checks);
 
     // If this expression is a call, store it in the dispatch table
     if let Expr::Call { callee, .. } = expr {
         match infer[*callee].as_callable_def() {
             Some(mun_hir::CallableDef::Struct(_)) => {
-                collect_intrinsic(context, target, &intrinsics::new, intrinsics);
+                collect_intrinsic(context, target, new, intrinsics);
                 // self.collect_intrinsic(module, entries, &intrinsics::drop);
                 *needs_alloc = true;
             }
+            // The caller returns immediately if the callee was interrupted
+            Some(mun_hir::CallableDef::Function(f)) if interrupt_checks && !f.is_extern(db) => {
+                collect_intrinsic(context, target, &intrinsics::execution_state, intrinsics);
+            }
             Some(mun_hir::CallableDef::Function(_)) => (),
+            // Built-in integer methods are generated inline
+            None if infer.int_method(expr_id).is_some() => (),
             None => panic!("expected a callable expression"),
         }
     }
 
+    // Overloaded operators are calls to the method that implements them
+    if let Some(method) = infer.method_resolution(expr_id) {
+        if interrupt_checks && !method.is_extern(db) {
+            collect_intrinsic(context, target, &intrinsics::execution_state, intrinsics);
+        }
+    }
+
+    if may_trap_on_overflow(expr_id, expr, infer, overflow_checks) {
+        collect_intrinsic(context, target, &intrinsics::overflow_trap, intrinsics);
+    }
+
     if let Expr::RecordLit { .. } = expr {
-        collect_intrinsic(context, target, &intrinsics::new, intrinsics);
+        collect_intrinsic(context, target, new, intrinsics);
         // self.collect_intrinsic(module, entries, &intrinsics::drop);
         *needs_alloc = true;
     }
@@ -63,14 +182,24 @@ fn collect_expr<'ink>(
         if let Some((ValueNs::StructId(_), _)) =
             resolver.resolve_path_as_value_fully(db.upcast(), path)
         {
-            collect_intrinsic(context, target, &intrinsics::new, intrinsics);
+            collect_intrinsic(context, target, new, intrinsics);
             // self.collect_intrinsic( module, entries, &intrinsics::drop);
             *needs_alloc = true;
         }
     }
 
     if let Expr::Array(_) = expr {
-        collect_intrinsic(context, target, &intrinsics::new_array, intrinsics);
+        collect_intrinsic(
+            context,
+            target,
+            new_array_intrinsic(heap_limit_checks),
+            intrinsics,
+        );
+        *needs_alloc = true;
+    }
+
+    if needs_write_barrier(db, expr_id, body, infer) {
+        collect_intrinsic(context, target, &intrinsics::gc_write_barrier, intrinsics);
         *needs_alloc = true;
     }
 
@@ -82,6 +211,9 @@ fn collect_expr<'ink>(
             db,
             intrinsics,
             needs_alloc,
+            overflow_checks,
+            heap_limit_checks,
+            interrupt_checks,
             expr_id,
             body,
             infer,
@@ -89,36 +221,98 @@ fn collect_expr<'ink>(
     });
 }
 
-/// Collects all intrinsics from the specified `body`.
+/// Returns the intrinsic that allocates an object. If `heap_limit_checks` is
+/// set, allocations fail when they exceed the hard heap limit.
+pub(crate) fn new_intrinsic(heap_limit_checks: bool) -> &'static dyn Intrinsic {
+    if heap_limit_checks {
+        &intrinsics::try_new
+    } else {
+        &intrinsics::new
+    }
+}
+
+/// Returns the intrinsic that allocates an array. If `heap_limit_checks` is
+/// set, allocations fail when they exceed the hard heap limit.
+pub(crate) fn new_array_intrinsic(heap_limit_checks: bool) -> &'static dyn Intrinsic {
+    if heap_limit_checks {
+        &intrinsics::try_new_array
+    } else {
+        &intrinsics::new_array
+    }
+}
+
+/// Collects all intrinsics from the specified `body`. If `overflow_checks` is
+/// set, integer arithmetic traps into the runtime when it overflows. If
+/// `budget_checks` is set, the body consumes the execution budget. If
+/// `heap_limit_checks` is set, allocations fail when they exceed the hard heap
+/// limit. Unless one of them is set, functions are never interrupted, so calls
+/// don't check whether the callee was.
+#[allow(clippy::too_many_arguments)]
 pub fn collect_fn_body<'ink>(
     context: &'ink Context,
     target: TargetData,
     db: &dyn HirDatabase,
     intrinsics: &mut IntrinsicsMap<'ink>,
     needs_alloc: &mut bool,
+    overflow_checks: bool,
+    budget_checks: bool,
+    heap_limit_checks: bool,
     body: &Arc<Body>,
     infer: &InferenceResult,
 ) {
+    let interrupt_checks = overflow_checks || budget_checks || heap_limit_checks;
+    if budget_checks {
+        collect_intrinsic(context, &target, &intrinsics::execution_state, intrinsics);
+    }
+
     collect_expr(
         context,
         &target,
         db,
         intrinsics,
         needs_alloc,
+        overflow_checks,
+        heap_limit_checks,
+        interrupt_checks,
         body.body_expr(),
         body,
         infer,
     );
 }
 
-/// Collects all intrinsics from a function wrapper body.
+/// Collects all intrinsics from a function wrapper body. `ret_ty` is the
+/// return type of the wrapped function. See [`collect_fn_body`] for
+/// `heap_limit_checks` and `interrupt_checks`.
+#[allow(clippy::too_many_arguments)]
 pub fn collect_wrapper_body<'ink>(
     context: &'ink Context,
     target: TargetData,
+    db: &dyn HirDatabase,
+    ret_ty: &Ty,
     intrinsics: &mut IntrinsicsMap<'ink>,
     needs_alloc: &mut bool,
+    heap_limit_checks: bool,
+    interrupt_checks: bool,
 ) {
-    collect_intrinsic(context, &target, &intrinsics::new, intrinsics);
+    collect_intrinsic(
+        context,
+        &target,
+        new_intrinsic(heap_limit_checks),
+        intrinsics,
+    );
     // self.collect_intrinsic(entries, &intrinsics::drop, module);
     *needs_alloc = true;
+
+    // The wrapper returns immediately if the wrapped function was interrupted
+    if interrupt_checks {
+        collect_intrinsic(context, &target, &intrinsics::execution_state, intrinsics);
+    }
+
+    // Value structs that are returned are copied into a new object on the heap
+    let returns_value_struct = ret_ty.as_struct().map_or(false, |s| {
+        s.data(db.upcast()).memory_kind == StructMemoryKind::Value
+    });
+    if returns_value_struct && contains_references(db, ret_ty) {
+        collect_intrinsic(context, &target, &intrinsics::gc_write_barrier, intrinsics);
+    }
 }
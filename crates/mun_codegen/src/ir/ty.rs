@@ -11,7 +11,8 @@ use inkwell::{
 };
 use mun_abi::Guid;
 use mun_hir::{
-    FloatBitness, HirDatabase, HirDisplay, IntBitness, ResolveBitness, Signedness, Ty, TyKind,
+    FloatBitness, HirDatabase, HirDisplay, IntBitness, ResolveBitness, Signedness,
+    StructMemoryKind, Ty, TyKind,
 };
 use smallvec::SmallVec;
 
@@ -407,8 +408,14 @@ pub fn guid_from_struct(db: &dyn HirDatabase, s: mun_hir::Struct) -> Guid {
         })
         .collect();
 
+    // A value struct and a gc struct with the same fields are different types
+    let memory_kind = match s.data(db.upcast()).memory_kind {
+        StructMemoryKind::Gc => "",
+        StructMemoryKind::Value => "(value)",
+    };
+
     Guid::from_str(&format!(
-        "struct {name}{{{fields}}}",
+        "struct{memory_kind} {name}{{{fields}}}",
         name = &name,
         fields = fields.join(",")
     ))
@@ -154,6 +154,8 @@ impl<'db, 'ink, 't> TypeTableBuilder<'db, 'ink, 't> {
                     self.maybe_collect_fn_signature(hir_fn);
                 }
                 Some(mun_hir::CallableDef::Struct(_)) => (),
+                // Built-in integer methods are generated inline
+                None if infer.int_method(expr_id).is_some() => (),
                 None => panic!("expected a callable expression"),
             }
         } else if let mun_hir::Expr::Array(..) = expr {
@@ -0,0 +1,183 @@
+//! Compiles assemblies into the running process with LLVM's ORC JIT, instead of
+//! linking them into munlibs.
+//!
+//! Every assembly is compiled into an object file by the regular code
+//! generation, which is then linked into its own LLJIT instance. All
+//! assemblies export the same entry points, so separate instances keep their
+//! symbols apart. This also allows a reloaded assembly to coexist with its
+//! previous version while the runtime relinks. Dropping an assembly disposes
+//! of its instance, which removes its code from the process.
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    ptr,
+};
+
+use inkwell::targets::{InitializationConfig, Target};
+use llvm_sys::{
+    core::LLVMCreateMemoryBufferWithMemoryRangeCopy,
+    error::{LLVMDisposeErrorMessage, LLVMErrorRef, LLVMGetErrorMessage},
+    orc2::{
+        lljit::{
+            LLVMOrcCreateLLJIT, LLVMOrcDisposeLLJIT, LLVMOrcLLJITAddObjectFile,
+            LLVMOrcLLJITGetGlobalPrefix, LLVMOrcLLJITGetMainJITDylib, LLVMOrcLLJITLookup,
+            LLVMOrcLLJITRef,
+        },
+        LLVMOrcCreateDynamicLibrarySearchGeneratorForProcess, LLVMOrcExecutorAddress,
+        LLVMOrcJITDylibAddGenerator,
+    },
+};
+use mun_abi as abi;
+
+/// An error that occurs while compiling assemblies with the JIT.
+#[derive(Debug, thiserror::Error)]
+pub enum JitError {
+    /// The JIT could not be created for the host
+    #[error("could not create JIT: {0}")]
+    CreateJit(String),
+    /// An assembly could not be added to the JIT
+    #[error("could not add assembly `{name}` to the JIT: {message}")]
+    AddAssembly { name: String, message: String },
+    /// An entry point of an assembly could not be linked
+    #[error("could not link `{symbol}` of assembly `{name}`: {message}")]
+    Lookup {
+        name: String,
+        symbol: String,
+        message: String,
+    },
+}
+
+/// Compiles assemblies into the running process. The assemblies have to be
+/// generated for the host target.
+#[derive(Clone)]
+pub struct JitEngine {
+    _private: (),
+}
+
+impl JitEngine {
+    /// Prepares the JIT for the host.
+    pub fn new() -> Result<Self, JitError> {
+        Target::initialize_native(&InitializationConfig::default()).map_err(JitError::CreateJit)?;
+        Ok(Self { _private: () })
+    }
+
+    /// Links the assembly called `name` from its `object` file into the running
+    /// process, and returns its entry points. Symbols that the assembly doesn't
+    /// define, like `memcpy`, are resolved from the running process. The
+    /// assembly is removed from the process when the returned [`JitAssembly`]
+    /// is dropped.
+    pub fn add_assembly(&self, name: &str, object: &[u8]) -> Result<JitAssembly, JitError> {
+        // SAFETY: Ownership of all created objects is transferred to the JIT, which is
+        // owned by the returned assembly.
+        unsafe {
+            let mut jit = ptr::null_mut();
+            into_result(LLVMOrcCreateLLJIT(&mut jit, ptr::null_mut()))
+                .map_err(JitError::CreateJit)?;
+
+            // Take ownership first, so the JIT is disposed of on failure
+            let jit = Jit(jit);
+
+            let mut process_symbols = ptr::null_mut();
+            into_result(LLVMOrcCreateDynamicLibrarySearchGeneratorForProcess(
+                &mut process_symbols,
+                LLVMOrcLLJITGetGlobalPrefix(jit.0),
+                None,
+                ptr::null_mut(),
+            ))
+            .map_err(JitError::CreateJit)?;
+            LLVMOrcJITDylibAddGenerator(LLVMOrcLLJITGetMainJITDylib(jit.0), process_symbols);
+
+            let buffer_name = CString::new(name).unwrap_or_default();
+            let buffer = LLVMCreateMemoryBufferWithMemoryRangeCopy(
+                object.as_ptr().cast::<c_char>(),
+                object.len(),
+                buffer_name.as_ptr(),
+            );
+            into_result(LLVMOrcLLJITAddObjectFile(
+                jit.0,
+                LLVMOrcLLJITGetMainJITDylib(jit.0),
+                buffer,
+            ))
+            .map_err(|message| JitError::AddAssembly {
+                name: name.to_owned(),
+                message,
+            })?;
+
+            // Looking up the entry points links the assembly
+            let lookup = |symbol: &str| {
+                let symbol_name =
+                    CString::new(symbol).expect("symbol names don't contain null characters");
+                let mut address: LLVMOrcExecutorAddress = 0;
+                into_result(LLVMOrcLLJITLookup(
+                    jit.0,
+                    &mut address,
+                    symbol_name.as_ptr(),
+                ))
+                .map(|()| address as usize)
+                .map_err(|message| JitError::Lookup {
+                    name: name.to_owned(),
+                    symbol: symbol.to_owned(),
+                    message,
+                })
+            };
+
+            let entry_points = abi::EntryPoints {
+                get_version: std::mem::transmute(lookup(abi::GET_VERSION_FN_NAME)?),
+                get_info: std::mem::transmute(lookup(abi::GET_INFO_FN_NAME)?),
+                set_allocator_handle: std::mem::transmute(lookup(
+                    abi::SET_ALLOCATOR_HANDLE_FN_NAME,
+                )?),
+            };
+
+            Ok(JitAssembly {
+                entry_points,
+                _jit: jit,
+            })
+        }
+    }
+}
+
+/// An assembly that was compiled into the running process. Its code is
+/// removed when the `JitAssembly` is dropped.
+pub struct JitAssembly {
+    entry_points: abi::EntryPoints,
+    _jit: Jit,
+}
+
+impl JitAssembly {
+    /// Returns the entry points of the assembly. The function pointers are only
+    /// valid for as long as the assembly is alive.
+    pub fn entry_points(&self) -> abi::EntryPoints {
+        self.entry_points
+    }
+}
+
+/// The LLJIT instance that contains the code of a single assembly.
+struct Jit(LLVMOrcLLJITRef);
+
+// SAFETY: The ORC JIT is thread-safe.
+unsafe impl Send for Jit {}
+unsafe impl Sync for Jit {}
+
+impl Drop for Jit {
+    fn drop(&mut self) {
+        // SAFETY: The runtime no longer uses the code of the assembly.
+        unsafe {
+            if let Err(error) = into_result(LLVMOrcDisposeLLJIT(self.0)) {
+                log::error!("could not dispose JIT: {error}");
+            }
+        }
+    }
+}
+
+/// Converts an `LLVMErrorRef` into a result, consuming the error.
+unsafe fn into_result(error: LLVMErrorRef) -> Result<(), String> {
+    if error.is_null() {
+        return Ok(());
+    }
+
+    let message = LLVMGetErrorMessage(error);
+    let result = CStr::from_ptr(message).to_string_lossy().into_owned();
+    LLVMDisposeErrorMessage(message);
+    Err(result)
+}
@@ -1,11 +1,18 @@
 pub use inkwell::{builder::Builder, context::Context, module::Module, OptimizationLevel};
 
 pub use crate::{
-    assembly::{AssemblyIr, TargetAssembly},
+    assembly::{
+        check_static_library, AssemblyIr, AssemblyObject, StaticLibrary, StaticLibraryError,
+        TargetAssembly,
+    },
     code_gen::AssemblyBuilder,
     db::{CodeGenDatabase, CodeGenDatabaseStorage},
+    jit::{JitAssembly, JitEngine, JitError},
     module_group::ModuleGroup,
-    module_partition::{ModuleGroupId, ModulePartition},
+    module_partition::{
+        check_module_grouping, ModuleGroupId, ModuleGrouping, ModuleGroupingError, ModulePartition,
+        NamedModuleGroup,
+    },
 };
 
 /// This library generates machine code from HIR using inkwell which is a safe
@@ -24,7 +31,9 @@ mod test;
 pub mod value;
 
 mod apple;
+mod archive;
 pub(crate) mod intrinsics;
+mod jit;
 mod linker;
 mod module_group;
 mod module_partition;
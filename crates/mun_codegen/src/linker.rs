@@ -1,6 +1,6 @@
 use std::{
     borrow::Cow,
-    fmt,
+    fmt, fs, io,
     path::{Path, PathBuf},
 };
 
@@ -8,7 +8,10 @@ use mun_abi as abi;
 use mun_target::{spec, spec::LinkerFlavor};
 use thiserror::Error;
 
-use crate::apple::get_apple_sdk_root;
+use crate::{
+    apple::get_apple_sdk_root,
+    archive::{write_archive, ArchiveKind, ArchiveMember},
+};
 
 #[derive(Error, Debug)]
 pub enum LinkerError {
@@ -20,6 +23,9 @@ pub enum LinkerError {
 
     /// Could not locate platform SDK
     PlatformSdkMissing(String),
+
+    /// Could not write a static library
+    ArchiveError(io::Error),
 }
 
 impl fmt::Display for LinkerError {
@@ -34,6 +40,7 @@ impl fmt::Display for LinkerError {
             LinkerError::PlatformSdkMissing(err) => {
                 write!(f, "could not find platform sdk: {err}")
             }
+            LinkerError::ArchiveError(err) => write!(f, "could not write static library: {err}"),
         }
     }
 }
@@ -43,9 +50,46 @@ pub fn create_with_target(target: &spec::Target) -> Box<dyn Linker> {
         LinkerFlavor::Ld => Box::new(LdLinker::new(target)),
         LinkerFlavor::Ld64 => Box::new(Ld64Linker::new(target)),
         LinkerFlavor::Msvc => Box::new(MsvcLinker::new(target)),
+        LinkerFlavor::WasmLd => Box::new(WasmLdLinker::new(target)),
     }
 }
 
+/// Bundles the object file at `object_path` into a static library at `path`,
+/// which is linked into the host by its native linker. The archive's symbol
+/// table only contains `exported_symbols`, because all other symbols are
+/// internal to the object file. MSVC targets must have been rejected by
+/// [`crate::check_static_library`].
+pub fn build_static_library(
+    target: &spec::Target,
+    object_path: &Path,
+    exported_symbols: &[&str],
+    path: &Path,
+) -> Result<(), LinkerError> {
+    debug_assert!(
+        !target.options.is_like_msvc,
+        "static libraries are not supported for MSVC targets"
+    );
+    let data = fs::read(object_path).map_err(LinkerError::ArchiveError)?;
+
+    // Apple platforms prefix C symbols with an underscore
+    let (kind, symbols): (_, Vec<String>) = if target.options.is_like_osx {
+        let symbols = exported_symbols.iter().map(|s| format!("_{s}")).collect();
+        (ArchiveKind::Bsd, symbols)
+    } else {
+        let symbols = exported_symbols.iter().map(|s| (*s).to_owned()).collect();
+        (ArchiveKind::Gnu, symbols)
+    };
+
+    let member = ArchiveMember {
+        name: "mun.o",
+        data: &data,
+        symbols: &symbols,
+    };
+
+    let mut file = fs::File::create(path).map_err(LinkerError::ArchiveError)?;
+    write_archive(&mut file, kind, &[member]).map_err(LinkerError::ArchiveError)
+}
+
 pub trait Linker {
     fn add_object(&mut self, path: &Path) -> Result<(), LinkerError>;
     fn build_shared_object(&mut self, path: &Path) -> Result<(), LinkerError>;
@@ -58,15 +102,32 @@ struct LdLinker {
 
 impl LdLinker {
     fn new(target: &spec::Target) -> Self {
-        LdLinker {
-            args: target
-                .options
-                .pre_link_args
-                .iter()
-                .cloned()
-                .map(Cow::into_owned)
-                .collect(),
+        let mut args: Vec<String> = target
+            .options
+            .pre_link_args
+            .iter()
+            .cloned()
+            .map(Cow::into_owned)
+            .collect();
+
+        // Explicitly select the emulation of the target, so cross-linking doesn't
+        // depend on the defaults of the host.
+        if let Some(emulation) = elf_emulation(&target.arch) {
+            args.push("-m".to_owned());
+            args.push(emulation.to_owned());
         }
+
+        LdLinker { args }
+    }
+}
+
+/// Returns the name of the ELF emulation that `lld` uses for `arch`.
+fn elf_emulation(arch: &str) -> Option<&'static str> {
+    match arch {
+        "x86_64" => Some("elf_x86_64"),
+        "aarch64" => Some("aarch64linux"),
+        "riscv64" => Some("elf64lriscv"),
+        _ => None,
     }
 }
 
@@ -255,3 +316,63 @@ impl Linker for MsvcLinker {
             .map_err(LinkerError::LinkError)
     }
 }
+
+struct WasmLdLinker {
+    args: Vec<String>,
+}
+
+impl WasmLdLinker {
+    fn new(target: &spec::Target) -> Self {
+        WasmLdLinker {
+            args: target
+                .options
+                .pre_link_args
+                .iter()
+                .cloned()
+                .map(Cow::into_owned)
+                .collect(),
+        }
+    }
+}
+
+impl Linker for WasmLdLinker {
+    fn add_object(&mut self, path: &Path) -> Result<(), LinkerError> {
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| LinkerError::PathError(path.to_owned()))?
+            .to_owned();
+        self.args.push(path_str);
+        Ok(())
+    }
+
+    fn build_shared_object(&mut self, path: &Path) -> Result<(), LinkerError> {
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| LinkerError::PathError(path.to_owned()))?;
+
+        // A munlib is a module without a start function, that only exports the entry
+        // points of the assembly. The intrinsics of the runtime are imported from the
+        // `abi::INTRINSICS_WASM_MODULE` module. Other symbols that the assembly
+        // doesn't define, like `memcpy`, are imported from the host.
+        self.args.push("--no-entry".to_owned());
+        self.args.push("--allow-undefined".to_owned());
+        self.args
+            .push(format!("--export={}", abi::GET_INFO_FN_NAME));
+        self.args
+            .push(format!("--export={}", abi::GET_VERSION_FN_NAME));
+        self.args
+            .push(format!("--export={}", abi::SET_ALLOCATOR_HANDLE_FN_NAME));
+
+        // Specify output path
+        self.args.push("-o".to_owned());
+        self.args.push(path_str.to_owned());
+
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> Result<(), LinkerError> {
+        lld_rs::link(lld_rs::LldFlavor::Wasm, &self.args)
+            .ok()
+            .map_err(LinkerError::LinkError)
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use mun_hir::HirDatabase;
 use mun_hir_input::{FileId, PackageSet, SourceDatabase, SourceRoot, SourceRootId};
@@ -8,7 +8,7 @@ use parking_lot::Mutex;
 
 use crate::{
     db::{CodeGenDatabase, CodeGenDatabaseStorage},
-    OptimizationLevel,
+    ModuleGrouping, OptimizationLevel,
 };
 
 /// A mock implementation of the IR database. It can be used to set up a simple
@@ -72,6 +72,12 @@ impl Default for MockDatabase {
             events: Mutex::default(),
         };
         db.set_optimization_level(OptimizationLevel::Default);
+        db.set_budget_checks(false);
+        db.set_heap_limit_checks(false);
+        db.set_debug_info(false);
+        db.set_source_directory(PathBuf::new());
+        db.set_module_grouping(ModuleGrouping::default());
+        db.set_static_library_name(None);
         db.set_target(Target::host_target().unwrap());
         db
     }
@@ -79,6 +79,31 @@ impl ModuleGroup {
         self.ordered_modules.iter().copied()
     }
 
+    /// Returns an iterator over all functions defined in the modules of the
+    /// group, including the functions defined in `impl` blocks.
+    pub fn functions<'s>(
+        &'s self,
+        db: &'s dyn HirDatabase,
+    ) -> impl Iterator<Item = mun_hir::Function> + 's {
+        self.iter().flat_map(move |module| {
+            let functions = module
+                .declarations(db)
+                .into_iter()
+                .filter_map(|def| match def {
+                    mun_hir::ModuleDef::Function(f) => Some(f),
+                    _ => None,
+                });
+            let assoc_functions = module
+                .impls(db)
+                .into_iter()
+                .flat_map(move |imp| imp.items(db))
+                .map(|item| match item {
+                    mun_hir::AssocItem::Function(f) => f,
+                });
+            functions.chain(assoc_functions)
+        })
+    }
+
     /// Returns true if the specified function should be exported from the
     /// module group. This indicates that when queried the resulting
     /// assembly will expose this function.
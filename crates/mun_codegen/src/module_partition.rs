@@ -1,10 +1,37 @@
 use std::{ops::Index, sync::Arc};
 
 use mun_hir_input::FileId;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{CodeGenDatabase, ModuleGroup};
 
+/// Describes how the modules of a package are grouped into assemblies.
+#[derive(Default, PartialEq, Eq, Clone, Debug)]
+pub enum ModuleGrouping {
+    /// Every module is compiled into its own assembly. This enables the most
+    /// fine-grained hot reloading.
+    #[default]
+    PerModule,
+
+    /// All modules of a package are compiled into a single assembly.
+    PerPackage,
+
+    /// Modules are compiled into the named groups. Modules that are not part
+    /// of any group are compiled into their own assembly.
+    Groups(Vec<NamedModuleGroup>),
+}
+
+/// A named group of modules that is compiled into a single assembly.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct NamedModuleGroup {
+    /// The name of the group
+    pub name: String,
+
+    /// The full names of the modules in the group, e.g. `foo::bar`. The root
+    /// module is referred to by an empty string.
+    pub modules: Vec<String>,
+}
+
 /// A `ModuleGroupId` refers to a single [`ModuleGroup`] in a
 /// [`ModulePartition`]
 #[derive(Default, PartialEq, Eq, Clone, Debug, Hash, PartialOrd, Ord, Copy)]
@@ -74,20 +101,141 @@ impl Index<ModuleGroupId> for ModulePartition {
 /// Builds a module partition from the contents of the database
 pub(crate) fn build_partition(db: &dyn CodeGenDatabase) -> Arc<ModulePartition> {
     let mut partition = ModulePartition::default();
-    for module in mun_hir::Package::all(db.upcast())
+    // A static library contains all modules, which therefore call each other
+    // directly instead of through the dispatch table.
+    let grouping = if db.static_library_name().is_some() {
+        ModuleGrouping::PerPackage
+    } else {
+        db.module_grouping()
+    };
+    for package in mun_hir::Package::all(db.upcast()) {
+        let modules = package.modules(db.upcast());
+        match &grouping {
+            ModuleGrouping::PerModule => {
+                for module in modules {
+                    add_single_module_group(db, &mut partition, module);
+                }
+            }
+            ModuleGrouping::PerPackage => {
+                // The group is named after the root module, so the assembly that is loaded
+                // by the runtime keeps its name.
+                partition.add_group(
+                    db.upcast(),
+                    ModuleGroup::new(db.upcast(), String::from("mod"), modules),
+                );
+            }
+            ModuleGrouping::Groups(groups) => {
+                let mut ungrouped_modules = modules;
+                for group in groups {
+                    let (grouped_modules, remaining_modules): (Vec<_>, Vec<_>) = ungrouped_modules
+                        .into_iter()
+                        .partition(|module| group.modules.contains(&module_path(db, *module)));
+                    ungrouped_modules = remaining_modules;
+
+                    if !grouped_modules.is_empty() {
+                        partition.add_group(
+                            db.upcast(),
+                            ModuleGroup::new(db.upcast(), group.name.clone(), grouped_modules),
+                        );
+                    }
+                }
+
+                for module in ungrouped_modules {
+                    add_single_module_group(db, &mut partition, module);
+                }
+            }
+        }
+    }
+    Arc::new(partition)
+}
+
+/// An error in the module groups of a package.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ModuleGroupingError {
+    /// A group refers to a module that doesn't exist
+    #[error("module group '{group}' refers to module '{module}', which does not exist")]
+    UnknownModule { group: String, module: String },
+
+    /// A group would be written to the same assembly as another group or module
+    #[error("module group '{0}' has the same name as another assembly of the package")]
+    ConflictingName(String),
+}
+
+/// Checks that the module groups of the packages in the database refer to
+/// existing modules, and that every group is written to its own assembly.
+pub fn check_module_grouping(db: &dyn CodeGenDatabase) -> Result<(), ModuleGroupingError> {
+    let ModuleGrouping::Groups(groups) = db.module_grouping() else {
+        return Ok(());
+    };
+
+    let module_paths: FxHashSet<String> = mun_hir::Package::all(db.upcast())
         .into_iter()
         .flat_map(|package| package.modules(db.upcast()))
-    {
-        let name = if module.name(db.upcast()).is_some() {
-            module.full_name(db.upcast())
-        } else {
-            String::from("mod")
-        };
-
-        partition.add_group(
-            db.upcast(),
-            ModuleGroup::new(db.upcast(), name, vec![module]),
-        );
+        .map(|module| module_path(db, module))
+        .collect();
+    for group in &groups {
+        if let Some(module) = group
+            .modules
+            .iter()
+            .find(|module| !module_paths.contains(*module))
+        {
+            return Err(ModuleGroupingError::UnknownModule {
+                group: group.name.clone(),
+                module: display_module_path(module),
+            });
+        }
     }
-    Arc::new(partition)
+
+    let module_partition = db.module_partition();
+    let mut group_names = FxHashMap::default();
+    for (_, module_group) in module_partition.iter() {
+        // Ungrouped modules are added after the groups, so the conflicting assembly
+        // that was added first is always a group.
+        if let Some(name) =
+            group_names.insert(module_group.relative_file_path(), &module_group.name)
+        {
+            return Err(ModuleGroupingError::ConflictingName(name.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the path of `module` relative to the root module of its package,
+/// e.g. `foo::bar`. The path of the root module is an empty string.
+fn module_path(db: &dyn CodeGenDatabase, module: mun_hir::Module) -> String {
+    let mut names: Vec<_> = module
+        .path_to_root(db.upcast())
+        .into_iter()
+        .filter_map(|module| module.name(db.upcast()))
+        .collect();
+    names.reverse();
+    names.join("::")
+}
+
+/// Returns the path of a module as it would be written in Mun code.
+fn display_module_path(path: &str) -> String {
+    if path.is_empty() {
+        String::from("package")
+    } else {
+        format!("package::{path}")
+    }
+}
+
+/// Adds a group to the partition that only contains `module`.
+fn add_single_module_group(
+    db: &dyn CodeGenDatabase,
+    partition: &mut ModulePartition,
+    module: mun_hir::Module,
+) {
+    let name = if module.name(db.upcast()).is_some() {
+        module.full_name(db.upcast())
+    } else {
+        String::from("mod")
+    };
+
+    partition.add_group(
+        db.upcast(),
+        ModuleGroup::new(db.upcast(), name, vec![module]),
+    );
 }
@@ -1,6 +1,7 @@
 use std::cell::RefCell;
 
 use inkwell::{context::Context, OptimizationLevel};
+use mun_abi as abi;
 use mun_db::Upcast;
 use mun_hir::{diagnostics::DiagnosticSink, HirDatabase};
 use mun_hir_input::{SourceDatabase, WithFixture};
@@ -1070,6 +1071,238 @@ fn nested_private_recursive_fn_with_args() {
     );
 }
 
+#[test]
+fn debug_info() {
+    let mut db = MockDatabase::with_files(
+        r"
+    pub struct Foo { a: i32, children: [Foo] }
+    pub struct(value) Bar(f64, bool)
+
+    pub fn main(foo: Foo) -> i32 {
+        let bar = Bar(1.0, true);
+        if bar.1 {
+            let b = foo.a;
+            b
+        } else {
+            0
+        }
+    }
+    ",
+    );
+    db.set_optimization_level(OptimizationLevel::None);
+    db.set_target(Target::host_target().unwrap());
+    db.set_debug_info(true);
+
+    let llvm_context = Context::create();
+    let code_gen = CodeGenContext::new(&llvm_context, db.upcast());
+    let module_partition = db.module_partition();
+    let (_, module_group) = module_partition.iter().next().unwrap();
+    let group_ir = gen_file_group_ir(&code_gen, module_group);
+    let file_ir = gen_file_ir(&code_gen, &group_ir, module_group);
+    file_ir
+        .llvm_module
+        .verify()
+        .expect("the debug information must be valid");
+
+    let ir = file_ir.llvm_module.print_to_string().to_string();
+    for expected in [
+        "!\"Debug Info Version\"",
+        "!DISubprogram(name: \"main\"",
+        "!DILocalVariable(name: \"foo\", arg: 1",
+        "!DILocalVariable(name: \"bar\"",
+        "!DILocalVariable(name: \"b\"",
+        "!DILexicalBlock(",
+        "!DICompositeType(tag: DW_TAG_structure_type, name: \"Foo\"",
+        "!DICompositeType(tag: DW_TAG_structure_type, name: \"[Foo]\"",
+        "!DIDerivedType(tag: DW_TAG_member, name: \"children\"",
+        "!DIBasicType(name: \"f64\", size: 64, encoding: DW_ATE_float)",
+    ] {
+        assert!(ir.contains(expected), "missing `{expected}` in:\n{ir}");
+    }
+}
+
+#[test]
+fn heap_limit_checks() {
+    let text = r"
+    pub struct Foo { a: i32 }
+
+    fn make_foo(a: i32) -> Foo {
+        Foo { a }
+    }
+
+    pub fn main() -> i32 {
+        make_foo(3).a
+    }
+    ";
+
+    let ir = |heap_limit_checks: bool| {
+        let (mut db, _file_id) = MockDatabase::with_single_file(text);
+        db.set_optimization_level(OptimizationLevel::Default);
+        db.set_heap_limit_checks(heap_limit_checks);
+
+        let llvm_context = Context::create();
+        let code_gen = CodeGenContext::new(&llvm_context, db.upcast());
+        let module_partition = db.module_partition();
+        let (_, module_group) = module_partition.iter().next().unwrap();
+        let group_ir = gen_file_group_ir(&code_gen, module_group);
+        let file_ir = gen_file_ir(&code_gen, &group_ir, module_group);
+        file_ir.llvm_module.print_to_string().to_string()
+    };
+
+    // Without any checks, functions are never interrupted
+    let unchecked = ir(false);
+    assert!(unchecked.contains("%new_ptr"), "{unchecked}");
+    assert!(!unchecked.contains("try_new"), "{unchecked}");
+    assert!(!unchecked.contains("execution_state"), "{unchecked}");
+
+    // Failed allocations interrupt the function, so callers check for interrupts
+    let checked = ir(true);
+    assert!(checked.contains("%try_new_ptr"), "{checked}");
+    assert!(checked.contains("is_allocation_failed"), "{checked}");
+    assert!(checked.contains("is_interrupted"), "{checked}");
+}
+
+#[test]
+fn static_library() {
+    let mut db = MockDatabase::with_files(
+        r"
+    //- /mod.mun
+    pub fn main() -> i32 { foo::bar() }
+
+    //- /foo.mun
+    pub fn bar() -> i32 { 5 }
+    ",
+    );
+    db.set_target(Target::host_target().unwrap());
+    db.set_static_library_name(Some(String::from("my-game")));
+
+    // All modules are compiled into a single assembly
+    let module_partition = db.module_partition();
+    assert_eq!(module_partition.iter().count(), 1);
+
+    let (module_group_id, _) = module_partition.iter().next().unwrap();
+    let ir = std::fs::read_to_string(db.assembly_ir(module_group_id).path()).unwrap();
+    assert!(
+        ir.contains("define i8* @mun_register_my_game()"),
+        "missing registration function in:\n{ir}"
+    );
+    assert!(
+        ir.contains("define internal i32 @get_version()"),
+        "entry points must be internal in:\n{ir}"
+    );
+
+    let archive = std::fs::read(db.static_library().path()).unwrap();
+    assert!(archive.starts_with(b"!<arch>\n"));
+}
+
+#[test]
+fn static_library_unsupported_target() {
+    let mut db = MockDatabase::with_files(
+        r"
+    //- /mod.mun
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+    db.set_static_library_name(Some(String::from("my-game")));
+
+    db.set_target(Target::search("x86_64-unknown-linux-gnu").unwrap());
+    assert!(crate::check_static_library(&db).is_ok());
+
+    // MSVC expects COFF archives, which cannot be written
+    db.set_target(Target::search("x86_64-pc-windows-msvc").unwrap());
+    assert!(crate::check_static_library(&db).is_err());
+}
+
+#[test]
+fn cross_compile_linux() {
+    // The `e_machine` field of the ELF header, for every supported Linux target
+    const TARGETS: [(&str, u16); 3] = [
+        ("x86_64-unknown-linux-gnu", 62),
+        ("aarch64-unknown-linux-gnu", 183),
+        ("riscv64gc-unknown-linux-gnu", 243),
+    ];
+
+    for (triple, machine) in TARGETS {
+        let mut db = MockDatabase::with_files(
+            r"
+        //- /mod.mun
+        pub fn main() -> i32 { 5 }
+        ",
+        );
+        db.set_target(Target::search(triple).unwrap());
+
+        let module_partition = db.module_partition();
+        let (module_group_id, _) = module_partition.iter().next().unwrap();
+        let munlib = std::fs::read(db.target_assembly(module_group_id).path()).unwrap();
+
+        assert!(munlib.starts_with(b"\x7fELF"), "{triple}: not an ELF file");
+        assert_eq!(
+            u16::from_le_bytes([munlib[18], munlib[19]]),
+            machine,
+            "{triple}: invalid machine type"
+        );
+    }
+}
+
+#[test]
+fn cross_compile_wasm() {
+    let mut db = MockDatabase::with_files(
+        r"
+    //- /mod.mun
+    pub struct Foo { a: i32 }
+
+    pub fn main() -> Foo { Foo { a: 5 } }
+    ",
+    );
+    db.set_target(Target::search("wasm32-unknown-unknown").unwrap());
+
+    let module_partition = db.module_partition();
+    let (module_group_id, _) = module_partition.iter().next().unwrap();
+    let munlib = std::fs::read(db.target_assembly(module_group_id).path()).unwrap();
+    let (imports, exports) = wasm_imports_and_exports(&munlib);
+
+    // Intrinsics are imported from the runtime instead of being passed through the
+    // dispatch table
+    assert!(
+        imports.contains(&(abi::INTRINSICS_WASM_MODULE.to_owned(), String::from("new"))),
+        "missing import of `new` in {imports:?}",
+    );
+    for symbol in [
+        abi::GET_INFO_FN_NAME,
+        abi::GET_VERSION_FN_NAME,
+        abi::SET_ALLOCATOR_HANDLE_FN_NAME,
+    ] {
+        assert!(
+            exports.iter().any(|export| export == symbol),
+            "missing export `{symbol}` in {exports:?}",
+        );
+    }
+}
+
+/// Returns the module and name of every import, and the name of every export
+/// of a WebAssembly module.
+fn wasm_imports_and_exports(bytes: &[u8]) -> (Vec<(String, String)>, Vec<String>) {
+    let mut imports = Vec::new();
+    let mut exports = Vec::new();
+    for payload in wasmparser::Parser::new(0).parse_all(bytes) {
+        match payload.expect("invalid WebAssembly module") {
+            wasmparser::Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = import.expect("invalid import");
+                    imports.push((import.module.to_owned(), import.name.to_owned()));
+                }
+            }
+            wasmparser::Payload::ExportSection(reader) => {
+                for export in reader {
+                    exports.push(export.expect("invalid export").name.to_owned());
+                }
+            }
+            _ => {}
+        }
+    }
+    (imports, exports)
+}
+
 fn test_snapshot(name: &str, text: &str) {
     test_snapshot_with_optimization(name, text, OptimizationLevel::Default);
 }
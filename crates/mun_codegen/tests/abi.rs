@@ -61,7 +61,7 @@ fn test_abi_compatibility() {
             size_in_bits: 64,
             alignment: 4,
             data: Struct(StructInfo(
-              guid: "69b56992-5a33-7f3e-c3e5-a5c8deb1bc4e",
+              guid: "b5b7cedf-3b56-0fef-1b5b-1d26c712514b",
               fields: [
                 Field(
                   name: "a",
@@ -152,7 +152,7 @@ fn test_abi_compatibility() {
       type_lut: [
         Elem(
           name: "Bar",
-          type: Concrete("69b56992-5a33-7f3e-c3e5-a5c8deb1bc4e"),
+          type: Concrete("b5b7cedf-3b56-0fef-1b5b-1d26c712514b"),
         ),
         Elem(
           name: "Foo",
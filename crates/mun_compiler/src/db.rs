@@ -1,4 +1,4 @@
-use mun_codegen::{CodeGenDatabase, CodeGenDatabaseStorage};
+use mun_codegen::{CodeGenDatabase, CodeGenDatabaseStorage, ModuleGrouping};
 use mun_db::Upcast;
 use mun_hir::{salsa, HirDatabase};
 use mun_hir_input::SourceDatabase;
@@ -57,6 +57,9 @@ impl CompilerDatabase {
 
         // Set the initial configuration
         db.set_config(config);
+        db.set_source_directory(std::env::current_dir().unwrap_or_default());
+        db.set_module_grouping(ModuleGrouping::default());
+        db.set_static_library_name(config.static_library.then(|| String::from("mod")));
 
         db
     }
@@ -65,6 +68,9 @@ impl CompilerDatabase {
     pub fn set_config(&mut self, config: &Config) {
         self.set_target(config.target.clone());
         self.set_optimization_level(config.optimization_lvl);
+        self.set_budget_checks(config.budget_checks);
+        self.set_heap_limit_checks(config.heap_limit_checks);
+        self.set_debug_info(config.debug_info);
     }
 }
 
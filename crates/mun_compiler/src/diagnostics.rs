@@ -2,7 +2,7 @@
 mod tests {
     use std::io::Cursor;
 
-    use crate::{Config, DisplayColor, Driver, PathOrInline, RelativePathBuf};
+    use crate::{Config, DisplayColor, Driver, MessageFormat, PathOrInline, RelativePathBuf};
 
     /// Compile passed source code and return all compilation errors
     fn compilation_errors(source_code: &str) -> String {
@@ -27,6 +27,31 @@ mod tests {
         String::from_utf8(compilation_errors).unwrap()
     }
 
+    /// Compile passed source code and return all compilation errors as parsed
+    /// JSON objects
+    fn json_compilation_errors(source_code: &str) -> Vec<serde_json::Value> {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: source_code.to_owned(),
+        };
+
+        let (driver, _) = Driver::with_file(Config::default(), input).unwrap();
+
+        let mut compilation_errors = Vec::<u8>::new();
+        let _ = driver
+            .emit_diagnostics_with_format(
+                &mut Cursor::new(&mut compilation_errors),
+                MessageFormat::Json,
+            )
+            .unwrap();
+
+        String::from_utf8(compilation_errors)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
     #[test]
     fn test_syntax_error() {
         insta::assert_snapshot!(compilation_errors("\n\nfn main(\n struct Foo\n"));
@@ -107,4 +132,52 @@ mod tests {
     fn test_expected_function() {
         insta::assert_snapshot!(compilation_errors("\n\nfn foo() { let a = 3; a(); }"));
     }
+
+    #[test]
+    fn test_has_errors() {
+        let has_errors = |source_code: &str| {
+            let input = PathOrInline::Inline {
+                rel_path: RelativePathBuf::from("main.mun"),
+                contents: source_code.to_owned(),
+            };
+            let (driver, _) = Driver::with_file(Config::default(), input).unwrap();
+            driver.has_errors()
+        };
+
+        assert!(!has_errors("pub fn main() -> i32 { 5 }"));
+        assert!(has_errors("fn main(\n struct Foo\n"));
+        assert!(has_errors("fn main() {\n    let a: i32 = true;\n}"));
+        assert!(has_errors("pub const A: u8 = 256;"));
+    }
+
+    #[test]
+    fn test_json_syntax_error() {
+        let errors = json_compilation_errors("fn main(\n struct Foo\n");
+        assert!(!errors.is_empty());
+        for error in &errors {
+            assert_eq!(error["severity"], "error");
+            assert_eq!(error["code"], "E0001");
+            assert_ne!(error["message"], "syntax error");
+            assert_eq!(error["spans"][0]["file"], "main.mun");
+            assert_eq!(error["spans"][0]["is_primary"], true);
+        }
+    }
+
+    #[test]
+    fn test_json_mismatched_type() {
+        let errors = json_compilation_errors("fn main() {\n    let a: i32 = true;\n}");
+        assert_eq!(errors.len(), 1);
+
+        let error = &errors[0];
+        assert_eq!(error["code"], "E0013");
+        assert_eq!(error["message"], "expected `i32`, found `bool`");
+
+        let span = &error["spans"][0];
+        assert_eq!(span["line_start"], 2);
+        assert_eq!(span["column_start"], 18);
+        assert_eq!(span["line_end"], 2);
+        assert_eq!(span["column_end"], 22);
+        assert_eq!(span["byte_start"], 29);
+        assert_eq!(span["byte_end"], 33);
+    }
 }
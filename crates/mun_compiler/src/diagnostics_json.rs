@@ -0,0 +1,137 @@
+use mun_diagnostics::DiagnosticForWith;
+use mun_hir::HirDatabase;
+use mun_hir_input::FileId;
+use mun_syntax::{SyntaxError, TextRange};
+use serde_derive::Serialize;
+
+/// A diagnostic in a format that can be consumed by other tools.
+#[derive(Serialize)]
+struct JsonDiagnostic {
+    /// The severity of the diagnostic, e.g. `error`
+    severity: &'static str,
+
+    /// The stable code that identifies the kind of diagnostic, e.g. `E0012`
+    code: &'static str,
+
+    /// The primary message of the diagnostic
+    message: String,
+
+    /// The locations in the source code that the diagnostic refers to. The
+    /// primary span is always the first.
+    spans: Vec<JsonSpan>,
+
+    /// Additional information about the diagnostic
+    notes: Vec<String>,
+}
+
+/// A range of source code that a diagnostic refers to. Lines and columns are
+/// one-based, and columns are counted in UTF-16 code units.
+#[derive(Serialize)]
+struct JsonSpan {
+    /// The path of the file, relative to the source directory
+    file: String,
+    byte_start: u32,
+    byte_end: u32,
+    line_start: u32,
+    column_start: u32,
+    line_end: u32,
+    column_end: u32,
+    is_primary: bool,
+    label: Option<String>,
+}
+
+impl JsonSpan {
+    fn new(
+        db: &impl HirDatabase,
+        file_id: FileId,
+        range: TextRange,
+        is_primary: bool,
+        label: Option<String>,
+    ) -> Self {
+        let line_index = db.line_index(file_id);
+        let start = line_index.line_col(range.start());
+        let end = line_index.line_col(range.end());
+        JsonSpan {
+            file: db.file_relative_path(file_id).to_string(),
+            byte_start: range.start().into(),
+            byte_end: range.end().into(),
+            line_start: start.line + 1,
+            column_start: start.col_utf16 + 1,
+            line_end: end.line + 1,
+            column_end: end.col_utf16 + 1,
+            is_primary,
+            label,
+        }
+    }
+}
+
+/// Writes the specified syntax error as a single line of JSON to the output
+/// stream.
+pub(crate) fn emit_syntax_error_json(
+    syntax_error: &SyntaxError,
+    db: &impl HirDatabase,
+    file_id: FileId,
+    writer: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let location = syntax_error.location();
+    let range = TextRange::new(location.offset(), location.end_offset());
+    let diagnostic = JsonDiagnostic {
+        severity: "error",
+        code: syntax_error.code(),
+        message: syntax_error.to_string(),
+        spans: vec![JsonSpan::new(db, file_id, range, true, None)],
+        notes: Vec::new(),
+    };
+    write_json(&diagnostic, writer)
+}
+
+/// Writes a diagnostic that is a result of HIR validation as a single line of
+/// JSON to the output stream.
+pub(crate) fn emit_hir_diagnostic_json(
+    diagnostic: &dyn mun_hir::Diagnostic,
+    db: &impl HirDatabase,
+    file_id: FileId,
+    writer: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    diagnostic.with_diagnostic(db, |diagnostic| {
+        let primary_span = match diagnostic.primary_annotation() {
+            Some(annotation) => JsonSpan::new(
+                db,
+                file_id,
+                annotation.range,
+                true,
+                Some(annotation.message),
+            ),
+            None => JsonSpan::new(db, file_id, diagnostic.range(), true, None),
+        };
+        let secondary_spans = diagnostic
+            .secondary_annotations()
+            .into_iter()
+            .map(|annotation| {
+                JsonSpan::new(
+                    db,
+                    annotation.range.file_id,
+                    annotation.range.value,
+                    false,
+                    Some(annotation.message),
+                )
+            });
+
+        let diagnostic = JsonDiagnostic {
+            severity: "error",
+            code: diagnostic.code(),
+            message: diagnostic.title(),
+            spans: std::iter::once(primary_span)
+                .chain(secondary_spans)
+                .collect(),
+            notes: diagnostic.footer(),
+        };
+        write_json(&diagnostic, writer)
+    })
+}
+
+/// Writes `diagnostic` to the output stream, followed by a newline.
+fn write_json(diagnostic: &JsonDiagnostic, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, diagnostic)?;
+    writeln!(writer)
+}
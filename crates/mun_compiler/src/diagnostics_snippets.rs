@@ -23,7 +23,7 @@ pub(crate) fn emit_syntax_error(
 
     let snippet = Snippet {
         title: Some(Annotation {
-            id: None,
+            id: Some(syntax_error.code()),
             label: Some("syntax error"),
             annotation_type: AnnotationType::Error,
         }),
@@ -138,7 +138,7 @@ fn emit_diagnostic(
     // Construct an annotation snippet to be able to emit it.
     let snippet = Snippet {
         title: Some(Annotation {
-            id: None,
+            id: Some(diagnostic.code()),
             label: Some(&title),
             annotation_type: AnnotationType::Error,
         }),
@@ -1,8 +1,11 @@
 //! `Driver` is a stateful compiler frontend that enables incremental
 //! compilation by retaining state from previous compilation.
 
-use mun_codegen::{AssemblyIr, CodeGenDatabase, ModuleGroup, TargetAssembly};
-use mun_hir::{AstDatabase, DiagnosticSink, Module};
+use mun_codegen::{
+    AssemblyIr, AssemblyObject, CodeGenDatabase, ModuleGroup, ModuleGroupId, ModuleGrouping,
+    NamedModuleGroup, StaticLibrary, TargetAssembly,
+};
+use mun_hir::{AstDatabase, DiagnosticSink, HirDatabase, Module};
 use mun_hir_input::{FileId, PackageSet, SourceDatabase, SourceRoot, SourceRootId};
 use mun_paths::RelativePathBuf;
 
@@ -13,6 +16,7 @@ use crate::{
 
 mod config;
 mod display_color;
+mod message_format;
 
 use std::{
     collections::HashMap,
@@ -27,8 +31,11 @@ use mun_db::Upcast;
 use mun_project::{Package, LOCKFILE_NAME};
 use walkdir::WalkDir;
 
-pub use self::{config::Config, display_color::DisplayColor};
-use crate::diagnostics_snippets::{emit_hir_diagnostic, emit_syntax_error};
+pub use self::{config::Config, display_color::DisplayColor, message_format::MessageFormat};
+use crate::{
+    diagnostics_json::{emit_hir_diagnostic_json, emit_syntax_error_json},
+    diagnostics_snippets::{emit_hir_diagnostic, emit_syntax_error},
+};
 
 pub const WORKSPACE: SourceRootId = SourceRootId(0);
 
@@ -41,7 +48,7 @@ pub struct Driver {
     file_id_to_path: HashMap<FileId, RelativePathBuf>,
     next_file_id: usize,
 
-    module_to_temp_assembly_path: HashMap<Module, PathBuf>,
+    module_group_to_temp_assembly_path: HashMap<ModuleGroup, PathBuf>,
 
     emit_ir: bool,
 }
@@ -56,7 +63,7 @@ impl Driver {
             path_to_file_id: HashMap::default(),
             file_id_to_path: HashMap::default(),
             next_file_id: 0,
-            module_to_temp_assembly_path: HashMap::default(),
+            module_group_to_temp_assembly_path: HashMap::default(),
             emit_ir: config.emit_ir,
         }
     }
@@ -71,10 +78,17 @@ impl Driver {
 
         // Get the path and contents of the path
         let (rel_path, text) = match path {
-            PathOrInline::Path(p) => (
-                RelativePathBuf::from_path("mod.mun").unwrap(),
-                std::fs::read_to_string(p)?,
-            ),
+            PathOrInline::Path(p) => {
+                if let Some(source_directory) = p.parent() {
+                    driver
+                        .db
+                        .set_source_directory(source_directory.to_path_buf());
+                }
+                (
+                    RelativePathBuf::from_path("mod.mun").unwrap(),
+                    std::fs::read_to_string(p)?,
+                )
+            }
             PathOrInline::Inline { rel_path, contents } => (rel_path, contents),
         };
 
@@ -110,7 +124,13 @@ impl Driver {
             .map_err(|e| anyhow::anyhow!("could not create package output directory: {}", e))?;
 
         // Construct the driver
+        let static_library = config.static_library;
         let mut driver = Driver::with_config(config, output_dir);
+        if static_library {
+            driver
+                .db
+                .set_static_library_name(Some(package.name().to_owned()));
+        }
 
         // Iterate over all files in the source directory of the package and store their
         // information in the database
@@ -118,6 +138,10 @@ impl Driver {
         if !source_directory.is_dir() {
             anyhow::bail!("the source directory does not exist")
         }
+        driver.db.set_source_directory(source_directory.clone());
+        driver
+            .db
+            .set_module_grouping(module_grouping(package.manifest().module_grouping()));
 
         for source_file_path in iter_source_files(&source_directory) {
             let relative_path = compute_source_relative_path(&source_directory, &source_file_path)?;
@@ -150,6 +174,12 @@ impl Driver {
 
         Ok((package, driver))
     }
+
+    /// Returns the database that contains the semantic information of the
+    /// source files, e.g. to interpret their functions.
+    pub fn database(&self) -> &dyn HirDatabase {
+        self.db.upcast()
+    }
 }
 
 impl Driver {
@@ -213,7 +243,16 @@ impl Driver {
         writer: &mut dyn std::io::Write,
         display_color: DisplayColor,
     ) -> Result<bool, anyhow::Error> {
-        let emit_colors = display_color.should_enable();
+        self.emit_diagnostics_with_format(writer, MessageFormat::Human(display_color))
+    }
+
+    /// Emits all diagnostic messages currently in the database in the specified
+    /// format; returns true if errors were emitted.
+    pub fn emit_diagnostics_with_format(
+        &self,
+        writer: &mut dyn std::io::Write,
+        message_format: MessageFormat,
+    ) -> Result<bool, anyhow::Error> {
         let mut has_error = false;
 
         for package in mun_hir::Package::all(self.db.upcast()) {
@@ -226,14 +265,19 @@ impl Driver {
 
                     // Emit all syntax diagnostics
                     for syntax_error in parse.errors().iter() {
-                        emit_syntax_error(
-                            syntax_error,
-                            relative_file_path.as_str(),
-                            &source_code,
-                            &line_index,
-                            emit_colors,
-                            writer,
-                        )?;
+                        match message_format {
+                            MessageFormat::Human(display_color) => emit_syntax_error(
+                                syntax_error,
+                                relative_file_path.as_str(),
+                                &source_code,
+                                &line_index,
+                                display_color.should_enable(),
+                                writer,
+                            )?,
+                            MessageFormat::Json => {
+                                emit_syntax_error_json(syntax_error, &self.db, file_id, writer)?;
+                            }
+                        }
                         has_error = true;
                     }
 
@@ -243,9 +287,19 @@ impl Driver {
                         self.db.upcast(),
                         &mut DiagnosticSink::new(|d| {
                             has_error = true;
-                            if let Err(e) =
-                                emit_hir_diagnostic(d, &self.db, file_id, emit_colors, writer)
-                            {
+                            let result = match message_format {
+                                MessageFormat::Human(display_color) => emit_hir_diagnostic(
+                                    d,
+                                    &self.db,
+                                    file_id,
+                                    display_color.should_enable(),
+                                    writer,
+                                ),
+                                MessageFormat::Json => {
+                                    emit_hir_diagnostic_json(d, &self.db, file_id, writer)
+                                }
+                            };
+                            if let Err(e) = result {
                                 error = Some(e);
                             };
                         }),
@@ -263,6 +317,31 @@ impl Driver {
         Ok(has_error)
     }
 
+    /// Returns true if the database contains errors, without rendering any
+    /// diagnostic messages.
+    pub fn has_errors(&self) -> bool {
+        for package in mun_hir::Package::all(self.db.upcast()) {
+            for module in package.modules(self.db.upcast()) {
+                if let Some(file_id) = module.file_id(self.db.upcast()) {
+                    if !self.db.parse(file_id).errors().is_empty() {
+                        return true;
+                    }
+
+                    let mut has_error = false;
+                    module.diagnostics(
+                        self.db.upcast(),
+                        &mut DiagnosticSink::new(|_| has_error = true),
+                    );
+                    if has_error {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
     /// Returns all diagnostics as a human readable string
     pub fn emit_diagnostics_to_string(
         &self,
@@ -326,6 +405,39 @@ impl Driver {
             .with_extension(AssemblyIr::EXTENSION)
     }
 
+    /// Get the path of the assembly that contains the root module of the
+    /// package. This is the assembly that a runtime loads first.
+    pub fn root_assembly_path(&self) -> PathBuf {
+        let package = mun_hir::Package::all(self.db.upcast())
+            .into_iter()
+            .next()
+            .expect("the driver contains a package");
+        self.assembly_output_path(package.root_module(self.db.upcast()))
+    }
+
+    /// Returns the object file of the assembly that the driver writes to
+    /// `assembly_path`, which can be compiled into the running process by a
+    /// [`mun_codegen::JitEngine`] instead. Returns `None` if there is no such
+    /// assembly.
+    pub fn assembly_object(&self, assembly_path: &Path) -> Option<Arc<AssemblyObject>> {
+        let module_partition = self.db.module_partition();
+        let (module_group_id, _) = module_partition.iter().find(|(_, module_group)| {
+            self.path_for_module_group(module_group)
+                .with_extension(TargetAssembly::EXTENSION)
+                == assembly_path
+        })?;
+        Some(self.db.assembly_object(module_group_id))
+    }
+
+    /// Returns the output path of the static library, if the driver builds one
+    /// instead of munlibs.
+    pub fn static_library_output_path(&self) -> Option<PathBuf> {
+        self.db.static_library_name().map(|library_name| {
+            self.out_dir
+                .join(StaticLibrary::file_name(&self.db.target(), &library_name))
+        })
+    }
+
     /// Returns the output path for the specified module group without an
     /// extension
     fn path_for_module_group(&self, module_group: &ModuleGroup) -> PathBuf {
@@ -335,16 +447,33 @@ impl Driver {
     /// Writes all assemblies. If `force` is false, the binary will not be
     /// written if there are no changes since last time it was written.
     pub fn write_all_assemblies(&mut self, force: bool) -> Result<(), anyhow::Error> {
+        // Code generation assumes that the package is free of errors, e.g. that all
+        // constants can be evaluated.
+        if self.has_errors() {
+            return Err(anyhow::anyhow!(
+                "could not write assemblies because the package contains errors"
+            ));
+        }
+        mun_codegen::check_module_grouping(&self.db)?;
+
         let _lock = self.acquire_filesystem_output_lock();
 
+        if !self.emit_ir {
+            if let Some(static_library_path) = self.static_library_output_path() {
+                mun_codegen::check_static_library(&self.db)?;
+                let static_library = self.db.static_library();
+                static_library.copy_to(static_library_path)?;
+                return Ok(());
+            }
+        }
+
         // Create a copy of all current files
-        for package in mun_hir::Package::all(self.db.upcast()) {
-            for module in package.modules(self.db.upcast()) {
-                if self.emit_ir {
-                    self.write_assembly_ir(module)?;
-                } else {
-                    self.write_target_assembly(module, force)?;
-                }
+        let module_partition = self.db.module_partition();
+        for (module_group_id, _) in module_partition.iter() {
+            if self.emit_ir {
+                self.write_assembly_ir(module_group_id)?;
+            } else {
+                self.write_target_assembly(module_group_id, force)?;
             }
         }
 
@@ -376,24 +505,19 @@ impl Driver {
         }
     }
 
-    /// Generates an assembly for the target machine and specified module and
-    /// stores it in the output location. If `force` is false, the binary
+    /// Generates an assembly for the target machine and specified module group
+    /// and stores it in the output location. If `force` is false, the binary
     /// will not be written if there are no changes since last time it was
     /// written. Returns `true` if the assembly was written, `false`
     /// if it was up to date.
     fn write_target_assembly(
         &mut self,
-        module: Module,
+        module_group_id: ModuleGroupId,
         force: bool,
     ) -> Result<bool, anyhow::Error> {
-        log::trace!("writing target assembly for {:?}", module);
-
-        // Find the module group to which the module belongs
         let module_partition = self.db.module_partition();
-        let module_group_id = module_partition
-            .group_for_module(module)
-            .expect("could not find the module in the module partition");
         let module_group = &module_partition[module_group_id];
+        log::trace!("writing target assembly for {}", module_group.name);
 
         // Get the compiled assembly
         let assembly = self.db.target_assembly(module_group_id);
@@ -407,8 +531,8 @@ impl Driver {
         if !force
             && assembly_path.is_file()
             && self
-                .module_to_temp_assembly_path
-                .get(&module)
+                .module_group_to_temp_assembly_path
+                .get(module_group)
                 .map(AsRef::as_ref)
                 == Some(assembly.path())
         {
@@ -419,23 +543,18 @@ impl Driver {
         assembly.copy_to(&assembly_path)?;
 
         // Store the information so we maybe don't have to write it next time
-        self.module_to_temp_assembly_path
-            .insert(module, assembly.path().to_path_buf());
+        self.module_group_to_temp_assembly_path
+            .insert(module_group.clone(), assembly.path().to_path_buf());
 
         Ok(true)
     }
 
-    /// Generates IR for the specified module and stores it in the output
+    /// Generates IR for the specified module group and stores it in the output
     /// location.
-    fn write_assembly_ir(&mut self, module: mun_hir::Module) -> Result<(), anyhow::Error> {
-        log::trace!("writing assembly IR for {:?}", module);
-
-        // Find the module group to which the module belongs
+    fn write_assembly_ir(&mut self, module_group_id: ModuleGroupId) -> Result<(), anyhow::Error> {
         let module_partition = self.db.module_partition();
-        let module_group_id = module_partition
-            .group_for_module(module)
-            .expect("could not find the module in the module partition");
         let module_group = &module_partition[module_group_id];
+        log::trace!("writing assembly IR for {}", module_group.name);
 
         // Get the compiled assembly
         let assembly_ir = self.db.assembly_ir(module_group_id);
@@ -537,3 +656,21 @@ pub fn iter_source_files(source_dir: &Path) -> impl Iterator<Item = PathBuf> {
         .filter(|e| is_source_file(e.path()))
         .map(|e| e.path().to_path_buf())
 }
+
+/// Converts the module grouping of a package manifest to the module grouping
+/// used by code generation.
+fn module_grouping(grouping: &mun_project::ModuleGrouping) -> ModuleGrouping {
+    match grouping {
+        mun_project::ModuleGrouping::PerModule => ModuleGrouping::PerModule,
+        mun_project::ModuleGrouping::PerPackage => ModuleGrouping::PerPackage,
+        mun_project::ModuleGrouping::Groups(groups) => ModuleGrouping::Groups(
+            groups
+                .iter()
+                .map(|group| NamedModuleGroup {
+                    name: group.name.clone(),
+                    modules: group.modules.clone(),
+                })
+                .collect(),
+        ),
+    }
+}
@@ -18,6 +18,23 @@ pub struct Config {
 
     /// Whether or not to emit an IR file instead of a munlib.
     pub emit_ir: bool,
+
+    /// Whether or not to instrument the generated code so that its execution
+    /// can be limited by the runtime (see `Runtime::invoke_with_budget`).
+    pub budget_checks: bool,
+
+    /// Whether or not to check allocations of the generated code against the
+    /// hard heap limit of the runtime, so exceeding the limit interrupts the
+    /// code (see `RuntimeBuilder::heap_limits`).
+    pub heap_limit_checks: bool,
+
+    /// Whether or not to emit DWARF debug information, so the generated code
+    /// can be debugged with gdb or lldb.
+    pub debug_info: bool,
+
+    /// Whether or not to emit a static library that is linked into the host,
+    /// instead of munlibs that are loaded by the runtime.
+    pub static_library: bool,
 }
 
 impl Default for Config {
@@ -30,6 +47,10 @@ impl Default for Config {
             optimization_lvl: OptimizationLevel::Default,
             out_dir: None,
             emit_ir: false,
+            budget_checks: false,
+            heap_limit_checks: false,
+            debug_info: false,
+            static_library: false,
         }
     }
 }
@@ -0,0 +1,25 @@
+use std::io;
+
+use super::DisplayColor;
+
+/// The format in which diagnostics are emitted.
+#[derive(Debug, Clone, Copy)]
+pub enum MessageFormat {
+    /// Human-readable snippets of the source code
+    Human(DisplayColor),
+
+    /// A JSON object for every diagnostic, each on a separate line
+    Json,
+}
+
+impl MessageFormat {
+    /// Returns the stream to which diagnostics are emitted. Human-readable
+    /// diagnostics are written to stderr, but JSON diagnostics are written to
+    /// stdout, where tools expect the stream of messages.
+    pub fn output(&self) -> Box<dyn io::Write> {
+        match self {
+            MessageFormat::Human(_) => Box::new(io::stderr()),
+            MessageFormat::Json => Box::new(io::stdout()),
+        }
+    }
+}
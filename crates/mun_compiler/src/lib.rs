@@ -3,12 +3,12 @@
 
 mod db;
 pub mod diagnostics;
+mod diagnostics_json;
 mod diagnostics_snippets;
 mod driver;
 
 use std::{
     ffi::OsStr,
-    io::stderr,
     path::{Path, PathBuf},
 };
 
@@ -21,7 +21,7 @@ pub use mun_target::spec::Target;
 
 pub use crate::{
     db::CompilerDatabase,
-    driver::{Config, DisplayColor, Driver},
+    driver::{Config, DisplayColor, Driver, MessageFormat},
 };
 
 #[derive(Debug, Clone)]
@@ -90,12 +90,12 @@ pub fn ensure_package_output_dir(
 pub fn compile_manifest(
     manifest_path: &Path,
     config: Config,
-    emit_colors: DisplayColor,
+    message_format: MessageFormat,
 ) -> Result<bool, anyhow::Error> {
     let (_package, mut driver) = Driver::with_package_path(manifest_path, config)?;
 
     // Emit diagnostics. If one of the snippets is an error, abort gracefully.
-    if driver.emit_diagnostics(&mut stderr(), emit_colors)? {
+    if driver.emit_diagnostics_with_format(&mut message_format.output(), message_format)? {
         return Ok(false);
     };
 
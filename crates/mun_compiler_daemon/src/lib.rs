@@ -1,11 +1,10 @@
 use std::{
-    io::stderr,
     path::Path,
     sync::{mpsc::channel, Arc},
     time::Duration,
 };
 
-use mun_compiler::{compute_source_relative_path, is_source_file, Config, DisplayColor, Driver};
+use mun_compiler::{compute_source_relative_path, is_source_file, Config, Driver, MessageFormat};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 
 /// Compiles and watches the package at the specified path. Recompiles changes
@@ -13,7 +12,7 @@ use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 pub fn compile_and_watch_manifest(
     manifest_path: &Path,
     config: Config,
-    display_color: DisplayColor,
+    message_format: MessageFormat,
 ) -> Result<bool, anyhow::Error> {
     // Create the compiler driver
     let (package, mut driver) = Driver::with_package_path(manifest_path, config)?;
@@ -24,10 +23,15 @@ pub fn compile_and_watch_manifest(
     let source_directory = package.source_directory();
 
     watcher.watch(&source_directory, RecursiveMode::Recursive)?;
-    println!("Watching: {}", source_directory.display());
+    // Stdout only contains the JSON messages when they are requested
+    match message_format {
+        MessageFormat::Human(_) => println!("Watching: {}", source_directory.display()),
+        MessageFormat::Json => eprintln!("Watching: {}", source_directory.display()),
+    }
 
     // Emit all current errors, and write the assemblies if no errors occured
-    if !driver.emit_diagnostics(&mut stderr(), display_color)? {
+    let mut output = message_format.output();
+    if !driver.emit_diagnostics_with_format(&mut output, message_format)? {
         driver.write_all_assemblies(false)?;
     }
 
@@ -49,7 +53,7 @@ pub fn compile_and_watch_manifest(
                     let file_contents = std::fs::read_to_string(path)?;
                     log::info!("Modifying {}", relative_path);
                     driver.update_file(relative_path, file_contents);
-                    if !driver.emit_diagnostics(&mut stderr(), display_color)? {
+                    if !driver.emit_diagnostics_with_format(&mut output, message_format)? {
                         driver.write_all_assemblies(false)?;
                     }
                 }
@@ -58,7 +62,7 @@ pub fn compile_and_watch_manifest(
                     let file_contents = std::fs::read_to_string(path)?;
                     log::info!("Creating {}", relative_path);
                     driver.add_file(relative_path, file_contents);
-                    if !driver.emit_diagnostics(&mut stderr(), display_color)? {
+                    if !driver.emit_diagnostics_with_format(&mut output, message_format)? {
                         driver.write_all_assemblies(false)?;
                     }
                 }
@@ -74,7 +78,7 @@ pub fn compile_and_watch_manifest(
                     //     std::fs::remove_file(assembly_path)?;
                     // }
                     driver.remove_file(relative_path);
-                    driver.emit_diagnostics(&mut stderr(), display_color)?;
+                    driver.emit_diagnostics_with_format(&mut output, message_format)?;
                 }
                 Rename(ref from, ref to) => {
                     // Renaming is done by changing the relative path of the original source file
@@ -86,7 +90,7 @@ pub fn compile_and_watch_manifest(
 
                     log::info!("Renaming {} to {}", from_relative_path, to_relative_path,);
                     driver.rename(from_relative_path, to_relative_path);
-                    if !driver.emit_diagnostics(&mut stderr(), display_color)? {
+                    if !driver.emit_diagnostics_with_format(&mut output, message_format)? {
                         driver.write_all_assemblies(false)?;
                     }
                 }
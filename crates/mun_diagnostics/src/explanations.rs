@@ -0,0 +1,155 @@
+//! Long-form explanations of the diagnostics emitted by the compiler, indexed
+//! by their stable code. Every explanation is written in markdown and contains
+//! examples of erroneous code and how to fix it.
+//!
+//! Examples are fenced code blocks tagged `mun`. A block that is additionally
+//! tagged `compile_fail` and a code, e.g. `mun,compile_fail,E0013`, must
+//! produce a diagnostic with that code. All other `mun` blocks must compile
+//! without diagnostics. Blocks tagged `ignore` are not compiled at all.
+
+macro_rules! explanations {
+    ($($code:literal),* $(,)?) => {
+        /// All explanations, sorted by their code.
+        const EXPLANATIONS: &[(&str, &str)] = &[
+            $(($code, include_str!(concat!("explanations/", $code, ".md"))),)*
+        ];
+    };
+}
+
+explanations!(
+    "E0001", "E0002", "E0003", "E0004", "E0005", "E0006", "E0007", "E0008", "E0009", "E0010",
+    "E0011", "E0012", "E0013", "E0014", "E0015", "E0016", "E0017", "E0018", "E0019", "E0020",
+    "E0021", "E0022", "E0023", "E0024", "E0025", "E0026", "E0027", "E0028", "E0029", "E0030",
+    "E0031", "E0032", "E0033", "E0034", "E0035", "E0036", "E0037", "E0038", "E0039", "E0040",
+    "E0041", "E0042",
+);
+
+/// Returns the long-form explanation of the diagnostic with the specified
+/// `code`, e.g. `E0012`, or `None` if no diagnostic has that code.
+pub fn explanation(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .binary_search_by_key(&code, |(code, _)| code)
+        .ok()
+        .map(|idx| EXPLANATIONS[idx].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_error_codes::ALL_CODES;
+    use mun_hir::{diagnostics::DiagnosticSink, AstDatabase, Package};
+    use mun_hir_input::WithFixture;
+    use mun_interpreter::InterpreterDatabase;
+
+    use super::{explanation, EXPLANATIONS};
+
+    /// An example in an explanation
+    struct Example<'a> {
+        /// The tags of the fenced code block, e.g. `["mun", "compile_fail",
+        /// "E0013"]`
+        tags: Vec<&'a str>,
+        source: String,
+    }
+
+    /// Returns all fenced code blocks in the specified markdown text
+    fn examples(text: &str) -> Vec<Example<'_>> {
+        let mut examples = Vec::new();
+        let mut lines = text.lines();
+        while let Some(line) = lines.next() {
+            if let Some(info) = line.strip_prefix("```") {
+                let source = lines
+                    .by_ref()
+                    .take_while(|line| !line.starts_with("```"))
+                    .fold(String::new(), |source, line| source + line + "\n");
+                examples.push(Example {
+                    tags: info.split(',').map(str::trim).collect(),
+                    source,
+                });
+            }
+        }
+        examples
+    }
+
+    /// Returns the codes of all diagnostics that are emitted for the
+    /// specified fixture.
+    fn diagnostic_codes(fixture: &str) -> Vec<&'static str> {
+        let db = InterpreterDatabase::with_files(fixture);
+        let modules = || {
+            Package::all(&db)
+                .into_iter()
+                .flat_map(|pkg| pkg.modules(&db))
+        };
+
+        let mut codes = Vec::new();
+        for module in modules() {
+            if let Some(file_id) = module.file_id(&db) {
+                codes.extend(
+                    db.parse(file_id)
+                        .errors()
+                        .iter()
+                        .map(mun_syntax::SyntaxError::code),
+                );
+            }
+        }
+
+        let mut sink = DiagnosticSink::new(|diagnostic| codes.push(diagnostic.code()));
+        for module in modules() {
+            module.diagnostics(&db, &mut sink);
+        }
+        drop(sink);
+
+        codes
+    }
+
+    #[test]
+    fn every_code_has_an_explanation() {
+        for code in ALL_CODES {
+            assert!(explanation(code).is_some(), "{code} has no explanation");
+        }
+
+        let explained: Vec<_> = EXPLANATIONS.iter().map(|(code, _)| *code).collect();
+        assert_eq!(
+            explained, ALL_CODES,
+            "explanations are not sorted, or explain unknown codes"
+        );
+    }
+
+    #[test]
+    fn examples_compile_as_described() {
+        for (code, text) in EXPLANATIONS {
+            let examples = examples(text);
+            assert!(
+                examples
+                    .iter()
+                    .any(|example| example.tags.contains(&"compile_fail")
+                        && example.tags.contains(code)),
+                "{code} has no erroneous code example"
+            );
+
+            for example in examples {
+                if example.tags.first() != Some(&"mun") || example.tags.contains(&"ignore") {
+                    continue;
+                }
+
+                let codes = diagnostic_codes(&example.source);
+                if example.tags.contains(&"compile_fail") {
+                    let expected = example
+                        .tags
+                        .iter()
+                        .find(|tag| tag.starts_with('E'))
+                        .unwrap_or_else(|| panic!("{code}: a failing example must specify a code"));
+                    assert!(
+                        codes.contains(expected),
+                        "{code}: example does not emit {expected}, but {codes:?}:\n{}",
+                        example.source
+                    );
+                } else {
+                    assert!(
+                        codes.is_empty(),
+                        "{code}: example should compile, but emits {codes:?}:\n{}",
+                        example.source
+                    );
+                }
+            }
+        }
+    }
+}
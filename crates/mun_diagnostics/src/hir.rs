@@ -55,6 +55,10 @@ struct GenericHirDiagnostic<'diag> {
 }
 
 impl<'diag> Diagnostic for GenericHirDiagnostic<'diag> {
+    fn code(&self) -> &'static str {
+        self.diagnostic.code()
+    }
+
     fn range(&self) -> TextRange {
         self.diagnostic.highlight_range()
     }
@@ -23,6 +23,10 @@ pub struct AccessUnknownField<'db, 'diag, DB: mun_hir::HirDatabase> {
 }
 
 impl<'db, 'diag, DB: mun_hir::HirDatabase> Diagnostic for AccessUnknownField<'db, 'diag, DB> {
+    fn code(&self) -> &'static str {
+        self.diag.code()
+    }
+
     fn range(&self) -> TextRange {
         self.location
     }
@@ -1,6 +1,7 @@
 use mun_hir::InFile;
 use mun_syntax::{ast, AstNode, Parse, SourceFile, SyntaxKind, SyntaxNodePtr, TextRange};
 
+use super::HirDiagnostic;
 use crate::{Diagnostic, SecondaryAnnotation, SourceAnnotation};
 
 /// For a given node returns the signature range (if that is applicable for the
@@ -38,6 +39,10 @@ fn syntax_node_signature_range(
             ast::TypeAliasDef::cast(syntax_node_ptr.to_node(parse.tree().syntax()))
                 .map_or_else(|| syntax_node_ptr.range(), |s| s.signature_range())
         }
+        SyntaxKind::CONST_DEF => {
+            ast::ConstDef::cast(syntax_node_ptr.to_node(parse.tree().syntax()))
+                .map_or_else(|| syntax_node_ptr.range(), |s| s.signature_range())
+        }
         _ => syntax_node_ptr.range(),
     }
 }
@@ -57,7 +62,7 @@ fn syntax_node_signature_range(
 ///     // ...
 /// }
 /// ```
-/// 
+///
 /// If the specified syntax node is not a function definition or structure
 /// definition, returns the range of the syntax node itself.
 fn syntax_node_identifier_range(
@@ -65,13 +70,14 @@ fn syntax_node_identifier_range(
     parse: &Parse<SourceFile>,
 ) -> TextRange {
     match syntax_node_ptr.kind() {
-        SyntaxKind::FUNCTION_DEF | SyntaxKind::STRUCT_DEF | SyntaxKind::TYPE_ALIAS_DEF => {
-            syntax_node_ptr
-                .to_node(parse.tree().syntax())
-                .children()
-                .find(|n| n.kind() == SyntaxKind::NAME)
-                .map_or_else(|| syntax_node_ptr.range(), |name| name.text_range())
-        }
+        SyntaxKind::FUNCTION_DEF
+        | SyntaxKind::STRUCT_DEF
+        | SyntaxKind::TYPE_ALIAS_DEF
+        | SyntaxKind::CONST_DEF => syntax_node_ptr
+            .to_node(parse.tree().syntax())
+            .children()
+            .find(|n| n.kind() == SyntaxKind::NAME)
+            .map_or_else(|| syntax_node_ptr.range(), |name| name.text_range()),
         _ => syntax_node_ptr.range(),
     }
 }
@@ -93,6 +99,10 @@ pub struct DuplicateDefinition<'db, 'diag, DB: mun_hir::HirDatabase> {
 }
 
 impl<'db, 'diag, DB: mun_hir::HirDatabase> Diagnostic for DuplicateDefinition<'db, 'diag, DB> {
+    fn code(&self) -> &'static str {
+        self.diag.code()
+    }
+
     fn range(&self) -> TextRange {
         syntax_node_identifier_range(
             &self.diag.definition.value,
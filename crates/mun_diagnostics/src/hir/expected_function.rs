@@ -19,6 +19,10 @@ pub struct ExpectedFunction<'db, 'diag, DB: mun_hir::HirDatabase> {
 }
 
 impl<'db, 'diag, DB: mun_hir::HirDatabase> Diagnostic for ExpectedFunction<'db, 'diag, DB> {
+    fn code(&self) -> &'static str {
+        self.diag.code()
+    }
+
     fn range(&self) -> TextRange {
         self.diag.highlight_range()
     }
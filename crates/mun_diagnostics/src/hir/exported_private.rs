@@ -11,6 +11,10 @@ pub struct ExportedPrivate<'db, 'diag, DB: mun_hir::HirDatabase> {
 }
 
 impl<'db, 'diag, DB: mun_hir::HirDatabase> Diagnostic for ExportedPrivate<'db, 'diag, DB> {
+    fn code(&self) -> &'static str {
+        self.diag.code()
+    }
+
     fn range(&self) -> TextRange {
         self.diag.highlight_range()
     }
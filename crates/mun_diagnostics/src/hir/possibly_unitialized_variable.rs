@@ -21,6 +21,10 @@ pub struct PossiblyUninitializedVariable<'db, 'diag, DB: mun_hir::HirDatabase> {
 impl<'db, 'diag, DB: mun_hir::HirDatabase> Diagnostic
     for PossiblyUninitializedVariable<'db, 'diag, DB>
 {
+    fn code(&self) -> &'static str {
+        self.diag.code()
+    }
+
     fn range(&self) -> TextRange {
         self.diag.highlight_range()
     }
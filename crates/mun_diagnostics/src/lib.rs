@@ -7,11 +7,14 @@
 //! reasons. This enables lazily querying the system for more information only
 //! when required.
 
+mod explanations;
 mod hir;
 
 use mun_hir::InFile;
 use mun_syntax::TextRange;
 
+pub use self::explanations::explanation;
+
 /// An annotation within the source code
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SourceAnnotation {
@@ -34,6 +37,10 @@ pub struct SecondaryAnnotation {
 
 /// The base trait for all diagnostics in this crate.
 pub trait Diagnostic {
+    /// Returns the stable code that identifies the kind of diagnostic, e.g.
+    /// `E0012`.
+    fn code(&self) -> &'static str;
+
     /// Returns the primary message of the diagnostic.
     fn title(&self) -> String;
 
@@ -0,0 +1,105 @@
+//! The stable codes that identify the diagnostics of the compiler. Every code
+//! has a long-form explanation in `mun_diagnostics`, which is shown by
+//! `mun explain`.
+//!
+//! Codes are never reused, so a code keeps referring to the same kind of
+//! diagnostic across versions of the compiler.
+
+macro_rules! codes {
+    ($($(#[doc = $doc:literal])* $name:ident = $code:literal,)*) => {
+        $(
+            $(#[doc = $doc])*
+            pub const $name: &str = $code;
+        )*
+
+        /// All codes, sorted.
+        pub const ALL_CODES: &[&str] = &[$($code),*];
+    };
+}
+
+codes! {
+    /// The source code could not be parsed.
+    SYNTAX_ERROR = "E0001",
+    /// A name was used that does not refer to a value in scope.
+    UNRESOLVED_VALUE = "E0002",
+    /// A name was used as a type, but no type with that name is in scope.
+    UNRESOLVED_TYPE = "E0003",
+    /// A type alias refers to itself, either directly or through other type aliases.
+    CYCLIC_TYPE = "E0004",
+    /// The value of a constant depends on itself.
+    CYCLIC_CONST = "E0005",
+    /// The value of a constant expression does not fit in its type.
+    CONST_OVERFLOW = "E0006",
+    /// A constant expression divides by zero.
+    CONST_DIVISION_BY_ZERO = "E0007",
+    /// The value of a constant contains an expression that cannot be evaluated at compile time.
+    NON_CONST_EXPR = "E0008",
+    /// An item was used outside of the module in which it is visible.
+    PRIVATE_ACCESS = "E0009",
+    /// A value that is not a function was called.
+    EXPECTED_FUNCTION = "E0010",
+    /// A public item exposes a type that is private.
+    EXPORTED_PRIVATE = "E0011",
+    /// A function was called with the wrong number of arguments.
+    PARAMETER_COUNT_MISMATCH = "E0012",
+    /// An expression has a different type than the one that was expected.
+    MISMATCHED_TYPE = "E0013",
+    /// The branches of an `if` expression have different types.
+    INCOMPATIBLE_BRANCH = "E0014",
+    /// The left-hand side of an assignment is not a place that can be assigned to.
+    INVALID_LHS = "E0015",
+    /// An `if` expression without an `else` branch was used as a value.
+    MISSING_ELSE_BRANCH = "E0016",
+    /// A binary operator was applied to operands of types that it does not support.
+    CANNOT_APPLY_BINARY_OP = "E0017",
+    /// A unary operator was applied to an operand of a type that it does not support.
+    CANNOT_APPLY_UNARY_OP = "E0018",
+    /// The same name was defined more than once in the same namespace.
+    DUPLICATE_DEFINITION = "E0019",
+    /// A `return` without a value was used in a function that returns a value.
+    RETURN_MISSING_EXPRESSION = "E0020",
+    /// A `break` expression was used outside of a loop.
+    BREAK_OUTSIDE_LOOP = "E0021",
+    /// A `break` with a value was used inside a loop that cannot have a value.
+    BREAK_WITH_VALUE_OUTSIDE_LOOP = "E0022",
+    /// A field was accessed that does not exist on the struct.
+    ACCESS_UNKNOWN_FIELD = "E0023",
+    /// A tuple struct literal was created with the wrong number of fields.
+    FIELD_COUNT_MISMATCH = "E0024",
+    /// A record struct literal does not specify all of the fields of the struct.
+    MISSING_FIELDS = "E0025",
+    /// A struct was created with a literal of a different kind than its definition.
+    MISMATCHED_STRUCT_LIT = "E0026",
+    /// A field was accessed on a value whose type has no fields.
+    NO_FIELDS = "E0027",
+    /// A record struct literal specifies a field that the struct does not have.
+    NO_SUCH_FIELD = "E0028",
+    /// A variable was used before a value was assigned to it.
+    POSSIBLY_UNINITIALIZED_VARIABLE = "E0029",
+    /// An `extern` function has a body.
+    EXTERN_CANNOT_HAVE_BODY = "E0030",
+    /// An `extern` function has a parameter or return type that is not a primitive type.
+    EXTERN_NON_PRIMITIVE_PARAM = "E0031",
+    /// An integer literal is too large to be represented by any integer type.
+    INT_LITERAL_TOO_LARGE = "E0032",
+    /// An integer literal does not fit in the type it is used as.
+    LITERAL_OUT_OF_RANGE = "E0033",
+    /// A numeric literal has a suffix that does not name a numeric type.
+    INVALID_LITERAL_SUFFIX = "E0034",
+    /// A binary, octal or hexadecimal literal has a floating-point suffix.
+    INVALID_FLOATING_POINT_LITERAL = "E0035",
+    /// A numeric literal contains digits that are not valid in its base.
+    INVALID_LITERAL = "E0036",
+    /// A type alias does not specify the type it refers to.
+    FREE_TYPE_ALIAS_WITHOUT_TYPE_REF = "E0037",
+    /// A `use` declaration refers to an item that does not exist.
+    UNRESOLVED_IMPORT = "E0038",
+    /// Two items with the same name were imported into the same module.
+    IMPORT_DUPLICATE_DEFINITION = "E0039",
+    /// A type alias is more visible than the type it refers to.
+    PRIVATE_TYPE_ALIAS = "E0040",
+    /// An inherent `impl` block was defined for a struct from another package.
+    IMPL_FOR_FOREIGN_TYPE = "E0041",
+    /// An inherent `impl` block was defined for a type that is not a struct.
+    INVALID_SELF_TY_IMPL = "E0042",
+}
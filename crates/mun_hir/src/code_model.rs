@@ -1,3 +1,4 @@
+mod r#const;
 mod function;
 mod r#impl;
 mod module;
@@ -8,11 +9,14 @@ mod type_alias;
 
 use std::sync::Arc;
 
+use mun_hir_input::FileId;
+
 pub use self::{
     function::{Function, FunctionData},
     module::{Module, ModuleDef},
     package::Package,
-    r#impl::{AssocItem, ImplData},
+    r#const::{Const, ConstData},
+    r#impl::{AssocItem, Impl, ImplData},
     r#struct::{Field, Struct, StructData, StructKind, StructMemoryKind},
     src::HasSource,
     type_alias::{TypeAlias, TypeAliasData},
@@ -23,19 +27,29 @@ use crate::{expr::BodySourceMap, HirDatabase, Name};
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DefWithBody {
     Function(Function),
+    Const(Const),
 }
-impl_froms!(DefWithBody: Function);
+impl_froms!(DefWithBody: Function, Const);
 
 impl DefWithBody {
     pub fn module(self, db: &dyn HirDatabase) -> Module {
         match self {
             DefWithBody::Function(f) => f.module(db),
+            DefWithBody::Const(c) => c.module(db),
+        }
+    }
+
+    pub fn file_id(self, db: &dyn HirDatabase) -> FileId {
+        match self {
+            DefWithBody::Function(f) => f.file_id(db),
+            DefWithBody::Const(c) => c.file_id(db),
         }
     }
 
     pub fn body_source_map(self, db: &dyn HirDatabase) -> Arc<BodySourceMap> {
         match self {
             DefWithBody::Function(f) => f.body_source_map(db),
+            DefWithBody::Const(c) => c.body_source_map(db),
         }
     }
 }
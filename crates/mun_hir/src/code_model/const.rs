@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use mun_hir_input::FileId;
+use mun_syntax::{ast::TypeAscriptionOwner, AstPtr};
+
+use super::Module;
+use crate::{
+    consteval::{ConstEvalError, ConstValue},
+    diagnostics::{ConstDivisionByZero, ConstOverflow, CyclicConst, NonConstExpr},
+    expr::BodySourceMap,
+    has_module::HasModule,
+    ids::{ConstId, Lookup},
+    name_resolution::Namespace,
+    resolve::HasResolver,
+    type_ref::{LocalTypeRefId, TypeRefMap, TypeRefSourceMap},
+    visibility::RawVisibility,
+    Body, DefDatabase, DiagnosticSink, ExprId, HasSource, HasVisibility, HirDatabase, InFile,
+    InferenceResult, Name, Ty, Visibility,
+};
+
+/// A compile-time constant (e.g. `const FOO: i32 = 5;`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Const {
+    pub(crate) id: ConstId,
+}
+
+impl From<ConstId> for Const {
+    fn from(id: ConstId) -> Self {
+        Const { id }
+    }
+}
+
+impl Const {
+    pub fn module(self, db: &dyn HirDatabase) -> Module {
+        self.id.module(db.upcast()).into()
+    }
+
+    pub fn file_id(self, db: &dyn HirDatabase) -> FileId {
+        self.id.lookup(db.upcast()).id.file_id
+    }
+
+    pub fn data(self, db: &dyn DefDatabase) -> Arc<ConstData> {
+        db.const_data(self.id)
+    }
+
+    pub fn name(self, db: &dyn HirDatabase) -> Name {
+        self.data(db.upcast()).name.clone()
+    }
+
+    /// Returns the declared type of the constant.
+    pub fn ty(self, db: &dyn HirDatabase) -> Ty {
+        db.type_for_def(self.into(), Namespace::Values)
+    }
+
+    pub fn body(self, db: &dyn HirDatabase) -> Arc<Body> {
+        db.body(self.id.into())
+    }
+
+    pub fn infer(self, db: &dyn HirDatabase) -> Arc<InferenceResult> {
+        db.infer(self.id.into())
+    }
+
+    /// Evaluates the initializer of the constant at compile time.
+    pub fn eval(self, db: &dyn HirDatabase) -> Result<ConstValue, ConstEvalError> {
+        db.const_eval(self.id)
+    }
+
+    pub(crate) fn body_source_map(self, db: &dyn HirDatabase) -> Arc<BodySourceMap> {
+        db.body_with_source_map(self.id.into()).1
+    }
+
+    pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink<'_>) {
+        let body = self.body(db);
+        body.add_diagnostics(db, self.into(), sink);
+        let infer = self.infer(db);
+        infer.add_diagnostics(db, self.into(), sink);
+
+        let file = self.file_id(db);
+        let expr_syntax = |expr: ExprId| {
+            self.body_source_map(db)
+                .expr_syntax(expr)
+                .map(|ptr| {
+                    ptr.value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+                })
+                .expect("could not retrieve expr from source map")
+        };
+        match self.eval(db) {
+            Ok(_) | Err(ConstEvalError::Invalid) => {}
+            Err(ConstEvalError::Cycle) => {
+                let src = self.source(db.upcast());
+                sink.push(CyclicConst {
+                    konst: InFile::new(src.file_id, AstPtr::new(&src.value)),
+                });
+            }
+            Err(ConstEvalError::DivisionByZero(expr)) => sink.push(ConstDivisionByZero {
+                file,
+                expr: expr_syntax(expr),
+            }),
+            Err(ConstEvalError::Overflow(expr)) => sink.push(ConstOverflow {
+                file,
+                expr: expr_syntax(expr),
+            }),
+            Err(ConstEvalError::NotConst(expr)) => sink.push(NonConstExpr {
+                file,
+                expr: expr_syntax(expr),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConstData {
+    pub name: Name,
+    pub visibility: RawVisibility,
+    pub type_ref_id: LocalTypeRefId,
+    type_ref_map: TypeRefMap,
+    type_ref_source_map: TypeRefSourceMap,
+}
+
+impl ConstData {
+    pub(crate) fn const_data_query(db: &dyn DefDatabase, id: ConstId) -> Arc<ConstData> {
+        let loc = id.lookup(db);
+        let item_tree = db.item_tree(loc.id.file_id);
+        let konst = &item_tree[loc.id.value];
+        let src = item_tree.source(db, loc.id.value);
+        let mut type_ref_builder = TypeRefMap::builder();
+        let type_ref_id = type_ref_builder.alloc_from_node_opt(src.ascribed_type().as_ref());
+        let (type_ref_map, type_ref_source_map) = type_ref_builder.finish();
+        Arc::new(ConstData {
+            name: konst.name.clone(),
+            visibility: item_tree[konst.visibility].clone(),
+            type_ref_id,
+            type_ref_map,
+            type_ref_source_map,
+        })
+    }
+
+    pub fn type_ref_source_map(&self) -> &TypeRefSourceMap {
+        &self.type_ref_source_map
+    }
+
+    pub fn type_ref_map(&self) -> &TypeRefMap {
+        &self.type_ref_map
+    }
+}
+
+impl HasVisibility for Const {
+    fn visibility(&self, db: &dyn HirDatabase) -> Visibility {
+        self.data(db.upcast())
+            .visibility
+            .resolve(db.upcast(), &self.id.resolver(db.upcast()))
+    }
+}
@@ -1,20 +1,21 @@
 use std::{iter::once, sync::Arc};
 
-use mun_hir_input::FileId;
-use mun_syntax::{ast, ast::TypeAscriptionOwner};
+use mun_hir_input::{FileId, LineCol};
+use mun_syntax::{ast, ast::TypeAscriptionOwner, AstNode};
 
-use super::Module;
+use super::{Impl, Module};
 use crate::{
     expr::{validator::ExprValidator, BodySourceMap},
     has_module::HasModule,
-    ids::{FunctionId, Lookup},
+    ids::{FunctionId, ItemContainerId, Lookup},
     item_tree::FunctionFlags,
+    name,
     name_resolution::Namespace,
     resolve::HasResolver,
     type_ref::{LocalTypeRefId, TypeRefMap, TypeRefSourceMap},
     visibility::RawVisibility,
-    Body, DefDatabase, DiagnosticSink, HasSource, HasVisibility, HirDatabase, InFile,
-    InferenceResult, Name, Pat, Ty, Visibility,
+    Body, DefDatabase, DiagnosticSink, ExprId, HasSource, HasVisibility, HirDatabase, InFile,
+    InferenceResult, Name, Pat, PatId, Ty, Visibility,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -50,6 +51,13 @@ impl FunctionData {
 
         let mut params = Vec::new();
         if let Some(param_list) = src.param_list() {
+            if let Some(self_param) = param_list.self_param() {
+                let type_ref = match self_param.ascribed_type().as_ref() {
+                    Some(type_ref) => type_ref_builder.alloc_from_node(type_ref),
+                    None => type_ref_builder.alloc_self(),
+                };
+                params.push(type_ref);
+            }
             for param in param_list.params() {
                 let type_ref = type_ref_builder.alloc_from_node_opt(param.ascribed_type().as_ref());
                 params.push(type_ref);
@@ -79,10 +87,17 @@ impl FunctionData {
         &self.name
     }
 
+    /// Returns the type references of the parameters, including the `self`
+    /// parameter.
     pub fn params(&self) -> &[LocalTypeRefId] {
         &self.params
     }
 
+    /// Returns true if the first parameter of this function is `self`.
+    pub fn has_self_param(&self) -> bool {
+        self.flags.has_self_param()
+    }
+
     pub fn visibility(&self) -> &RawVisibility {
         &self.visibility
     }
@@ -111,19 +126,43 @@ impl Function {
     }
 
     /// Returns the full name of the function including all module specifiers
-    /// (e.g: `foo::bar`).
+    /// and, for associated functions, the name of the type (e.g: `foo::bar` or
+    /// `foo::Bar::baz`).
     pub fn full_name(self, db: &dyn HirDatabase) -> String {
         itertools::Itertools::intersperse(
             self.module(db)
                 .path_to_root(db)
                 .into_iter()
                 .filter_map(|module| module.name(db))
+                .chain(self.self_ty_name(db))
                 .chain(once(self.name(db).to_string())),
             String::from("::"),
         )
         .collect()
     }
 
+    /// Returns the name of the function as it is referred to from within its
+    /// own module (e.g: `bar` or `Bar::baz`).
+    pub fn local_name(self, db: &dyn HirDatabase) -> String {
+        match self.self_ty_name(db) {
+            Some(self_ty) => format!("{self_ty}::{}", self.name(db)),
+            None => self.name(db).to_string(),
+        }
+    }
+
+    /// Returns the name of the type this function is associated with, if any.
+    fn self_ty_name(self, db: &dyn HirDatabase) -> Option<String> {
+        match self.id.lookup(db.upcast()).container {
+            ItemContainerId::ImplId(impl_id) => Some(
+                Impl::from(impl_id)
+                    .self_ty(db)
+                    .as_struct()
+                    .map_or_else(|| String::from("{unknown}"), |s| s.name(db).to_string()),
+            ),
+            ItemContainerId::ModuleId(_) => None,
+        }
+    }
+
     pub fn file_id(self, db: &dyn HirDatabase) -> FileId {
         self.id.lookup(db.upcast()).id.file_id
     }
@@ -172,15 +211,51 @@ impl Function {
         db.fn_data(self.id).flags.is_extern()
     }
 
+    /// Returns true if the first parameter of this function is `self`.
+    pub fn has_self_param(self, db: &dyn HirDatabase) -> bool {
+        db.fn_data(self.id).has_self_param()
+    }
+
     pub(crate) fn body_source_map(self, db: &dyn HirDatabase) -> Arc<BodySourceMap> {
         db.body_with_source_map(self.id.into()).1
     }
 
+    /// Returns the position of the start of the specified expression in the
+    /// file of the function, or `None` if the expression has no source.
+    pub fn expr_position(self, db: &dyn HirDatabase, expr: ExprId) -> Option<LineCol> {
+        let source = self.body_source_map(db).expr_syntax(expr)?;
+        let offset = source
+            .value
+            .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+            .range()
+            .start();
+        Some(db.line_index(source.file_id).line_col(offset))
+    }
+
+    /// Returns the position of the start of the specified pattern in the file
+    /// of the function, or `None` if the pattern has no source.
+    pub fn pat_position(self, db: &dyn HirDatabase, pat: PatId) -> Option<LineCol> {
+        let source = self.body_source_map(db).pat_syntax(pat)?;
+        let offset = source
+            .value
+            .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+            .range()
+            .start();
+        Some(db.line_index(source.file_id).line_col(offset))
+    }
+
+    /// Returns the position of the start of the declaration of the function.
+    pub fn position(self, db: &dyn HirDatabase) -> LineCol {
+        let source = self.source(db.upcast());
+        let offset = source.value.syntax().text_range().start();
+        db.line_index(source.file_id).line_col(offset)
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink<'_>) {
         let body = self.body(db);
         body.add_diagnostics(db, self.into(), sink);
         let infer = self.infer(db);
-        infer.add_diagnostics(db, self, sink);
+        infer.add_diagnostics(db, self.into(), sink);
         let validator = ExprValidator::new(self, db);
         validator.validate_body(sink);
     }
@@ -211,13 +286,14 @@ impl Param {
         &self.ty
     }
 
-    /// Returns the source of the parameter.
+    /// Returns the source of the parameter. Returns `None` for the `self`
+    /// parameter.
     pub fn source(&self, db: &dyn HirDatabase) -> Option<InFile<ast::Param>> {
         let InFile { file_id, value } = self.func.source(db.upcast());
         let params = value.param_list()?;
         params
             .params()
-            .nth(self.idx)
+            .nth(self.param_idx(db)?)
             .map(|value| InFile { file_id, value })
     }
 
@@ -227,8 +303,11 @@ impl Param {
     /// name. If the function parameter is a wildcard for instance then this
     /// function will return `None`.
     pub fn name(&self, db: &dyn HirDatabase) -> Option<Name> {
+        let Some(idx) = self.param_idx(db) else {
+            return Some(name![self]);
+        };
         let body = self.func.body(db);
-        let pat_id = body.params().get(self.idx)?.0;
+        let pat_id = body.params().get(idx)?.0;
         let pat = &body[pat_id];
         if let Pat::Bind { name, .. } = pat {
             Some(name.clone())
@@ -236,6 +315,16 @@ impl Param {
             None
         }
     }
+
+    /// Returns the index of this parameter in the parameter list excluding the
+    /// `self` parameter, or `None` if this is the `self` parameter.
+    fn param_idx(&self, db: &dyn HirDatabase) -> Option<usize> {
+        if self.func.has_self_param(db) {
+            self.idx.checked_sub(1)
+        } else {
+            Some(self.idx)
+        }
+    }
 }
 
 impl HasVisibility for Function {
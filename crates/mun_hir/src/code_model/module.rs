@@ -1,6 +1,6 @@
 use mun_hir_input::{FileId, ModuleId};
 
-use super::{r#impl::Impl, AssocItem, Function, Package, Struct, TypeAlias};
+use super::{r#impl::Impl, AssocItem, Const, Function, Package, Struct, TypeAlias};
 use crate::{ids::ItemDefinitionId, primitive_type::PrimitiveType, DiagnosticSink, HirDatabase};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -97,6 +97,7 @@ impl Module {
                 ModuleDef::Function(f) => f.diagnostics(db, sink),
                 ModuleDef::Struct(s) => s.diagnostics(db, sink),
                 ModuleDef::TypeAlias(t) => t.diagnostics(db, sink),
+                ModuleDef::Const(c) => c.diagnostics(db, sink),
                 _ => (),
             }
         }
@@ -164,6 +165,7 @@ pub enum ModuleDef {
     PrimitiveType(PrimitiveType),
     Struct(Struct),
     TypeAlias(TypeAlias),
+    Const(Const),
 }
 
 impl From<Function> for ModuleDef {
@@ -190,6 +192,12 @@ impl From<TypeAlias> for ModuleDef {
     }
 }
 
+impl From<Const> for ModuleDef {
+    fn from(t: Const) -> Self {
+        ModuleDef::Const(t)
+    }
+}
+
 impl From<Module> for ModuleDef {
     fn from(m: Module) -> Self {
         ModuleDef::Module(m)
@@ -203,6 +211,7 @@ impl From<ItemDefinitionId> for ModuleDef {
             ItemDefinitionId::FunctionId(id) => Function { id }.into(),
             ItemDefinitionId::StructId(id) => Struct { id }.into(),
             ItemDefinitionId::TypeAliasId(id) => TypeAlias { id }.into(),
+            ItemDefinitionId::ConstId(id) => Const { id }.into(),
             ItemDefinitionId::PrimitiveType(id) => id.into(),
         }
     }
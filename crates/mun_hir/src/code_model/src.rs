@@ -1,7 +1,7 @@
 use mun_syntax::ast;
 
 use crate::{
-    code_model::{Field, Function, Struct, TypeAlias},
+    code_model::{Const, Field, Function, Struct, TypeAlias},
     ids::{AssocItemLoc, Lookup},
     in_file::InFile,
     item_tree::{ItemTreeId, ItemTreeNode},
@@ -92,3 +92,10 @@ impl HasSource for TypeAlias {
         self.id.lookup(db).source(db)
     }
 }
+
+impl HasSource for Const {
+    type Ast = ast::ConstDef;
+    fn source(&self, db: &dyn DefDatabase) -> InFile<Self::Ast> {
+        self.id.lookup(db).source(db)
+    }
+}
@@ -2,10 +2,11 @@ use std::{fmt, iter::once, sync::Arc};
 
 pub use ast::StructMemoryKind;
 use la_arena::{Arena, Idx};
-use mun_hir_input::FileId;
+use mun_hir_input::{FileId, LineCol};
 use mun_syntax::{
     ast,
     ast::{NameOwner, TypeAscriptionOwner, VisibilityOwner},
+    AstNode,
 };
 
 use super::Module;
@@ -18,7 +19,7 @@ use crate::{
     ty::lower::LowerTyMap,
     type_ref::{LocalTypeRefId, TypeRefMap, TypeRefSourceMap},
     visibility::RawVisibility,
-    DefDatabase, DiagnosticSink, HasVisibility, HirDatabase, Name, Ty, Visibility,
+    DefDatabase, DiagnosticSink, HasSource, HasVisibility, HirDatabase, Name, Ty, Visibility,
 };
 
 pub(crate) mod validator;
@@ -79,6 +80,13 @@ impl Struct {
         db.struct_data(self.id)
     }
 
+    /// Returns the position of the start of the declaration of the struct.
+    pub fn position(self, db: &dyn HirDatabase) -> LineCol {
+        let source = self.source(db.upcast());
+        let offset = source.value.syntax().text_range().start();
+        db.line_index(source.file_id).line_col(offset)
+    }
+
     /// Returns the name of the struct non including any module specifiers (e.g:
     /// `Bar`).
     pub fn name(self, db: &dyn HirDatabase) -> Name {
@@ -0,0 +1,387 @@
+//! Compile-time evaluation of constant expressions.
+//!
+//! The initializer of every `const` item is evaluated by the [`const_eval`]
+//! query. Constants may refer to other constants; cycles between constants are
+//! detected by salsa and reported as [`ConstEvalError::Cycle`].
+//!
+//! [`const_eval`]: crate::HirDatabase::const_eval
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+
+use crate::{
+    expr::{ArithOp, BinaryOp, CmpOp, Expr, ExprId, Literal, LogicOp, Ordering, UnaryOp},
+    ids::ConstId,
+    resolve::{resolver_for_expr, ValueNs},
+    ty::{IntTy, ResolveBitness, TyKind},
+    Body, FloatBitness, HirDatabase, InferenceResult,
+};
+
+/// The value of a constant after evaluation.
+#[derive(Debug, Clone, Copy)]
+pub enum ConstValue {
+    Bool(bool),
+
+    /// The value of a signed integer
+    Int(i128),
+
+    /// The value of an unsigned integer
+    UInt(u128),
+
+    Float(f64),
+}
+
+/// Floating-point values are compared by their bits, which makes the
+/// comparison reflexive (`NaN == NaN`) and distinguishes `0.0` from `-0.0`.
+/// This is what the query system needs to detect whether a value changed.
+impl PartialEq for ConstValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => lhs == rhs,
+            (ConstValue::Int(lhs), ConstValue::Int(rhs)) => lhs == rhs,
+            (ConstValue::UInt(lhs), ConstValue::UInt(rhs)) => lhs == rhs,
+            (ConstValue::Float(lhs), ConstValue::Float(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ConstValue {}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstValue::Bool(value) => write!(f, "{value}"),
+            ConstValue::Int(value) => write!(f, "{value}"),
+            ConstValue::UInt(value) => write!(f, "{value}"),
+            ConstValue::Float(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+/// An error that occurred while evaluating a constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstEvalError {
+    /// The constant (indirectly) depends on its own value.
+    Cycle,
+
+    /// A division or remainder by zero was encountered.
+    DivisionByZero(ExprId),
+
+    /// The result of an expression does not fit in its type.
+    Overflow(ExprId),
+
+    /// The expression cannot be evaluated at compile time.
+    NotConst(ExprId),
+
+    /// The constant cannot be evaluated because of an error that is reported
+    /// elsewhere, e.g. a type error or an error in a constant it depends on.
+    Invalid,
+}
+
+pub(crate) fn const_eval_query(
+    db: &dyn HirDatabase,
+    def: ConstId,
+) -> Result<ConstValue, ConstEvalError> {
+    let body = db.body(def.into());
+    let infer = db.infer(def.into());
+    if !infer.diagnostics.is_empty() {
+        return Err(ConstEvalError::Invalid);
+    }
+
+    ConstEvaluator {
+        db,
+        body: &body,
+        infer: &infer,
+    }
+    .eval(body.body_expr())
+}
+
+pub(crate) fn const_eval_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &ConstId,
+) -> Result<ConstValue, ConstEvalError> {
+    Err(ConstEvalError::Cycle)
+}
+
+struct ConstEvaluator<'a> {
+    db: &'a dyn HirDatabase,
+    body: &'a Body,
+    infer: &'a InferenceResult,
+}
+
+impl ConstEvaluator<'_> {
+    fn eval(&self, expr: ExprId) -> Result<ConstValue, ConstEvalError> {
+        match &self.body[expr] {
+            Expr::Literal(Literal::Bool(value)) => Ok(ConstValue::Bool(*value)),
+            Expr::Literal(Literal::Int(lit)) => self.uint(expr, Some(lit.value)),
+            Expr::Literal(Literal::Float(lit)) => Ok(self.float(expr, lit.value)),
+            Expr::Path(path) => {
+                let resolver = resolver_for_expr(self.db.upcast(), self.body.owner(), expr);
+                match resolver.resolve_path_as_value_fully(self.db.upcast(), path) {
+                    // Errors in the referenced constant are reported for that constant itself
+                    Some((ValueNs::ConstId(id), _)) => {
+                        self.db.const_eval(id).or(Err(ConstEvalError::Invalid))
+                    }
+                    _ => Err(ConstEvalError::NotConst(expr)),
+                }
+            }
+            Expr::Block {
+                statements,
+                tail: Some(tail),
+            } if statements.is_empty() => self.eval(*tail),
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch: Some(else_branch),
+            } => match self.eval(*condition)? {
+                ConstValue::Bool(true) => self.eval(*then_branch),
+                ConstValue::Bool(false) => self.eval(*else_branch),
+                _ => Err(ConstEvalError::Invalid),
+            },
+            Expr::UnaryOp { expr: operand, op } => self.eval_unary_op(expr, *operand, *op),
+            Expr::BinaryOp {
+                lhs,
+                rhs,
+                op: Some(op),
+            } => self.eval_binary_op(expr, *lhs, *rhs, *op),
+            _ => Err(ConstEvalError::NotConst(expr)),
+        }
+    }
+
+    fn eval_unary_op(
+        &self,
+        expr: ExprId,
+        operand: ExprId,
+        op: UnaryOp,
+    ) -> Result<ConstValue, ConstEvalError> {
+        // A negated literal is evaluated as a whole, otherwise the minimum value of a
+        // signed type (e.g. `-128` for `i8`) would overflow before it is negated.
+        if let (UnaryOp::Neg, Expr::Literal(Literal::Int(lit))) = (op, &self.body[operand]) {
+            return self.int(expr, 0i128.checked_sub_unsigned(lit.value));
+        }
+
+        match (op, self.eval(operand)?) {
+            (UnaryOp::Not, ConstValue::Bool(value)) => Ok(ConstValue::Bool(!value)),
+            (UnaryOp::Not, ConstValue::Int(value)) => self.int(expr, Some(!value)),
+            // For unsigned integers `!value` is `max - value`
+            (UnaryOp::Not, ConstValue::UInt(value)) => {
+                self.uint(expr, self.int_ty(expr)?.max().checked_sub(value))
+            }
+            (UnaryOp::Neg, ConstValue::Int(value)) => self.int(expr, value.checked_neg()),
+            (UnaryOp::Neg, ConstValue::UInt(value)) => {
+                self.int(expr, 0i128.checked_sub_unsigned(value))
+            }
+            (UnaryOp::Neg, ConstValue::Float(value)) => Ok(self.float(expr, -value)),
+            _ => Err(ConstEvalError::Invalid),
+        }
+    }
+
+    fn eval_binary_op(
+        &self,
+        expr: ExprId,
+        lhs: ExprId,
+        rhs: ExprId,
+        op: BinaryOp,
+    ) -> Result<ConstValue, ConstEvalError> {
+        match op {
+            BinaryOp::LogicOp(op) => {
+                // Logical operators short-circuit
+                let ConstValue::Bool(lhs) = self.eval(lhs)? else {
+                    return Err(ConstEvalError::Invalid);
+                };
+                match (op, lhs) {
+                    (LogicOp::And, false) => Ok(ConstValue::Bool(false)),
+                    (LogicOp::Or, true) => Ok(ConstValue::Bool(true)),
+                    _ => self.eval(rhs),
+                }
+            }
+            BinaryOp::ArithOp(op) => self.eval_arith_op(expr, self.eval(lhs)?, self.eval(rhs)?, op),
+            BinaryOp::CmpOp(op) => Self::eval_cmp_op(self.eval(lhs)?, self.eval(rhs)?, op),
+            BinaryOp::Assignment { .. } => Err(ConstEvalError::NotConst(expr)),
+        }
+    }
+
+    fn eval_cmp_op(
+        lhs: ConstValue,
+        rhs: ConstValue,
+        op: CmpOp,
+    ) -> Result<ConstValue, ConstEvalError> {
+        let ordering = match (lhs, rhs) {
+            (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => lhs.partial_cmp(&rhs),
+            (ConstValue::Int(lhs), ConstValue::Int(rhs)) => lhs.partial_cmp(&rhs),
+            (ConstValue::UInt(lhs), ConstValue::UInt(rhs)) => lhs.partial_cmp(&rhs),
+            (ConstValue::Float(lhs), ConstValue::Float(rhs)) => lhs.partial_cmp(&rhs),
+            _ => return Err(ConstEvalError::Invalid),
+        };
+        let result = match (op, ordering) {
+            (CmpOp::Eq { negated }, ordering) => {
+                (ordering == Some(std::cmp::Ordering::Equal)) != negated
+            }
+            // Ordered comparisons with NaN are always false
+            (CmpOp::Ord { .. }, None) => false,
+            (CmpOp::Ord { ordering, strict }, Some(result)) => match ordering {
+                Ordering::Less if strict => result.is_lt(),
+                Ordering::Less => result.is_le(),
+                Ordering::Greater if strict => result.is_gt(),
+                Ordering::Greater => result.is_ge(),
+            },
+        };
+        Ok(ConstValue::Bool(result))
+    }
+
+    fn eval_arith_op(
+        &self,
+        expr: ExprId,
+        lhs: ConstValue,
+        rhs: ConstValue,
+        op: ArithOp,
+    ) -> Result<ConstValue, ConstEvalError> {
+        if let ArithOp::LeftShift | ArithOp::RightShift = op {
+            return self.eval_shift_op(expr, lhs, rhs, op);
+        }
+
+        match (lhs, rhs) {
+            (ConstValue::Int(lhs), ConstValue::Int(rhs)) => {
+                let value = match op {
+                    ArithOp::Add => lhs.checked_add(rhs),
+                    ArithOp::Subtract => lhs.checked_sub(rhs),
+                    ArithOp::Multiply => lhs.checked_mul(rhs),
+                    ArithOp::Divide | ArithOp::Remainder if rhs == 0 => {
+                        return Err(ConstEvalError::DivisionByZero(expr))
+                    }
+                    ArithOp::Divide => lhs.checked_div(rhs),
+                    ArithOp::Remainder => lhs.checked_rem(rhs),
+                    ArithOp::BitAnd => Some(lhs & rhs),
+                    ArithOp::BitOr => Some(lhs | rhs),
+                    ArithOp::BitXor => Some(lhs ^ rhs),
+                    ArithOp::LeftShift | ArithOp::RightShift => {
+                        unreachable!("shifts are evaluated separately")
+                    }
+                };
+                self.int(expr, value)
+            }
+            (ConstValue::UInt(lhs), ConstValue::UInt(rhs)) => {
+                let value = match op {
+                    ArithOp::Add => lhs.checked_add(rhs),
+                    ArithOp::Subtract => lhs.checked_sub(rhs),
+                    ArithOp::Multiply => lhs.checked_mul(rhs),
+                    ArithOp::Divide | ArithOp::Remainder if rhs == 0 => {
+                        return Err(ConstEvalError::DivisionByZero(expr))
+                    }
+                    ArithOp::Divide => lhs.checked_div(rhs),
+                    ArithOp::Remainder => lhs.checked_rem(rhs),
+                    ArithOp::BitAnd => Some(lhs & rhs),
+                    ArithOp::BitOr => Some(lhs | rhs),
+                    ArithOp::BitXor => Some(lhs ^ rhs),
+                    ArithOp::LeftShift | ArithOp::RightShift => {
+                        unreachable!("shifts are evaluated separately")
+                    }
+                };
+                self.uint(expr, value)
+            }
+            (ConstValue::Float(lhs), ConstValue::Float(rhs)) => {
+                let value = match op {
+                    ArithOp::Add => lhs + rhs,
+                    ArithOp::Subtract => lhs - rhs,
+                    ArithOp::Multiply => lhs * rhs,
+                    ArithOp::Divide => lhs / rhs,
+                    ArithOp::Remainder => lhs % rhs,
+                    _ => return Err(ConstEvalError::Invalid),
+                };
+                Ok(self.float(expr, value))
+            }
+            _ => Err(ConstEvalError::Invalid),
+        }
+    }
+
+    fn eval_shift_op(
+        &self,
+        expr: ExprId,
+        lhs: ConstValue,
+        rhs: ConstValue,
+        op: ArithOp,
+    ) -> Result<ConstValue, ConstEvalError> {
+        let ty = self.int_ty(expr)?;
+        let shift = match rhs {
+            ConstValue::Int(rhs) => u32::try_from(rhs).ok(),
+            ConstValue::UInt(rhs) => u32::try_from(rhs).ok(),
+            _ => return Err(ConstEvalError::Invalid),
+        }
+        .filter(|shift| *shift < ty.bits())
+        .ok_or(ConstEvalError::Overflow(expr))?;
+
+        // Bits shifted out of the type are discarded
+        match (lhs, op) {
+            (ConstValue::Int(lhs), ArithOp::LeftShift) => {
+                self.int(expr, Some(truncate(ty, lhs.wrapping_shl(shift))))
+            }
+            (ConstValue::Int(lhs), _) => self.int(expr, Some(lhs >> shift)),
+            (ConstValue::UInt(lhs), ArithOp::LeftShift) => {
+                self.uint(expr, Some(lhs.wrapping_shl(shift) & ty.max()))
+            }
+            (ConstValue::UInt(lhs), _) => self.uint(expr, Some(lhs >> shift)),
+            _ => Err(ConstEvalError::Invalid),
+        }
+    }
+
+    /// Returns the integer type of the specified expression.
+    fn int_ty(&self, expr: ExprId) -> Result<IntTy, ConstEvalError> {
+        match self.infer[expr].interned() {
+            TyKind::Int(ty) => Ok(ty.resolve(&self.db.target_data_layout())),
+            _ => Err(ConstEvalError::Invalid),
+        }
+    }
+
+    /// Constructs an integer value for the specified expression from a value
+    /// that may be negative, making sure that it fits in the type of the
+    /// expression.
+    fn int(&self, expr: ExprId, value: Option<i128>) -> Result<ConstValue, ConstEvalError> {
+        match value {
+            Some(value) if value >= 0 => self.uint(expr, Some(value as u128)),
+            Some(value) if value >= self.int_ty(expr)?.min() => Ok(ConstValue::Int(value)),
+            _ => Err(ConstEvalError::Overflow(expr)),
+        }
+    }
+
+    /// Constructs an integer value for the specified expression from a
+    /// non-negative value, making sure that it fits in the type of the
+    /// expression.
+    fn uint(&self, expr: ExprId, value: Option<u128>) -> Result<ConstValue, ConstEvalError> {
+        let ty = self.int_ty(expr)?;
+        match value {
+            Some(value) if value <= ty.max() && ty.min() < 0 => Ok(ConstValue::Int(value as i128)),
+            Some(value) if value <= ty.max() => Ok(ConstValue::UInt(value)),
+            _ => Err(ConstEvalError::Overflow(expr)),
+        }
+    }
+
+    /// Constructs a floating-point value for the specified expression, rounding
+    /// it to the precision of the type of the expression.
+    fn float(&self, expr: ExprId, value: f64) -> ConstValue {
+        match self.infer[expr].interned() {
+            TyKind::Float(ty) if ty.bitness == FloatBitness::X32 => {
+                ConstValue::Float(f64::from(value as f32))
+            }
+            _ => ConstValue::Float(value),
+        }
+    }
+}
+
+/// Truncates `value` to the number of bits of the specified type.
+fn truncate(ty: IntTy, value: i128) -> i128 {
+    let bits = ty.bits();
+    if bits >= 128 {
+        return value;
+    }
+
+    let value = value & ((1i128 << bits) - 1);
+    if ty.min() < 0 && value >> (bits - 1) != 0 {
+        value - (1i128 << bits)
+    } else {
+        value
+    }
+}
@@ -0,0 +1,181 @@
+use mun_hir_input::WithFixture;
+
+use crate::{mock::MockDatabase, utils::tests::diagnostics, ModuleDef, Package};
+
+fn eval_consts(text: &str) -> String {
+    let (db, _file_id) = MockDatabase::with_single_file(text);
+
+    let mut result = Vec::new();
+    for module in Package::all(&db).iter().flat_map(|pkg| pkg.modules(&db)) {
+        for decl in module.declarations(&db) {
+            if let ModuleDef::Const(konst) = decl {
+                let value = match konst.eval(&db) {
+                    Ok(value) => value.to_string(),
+                    Err(err) => format!("{err:?}"),
+                };
+                result.push(format!("{} = {value}", konst.name(&db)));
+            }
+        }
+    }
+
+    let diagnostics = diagnostics(text);
+    if !diagnostics.is_empty() {
+        result.push(diagnostics);
+    }
+
+    result.join("\n")
+}
+
+#[test]
+fn literals() {
+    insta::assert_snapshot!(eval_consts(r#"
+    const A: i32 = 5;
+    const B: f64 = 1.5;
+    const C: bool = true;
+    const D: u8 = 255;
+    const E: f32 = 0.1;
+    "#), @r#"
+    A = 5
+    B = 1.5
+    C = true
+    D = 255
+    E = 0.10000000149011612
+    "#);
+}
+
+#[test]
+fn arithmetic() {
+    insta::assert_snapshot!(eval_consts(r#"
+    const A: i32 = 1 + 2 * 3;
+    const B: i32 = (1 + 2) * 3;
+    const C: i32 = -7 / 2;
+    const D: i32 = -7 % 2;
+    const E: i32 = 1 << 4 | 3;
+    const F: u8 = !0;
+    const G: i8 = !0;
+    const H: f64 = 1.0 / 4.0 - 0.5;
+    const I: i64 = 0xff & 0x0f ^ 0x3;
+    const J: i32 = 256 >> 4;
+    const K: i8 = -128;
+    "#), @r#"
+    A = 7
+    B = 9
+    C = -3
+    D = -1
+    E = 19
+    F = 255
+    G = -1
+    H = -0.25
+    I = 12
+    J = 16
+    K = -128
+    "#);
+}
+
+#[test]
+fn unsigned_128() {
+    insta::assert_snapshot!(eval_consts(r#"
+    const A: u128 = 340282366920938463463374607431768211455;
+    const B: u128 = A - 1;
+    const C: u128 = !0;
+    const D: u128 = 1 << 127;
+    const E: u128 = D / 2 + D;
+    const F: bool = A > D;
+    const G: u128 = A + 1;
+    const H: i128 = 170141183460469231731687303715884105728;
+    "#), @r#"
+    A = 340282366920938463463374607431768211455
+    B = 340282366920938463463374607431768211454
+    C = 340282366920938463463374607431768211455
+    D = 170141183460469231731687303715884105728
+    E = 255211775190703847597530955573826158592
+    F = true
+    G = Overflow(Idx::<Expr>(2))
+    H = Overflow(Idx::<Expr>(0))
+    192..197: attempt to compute a value that overflows its type in a constant expression
+    215..254: attempt to compute a value that overflows its type in a constant expression
+    "#);
+}
+
+#[test]
+fn comparisons() {
+    insta::assert_snapshot!(eval_consts(r#"
+    const A: bool = 1 < 2;
+    const B: bool = 2.0 >= 3.0;
+    const C: bool = true == false;
+    const D: bool = 5 != 5 || 3 <= 3;
+    const E: bool = false && 1 / 0 == 0;
+    const F: i32 = if A { 1 } else { 2 };
+    "#), @r#"
+    A = true
+    B = false
+    C = false
+    D = true
+    E = false
+    F = 1
+    "#);
+}
+
+#[test]
+fn references() {
+    insta::assert_snapshot!(eval_consts(r#"
+    const A: i32 = B * 2;
+    const B: i32 = C + 1;
+    const C: i32 = 20;
+    pub const D: f64 = 3.0 * E;
+    const E: f64 = 2.0;
+    "#), @r#"
+    A = 42
+    B = 21
+    C = 20
+    D = 6.0
+    E = 2.0
+    "#);
+}
+
+#[test]
+fn cycle() {
+    insta::assert_snapshot!(eval_consts(r#"
+    const A: i32 = B;
+    const B: i32 = A + 1;
+    const C: i32 = C;
+    const D: i32 = A;
+    "#), @r#"
+    A = Cycle
+    B = Cycle
+    C = Cycle
+    D = Invalid
+    0..17: cycle detected when evaluating constant
+    18..39: cycle detected when evaluating constant
+    40..57: cycle detected when evaluating constant
+    "#);
+}
+
+#[test]
+fn errors() {
+    insta::assert_snapshot!(eval_consts(r#"
+    const A: u8 = 255 + 1;
+    const B: i32 = 1 / 0;
+    const C: i8 = (-127 - 1) / -1;
+    const D: i32 = 1 << 32;
+    const E: i32 = foo();
+    const F: u32 = -1;
+    const G: i32 = true;
+    fn foo() -> i32 { 5 }
+    "#), @r#"
+    A = Overflow(Idx::<Expr>(2))
+    B = DivisionByZero(Idx::<Expr>(2))
+    C = Overflow(Idx::<Expr>(6))
+    D = Overflow(Idx::<Expr>(2))
+    E = NotConst(Idx::<Expr>(1))
+    F = Overflow(Idx::<Expr>(1))
+    G = Invalid
+    14..21: attempt to compute a value that overflows its type in a constant expression
+    38..43: attempt to divide by zero in a constant expression
+    59..74: attempt to compute a value that overflows its type in a constant expression
+    91..98: attempt to compute a value that overflows its type in a constant expression
+    115..120: expression cannot be evaluated at compile time
+    137..139: attempt to compute a value that overflows its type in a constant expression
+    156..160: mismatched type
+    "#);
+}
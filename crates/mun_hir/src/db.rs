@@ -8,10 +8,11 @@ use mun_syntax::{ast, Parse, SourceFile};
 use mun_target::{abi, spec::Target};
 
 use crate::{
-    code_model::{FunctionData, ImplData, StructData, TypeAliasData},
+    code_model::{ConstData, FunctionData, ImplData, StructData, TypeAliasData},
+    consteval::{ConstEvalError, ConstValue},
     expr::BodySourceMap,
     ids,
-    ids::{DefWithBodyId, FunctionId, ImplId},
+    ids::{ConstId, DefWithBodyId, FunctionId, ImplId},
     item_tree::{self, ItemTree},
     method_resolution::InherentImpls,
     name_resolution::Namespace,
@@ -45,6 +46,8 @@ pub trait InternDatabase: SourceDatabase {
     #[salsa::interned]
     fn intern_type_alias(&self, loc: ids::TypeAliasLoc) -> ids::TypeAliasId;
     #[salsa::interned]
+    fn intern_const(&self, loc: ids::ConstLoc) -> ids::ConstId;
+    #[salsa::interned]
     fn intern_impl(self, loc: ids::ImplLoc) -> ids::ImplId;
 }
 
@@ -61,6 +64,9 @@ pub trait DefDatabase: InternDatabase + AstDatabase + Upcast<dyn AstDatabase> {
     #[salsa::invoke(TypeAliasData::type_alias_data_query)]
     fn type_alias_data(&self, id: ids::TypeAliasId) -> Arc<TypeAliasData>;
 
+    #[salsa::invoke(ConstData::const_data_query)]
+    fn const_data(&self, id: ids::ConstId) -> Arc<ConstData>;
+
     #[salsa::invoke(crate::FunctionData::fn_data_query)]
     fn fn_data(&self, func: FunctionId) -> Arc<FunctionData>;
 
@@ -114,6 +120,11 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::ty::type_for_def)]
     fn type_for_def(&self, def: TypableDef, ns: Namespace) -> Ty;
 
+    /// Evaluates the initializer of a constant at compile time.
+    #[salsa::invoke(crate::consteval::const_eval_query)]
+    #[salsa::cycle(crate::consteval::const_eval_recover)]
+    fn const_eval(&self, def: ConstId) -> Result<ConstValue, ConstEvalError>;
+
     #[salsa::invoke(crate::ty::type_for_impl_self)]
     fn type_for_impl_self(&self, def: ImplId) -> Ty;
 
@@ -1,5 +1,6 @@
 use std::{any::Any, fmt};
 
+use mun_error_codes as error_codes;
 use mun_hir_input::FileId;
 use mun_syntax::{ast, AstPtr, SmolStr, SyntaxNode, SyntaxNodePtr, TextRange};
 
@@ -16,6 +17,9 @@ use crate::{code_model::StructKind, in_file::InFile, HirDatabase, IntTy, Name, T
 /// not include source locations. Such internal diagnostics are transformed into
 /// an instance of `Diagnostic` on demand.
 pub trait Diagnostic: Any + Send + Sync + fmt::Debug + 'static {
+    /// Returns a stable code that identifies the kind of diagnostic, e.g.
+    /// `E0012`. Codes are never reused once a diagnostic is removed.
+    fn code(&self) -> &'static str;
     fn message(&self) -> String;
     fn source(&self) -> InFile<SyntaxNodePtr>;
     fn highlight_range(&self) -> TextRange {
@@ -86,6 +90,10 @@ pub struct UnresolvedValue {
 }
 
 impl Diagnostic for UnresolvedValue {
+    fn code(&self) -> &'static str {
+        error_codes::UNRESOLVED_VALUE
+    }
+
     fn message(&self) -> String {
         "undefined value".to_string()
     }
@@ -106,6 +114,10 @@ pub struct UnresolvedType {
 }
 
 impl Diagnostic for UnresolvedType {
+    fn code(&self) -> &'static str {
+        error_codes::UNRESOLVED_TYPE
+    }
+
     fn message(&self) -> String {
         "undefined type".to_string()
     }
@@ -126,6 +138,10 @@ pub struct CyclicType {
 }
 
 impl Diagnostic for CyclicType {
+    fn code(&self) -> &'static str {
+        error_codes::CYCLIC_TYPE
+    }
+
     fn message(&self) -> String {
         "cyclic type".to_string()
     }
@@ -139,6 +155,101 @@ impl Diagnostic for CyclicType {
     }
 }
 
+#[derive(Debug)]
+pub struct CyclicConst {
+    pub konst: InFile<AstPtr<ast::ConstDef>>,
+}
+
+impl Diagnostic for CyclicConst {
+    fn code(&self) -> &'static str {
+        error_codes::CYCLIC_CONST
+    }
+
+    fn message(&self) -> String {
+        "cycle detected when evaluating constant".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.konst.clone().map(Into::into)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstOverflow {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for ConstOverflow {
+    fn code(&self) -> &'static str {
+        error_codes::CONST_OVERFLOW
+    }
+
+    fn message(&self) -> String {
+        "attempt to compute a value that overflows its type in a constant expression".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstDivisionByZero {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for ConstDivisionByZero {
+    fn code(&self) -> &'static str {
+        error_codes::CONST_DIVISION_BY_ZERO
+    }
+
+    fn message(&self) -> String {
+        "attempt to divide by zero in a constant expression".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct NonConstExpr {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for NonConstExpr {
+    fn code(&self) -> &'static str {
+        error_codes::NON_CONST_EXPR
+    }
+
+    fn message(&self) -> String {
+        "expression cannot be evaluated at compile time".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct PrivateAccess {
     pub file: FileId,
@@ -146,6 +257,10 @@ pub struct PrivateAccess {
 }
 
 impl Diagnostic for PrivateAccess {
+    fn code(&self) -> &'static str {
+        error_codes::PRIVATE_ACCESS
+    }
+
     fn message(&self) -> String {
         "access of private type".to_string()
     }
@@ -167,6 +282,10 @@ pub struct ExpectedFunction {
 }
 
 impl Diagnostic for ExpectedFunction {
+    fn code(&self) -> &'static str {
+        error_codes::EXPECTED_FUNCTION
+    }
+
     fn message(&self) -> String {
         "expected function type".to_string()
     }
@@ -187,6 +306,10 @@ pub struct ExportedPrivate {
 }
 
 impl Diagnostic for ExportedPrivate {
+    fn code(&self) -> &'static str {
+        error_codes::EXPORTED_PRIVATE
+    }
+
     fn message(&self) -> String {
         "can't leak private type".to_string()
     }
@@ -209,6 +332,10 @@ pub struct ParameterCountMismatch {
 }
 
 impl Diagnostic for ParameterCountMismatch {
+    fn code(&self) -> &'static str {
+        error_codes::PARAMETER_COUNT_MISMATCH
+    }
+
     fn message(&self) -> String {
         format!(
             "this function takes {} parameters but {} parameters was supplied",
@@ -234,6 +361,10 @@ pub struct MismatchedType {
 }
 
 impl Diagnostic for MismatchedType {
+    fn code(&self) -> &'static str {
+        error_codes::MISMATCHED_TYPE
+    }
+
     fn message(&self) -> String {
         "mismatched type".to_string()
     }
@@ -256,6 +387,10 @@ pub struct IncompatibleBranch {
 }
 
 impl Diagnostic for IncompatibleBranch {
+    fn code(&self) -> &'static str {
+        error_codes::INCOMPATIBLE_BRANCH
+    }
+
     fn message(&self) -> String {
         "mismatched branches".to_string()
     }
@@ -282,6 +417,10 @@ pub struct InvalidLhs {
 }
 
 impl Diagnostic for InvalidLhs {
+    fn code(&self) -> &'static str {
+        error_codes::INVALID_LHS
+    }
+
     fn message(&self) -> String {
         "invalid left hand side of expression".to_string()
     }
@@ -303,6 +442,10 @@ pub struct MissingElseBranch {
 }
 
 impl Diagnostic for MissingElseBranch {
+    fn code(&self) -> &'static str {
+        error_codes::MISSING_ELSE_BRANCH
+    }
+
     fn message(&self) -> String {
         "missing else branch".to_string()
     }
@@ -325,6 +468,10 @@ pub struct CannotApplyBinaryOp {
 }
 
 impl Diagnostic for CannotApplyBinaryOp {
+    fn code(&self) -> &'static str {
+        error_codes::CANNOT_APPLY_BINARY_OP
+    }
+
     fn message(&self) -> String {
         "cannot apply binary operator".to_string()
     }
@@ -346,6 +493,10 @@ pub struct CannotApplyUnaryOp {
 }
 
 impl Diagnostic for CannotApplyUnaryOp {
+    fn code(&self) -> &'static str {
+        error_codes::CANNOT_APPLY_UNARY_OP
+    }
+
     fn message(&self) -> String {
         "cannot apply unary operator".to_string()
     }
@@ -367,6 +518,10 @@ pub struct DuplicateDefinition {
 }
 
 impl Diagnostic for DuplicateDefinition {
+    fn code(&self) -> &'static str {
+        error_codes::DUPLICATE_DEFINITION
+    }
+
     fn message(&self) -> String {
         format!("the name `{}` is defined multiple times", self.name)
     }
@@ -387,6 +542,10 @@ pub struct ReturnMissingExpression {
 }
 
 impl Diagnostic for ReturnMissingExpression {
+    fn code(&self) -> &'static str {
+        error_codes::RETURN_MISSING_EXPRESSION
+    }
+
     fn message(&self) -> String {
         "`return;` in a function whose return type is not `()`".to_owned()
     }
@@ -407,6 +566,10 @@ pub struct BreakOutsideLoop {
 }
 
 impl Diagnostic for BreakOutsideLoop {
+    fn code(&self) -> &'static str {
+        error_codes::BREAK_OUTSIDE_LOOP
+    }
+
     fn message(&self) -> String {
         "`break` outside of a loop".to_owned()
     }
@@ -427,6 +590,10 @@ pub struct BreakWithValueOutsideLoop {
 }
 
 impl Diagnostic for BreakWithValueOutsideLoop {
+    fn code(&self) -> &'static str {
+        error_codes::BREAK_WITH_VALUE_OUTSIDE_LOOP
+    }
+
     fn message(&self) -> String {
         "`break` with value can only appear in a `loop`".to_owned()
     }
@@ -449,6 +616,10 @@ pub struct AccessUnknownField {
 }
 
 impl Diagnostic for AccessUnknownField {
+    fn code(&self) -> &'static str {
+        error_codes::ACCESS_UNKNOWN_FIELD
+    }
+
     fn message(&self) -> String {
         "attempted to access a non-existent field in a struct.".to_string()
     }
@@ -471,6 +642,10 @@ pub struct FieldCountMismatch {
 }
 
 impl Diagnostic for FieldCountMismatch {
+    fn code(&self) -> &'static str {
+        error_codes::FIELD_COUNT_MISMATCH
+    }
+
     fn message(&self) -> String {
         format!(
             "this tuple struct literal has {} field{} but {} field{} supplied",
@@ -499,6 +674,10 @@ pub struct MissingFields {
 }
 
 impl Diagnostic for MissingFields {
+    fn code(&self) -> &'static str {
+        error_codes::MISSING_FIELDS
+    }
+
     fn message(&self) -> String {
         use std::fmt::Write;
         let mut message = "missing record fields:\n".to_string();
@@ -526,6 +705,10 @@ pub struct MismatchedStructLit {
 }
 
 impl Diagnostic for MismatchedStructLit {
+    fn code(&self) -> &'static str {
+        error_codes::MISMATCHED_STRUCT_LIT
+    }
+
     fn message(&self) -> String {
         format!(
             "mismatched struct literal kind. expected `{}`, found `{}`",
@@ -550,6 +733,10 @@ pub struct NoFields {
 }
 
 impl Diagnostic for NoFields {
+    fn code(&self) -> &'static str {
+        error_codes::NO_FIELDS
+    }
+
     fn message(&self) -> String {
         "attempted to access a field on a primitive type.".to_string()
     }
@@ -569,6 +756,10 @@ pub struct NoSuchField {
 }
 
 impl Diagnostic for NoSuchField {
+    fn code(&self) -> &'static str {
+        error_codes::NO_SUCH_FIELD
+    }
+
     fn message(&self) -> String {
         "no such field".to_string()
     }
@@ -589,6 +780,10 @@ pub struct PossiblyUninitializedVariable {
 }
 
 impl Diagnostic for PossiblyUninitializedVariable {
+    fn code(&self) -> &'static str {
+        error_codes::POSSIBLY_UNINITIALIZED_VARIABLE
+    }
+
     fn message(&self) -> String {
         "use of possibly-uninitialized variable".to_string()
     }
@@ -608,6 +803,10 @@ pub struct ExternCannotHaveBody {
 }
 
 impl Diagnostic for ExternCannotHaveBody {
+    fn code(&self) -> &'static str {
+        error_codes::EXTERN_CANNOT_HAVE_BODY
+    }
+
     fn message(&self) -> String {
         "extern functions cannot have bodies".to_string()
     }
@@ -627,6 +826,10 @@ pub struct ExternNonPrimitiveParam {
 }
 
 impl Diagnostic for ExternNonPrimitiveParam {
+    fn code(&self) -> &'static str {
+        error_codes::EXTERN_NON_PRIMITIVE_PARAM
+    }
+
     fn message(&self) -> String {
         "extern functions can only have primitives as parameter- and return types".to_string()
     }
@@ -647,6 +850,10 @@ pub struct IntLiteralTooLarge {
 }
 
 impl Diagnostic for IntLiteralTooLarge {
+    fn code(&self) -> &'static str {
+        error_codes::INT_LITERAL_TOO_LARGE
+    }
+
     fn message(&self) -> String {
         "int literal is too large".to_owned()
     }
@@ -668,6 +875,10 @@ pub struct LiteralOutOfRange {
 }
 
 impl Diagnostic for LiteralOutOfRange {
+    fn code(&self) -> &'static str {
+        error_codes::LITERAL_OUT_OF_RANGE
+    }
+
     fn message(&self) -> String {
         format!("literal out of range for `{}`", self.int_ty.as_str())
     }
@@ -690,6 +901,10 @@ pub struct InvalidLiteralSuffix {
 }
 
 impl Diagnostic for InvalidLiteralSuffix {
+    fn code(&self) -> &'static str {
+        error_codes::INVALID_LITERAL_SUFFIX
+    }
+
     fn message(&self) -> String {
         format!("invalid suffix `{}`", self.suffix)
     }
@@ -712,6 +927,10 @@ pub struct InvalidFloatingPointLiteral {
 }
 
 impl Diagnostic for InvalidFloatingPointLiteral {
+    fn code(&self) -> &'static str {
+        error_codes::INVALID_FLOATING_POINT_LITERAL
+    }
+
     fn message(&self) -> String {
         match self.base {
             2 => "binary float literal is not supported".to_owned(),
@@ -737,6 +956,10 @@ pub struct InvalidLiteral {
 }
 
 impl Diagnostic for InvalidLiteral {
+    fn code(&self) -> &'static str {
+        error_codes::INVALID_LITERAL
+    }
+
     fn message(&self) -> String {
         "invalid literal value".to_owned()
     }
@@ -756,6 +979,10 @@ pub struct FreeTypeAliasWithoutTypeRef {
 }
 
 impl Diagnostic for FreeTypeAliasWithoutTypeRef {
+    fn code(&self) -> &'static str {
+        error_codes::FREE_TYPE_ALIAS_WITHOUT_TYPE_REF
+    }
+
     fn message(&self) -> String {
         "free type alias without type ref".to_string()
     }
@@ -775,6 +1002,10 @@ pub struct UnresolvedImport {
 }
 
 impl Diagnostic for UnresolvedImport {
+    fn code(&self) -> &'static str {
+        error_codes::UNRESOLVED_IMPORT
+    }
+
     fn message(&self) -> String {
         "unresolved import".to_string()
     }
@@ -794,6 +1025,10 @@ pub struct ImportDuplicateDefinition {
 }
 
 impl Diagnostic for ImportDuplicateDefinition {
+    fn code(&self) -> &'static str {
+        error_codes::IMPORT_DUPLICATE_DEFINITION
+    }
+
     fn message(&self) -> String {
         "a second item with the same name imported. Try to use an alias.".to_string()
     }
@@ -815,6 +1050,10 @@ pub struct PrivateTypeAlias {
 }
 
 impl Diagnostic for PrivateTypeAlias {
+    fn code(&self) -> &'static str {
+        error_codes::PRIVATE_TYPE_ALIAS
+    }
+
     fn message(&self) -> String {
         format!("{} `{}` is private", self.kind, self.name)
     }
@@ -834,6 +1073,10 @@ pub struct ImplForForeignType {
 }
 
 impl Diagnostic for ImplForForeignType {
+    fn code(&self) -> &'static str {
+        error_codes::IMPL_FOR_FOREIGN_TYPE
+    }
+
     fn message(&self) -> String {
         String::from("cannot define inherent `impl` for foreign type")
     }
@@ -853,6 +1096,10 @@ pub struct InvalidSelfTyImpl {
 }
 
 impl Diagnostic for InvalidSelfTyImpl {
+    fn code(&self) -> &'static str {
+        error_codes::INVALID_SELF_TY_IMPL
+    }
+
     fn message(&self) -> String {
         String::from("inherent `impl` blocks can only be added for structs")
     }
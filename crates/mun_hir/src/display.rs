@@ -82,6 +82,9 @@ impl HirDisplay for Function {
             let name = param.name(db);
             if idx != 0 {
                 write!(f, ", ")?;
+            } else if data.has_self_param() {
+                write!(f, "self")?;
+                continue;
             }
             match name {
                 Some(name) => write!(f, "{name}: ")?,
@@ -74,6 +74,12 @@ impl Body {
                 collector = ExprCollector::new(def, src.file_id, db);
                 collector.collect_fn_body(&src.value);
             }
+            DefWithBodyId::ConstId(c) => {
+                let c = c.lookup(db);
+                let src = c.source(db);
+                collector = ExprCollector::new(def, src.file_id, db);
+                collector.collect_const_body(&src.value);
+            }
         }
 
         let (body, source_map) = collector.finish();
@@ -568,6 +574,16 @@ impl<'a> ExprCollector<'a> {
         self.ret_type = Some(ret_type);
     }
 
+    fn collect_const_body(&mut self, node: &ast::ConstDef) {
+        let body = self.collect_expr_opt(node.body());
+        self.body_expr = Some(body);
+
+        let ret_type = self
+            .type_ref_builder
+            .alloc_from_node_opt(node.ascribed_type().as_ref());
+        self.ret_type = Some(ret_type);
+    }
+
     fn collect_block_opt(&mut self, block: Option<ast::BlockExpr>) -> ExprId {
         if let Some(block) = block {
             self.collect_block(block)
@@ -72,13 +72,12 @@ impl<'a> ExprValidator<'a> {
         let file_id = self.func.source(self.db.upcast()).file_id;
         param_types
             .filter(|(ty, _)| !type_is_allowed(ty))
-            .for_each(|(_, type_ref)| {
+            // An implicitly typed `self` parameter has no syntax to point at
+            .filter_map(|(_, type_ref)| fn_data.type_ref_source_map().type_ref_syntax(*type_ref))
+            .for_each(|type_ref| {
                 sink.push(ExportedPrivate {
                     file: file_id,
-                    type_ref: fn_data
-                        .type_ref_source_map()
-                        .type_ref_syntax(*type_ref)
-                        .unwrap(),
+                    type_ref,
                 });
             });
     }
@@ -103,11 +102,14 @@ impl<'a> ExprValidator<'a> {
             let fn_data = self.func.data(self.db.upcast());
             for (arg_ty, ty_ref) in sig.params().iter().zip(fn_data.params()) {
                 if arg_ty.as_struct().is_some() {
-                    let arg_ptr = fn_data
+                    // An implicitly typed `self` parameter has no syntax to point at
+                    let Some(arg_ptr) = fn_data
                         .type_ref_source_map()
                         .type_ref_syntax(*ty_ref)
                         .map(|ptr| ptr.syntax_node_ptr())
-                        .unwrap();
+                    else {
+                        continue;
+                    };
                     sink.push(ExternNonPrimitiveParam {
                         param: InFile::new(self.func.source(self.db.upcast()).file_id, arg_ptr),
                     });
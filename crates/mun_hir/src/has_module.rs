@@ -2,7 +2,7 @@ use mun_hir_input::ModuleId;
 
 use crate::{
     ids::{
-        AssocItemId, AssocItemLoc, FunctionId, ImplId, ItemContainerId, Lookup, StructId,
+        AssocItemId, AssocItemLoc, ConstId, FunctionId, ImplId, ItemContainerId, Lookup, StructId,
         TypeAliasId,
     },
     item_tree::ItemTreeNode,
@@ -53,6 +53,12 @@ impl HasModule for TypeAliasId {
     }
 }
 
+impl HasModule for ConstId {
+    fn module(&self, db: &dyn DefDatabase) -> ModuleId {
+        self.lookup(db).module
+    }
+}
+
 impl HasModule for AssocItemId {
     fn module(&self, db: &dyn DefDatabase) -> ModuleId {
         match self {
@@ -3,7 +3,7 @@ use std::hash::{Hash, Hasher};
 use mun_hir_input::ModuleId;
 
 use crate::{
-    item_tree::{Function, Impl, ItemTreeId, ItemTreeNode, Struct, TypeAlias},
+    item_tree::{Const, Function, Impl, ItemTreeId, ItemTreeNode, Struct, TypeAlias},
     primitive_type::PrimitiveType,
     DefDatabase,
 };
@@ -134,6 +134,12 @@ impl_intern!(
     lookup_intern_type_alias
 );
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstId(salsa::InternId);
+
+pub(crate) type ConstLoc = ItemLoc<Const>;
+impl_intern!(ConstId, ConstLoc, intern_const, lookup_intern_const);
+
 pub trait Intern {
     type ID;
     fn intern(self, db: &dyn DefDatabase) -> Self::ID;
@@ -150,6 +156,7 @@ pub enum ItemDefinitionId {
     FunctionId(FunctionId),
     StructId(StructId),
     TypeAliasId(TypeAliasId),
+    ConstId(ConstId),
     PrimitiveType(PrimitiveType),
 }
 
@@ -177,6 +184,12 @@ impl From<TypeAliasId> for ItemDefinitionId {
     }
 }
 
+impl From<ConstId> for ItemDefinitionId {
+    fn from(id: ConstId) -> Self {
+        ItemDefinitionId::ConstId(id)
+    }
+}
+
 impl From<PrimitiveType> for ItemDefinitionId {
     fn from(id: PrimitiveType) -> Self {
         ItemDefinitionId::PrimitiveType(id)
@@ -193,6 +206,7 @@ pub enum AssocItemId {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DefWithBodyId {
     FunctionId(FunctionId),
+    ConstId(ConstId),
 }
 
 impl From<FunctionId> for DefWithBodyId {
@@ -200,3 +214,9 @@ impl From<FunctionId> for DefWithBodyId {
         DefWithBodyId::FunctionId(id)
     }
 }
+
+impl From<ConstId> for DefWithBodyId {
+    fn from(id: ConstId) -> Self {
+        DefWithBodyId::ConstId(id)
+    }
+}
@@ -226,7 +226,9 @@ impl PerNs<(ItemDefinitionId, Visibility)> {
         has_constructor: bool,
     ) -> PerNs<(ItemDefinitionId, Visibility)> {
         match def {
-            ItemDefinitionId::FunctionId(_) => PerNs::values((def, vis)),
+            ItemDefinitionId::FunctionId(_) | ItemDefinitionId::ConstId(_) => {
+                PerNs::values((def, vis))
+            }
             ItemDefinitionId::StructId(_) => {
                 if has_constructor {
                     PerNs::both((def, vis), (def, vis))
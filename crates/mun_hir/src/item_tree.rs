@@ -117,6 +117,7 @@ struct ItemTreeData {
     structs: Arena<Struct>,
     fields: Arena<Field>,
     type_aliases: Arena<TypeAlias>,
+    consts: Arena<Const>,
     impls: Arena<Impl>,
 
     visibilities: ItemVisibilities,
@@ -228,6 +229,7 @@ mod_items! {
     Function in functions -> ast::FunctionDef,
     Struct in structs -> ast::StructDef,
     TypeAlias in type_aliases -> ast::TypeAliasDef,
+    Const in consts -> ast::ConstDef,
     Import in imports -> ast::Use,
     Impl in impls -> ast::Impl,
 }
@@ -374,6 +376,15 @@ pub struct TypeAlias {
     pub ast_id: FileAstId<ast::TypeAliasDef>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Const {
+    pub name: Name,
+    pub visibility: RawVisibilityId,
+    pub types: TypeRefMap,
+    pub type_ref: Option<LocalTypeRefId>,
+    pub ast_id: FileAstId<ast::ConstDef>,
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum AssociatedItem {
     Function(LocalItemTreeId<Function>),
@@ -513,6 +524,10 @@ mod diagnostics {
                         item_tree.file_id,
                         SyntaxNodePtr::new(item_tree.source(db, item).syntax()),
                     ),
+                    ModItem::Const(item) => InFile::new(
+                        item_tree.file_id,
+                        SyntaxNodePtr::new(item_tree.source(db, item).syntax()),
+                    ),
                     ModItem::Import(it) => {
                         let import = &item_tree[it];
                         let import_src = item_tree.source(db, it);
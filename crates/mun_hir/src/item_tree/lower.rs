@@ -10,9 +10,9 @@ use mun_syntax::ast::{
 use smallvec::SmallVec;
 
 use super::{
-    diagnostics, AssociatedItem, Field, Fields, Function, FunctionFlags, IdRange, Impl, ItemTree,
-    ItemTreeData, ItemTreeNode, ItemVisibilities, LocalItemTreeId, ModItem, Param, ParamAstId,
-    RawVisibilityId, Struct, TypeAlias,
+    diagnostics, AssociatedItem, Const, Field, Fields, Function, FunctionFlags, IdRange, Impl,
+    ItemTree, ItemTreeData, ItemTreeNode, ItemVisibilities, LocalItemTreeId, ModItem, Param,
+    ParamAstId, RawVisibilityId, Struct, TypeAlias,
 };
 use crate::{
     item_tree::Import,
@@ -77,6 +77,7 @@ impl Context {
                 ModItem::Function(item) => Some(&self.data.functions[item.index].name),
                 ModItem::Struct(item) => Some(&self.data.structs[item.index].name),
                 ModItem::TypeAlias(item) => Some(&self.data.type_aliases[item.index].name),
+                ModItem::Const(item) => Some(&self.data.consts[item.index].name),
                 ModItem::Import(item) => {
                     let import = &self.data.imports[item.index];
                     if import.is_glob {
@@ -118,6 +119,7 @@ impl Context {
             ast::ModuleItemKind::FunctionDef(ast) => self.lower_function(&ast).map(Into::into),
             ast::ModuleItemKind::StructDef(ast) => self.lower_struct(&ast).map(Into::into),
             ast::ModuleItemKind::TypeAliasDef(ast) => self.lower_type_alias(&ast).map(Into::into),
+            ast::ModuleItemKind::ConstDef(ast) => self.lower_const(&ast).map(Into::into),
             ast::ModuleItemKind::Use(ast) => Some(ModItems(
                 self.lower_use(&ast).into_iter().map(Into::into).collect(),
             )),
@@ -309,6 +311,24 @@ impl Context {
         Some(self.data.type_aliases.alloc(res).into())
     }
 
+    /// Lowers a constant (e.g. `const FOO: i32 = 5`)
+    fn lower_const(&mut self, konst: &ast::ConstDef) -> Option<LocalItemTreeId<Const>> {
+        let name = konst.name()?.as_name();
+        let visibility = lower_visibility(konst);
+        let mut types = TypeRefMap::builder();
+        let type_ref = konst.ascribed_type().map(|ty| types.alloc_from_node(&ty));
+        let ast_id = self.source_ast_id_map.ast_id(konst);
+        let (types, _types_source_map) = types.finish();
+        let res = Const {
+            name,
+            visibility,
+            types,
+            type_ref,
+            ast_id,
+        };
+        Some(self.data.consts.alloc(res).into())
+    }
+
     fn lower_impl(&mut self, impl_def: &ast::Impl) -> Option<LocalItemTreeId<Impl>> {
         let ast_id = self.source_ast_id_map.ast_id(impl_def);
         let mut types = TypeRefMap::builder();
@@ -2,8 +2,8 @@ use std::{fmt, fmt::Write};
 
 use crate::{
     item_tree::{
-        Fields, Function, Impl, Import, ItemTree, LocalItemTreeId, ModItem, Param, RawVisibilityId,
-        Struct, TypeAlias,
+        Const, Fields, Function, Impl, Import, ItemTree, LocalItemTreeId, ModItem, Param,
+        RawVisibilityId, Struct, TypeAlias,
     },
     path::ImportAlias,
     pretty::{print_path, print_type_ref},
@@ -68,6 +68,7 @@ impl Printer<'_> {
             ModItem::Function(it) => self.print_function(it),
             ModItem::Struct(it) => self.print_struct(it),
             ModItem::TypeAlias(it) => self.print_type_alias(it),
+            ModItem::Const(it) => self.print_const(it),
             ModItem::Import(it) => self.print_use(it),
             ModItem::Impl(it) => self.print_impl(it),
         }
@@ -115,6 +116,24 @@ impl Printer<'_> {
         writeln!(self, ";")
     }
 
+    /// Prints a constant to the buffer.
+    fn print_const(&mut self, it: LocalItemTreeId<Const>) -> fmt::Result {
+        let Const {
+            name,
+            visibility,
+            types,
+            type_ref,
+            ast_id: _,
+        } = &self.tree[it];
+        self.print_visibility(*visibility)?;
+        write!(self, "const {name}")?;
+        if let Some(ty) = type_ref {
+            write!(self, ": ")?;
+            self.print_type_ref(*ty, types)?;
+        }
+        writeln!(self, " = _;")
+    }
+
     /// Prints a struct to the buffer.
     fn print_struct(&mut self, it: LocalItemTreeId<Struct>) -> fmt::Result {
         let Struct {
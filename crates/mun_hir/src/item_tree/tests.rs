@@ -94,3 +94,16 @@ fn test_duplicate_import() {
     )
     .unwrap());
 }
+
+#[test]
+fn test_consts() {
+    insta::assert_snapshot!(print_item_tree(
+        r#"
+    const FOO: i32 = 5;
+    pub(package) const BAR: f64 = FOO * 2.0;
+    const BAZ = 3;
+    const FOO: bool = true;
+    "#
+    )
+    .unwrap());
+}
@@ -11,10 +11,11 @@ pub use mun_hir_input::ModuleId;
 pub use salsa;
 
 pub use self::code_model::{
-    Field, Function, FunctionData, HasSource, Module, ModuleDef, Package, Struct, StructMemoryKind,
-    TypeAlias,
+    AssocItem, Const, Field, Function, FunctionData, HasSource, Impl, Module, ModuleDef, Package,
+    Struct, StructMemoryKind, TypeAlias,
 };
 pub use crate::{
+    consteval::{ConstEvalError, ConstValue},
     db::{
         AstDatabase, AstDatabaseStorage, DefDatabase, DefDatabaseStorage, HirDatabase,
         HirDatabaseStorage, InternDatabase, InternDatabaseStorage,
@@ -33,8 +34,8 @@ pub use crate::{
     primitive_type::{FloatBitness, IntBitness, Signedness},
     resolve::{resolver_for_expr, resolver_for_scope, Resolver, TypeNs, ValueNs},
     ty::{
-        lower::CallableDef, FloatTy, InferenceResult, IntTy, ResolveBitness, Substitution, Ty,
-        TyKind,
+        lower::CallableDef, FloatTy, InferenceResult, IntMethod, IntTy, OverflowBehavior,
+        ResolveBitness, Substitution, Ty, TyKind,
     },
     visibility::{HasVisibility, Visibility},
 };
@@ -43,6 +44,7 @@ use crate::{name::AsName, source_id::AstIdMap};
 #[macro_use]
 mod macros;
 mod code_model;
+mod consteval;
 mod db;
 pub mod diagnostics;
 mod display;
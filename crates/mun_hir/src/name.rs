@@ -116,6 +116,11 @@ pub mod known {
         bool,
     );
 
+    known_names!(
+        // Methods that overload operators
+        add, sub, mul, div, rem, shl, shr, bitand, bitor, bitxor, neg, not, eq,
+    );
+
     // self/Self cannot be used as an identifier
     pub const SELF_PARAM: super::Name = super::Name::new_static("self");
     pub const SELF_TYPE: super::Name = super::Name::new_static("Self");
@@ -2,7 +2,7 @@ use mun_hir_input::WithFixture;
 use rustc_hash::FxHashSet;
 
 use crate::{
-    db::DefDatabase, ids::ItemDefinitionId, mock::MockDatabase, package_defs::PackageDefs,
+    db::DefDatabase, ids::ItemDefinitionId, mock::MockDatabase, package_defs::PackageDefs, Const,
     DiagnosticSink, Function, HirDatabase, Module, Package, Struct, TypeAlias,
 };
 
@@ -238,9 +238,7 @@ fn tree_for_module(
     // Construct a tree node
     let mut node = text_trees::StringTreeNode::new(format!(
         "mod {}",
-        module
-            .name(db)
-            .map_or_else(|| "mod".to_owned(), |name| name.to_string())
+        module.name(db).unwrap_or_else(|| "mod".to_owned())
     ));
 
     // Add module level diagnostics
@@ -324,6 +322,20 @@ fn tree_for_module(
                     node.push(format!("use type {fully_qualified_name}"));
                 }
             }
+            ItemDefinitionId::ConstId(konst) => {
+                let konst: Const = (*konst).into();
+                let name = konst.name(db);
+                if is_local {
+                    node.push(format!("const {name}"));
+                } else {
+                    let fully_qualified_name = format!(
+                        "{}::{}",
+                        fully_qualified_module_path(db, konst.module(db)),
+                        name
+                    );
+                    node.push(format!("use const {fully_qualified_name}"));
+                }
+            }
             ItemDefinitionId::PrimitiveType(_) => {}
         }
     }
@@ -342,10 +354,7 @@ fn fully_qualified_module_path(db: &dyn HirDatabase, module: Module) -> String {
         module
             .path_to_root(db)
             .into_iter()
-            .map(|m| {
-                m.name(db)
-                    .map_or_else(|| "package".to_owned(), |name| name.to_string())
-            })
+            .map(|m| m.name(db).unwrap_or_else(|| "package".to_owned()))
             .rev(),
         "::".to_string(),
     )
@@ -71,6 +71,7 @@ register_ast_id_node! {
         StructDef,
         Impl,
         TypeAliasDef,
+        ConstDef,
     Param, SelfParam
 }
 
@@ -11,6 +11,7 @@ pub use infer::InferenceResult;
 pub(crate) use lower::{
     callable_item_sig, fn_sig_for_fn, type_for_def, type_for_impl_self, CallableDef, TypableDef,
 };
+pub use op::{IntMethod, OverflowBehavior};
 pub use primitives::{FloatTy, IntTy};
 pub use resolve::ResolveBitness;
 use smallvec::SmallVec;
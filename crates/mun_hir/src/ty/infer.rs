@@ -2,10 +2,10 @@ use std::{ops::Index, sync::Arc};
 
 use la_arena::ArenaMap;
 use mun_hir_input::ModuleId;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
-    code_model::{Struct, StructKind},
+    code_model::{DefWithBody, Struct, StructKind},
     diagnostics::DiagnosticSink,
     expr::{Body, Expr, ExprId, Literal, Pat, PatId, RecordLitField, Statement, UnaryOp},
     name_resolution::Namespace,
@@ -13,7 +13,7 @@ use crate::{
     ty::{
         infer::{diagnostics::InferenceDiagnostic, type_variable::TypeVariableTable},
         lower::LowerDiagnostic,
-        op, Ty, TypableDef,
+        op, FnSig, IntMethod, Ty, TypableDef,
     },
     type_ref::LocalTypeRefId,
     BinaryOp, Function, HirDatabase, Name, Path,
@@ -26,8 +26,9 @@ mod unify;
 use crate::{
     expr::{LiteralFloat, LiteralFloatKind, LiteralInt, LiteralIntKind},
     has_module::HasModule,
-    ids::DefWithBodyId,
+    ids::{DefWithBodyId, FunctionId},
     method_resolution::lookup_method,
+    primitive_type::PrimitiveType,
     resolve::{resolver_for_expr, HasResolver, ResolveValueResult},
     ty::{
         primitives::{FloatTy, IntTy},
@@ -57,6 +58,8 @@ impl Default for InternedStandardTypes {
 pub struct InferenceResult {
     pub(crate) type_of_expr: ArenaMap<ExprId, Ty>,
     pub(crate) type_of_pat: ArenaMap<PatId, Ty>,
+    pub(crate) method_resolutions: FxHashMap<ExprId, FunctionId>,
+    pub(crate) int_methods: FxHashMap<ExprId, IntMethod>,
     pub(crate) diagnostics: Vec<diagnostics::InferenceDiagnostic>,
 
     /// Interned Unknown to return references to.
@@ -82,12 +85,27 @@ impl Index<PatId> for InferenceResult {
 }
 
 impl InferenceResult {
+    /// Returns the method that implements the operator of the specified unary
+    /// or binary operator expression, if the operator is overloaded.
+    pub fn method_resolution(&self, expr: ExprId) -> Option<Function> {
+        self.method_resolutions
+            .get(&expr)
+            .copied()
+            .map(Function::from)
+    }
+
+    /// Returns the built-in integer method that is called by the specified call
+    /// expression, e.g. `i32::wrapping_add(a, b)`.
+    pub fn int_method(&self, expr: ExprId) -> Option<IntMethod> {
+        self.int_methods.get(&expr).copied()
+    }
+
     /// Adds all the `InferenceDiagnostic`s of the result to the
     /// `DiagnosticSink`.
     pub(crate) fn add_diagnostics(
         &self,
         db: &dyn HirDatabase,
-        owner: Function,
+        owner: DefWithBody,
         sink: &mut DiagnosticSink<'_>,
     ) {
         self.diagnostics
@@ -105,7 +123,7 @@ pub fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceRes
     let mut ctx = InferenceResultBuilder::new(db, &body, resolver);
 
     match def {
-        DefWithBodyId::FunctionId(_) => ctx.infer_signature(),
+        DefWithBodyId::FunctionId(_) | DefWithBodyId::ConstId(_) => ctx.infer_signature(),
     }
 
     ctx.infer_body();
@@ -155,6 +173,8 @@ struct InferenceResultBuilder<'a> {
 
     type_of_expr: ArenaMap<ExprId, Ty>,
     type_of_pat: ArenaMap<PatId, Ty>,
+    method_resolutions: FxHashMap<ExprId, FunctionId>,
+    int_methods: FxHashMap<ExprId, IntMethod>,
     diagnostics: Vec<InferenceDiagnostic>,
 
     type_variables: TypeVariableTable,
@@ -177,6 +197,8 @@ impl<'a> InferenceResultBuilder<'a> {
         InferenceResultBuilder {
             type_of_expr: ArenaMap::default(),
             type_of_pat: ArenaMap::default(),
+            method_resolutions: FxHashMap::default(),
+            int_methods: FxHashMap::default(),
             diagnostics: Vec::default(),
             active_loop: None,
             type_variables: TypeVariableTable::default(),
@@ -191,6 +213,7 @@ impl<'a> InferenceResultBuilder<'a> {
     pub fn module(&self) -> ModuleId {
         match self.body.owner() {
             DefWithBodyId::FunctionId(func) => func.module(self.db.upcast()),
+            DefWithBodyId::ConstId(konst) => konst.module(self.db.upcast()),
         }
     }
 
@@ -352,17 +375,22 @@ impl<'a> InferenceResultBuilder<'a> {
                             });
                         }
                     };
-                    let rhs_expected = op::binary_op_rhs_expectation(*op, lhs_ty.clone());
-                    if lhs_ty.is_known() && rhs_expected.is_unknown() {
-                        self.diagnostics
-                            .push(InferenceDiagnostic::CannotApplyBinaryOp {
-                                id: tgt_expr,
-                                lhs: lhs_ty,
-                                rhs: rhs_expected.clone(),
-                            });
+                    if let Some(ty) = self.infer_overloaded_binary_op(tgt_expr, *op, &lhs_ty, *rhs)
+                    {
+                        ty
+                    } else {
+                        let rhs_expected = op::binary_op_rhs_expectation(*op, lhs_ty.clone());
+                        if lhs_ty.is_known() && rhs_expected.is_unknown() {
+                            self.diagnostics
+                                .push(InferenceDiagnostic::CannotApplyBinaryOp {
+                                    id: tgt_expr,
+                                    lhs: lhs_ty,
+                                    rhs: rhs_expected.clone(),
+                                });
+                        }
+                        let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expected));
+                        op::binary_op_return_ty(*op, rhs_ty)
                     }
-                    let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expected));
-                    op::binary_op_return_ty(*op, rhs_ty)
                 }
                 _ => error_type(),
             },
@@ -475,33 +503,37 @@ impl<'a> InferenceResultBuilder<'a> {
             Expr::UnaryOp { expr, op } => {
                 let inner_ty =
                     self.infer_expr_inner(*expr, &Expectation::none(), &CheckParams::default());
-                match op {
-                    UnaryOp::Not => match inner_ty.interned() {
-                        TyKind::Bool | TyKind::Int(_) | TyKind::InferenceVar(InferTy::Int(_)) => {
-                            inner_ty
-                        }
-                        _ => {
-                            self.diagnostics
-                                .push(InferenceDiagnostic::CannotApplyUnaryOp {
-                                    id: *expr,
-                                    ty: inner_ty,
-                                });
-                            error_type()
-                        }
-                    },
-                    UnaryOp::Neg => match inner_ty.interned() {
-                        TyKind::Float(_)
-                        | TyKind::Int(_)
-                        | TyKind::InferenceVar(InferTy::Int(_) | InferTy::Float(_)) => inner_ty,
-                        _ => {
-                            self.diagnostics
-                                .push(InferenceDiagnostic::CannotApplyUnaryOp {
-                                    id: *expr,
-                                    ty: inner_ty,
-                                });
-                            error_type()
-                        }
-                    },
+                if let Some(ty) = self.infer_overloaded_unary_op(tgt_expr, *op, &inner_ty) {
+                    ty
+                } else {
+                    match op {
+                        UnaryOp::Not => match inner_ty.interned() {
+                            TyKind::Bool
+                            | TyKind::Int(_)
+                            | TyKind::InferenceVar(InferTy::Int(_)) => inner_ty,
+                            _ => {
+                                self.diagnostics
+                                    .push(InferenceDiagnostic::CannotApplyUnaryOp {
+                                        id: *expr,
+                                        ty: inner_ty,
+                                    });
+                                error_type()
+                            }
+                        },
+                        UnaryOp::Neg => match inner_ty.interned() {
+                            TyKind::Float(_)
+                            | TyKind::Int(_)
+                            | TyKind::InferenceVar(InferTy::Int(_) | InferTy::Float(_)) => inner_ty,
+                            _ => {
+                                self.diagnostics
+                                    .push(InferenceDiagnostic::CannotApplyUnaryOp {
+                                        id: *expr,
+                                        ty: inner_ty,
+                                    });
+                                error_type()
+                            }
+                        },
+                    }
                 }
             }
             Expr::Array(array) => {
@@ -587,6 +619,10 @@ impl<'a> InferenceResultBuilder<'a> {
         args: &[ExprId],
         _expected: &Expectation,
     ) -> Ty {
+        if let Some((int_ty, method)) = self.resolve_int_method(callee) {
+            return self.infer_int_method_call(tgt_expr, int_ty, method, args);
+        }
+
         let callee_ty = self.infer_expr_inner(
             callee,
             &Expectation::none(),
@@ -647,6 +683,44 @@ impl<'a> InferenceResultBuilder<'a> {
         }
     }
 
+    /// Resolves the callee of a call expression as a built-in integer method,
+    /// e.g. `i32::wrapping_add`. Returns the integer type and the method.
+    fn resolve_int_method(&self, callee: ExprId) -> Option<(Ty, IntMethod)> {
+        let Expr::Path(path) = &self.body[callee] else {
+            return None;
+        };
+
+        let resolver = resolver_for_expr(self.db.upcast(), self.body.owner(), callee);
+        match resolver.resolve_path_as_value(self.db.upcast(), path)? {
+            ResolveValueResult::Partial(
+                TypeNs::PrimitiveType(PrimitiveType::Int(int_ty)),
+                remaining_index,
+            ) if remaining_index == path.segments.len() - 1 => {
+                let method = IntMethod::from_name(&path.segments[remaining_index])?;
+                Some((TyKind::Int(int_ty.into()).intern(), method))
+            }
+            _ => None,
+        }
+    }
+
+    /// Infers the type of a call to a built-in integer method. Both operands
+    /// and the result have the integer type of the method.
+    fn infer_int_method_call(
+        &mut self,
+        tgt_expr: ExprId,
+        int_ty: Ty,
+        method: IntMethod,
+        args: &[ExprId],
+    ) -> Ty {
+        self.check_call_argument_count(tgt_expr, false, args.len(), 2);
+        for &arg in args {
+            self.infer_expr_coerce(arg, &Expectation::has_type(int_ty.clone()));
+        }
+
+        self.int_methods.insert(tgt_expr, method);
+        int_ty
+    }
+
     /// Checks whether the specified struct type is a unit struct.
     fn check_unit_struct_lit(&mut self, tgt_expr: ExprId, expected: Struct) {
         let struct_data = expected.data(self.db.upcast());
@@ -765,6 +839,76 @@ impl<'a> InferenceResultBuilder<'a> {
         Some(ValueNs::FunctionId(function_id))
     }
 
+    /// Infers the type of a binary operation whose left hand side is a struct
+    /// that overloads the operator with a method, e.g. `fn add(self, rhs: Self)
+    /// -> Self` for both `+` and `+=`. Returns `None` if the operator is not
+    /// overloaded for the type of the left hand side.
+    fn infer_overloaded_binary_op(
+        &mut self,
+        tgt_expr: ExprId,
+        op: BinaryOp,
+        lhs_ty: &Ty,
+        rhs: ExprId,
+    ) -> Option<Ty> {
+        let name = op::binary_op_method_name(op)?;
+        let (method, sig) = self.resolve_operator_method(tgt_expr, lhs_ty, &name, 2)?;
+        let ty = match op {
+            BinaryOp::ArithOp(_) => sig.ret().clone(),
+            BinaryOp::CmpOp(_) if matches!(sig.ret().interned(), TyKind::Bool) => sig.ret().clone(),
+            // Compound assignment stores the result back into the left hand side
+            BinaryOp::Assignment { .. } if sig.ret() == lhs_ty => Ty::unit(),
+            _ => return None,
+        };
+
+        self.infer_expr_coerce(rhs, &Expectation::has_type(sig.params()[1].clone()));
+        self.method_resolutions.insert(tgt_expr, method);
+        Some(ty)
+    }
+
+    /// Infers the type of a unary operation on a struct that overloads the
+    /// operator with a method, e.g. `fn neg(self) -> Self`. Returns `None` if
+    /// the operator is not overloaded for the type of the operand.
+    fn infer_overloaded_unary_op(
+        &mut self,
+        tgt_expr: ExprId,
+        op: UnaryOp,
+        operand_ty: &Ty,
+    ) -> Option<Ty> {
+        let name = op::unary_op_method_name(op);
+        let (method, sig) = self.resolve_operator_method(tgt_expr, operand_ty, &name, 1)?;
+        self.method_resolutions.insert(tgt_expr, method);
+        Some(sig.ret().clone())
+    }
+
+    /// Looks up the method that overloads an operator for `self_ty`. Only
+    /// methods that take `self` and have exactly `num_params` parameters
+    /// (including `self`) are considered.
+    fn resolve_operator_method(
+        &mut self,
+        tgt_expr: ExprId,
+        self_ty: &Ty,
+        name: &Name,
+        num_params: usize,
+    ) -> Option<(FunctionId, FnSig)> {
+        let (method, is_visible) = match lookup_method(self.db, self_ty, self.module(), name) {
+            Ok(method) => (method, true),
+            Err(Some(method)) => (method, false),
+            Err(None) => return None,
+        };
+
+        let sig = self.db.callable_sig(Function::from(method).into());
+        if !self.db.fn_data(method).has_self_param() || sig.params().len() != num_params {
+            return None;
+        }
+
+        if !is_visible {
+            self.diagnostics
+                .push(InferenceDiagnostic::PathIsPrivate { id: tgt_expr });
+        }
+
+        Some((method, sig))
+    }
+
     fn resolve_value_path_inner(
         &mut self,
         resolver: &Resolver,
@@ -817,6 +961,12 @@ impl<'a> InferenceResultBuilder<'a> {
                         .type_for_def(TypableDef::Struct(s.into()), Namespace::Values);
                     Some(ty)
                 }
+                ValueNs::ConstId(c) => {
+                    let ty = self
+                        .db
+                        .type_for_def(TypableDef::Const(c.into()), Namespace::Values);
+                    Some(ty)
+                }
             }
         } else {
             // If no value was found, try to resolve the path as a type. This will always
@@ -883,12 +1033,13 @@ impl<'a> InferenceResultBuilder<'a> {
             *ty = resolved;
         }
         InferenceResult {
-            //            method_resolutions: self.method_resolutions,
             //            field_resolutions: self.field_resolutions,
             //            variant_resolutions: self.variant_resolutions,
             //            assoc_resolutions: self.assoc_resolutions,
             type_of_expr: expr_types,
             type_of_pat: pat_types,
+            method_resolutions: self.method_resolutions,
+            int_methods: self.int_methods,
             diagnostics: self.diagnostics,
             standard_types: InternedStandardTypes::default(),
         }
@@ -1113,7 +1264,7 @@ impl From<PatId> for ExprOrPatId {
 
 mod diagnostics {
     use crate::{
-        code_model::{src::HasSource, StructKind},
+        code_model::{DefWithBody, StructKind},
         diagnostics::{
             AccessUnknownField, BreakOutsideLoop, BreakWithValueOutsideLoop, CannotApplyBinaryOp,
             CannotApplyUnaryOp, CyclicType, DiagnosticSink, ExpectedFunction, FieldCountMismatch,
@@ -1123,7 +1274,7 @@ mod diagnostics {
         },
         ty::infer::ExprOrPatId,
         type_ref::LocalTypeRefId,
-        ExprId, Function, HirDatabase, IntTy, Name, Ty,
+        ExprId, HirDatabase, IntTy, Name, Ty,
     };
 
     #[derive(Debug, PartialEq, Eq, Clone)]
@@ -1226,10 +1377,10 @@ mod diagnostics {
         pub(crate) fn add_to(
             &self,
             db: &dyn HirDatabase,
-            owner: Function,
+            owner: DefWithBody,
             sink: &mut DiagnosticSink<'_>,
         ) {
-            let file = owner.source(db.upcast()).file_id;
+            let file = owner.file_id(db);
             let body = owner.body_source_map(db);
             match self {
                 InferenceDiagnostic::UnresolvedValue { id } => {
@@ -15,7 +15,7 @@ use crate::{
     resolve::{HasResolver, Resolver, TypeNs},
     ty::{FnSig, Substitution, Ty, TyKind},
     type_ref::{LocalTypeRefId, TypeRef, TypeRefMap, TypeRefSourceMap},
-    Function, HasVisibility, HirDatabase, ModuleDef, Path, Struct, TypeAlias, Visibility,
+    Const, Function, HasVisibility, HirDatabase, ModuleDef, Path, Struct, TypeAlias, Visibility,
 };
 
 /// A struct which holds resolved type references to `Ty`s.
@@ -175,6 +175,7 @@ pub enum TypableDef {
     PrimitiveType(PrimitiveType),
     Struct(Struct),
     TypeAlias(TypeAlias),
+    Const(Const),
 }
 
 impl From<Function> for TypableDef {
@@ -195,6 +196,12 @@ impl From<Struct> for TypableDef {
     }
 }
 
+impl From<Const> for TypableDef {
+    fn from(f: Const) -> Self {
+        TypableDef::Const(f)
+    }
+}
+
 impl From<ModuleDef> for Option<TypableDef> {
     fn from(d: ModuleDef) -> Self {
         match d {
@@ -202,6 +209,7 @@ impl From<ModuleDef> for Option<TypableDef> {
             ModuleDef::PrimitiveType(t) => Some(TypableDef::PrimitiveType(t)),
             ModuleDef::Struct(t) => Some(TypableDef::Struct(t)),
             ModuleDef::TypeAlias(t) => Some(TypableDef::TypeAlias(t)),
+            ModuleDef::Const(c) => Some(TypableDef::Const(c)),
             ModuleDef::Module(_) => None,
         }
     }
@@ -244,9 +252,10 @@ pub(crate) fn type_for_def(db: &dyn HirDatabase, def: TypableDef, ns: Namespace)
         (TypableDef::Struct(s), Namespace::Values) => type_for_struct_constructor(db, s),
         (TypableDef::Struct(s), Namespace::Types) => type_for_struct(db, s),
         (TypableDef::TypeAlias(t), Namespace::Types) => type_for_type_alias(db, t),
+        (TypableDef::Const(c), Namespace::Values) => type_for_const(db, c),
 
         // 'error' cases:
-        (TypableDef::Function(_), Namespace::Types)
+        (TypableDef::Function(_) | TypableDef::Const(_), Namespace::Types)
         | (TypableDef::PrimitiveType(_) | TypableDef::TypeAlias(_), Namespace::Values) => {
             TyKind::Unknown.intern()
         }
@@ -324,6 +333,13 @@ fn type_for_type_alias(_db: &dyn HirDatabase, def: TypeAlias) -> Ty {
     TyKind::TypeAlias(def).intern()
 }
 
+/// Build the declared type of a constant.
+fn type_for_const(db: &dyn HirDatabase, def: Const) -> Ty {
+    let data = def.data(db.upcast());
+    let resolver = def.id.resolver(db.upcast());
+    Ty::from_hir(db, &resolver, data.type_ref_map(), data.type_ref_id).0
+}
+
 pub(crate) fn lower_impl_query(db: &dyn HirDatabase, impl_id: ImplId) -> Arc<LowerTyMap> {
     let impl_data = db.impl_data(impl_id);
     let resolver = impl_id.resolver(db.upcast());
@@ -1,6 +1,7 @@
 use crate::{
+    name,
     ty::{infer::InferTy, TyKind},
-    ArithOp, BinaryOp, Ty,
+    ArithOp, BinaryOp, CmpOp, Name, Ty, UnaryOp,
 };
 
 /// Given a binary operation and the type on the left of that operation, returns
@@ -73,3 +74,90 @@ pub(super) fn binary_op_return_ty(op: BinaryOp, rhs_ty: Ty) -> Ty {
         BinaryOp::Assignment { .. } => Ty::unit(),
     }
 }
+
+/// Returns the name of the method through which a struct can overload the
+/// specified binary operator, or `None` if the operator cannot be overloaded.
+///
+/// Compound assignment operators (e.g. `+=`) are implemented by the same method
+/// as their arithmetic counterpart.
+pub(super) fn binary_op_method_name(op: BinaryOp) -> Option<Name> {
+    let name = match op {
+        BinaryOp::ArithOp(op) | BinaryOp::Assignment { op: Some(op) } => match op {
+            ArithOp::Add => name![add],
+            ArithOp::Subtract => name![sub],
+            ArithOp::Multiply => name![mul],
+            ArithOp::Divide => name![div],
+            ArithOp::Remainder => name![rem],
+            ArithOp::LeftShift => name![shl],
+            ArithOp::RightShift => name![shr],
+            ArithOp::BitAnd => name![bitand],
+            ArithOp::BitOr => name![bitor],
+            ArithOp::BitXor => name![bitxor],
+        },
+        // `a != b` is implemented as `!a.eq(b)`
+        BinaryOp::CmpOp(CmpOp::Eq { .. }) => name![eq],
+        BinaryOp::CmpOp(CmpOp::Ord { .. })
+        | BinaryOp::LogicOp(_)
+        | BinaryOp::Assignment { op: None } => return None,
+    };
+    Some(name)
+}
+
+/// Returns the name of the method through which a struct can overload the
+/// specified unary operator.
+pub(super) fn unary_op_method_name(op: UnaryOp) -> Name {
+    match op {
+        UnaryOp::Neg => name![neg],
+        UnaryOp::Not => name![not],
+    }
+}
+
+/// Describes what happens when the result of an integer operation does not fit
+/// in its type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowBehavior {
+    /// The result wraps around at the boundary of the type.
+    Wrapping,
+
+    /// The result is clamped to the minimum or maximum value of the type.
+    Saturating,
+
+    /// Execution traps into the runtime, regardless of the optimization level.
+    /// Unlike the `checked_*` methods of Rust, which return an `Option`, this
+    /// corresponds to the `strict_*` methods.
+    Strict,
+}
+
+/// An arithmetic method that is built into every integer type, e.g.
+/// `i32::wrapping_add(a, b)`. These provide explicit overflow behavior,
+/// independent of whether overflow checks are enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IntMethod {
+    /// The arithmetic operation; either addition, subtraction or
+    /// multiplication.
+    pub op: ArithOp,
+
+    /// What happens when the result overflows.
+    pub overflow: OverflowBehavior,
+}
+
+impl IntMethod {
+    /// Returns the built-in integer method with the specified name, e.g.
+    /// `saturating_sub`.
+    pub(super) fn from_name(name: &Name) -> Option<IntMethod> {
+        let (overflow, op) = name.as_str()?.split_once('_')?;
+        let overflow = match overflow {
+            "wrapping" => OverflowBehavior::Wrapping,
+            "saturating" => OverflowBehavior::Saturating,
+            "strict" => OverflowBehavior::Strict,
+            _ => return None,
+        };
+        let op = match op {
+            "add" => ArithOp::Add,
+            "sub" => ArithOp::Subtract,
+            "mul" => ArithOp::Multiply,
+            _ => return None,
+        };
+        Some(IntMethod { op, overflow })
+    }
+}
@@ -148,6 +148,33 @@ impl IntTy {
             },
         }
     }
+
+    /// Returns the minimum number that this instance can contain.
+    pub fn min(self) -> i128 {
+        match self.signedness {
+            Signedness::Signed => match self.bitness {
+                IntBitness::X8 => i8::MIN.into(),
+                IntBitness::X16 => i16::MIN.into(),
+                IntBitness::X32 => i32::MIN.into(),
+                IntBitness::X64 => i64::MIN.into(),
+                IntBitness::X128 => i128::MIN,
+                IntBitness::Xsize => unreachable!("cannot determine min size of variable bitness"),
+            },
+            Signedness::Unsigned => 0,
+        }
+    }
+
+    /// Returns the number of bits required to store a value of this type.
+    pub fn bits(self) -> u32 {
+        match self.bitness {
+            IntBitness::X8 => 8,
+            IntBitness::X16 => 16,
+            IntBitness::X32 => 32,
+            IntBitness::X64 => 64,
+            IntBitness::X128 => 128,
+            IntBitness::Xsize => unreachable!("cannot determine bits of variable bitness"),
+        }
+    }
 }
 
 impl From<abi::Integer> for IntBitness {
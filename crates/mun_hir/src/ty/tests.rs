@@ -1118,6 +1118,244 @@ fn invalid_binary_ops() {
     "###);
 }
 
+#[test]
+fn overloaded_ops() {
+    insta::assert_snapshot!(infer(
+        r#"
+    struct(value) Vec2 { x: f32, y: f32 }
+
+    impl Vec2 {
+        fn add(self, rhs: Self) -> Self { Vec2 { x: self.x + rhs.x, y: self.y + rhs.y } }
+        fn mul(self, rhs: f32) -> Self { Vec2 { x: self.x * rhs, y: self.y * rhs } }
+        fn neg(self) -> Self { Vec2 { x: -self.x, y: -self.y } }
+        fn eq(self, rhs: Self) -> bool { self.x == rhs.x && self.y == rhs.y }
+    }
+
+    fn foo(a: Vec2, b: Vec2) {
+        let c = a + b * 2.0;
+        let d = -c;
+        let e = a == b;
+        let f = a != b;
+        let g = a;
+        g += b;
+    }
+    "#),
+    @r#"
+    363..364 'a': Vec2
+    372..373 'b': Vec2
+    381..492 '{     ...= b; }': ()
+    391..392 'c': Vec2
+    395..396 'a': Vec2
+    395..406 'a + b * 2.0': Vec2
+    399..400 'b': Vec2
+    399..406 'b * 2.0': Vec2
+    403..406 '2.0': f32
+    416..417 'd': Vec2
+    420..422 '-c': Vec2
+    421..422 'c': Vec2
+    432..433 'e': bool
+    436..437 'a': Vec2
+    436..442 'a == b': bool
+    441..442 'b': Vec2
+    452..453 'f': bool
+    456..457 'a': Vec2
+    456..462 'a != b': bool
+    461..462 'b': Vec2
+    472..473 'g': Vec2
+    476..477 'a': Vec2
+    483..484 'g': Vec2
+    483..489 'g += b': ()
+    488..489 'b': Vec2
+    62..66 'self': Vec2
+    68..71 'rhs': Vec2
+    87..136 '{ Vec2....y } }': Vec2
+    89..134 'Vec2 {...hs.y }': Vec2
+    99..103 'self': Vec2
+    99..105 'self.x': f32
+    99..113 'self.x + rhs.x': f32
+    108..111 'rhs': Vec2
+    108..113 'rhs.x': f32
+    118..122 'self': Vec2
+    118..124 'self.y': f32
+    118..132 'self.y + rhs.y': f32
+    127..130 'rhs': Vec2
+    127..132 'rhs.y': f32
+    148..152 'self': Vec2
+    154..157 'rhs': f32
+    172..217 '{ Vec2...hs } }': Vec2
+    174..215 'Vec2 {... rhs }': Vec2
+    184..188 'self': Vec2
+    184..190 'self.x': f32
+    184..196 'self.x * rhs': f32
+    193..196 'rhs': f32
+    201..205 'self': Vec2
+    201..207 'self.y': f32
+    201..213 'self.y * rhs': f32
+    210..213 'rhs': f32
+    229..233 'self': Vec2
+    243..278 '{ Vec2....y } }': Vec2
+    245..276 'Vec2 {...lf.y }': Vec2
+    255..262 '-self.x': f32
+    256..260 'self': Vec2
+    256..262 'self.x': f32
+    267..274 '-self.y': f32
+    268..272 'self': Vec2
+    268..274 'self.y': f32
+    289..293 'self': Vec2
+    295..298 'rhs': Vec2
+    314..352 '{ self...hs.y }': bool
+    316..320 'self': Vec2
+    316..322 'self.x': f32
+    316..331 'self.x == rhs.x': bool
+    316..350 'self.x... rhs.y': bool
+    326..329 'rhs': Vec2
+    326..331 'rhs.x': f32
+    335..339 'self': Vec2
+    335..341 'self.y': f32
+    335..350 'self.y == rhs.y': bool
+    345..348 'rhs': Vec2
+    345..350 'rhs.y': f32
+    "#);
+}
+
+#[test]
+fn invalid_overloaded_ops() {
+    insta::assert_snapshot!(infer(
+        r#"
+    struct Foo;
+
+    impl Foo {
+        fn add(self, rhs: Foo) -> i32 { 0 }
+        fn sub(rhs: Foo) -> Foo { Foo }
+        fn eq(self, rhs: Foo) -> i32 { 0 }
+    }
+
+    fn foo(a: Foo) {
+        let c = a + 1;      // error: mismatched type
+        let d = a - a;      // error: `sub` does not take `self`
+        let e = a == a;     // error: `eq` does not return a bool
+        let f = a;
+        f += a;             // error: `add` does not return `Foo`
+        let h = a * a;      // error: no `mul` method
+    }
+    "#),
+    @r#"
+    175..176: mismatched type
+    221..226: cannot apply binary operator
+    282..288: cannot apply binary operator
+    351..357: cannot apply binary operator
+    421..426: cannot apply binary operator
+    149..150 'a': Foo
+    157..460 '{     ...thod }': ()
+    167..168 'c': i32
+    171..172 'a': Foo
+    171..176 'a + 1': i32
+    175..176 '1': i32
+    217..218 'd': {unknown}
+    221..222 'a': Foo
+    221..226 'a - a': {unknown}
+    225..226 'a': Foo
+    278..279 'e': bool
+    282..283 'a': Foo
+    282..288 'a == a': bool
+    287..288 'a': Foo
+    340..341 'f': Foo
+    344..345 'a': Foo
+    351..352 'f': Foo
+    351..357 'f += a': ()
+    356..357 'a': Foo
+    417..418 'h': {unknown}
+    421..422 'a': Foo
+    421..426 'a * a': {unknown}
+    425..426 'a': Foo
+    35..39 'self': Foo
+    41..44 'rhs': Foo
+    58..63 '{ 0 }': i32
+    60..61 '0': i32
+    75..78 'rhs': Foo
+    92..99 '{ Foo }': Foo
+    94..97 'Foo': Foo
+    110..114 'self': Foo
+    116..119 'rhs': Foo
+    133..138 '{ 0 }': i32
+    135..136 '0': i32
+    "#);
+}
+
+#[test]
+fn private_overloaded_op() {
+    insta::assert_snapshot!(infer(
+        r#"
+    //- /foo.mun
+    pub struct Foo;
+
+    impl Foo {
+        fn neg(self) -> Self { self }
+    }
+
+    //- /mod.mun
+    fn main(a: foo::Foo) {
+        let b = -a;         // error: `neg` is private
+    }
+    "#),
+    @r#"
+    35..37: access of private type
+    8..9 'a': Foo
+    21..75 '{     ...vate }': ()
+    31..32 'b': Foo
+    35..37 '-a': Foo
+    36..37 'a': Foo
+    39..43 'self': Foo
+    53..61 '{ self }': Foo
+    55..59 'self': Foo
+    "#);
+}
+
+#[test]
+fn int_methods() {
+    insta::assert_snapshot!(infer(
+        r#"
+    fn main(a: i32, b: u8) {
+        let c = i32::wrapping_add(a, 5);
+        let d = u8::saturating_sub(b, 10);
+        let e = i64::strict_mul(3, 4);
+        let f = i32::wrapping_add(a, b);   // error: mismatched type
+        let g = u8::wrapping_add(b);       // error: expected 2 parameters
+        let h = i32::wrapping_div(a, a);   // error: unresolved value
+    }
+    "#),
+    @r#"
+    169..170: mismatched type
+    213..232: this function takes 2 parameters but 1 parameters was supplied
+    284..301: undefined value
+    8..9 'a': i32
+    16..17 'b': u8
+    23..339 '{     ...alue }': ()
+    33..34 'c': i32
+    37..60 'i32::w...(a, 5)': i32
+    55..56 'a': i32
+    58..59 '5': i32
+    70..71 'd': u8
+    74..99 'u8::sa...b, 10)': u8
+    93..94 'b': u8
+    96..98 '10': u8
+    109..110 'e': i64
+    113..134 'i64::s...(3, 4)': i64
+    129..130 '3': i64
+    132..133 '4': i64
+    144..145 'f': i32
+    148..171 'i32::w...(a, b)': i32
+    166..167 'a': i32
+    169..170 'b': u8
+    209..210 'g': u8
+    213..232 'u8::wr...add(b)': u8
+    230..231 'b': u8
+    280..281 'h': {unknown}
+    284..301 'i32::w...ng_div': {unknown}
+    284..307 'i32::w...(a, a)': {unknown}
+    "#);
+}
+
 #[test]
 fn struct_decl() {
     insta::assert_snapshot!(infer(
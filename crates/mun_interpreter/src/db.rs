@@ -0,0 +1,54 @@
+use mun_db::Upcast;
+use mun_hir::{salsa, AstDatabase, DefDatabase, HirDatabase};
+use mun_hir_input::SourceDatabase;
+use mun_target::spec::Target;
+
+/// A database that only contains the queries required to interpret Mun code,
+/// without the code generation queries that depend on LLVM.
+#[salsa::database(
+    mun_hir_input::SourceDatabaseStorage,
+    mun_hir::InternDatabaseStorage,
+    mun_hir::AstDatabaseStorage,
+    mun_hir::DefDatabaseStorage,
+    mun_hir::HirDatabaseStorage
+)]
+pub struct InterpreterDatabase {
+    storage: salsa::Storage<Self>,
+}
+
+impl Upcast<dyn AstDatabase> for InterpreterDatabase {
+    fn upcast(&self) -> &(dyn AstDatabase + 'static) {
+        self
+    }
+}
+
+impl Upcast<dyn SourceDatabase> for InterpreterDatabase {
+    fn upcast(&self) -> &(dyn SourceDatabase + 'static) {
+        self
+    }
+}
+
+impl Upcast<dyn DefDatabase> for InterpreterDatabase {
+    fn upcast(&self) -> &(dyn DefDatabase + 'static) {
+        self
+    }
+}
+
+impl Upcast<dyn HirDatabase> for InterpreterDatabase {
+    fn upcast(&self) -> &(dyn HirDatabase + 'static) {
+        self
+    }
+}
+
+impl Default for InterpreterDatabase {
+    /// Constructs a database for the host target
+    fn default() -> Self {
+        let mut db = InterpreterDatabase {
+            storage: salsa::Storage::default(),
+        };
+        db.set_target(Target::host_target().expect("unable to determine host target"));
+        db
+    }
+}
+
+impl salsa::Database for InterpreterDatabase {}
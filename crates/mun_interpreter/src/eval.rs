@@ -0,0 +1,871 @@
+use std::{collections::HashMap, ptr::NonNull, sync::Arc};
+
+use mun_hir::{
+    ArithOp, BinaryOp, Body, CallableDef, CmpOp, ConstValue, Expr, ExprId, Function, HirDatabase,
+    InferenceResult, IntMethod, IntTy, Literal, LogicOp, Name, Ordering, OverflowBehavior, Pat,
+    PatId, Path, RecordLitField, ResolveBitness, Signedness, Statement, Struct, TyKind, UnaryOp,
+    ValueNs,
+};
+use mun_memory::{
+    gc::{Array, GcPtr, GcRuntime},
+    Type,
+};
+
+use crate::{
+    value::{self, object_data, offset},
+    InterpretError, Interpreter, PanicKind, Value,
+};
+
+/// The reason why the evaluation of an expression did not produce a value.
+enum Unwind {
+    /// A `return` expression was evaluated
+    Return(Value),
+
+    /// A `break` expression was evaluated
+    Break(Value),
+
+    /// An error occurred
+    Error(InterpretError),
+}
+
+impl From<InterpretError> for Unwind {
+    fn from(error: InterpretError) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+type EvalResult<T = Value> = Result<T, Unwind>;
+
+/// A location that can be assigned to.
+enum Place {
+    /// A local variable, or a field of a value struct that is stored in a
+    /// local variable. The path contains the indices of the nested fields.
+    Local(PatId, Vec<usize>),
+
+    /// A value of the specified type that is stored in memory, e.g. the field
+    /// of a garbage collected struct or the element of an array.
+    Memory(NonNull<u8>, Type),
+}
+
+/// The state of a single call to a Mun function.
+pub(crate) struct Frame<'i, 'db> {
+    interpreter: &'i mut Interpreter<'db>,
+    function: Function,
+    body: Arc<Body>,
+    infer: Arc<InferenceResult>,
+    locals: HashMap<PatId, Value>,
+}
+
+impl<'i, 'db> Frame<'i, 'db> {
+    /// Constructs a frame for a call of `function` with `args`.
+    pub fn new(
+        interpreter: &'i mut Interpreter<'db>,
+        function: Function,
+        args: Vec<Value>,
+    ) -> Self {
+        let db = interpreter.db;
+        let body = function.body(db);
+        let infer = function.infer(db);
+        let locals = body
+            .self_param()
+            .into_iter()
+            .chain(body.params())
+            .map(|(pat, _)| *pat)
+            .zip(args)
+            .collect();
+        Self {
+            interpreter,
+            function,
+            body,
+            infer,
+            locals,
+        }
+    }
+
+    /// Evaluates the body of the function and returns its result.
+    pub fn run(mut self) -> Result<Value, InterpretError> {
+        let body_expr = self.body.body_expr();
+        match self.eval(body_expr) {
+            Ok(value) | Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Break(_)) => unreachable!("`break` outside of a loop"),
+            Err(Unwind::Error(error)) => Err(error),
+        }
+    }
+
+    fn db(&self) -> &'db dyn HirDatabase {
+        self.interpreter.db
+    }
+
+    fn eval(&mut self, expr: ExprId) -> EvalResult {
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Missing => Err(self.invalid()),
+            Expr::Literal(literal) => self.eval_literal(expr, literal),
+            Expr::Path(path) => self.eval_path(expr, path),
+            Expr::Block { statements, tail } => self.eval_block(statements, *tail),
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => self.eval_if(*condition, *then_branch, *else_branch),
+            Expr::Return { expr } => self.eval_return(*expr),
+            Expr::Break { expr } => self.eval_break(*expr),
+            Expr::Loop { body } => self.eval_loop(*body),
+            Expr::While { condition, body } => self.eval_while(*condition, *body),
+            Expr::UnaryOp { expr: operand, op } => self.eval_unary_op(expr, *operand, *op),
+            Expr::BinaryOp { lhs, rhs, op } => match op {
+                Some(op) => self.eval_binary_op(expr, *lhs, *rhs, *op),
+                None => Err(self.invalid()),
+            },
+            Expr::Call { callee, args } => self.eval_call(expr, *callee, args),
+            Expr::RecordLit { fields, .. } => self.eval_record_lit(expr, fields),
+            Expr::Field {
+                expr: receiver,
+                name,
+            } => self.eval_field(*receiver, name),
+            Expr::Array(elements) => self.eval_array(expr, elements),
+            Expr::Index { base, index } => self.eval_index(expr, *base, *index),
+        }
+    }
+
+    fn eval_return(&mut self, expr: Option<ExprId>) -> EvalResult {
+        Err(Unwind::Return(self.eval_opt(expr)?))
+    }
+
+    fn eval_break(&mut self, expr: Option<ExprId>) -> EvalResult {
+        Err(Unwind::Break(self.eval_opt(expr)?))
+    }
+
+    /// Evaluates `expr` if it exists, or returns the unit value.
+    fn eval_opt(&mut self, expr: Option<ExprId>) -> EvalResult {
+        match expr {
+            Some(expr) => self.eval(expr),
+            None => Ok(Value::Unit),
+        }
+    }
+
+    fn eval_bool(&mut self, expr: ExprId) -> EvalResult<bool> {
+        match self.eval(expr)? {
+            Value::Bool(value) => Ok(value),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    fn eval_int(&mut self, expr: ExprId) -> EvalResult<i128> {
+        match self.eval(expr)? {
+            Value::Int(value) => Ok(value),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    fn eval_if(
+        &mut self,
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: Option<ExprId>,
+    ) -> EvalResult {
+        if self.eval_bool(condition)? {
+            self.eval(then_branch)
+        } else {
+            self.eval_opt(else_branch)
+        }
+    }
+
+    fn eval_loop(&mut self, body: ExprId) -> EvalResult {
+        loop {
+            match self.eval(body) {
+                Ok(_) => {}
+                Err(Unwind::Break(value)) => return Ok(value),
+                Err(unwind) => return Err(unwind),
+            }
+        }
+    }
+
+    fn eval_while(&mut self, condition: ExprId, body: ExprId) -> EvalResult {
+        while self.eval_bool(condition)? {
+            match self.eval(body) {
+                Ok(_) => {}
+                Err(Unwind::Break(_)) => break,
+                Err(unwind) => return Err(unwind),
+            }
+        }
+        Ok(Value::Unit)
+    }
+
+    fn eval_literal(&mut self, expr: ExprId, literal: &Literal) -> EvalResult {
+        match literal {
+            Literal::Bool(value) => Ok(Value::Bool(*value)),
+            Literal::Int(literal) => Ok(Value::Int(wrap(self.int_ty(expr), literal.value as i128))),
+            Literal::Float(literal) => Ok(self.float(expr, literal.value)),
+            Literal::String(_) => {
+                Err(InterpretError::Unsupported(String::from("string literals")).into())
+            }
+        }
+    }
+
+    fn eval_path(&mut self, expr: ExprId, path: &Path) -> EvalResult {
+        match self.resolve_path(expr, path)? {
+            ValueNs::LocalBinding(pat) => Ok(self.locals.get(&pat).cloned().unwrap_or(Value::Unit)),
+            ValueNs::StructId(_) => {
+                let s = self.struct_of(expr)?;
+                self.construct(s, Vec::new())
+            }
+            ValueNs::ConstId(id) => {
+                let value = mun_hir::Const::from(id)
+                    .eval(self.db())
+                    .map_err(|_err| self.invalid())?;
+                Ok(match value {
+                    ConstValue::Bool(value) => Value::Bool(value),
+                    ConstValue::Int(value) => Value::Int(wrap(self.int_ty(expr), value)),
+                    ConstValue::UInt(value) => Value::Int(value as i128),
+                    ConstValue::Float(value) => self.float(expr, value),
+                })
+            }
+            ValueNs::FunctionId(_) | ValueNs::ImplSelf(_) => {
+                Err(InterpretError::Unsupported(String::from("function references")).into())
+            }
+        }
+    }
+
+    fn eval_block(&mut self, statements: &[Statement], tail: Option<ExprId>) -> EvalResult {
+        for statement in statements {
+            match statement {
+                Statement::Let {
+                    pat, initializer, ..
+                } => self.eval_let(*pat, *initializer)?,
+                Statement::Expr(expr) => {
+                    self.eval(*expr)?;
+                }
+            }
+        }
+        self.eval_opt(tail)
+    }
+
+    fn eval_let(&mut self, pat: PatId, initializer: Option<ExprId>) -> EvalResult<()> {
+        let value = self.eval_opt(initializer)?;
+        if let Pat::Bind { .. } = self.body[pat] {
+            self.locals.insert(pat, value);
+        }
+        Ok(())
+    }
+
+    fn eval_unary_op(&mut self, expr: ExprId, operand: ExprId, op: UnaryOp) -> EvalResult {
+        let value = self.eval(operand)?;
+
+        // The operator is overloaded by a method (e.g. `fn neg(self) -> Self`)
+        if let Some(method) = self.infer.method_resolution(expr) {
+            return self.call(method, vec![value]);
+        }
+
+        match (op, value) {
+            (UnaryOp::Not, Value::Bool(value)) => Ok(Value::Bool(!value)),
+            (UnaryOp::Not, Value::Int(value)) => Ok(Value::Int(wrap(self.int_ty(operand), !value))),
+            (UnaryOp::Neg, Value::Float(value)) => Ok(Value::Float(-value)),
+            (UnaryOp::Neg, Value::Int(value)) => self
+                .int_arith(expr, self.int_ty(operand), 0, value, ArithOp::Subtract)
+                .map(Value::Int),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    fn eval_binary_op(
+        &mut self,
+        expr: ExprId,
+        lhs: ExprId,
+        rhs: ExprId,
+        op: BinaryOp,
+    ) -> EvalResult {
+        match op {
+            BinaryOp::Assignment { op } => self.eval_assignment(expr, lhs, rhs, op),
+            BinaryOp::LogicOp(op) => self.eval_logic_op(lhs, rhs, op),
+            BinaryOp::ArithOp(_) | BinaryOp::CmpOp(_) => {
+                let lhs_value = self.eval(lhs)?;
+                let rhs_value = self.eval(rhs)?;
+                self.binary_op(expr, lhs, lhs_value, rhs_value, op)
+            }
+        }
+    }
+
+    fn eval_assignment(
+        &mut self,
+        expr: ExprId,
+        lhs: ExprId,
+        rhs: ExprId,
+        op: Option<ArithOp>,
+    ) -> EvalResult {
+        let Some(op) = op else {
+            let value = self.eval(rhs)?;
+            let place = self.place(lhs)?;
+            self.store(place, value);
+            return Ok(Value::Unit);
+        };
+
+        // The left hand side is evaluated once, to the place that is both read and
+        // written
+        let place = self.place(lhs)?;
+        let lhs_value = self.load(&place);
+        let rhs_value = self.eval(rhs)?;
+        let value = match self.infer.method_resolution(expr) {
+            Some(method) => self.call(method, vec![lhs_value, rhs_value])?,
+            None => self.arith(expr, lhs, lhs_value, rhs_value, op)?,
+        };
+        self.store(place, value);
+        Ok(Value::Unit)
+    }
+
+    fn eval_logic_op(&mut self, lhs: ExprId, rhs: ExprId, op: LogicOp) -> EvalResult {
+        // Logical operators short-circuit
+        match (op, self.eval_bool(lhs)?) {
+            (LogicOp::And, false) => Ok(Value::Bool(false)),
+            (LogicOp::Or, true) => Ok(Value::Bool(true)),
+            _ => self.eval_bool(rhs).map(Value::Bool),
+        }
+    }
+
+    /// Applies an arithmetic or comparison operator to the values of `lhs_expr`
+    /// and the right hand side of `expr`.
+    fn binary_op(
+        &mut self,
+        expr: ExprId,
+        lhs_expr: ExprId,
+        lhs: Value,
+        rhs: Value,
+        op: BinaryOp,
+    ) -> EvalResult {
+        // The operator is overloaded by a method of the type on the left hand side,
+        // e.g. `fn add(self, rhs: Self) -> Self`
+        if let Some(method) = self.infer.method_resolution(expr) {
+            let value = self.call(method, vec![lhs, rhs])?;
+            return match (op, value) {
+                // `a != b` is implemented as `!a.eq(b)`
+                (BinaryOp::CmpOp(CmpOp::Eq { negated: true }), Value::Bool(value)) => {
+                    Ok(Value::Bool(!value))
+                }
+                (_, value) => Ok(value),
+            };
+        }
+
+        match op {
+            BinaryOp::ArithOp(op) => self.arith(expr, lhs_expr, lhs, rhs, op),
+            BinaryOp::CmpOp(op) => self.compare(lhs_expr, lhs, rhs, op),
+            BinaryOp::Assignment { .. } | BinaryOp::LogicOp(_) => {
+                unreachable!("operator is evaluated separately")
+            }
+        }
+    }
+
+    /// Calculates an arithmetic operation between the values of `lhs_expr` and
+    /// the right hand side of `expr`.
+    fn arith(
+        &mut self,
+        expr: ExprId,
+        lhs_expr: ExprId,
+        lhs: Value,
+        rhs: Value,
+        op: ArithOp,
+    ) -> EvalResult {
+        match (lhs, rhs) {
+            (Value::Int(lhs), Value::Int(rhs)) => self
+                .int_arith(expr, self.int_ty(lhs_expr), lhs, rhs, op)
+                .map(Value::Int),
+            (Value::Float(lhs), Value::Float(rhs)) => {
+                let value = match op {
+                    ArithOp::Add => lhs + rhs,
+                    ArithOp::Subtract => lhs - rhs,
+                    ArithOp::Multiply => lhs * rhs,
+                    ArithOp::Divide => lhs / rhs,
+                    ArithOp::Remainder => lhs % rhs,
+                    ArithOp::LeftShift
+                    | ArithOp::RightShift
+                    | ArithOp::BitAnd
+                    | ArithOp::BitOr
+                    | ArithOp::BitXor => return Err(self.invalid()),
+                };
+                Ok(self.float(lhs_expr, value))
+            }
+            (Value::Bool(lhs), Value::Bool(rhs)) => match op {
+                ArithOp::BitAnd => Ok(Value::Bool(lhs & rhs)),
+                ArithOp::BitOr => Ok(Value::Bool(lhs | rhs)),
+                ArithOp::BitXor => Ok(Value::Bool(lhs ^ rhs)),
+                _ => Err(self.invalid()),
+            },
+            _ => Err(self.invalid()),
+        }
+    }
+
+    /// Calculates an arithmetic operation between two integers of type `ty`.
+    /// Additions, subtractions, multiplications and signed divisions wrap
+    /// around, unless overflow checks are enabled.
+    fn int_arith(
+        &self,
+        expr: ExprId,
+        ty: IntTy,
+        lhs: i128,
+        rhs: i128,
+        op: ArithOp,
+    ) -> EvalResult<i128> {
+        let value = match op {
+            ArithOp::Add | ArithOp::Subtract | ArithOp::Multiply => {
+                if self.interpreter.overflow_checks {
+                    return checked_op(ty, lhs, rhs, op)
+                        .ok_or_else(|| self.panic(expr, PanicKind::Overflow));
+                }
+                wrapping_op(lhs, rhs, op)
+            }
+            ArithOp::Divide | ArithOp::Remainder => {
+                if rhs == 0 {
+                    return Err(self.panic(expr, PanicKind::DivisionByZero));
+                }
+                // Dividing the minimum of a signed type by -1 overflows, which also applies to
+                // the remainder
+                if self.interpreter.overflow_checks
+                    && ty.signedness == Signedness::Signed
+                    && lhs == ty.min()
+                    && rhs == -1
+                {
+                    return Err(self.panic(expr, PanicKind::Overflow));
+                }
+                match (ty.signedness, op) {
+                    (Signedness::Signed, ArithOp::Divide) => lhs.wrapping_div(rhs),
+                    (Signedness::Signed, _) => lhs.wrapping_rem(rhs),
+                    (Signedness::Unsigned, ArithOp::Divide) => {
+                        ((lhs as u128) / (rhs as u128)) as i128
+                    }
+                    (Signedness::Unsigned, _) => ((lhs as u128) % (rhs as u128)) as i128,
+                }
+            }
+            ArithOp::LeftShift | ArithOp::RightShift => {
+                let shift = match u32::try_from(rhs) {
+                    Ok(shift) if shift < ty.bits() => shift,
+                    _ => return Err(self.panic(expr, PanicKind::Overflow)),
+                };
+                match (ty.signedness, op) {
+                    (_, ArithOp::LeftShift) => lhs << shift,
+                    (Signedness::Signed, _) => lhs >> shift,
+                    (Signedness::Unsigned, _) => ((lhs as u128) >> shift) as i128,
+                }
+            }
+            ArithOp::BitAnd => lhs & rhs,
+            ArithOp::BitOr => lhs | rhs,
+            ArithOp::BitXor => lhs ^ rhs,
+        };
+        Ok(wrap(ty, value))
+    }
+
+    /// Compares the value of `lhs_expr` to `rhs`.
+    fn compare(&self, lhs_expr: ExprId, lhs: Value, rhs: Value, op: CmpOp) -> EvalResult {
+        let ordering = match (lhs, rhs) {
+            (Value::Int(lhs), Value::Int(rhs)) => match self.int_ty(lhs_expr).signedness {
+                Signedness::Signed => lhs.partial_cmp(&rhs),
+                Signedness::Unsigned => (lhs as u128).partial_cmp(&(rhs as u128)),
+            },
+            (Value::Float(lhs), Value::Float(rhs)) => lhs.partial_cmp(&rhs),
+            (Value::Bool(lhs), Value::Bool(rhs)) => lhs.partial_cmp(&rhs),
+            _ => return Err(self.invalid()),
+        };
+
+        // Floating-point comparisons are ordered; they are all false if either operand is NaN.
+        let Some(ordering) = ordering else {
+            return Ok(Value::Bool(false));
+        };
+        let result = match op {
+            CmpOp::Eq { negated } => ordering.is_eq() != negated,
+            CmpOp::Ord {
+                ordering: Ordering::Less,
+                strict,
+            } => ordering.is_lt() || (!strict && ordering.is_eq()),
+            CmpOp::Ord {
+                ordering: Ordering::Greater,
+                strict,
+            } => ordering.is_gt() || (!strict && ordering.is_eq()),
+        };
+        Ok(Value::Bool(result))
+    }
+
+    fn eval_call(&mut self, expr: ExprId, callee: ExprId, args: &[ExprId]) -> EvalResult {
+        if let Some(method) = self.infer.int_method(expr) {
+            return self.eval_int_method(expr, method, args);
+        }
+
+        // The arguments are evaluated in a plain loop, which uses less stack per nested
+        // call than an iterator adapter in unoptimized builds
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args {
+            values.push(self.eval(*arg)?);
+        }
+        let callable = self.infer[callee].as_callable_def();
+        match callable {
+            Some(CallableDef::Function(function)) => self.call(function, values),
+            Some(CallableDef::Struct(s)) => self.construct(s, values),
+            None => Err(self.invalid()),
+        }
+    }
+
+    /// Evaluates a call to a built-in integer method, e.g.
+    /// `i32::saturating_add(a, b)`.
+    fn eval_int_method(&mut self, expr: ExprId, method: IntMethod, args: &[ExprId]) -> EvalResult {
+        let [lhs, rhs] = args else {
+            return Err(self.invalid());
+        };
+        let lhs = self.eval_int(*lhs)?;
+        let rhs = self.eval_int(*rhs)?;
+        let ty = self.int_ty(expr);
+        let value = match method.overflow {
+            OverflowBehavior::Wrapping => wrap(ty, wrapping_op(lhs, rhs, method.op)),
+            OverflowBehavior::Saturating => checked_op(ty, lhs, rhs, method.op)
+                .unwrap_or_else(|| saturated_op(ty, lhs, rhs, method.op)),
+            OverflowBehavior::Strict => checked_op(ty, lhs, rhs, method.op)
+                .ok_or_else(|| self.panic(expr, PanicKind::Overflow))?,
+        };
+        Ok(Value::Int(value))
+    }
+
+    fn call(&mut self, function: Function, args: Vec<Value>) -> EvalResult {
+        Ok(self.interpreter.call(function, args)?)
+    }
+
+    fn eval_record_lit(&mut self, expr: ExprId, fields: &[RecordLitField]) -> EvalResult {
+        let s = self.struct_of(expr)?;
+        let mut values = vec![Value::Unit; s.fields(self.db()).len()];
+        for field in fields {
+            let index = self.struct_field_index(s, &field.name)?;
+            values[index] = self.eval(field.expr)?;
+        }
+        self.construct(s, values)
+    }
+
+    /// Constructs an instance of the struct `s` from the values of its fields.
+    fn construct(&mut self, s: Struct, fields: Vec<Value>) -> EvalResult {
+        let ty = self.interpreter.struct_type(s)?;
+        if ty.is_value_type() {
+            return Ok(Value::Struct { ty, fields });
+        }
+
+        let struct_ty = ty.as_struct().expect("a struct always has a struct type");
+        let gc = &self.interpreter.gc;
+        let object = gc.alloc(&ty);
+        // Safety: the fields are written within the bounds of the newly allocated object
+        unsafe {
+            let data = object_data(object);
+            for (field, value) in struct_ty.fields().iter().zip(fields) {
+                value::write(gc, offset(data, field.offset()), &field.ty(), value);
+            }
+        }
+        Ok(Value::Gc(object))
+    }
+
+    /// Returns the value of the field with the specified `index` of the struct
+    /// `value`.
+    fn field(&self, value: Value, index: usize) -> EvalResult {
+        match value {
+            Value::Struct { mut fields, .. } => Ok(fields.swap_remove(index)),
+            Value::Gc(object) => {
+                let (ptr, ty) = self.object_field(object, index);
+                // Safety: the field is within the bounds of a live object
+                Ok(unsafe { value::read(ptr, &ty) })
+            }
+            _ => Err(self.invalid()),
+        }
+    }
+
+    /// Returns a pointer to, and the type of, the field with the specified
+    /// `index` of the garbage collected struct `object`.
+    fn object_field(&self, object: GcPtr, index: usize) -> (NonNull<u8>, Type) {
+        let ty = self.interpreter.gc.ptr_type(object);
+        let field = ty
+            .as_struct()
+            .and_then(|s| s.fields().get(index))
+            .expect("the object must be a struct with the field");
+        // Safety: the field is within the bounds of a live object
+        let ptr = unsafe { offset(object_data(object), field.offset()) };
+        (ptr, field.ty())
+    }
+
+    fn eval_array(&mut self, expr: ExprId, elements: &[ExprId]) -> EvalResult {
+        let values = elements
+            .iter()
+            .map(|element| self.eval(*element))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ty = self.infer[expr].clone();
+        let ty = self.interpreter.types.ty(self.db(), &ty)?;
+        let gc = &self.interpreter.gc;
+        let array = gc.alloc_array(&ty, values.len());
+        let element_ty = array.element_type();
+        for (index, value) in values.into_iter().enumerate() {
+            // Safety: the elements are written within the bounds of the newly allocated
+            // array
+            unsafe {
+                let ptr = offset(array.data(), index * array.element_stride());
+                value::write(gc, ptr, &element_ty, value);
+            }
+        }
+        Ok(Value::Gc(array.as_raw()))
+    }
+
+    fn eval_field(&mut self, receiver: ExprId, name: &Name) -> EvalResult {
+        let index = self.field_index(receiver, name)?;
+        let receiver = self.eval(receiver)?;
+        self.field(receiver, index)
+    }
+
+    fn eval_index(&mut self, expr: ExprId, base: ExprId, index: ExprId) -> EvalResult {
+        let (ptr, ty) = self.element(expr, base, index)?;
+        // Safety: the element is within the bounds of a live array
+        Ok(unsafe { value::read(ptr, &ty) })
+    }
+
+    /// Returns a pointer to, and the type of, the element of the array `base`
+    /// at `index`.
+    fn element(
+        &mut self,
+        expr: ExprId,
+        base: ExprId,
+        index: ExprId,
+    ) -> EvalResult<(NonNull<u8>, Type)> {
+        let array = match self.eval(base)? {
+            Value::Gc(object) => self
+                .interpreter
+                .gc
+                .array(object)
+                .ok_or_else(|| self.invalid())?,
+            _ => return Err(self.invalid()),
+        };
+        let index = self.eval_int(index)?;
+
+        let length = array.length();
+        let position = match usize::try_from(index) {
+            Ok(position) if position < length => position,
+            _ => return Err(self.panic(expr, PanicKind::IndexOutOfBounds { index, length })),
+        };
+        // Safety: the element is within the bounds of the array
+        let ptr = unsafe { offset(array.data(), position * array.element_stride()) };
+        Ok((ptr, array.element_type()))
+    }
+
+    /// Returns the value that is stored in `place`.
+    fn load(&self, place: &Place) -> Value {
+        match place {
+            Place::Local(pat, path) => self.local(*pat, path).clone(),
+            // Safety: places in memory always point to live objects
+            Place::Memory(ptr, ty) => unsafe { value::read(*ptr, ty) },
+        }
+    }
+
+    /// Stores `value` in `place`.
+    fn store(&mut self, place: Place, value: Value) {
+        match place {
+            Place::Local(pat, path) => {
+                let mut target = self.locals.entry(pat).or_insert(Value::Unit);
+                for index in path {
+                    target = match target {
+                        Value::Struct { fields, .. } => &mut fields[index],
+                        _ => unreachable!("only value structs are stored in locals"),
+                    };
+                }
+                *target = value;
+            }
+            // Safety: places in memory always point to live objects
+            Place::Memory(ptr, ty) => unsafe {
+                value::write(&self.interpreter.gc, ptr, &ty, value);
+            },
+        }
+    }
+
+    /// Returns the place that is described by `expr`.
+    fn place(&mut self, expr: ExprId) -> EvalResult<Place> {
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Path(path) => match self.resolve_path(expr, path)? {
+                ValueNs::LocalBinding(pat) => Ok(Place::Local(pat, Vec::new())),
+                _ => Err(self.invalid()),
+            },
+            Expr::Field {
+                expr: receiver,
+                name,
+            } => {
+                let index = self.field_index(*receiver, name)?;
+                match self.place(*receiver)? {
+                    Place::Local(pat, mut path) => {
+                        if let Value::Gc(object) = self.local(pat, &path) {
+                            let (ptr, ty) = self.object_field(*object, index);
+                            Ok(Place::Memory(ptr, ty))
+                        } else {
+                            path.push(index);
+                            Ok(Place::Local(pat, path))
+                        }
+                    }
+                    Place::Memory(ptr, ty) if ty.is_reference_type() => {
+                        // Safety: places in memory always point to live objects
+                        let object = unsafe { ptr.cast::<GcPtr>().as_ptr().read_unaligned() };
+                        let (ptr, ty) = self.object_field(object, index);
+                        Ok(Place::Memory(ptr, ty))
+                    }
+                    Place::Memory(ptr, ty) => {
+                        let field = ty
+                            .as_struct()
+                            .and_then(|s| s.fields().get(index))
+                            .expect("the value must be a struct with the field");
+                        // Safety: the field is within the bounds of the value struct
+                        let ptr = unsafe { offset(ptr, field.offset()) };
+                        Ok(Place::Memory(ptr, field.ty()))
+                    }
+                }
+            }
+            Expr::Index { base, index } => {
+                let (ptr, ty) = self.element(expr, *base, *index)?;
+                Ok(Place::Memory(ptr, ty))
+            }
+            _ => Err(
+                InterpretError::Unsupported(String::from("assignments to temporary values")).into(),
+            ),
+        }
+    }
+
+    /// Returns the (field of the) local variable that is bound by `pat`.
+    fn local(&self, pat: PatId, path: &[usize]) -> &Value {
+        path.iter().fold(
+            self.locals.get(&pat).unwrap_or(&Value::Unit),
+            |value, index| match value {
+                Value::Struct { fields, .. } => &fields[*index],
+                _ => unreachable!("only value structs are stored in locals"),
+            },
+        )
+    }
+
+    fn resolve_path(&self, expr: ExprId, path: &Path) -> EvalResult<ValueNs> {
+        let db = self.db();
+        mun_hir::resolver_for_expr(db.upcast(), self.body.owner(), expr)
+            .resolve_path_as_value_fully(db.upcast(), path)
+            .map(|(value, _)| value)
+            .ok_or_else(|| self.invalid())
+    }
+
+    /// Returns the struct type of `expr`.
+    fn struct_of(&self, expr: ExprId) -> EvalResult<Struct> {
+        self.infer[expr].as_struct().ok_or_else(|| self.invalid())
+    }
+
+    /// Returns the index of the field called `name` of the struct `receiver`.
+    fn field_index(&self, receiver: ExprId, name: &Name) -> EvalResult<usize> {
+        let s = self.struct_of(receiver)?;
+        self.struct_field_index(s, name)
+    }
+
+    fn struct_field_index(&self, s: Struct, name: &Name) -> EvalResult<usize> {
+        let field = s.field(self.db(), name).ok_or_else(|| self.invalid())?;
+        Ok(field.index(self.db()) as usize)
+    }
+
+    /// Returns the integer type of `expr`, with its bitness resolved for the
+    /// target.
+    fn int_ty(&self, expr: ExprId) -> IntTy {
+        match self.infer[expr].interned() {
+            TyKind::Int(ty) => ty.resolve(&self.db().target_data_layout()),
+            _ => unreachable!("expected an integer type"),
+        }
+    }
+
+    /// Returns `value` as a float of the type of `expr`.
+    fn float(&self, expr: ExprId, value: f64) -> Value {
+        match self.infer[expr].interned() {
+            TyKind::Float(ty)
+                if ty.bitness.resolve(&self.db().target_data_layout())
+                    == mun_hir::FloatBitness::X32 =>
+            {
+                Value::Float(f64::from(value as f32))
+            }
+            _ => Value::Float(value),
+        }
+    }
+
+    /// Returns an error that reports an invalid operation at `expr`.
+    fn panic(&self, expr: ExprId, kind: PanicKind) -> Unwind {
+        let db = self.db();
+        let (line, column) = self
+            .function
+            .expr_position(db, expr)
+            .map_or((0, 0), |position| {
+                (position.line + 1, position.col_utf16 + 1)
+            });
+        InterpretError::Panic {
+            kind,
+            function: self.function.full_name(db),
+            line,
+            column,
+        }
+        .into()
+    }
+
+    /// Returns an error for code that doesn't pass type checking. This only
+    /// happens when code contains errors, which are reported before it is
+    /// executed.
+    fn invalid(&self) -> Unwind {
+        InterpretError::InvalidFunction(self.function.full_name(self.db())).into()
+    }
+}
+
+/// Truncates `value` to the bits of the integer type `ty`, and sign-extends
+/// the result if `ty` is signed.
+fn wrap(ty: IntTy, value: i128) -> i128 {
+    let unused_bits = 128 - ty.bits();
+    match ty.signedness {
+        Signedness::Signed => (value << unused_bits) >> unused_bits,
+        Signedness::Unsigned => (((value as u128) << unused_bits) >> unused_bits) as i128,
+    }
+}
+
+/// Calculates an addition, subtraction or multiplication whose result wraps
+/// around. The result still has to be wrapped to the type of the operands.
+fn wrapping_op(lhs: i128, rhs: i128, op: ArithOp) -> i128 {
+    match op {
+        ArithOp::Add => lhs.wrapping_add(rhs),
+        ArithOp::Subtract => lhs.wrapping_sub(rhs),
+        ArithOp::Multiply => lhs.wrapping_mul(rhs),
+        _ => unreachable!("only additions, subtractions and multiplications can overflow"),
+    }
+}
+
+/// Calculates an addition, subtraction or multiplication of two integers of
+/// type `ty`, or returns `None` if the result doesn't fit in `ty`.
+fn checked_op(ty: IntTy, lhs: i128, rhs: i128, op: ArithOp) -> Option<i128> {
+    match ty.signedness {
+        Signedness::Signed => {
+            let value = match op {
+                ArithOp::Add => lhs.checked_add(rhs),
+                ArithOp::Subtract => lhs.checked_sub(rhs),
+                ArithOp::Multiply => lhs.checked_mul(rhs),
+                _ => unreachable!("only additions, subtractions and multiplications can overflow"),
+            }?;
+            (ty.min() <= value && value <= ty.max() as i128).then_some(value)
+        }
+        Signedness::Unsigned => {
+            let (lhs, rhs) = (lhs as u128, rhs as u128);
+            let value = match op {
+                ArithOp::Add => lhs.checked_add(rhs),
+                ArithOp::Subtract => lhs.checked_sub(rhs),
+                ArithOp::Multiply => lhs.checked_mul(rhs),
+                _ => unreachable!("only additions, subtractions and multiplications can overflow"),
+            }?;
+            (value <= ty.max()).then_some(value as i128)
+        }
+    }
+}
+
+/// Returns the bound of `ty` that an addition, subtraction or multiplication
+/// that overflows saturates to.
+fn saturated_op(ty: IntTy, lhs: i128, rhs: i128, op: ArithOp) -> i128 {
+    let overflows_upwards = match (ty.signedness, op) {
+        (Signedness::Unsigned, ArithOp::Subtract) => false,
+        (Signedness::Unsigned, _) => true,
+        (Signedness::Signed, ArithOp::Add) => rhs >= 0,
+        (Signedness::Signed, ArithOp::Subtract) => rhs < 0,
+        // The result is negative if exactly one of the operands is negative
+        (Signedness::Signed, _) => (lhs < 0) == (rhs < 0),
+    };
+    if overflows_upwards {
+        ty.max() as i128
+    } else {
+        ty.min()
+    }
+}
@@ -0,0 +1,284 @@
+//! An interpreter that executes Mun functions directly from their high-level
+//! intermediate representation (HIR), without generating machine code.
+//!
+//! The interpreter evaluates the [`Body`](mun_hir::Body) of a function on top
+//! of its [`InferenceResult`](mun_hir::InferenceResult). Garbage collected
+//! structs and arrays are allocated with a [`GarbageCollector`] from
+//! `mun_memory`, using the same memory layout and [`Type`]s as compiled
+//! assemblies. This makes it possible to iterate on Mun code without invoking
+//! LLVM, and to run Mun code on platforms for which no machine code can be
+//! generated.
+
+mod db;
+mod eval;
+mod types;
+mod value;
+
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use mun_hir::{DiagnosticSink, Function, HirDatabase, ModuleDef, Package, Struct};
+use mun_memory::{
+    gc::{Event, MarkSweep, NoopObserver},
+    Type,
+};
+
+pub use self::{db::InterpreterDatabase, value::Value};
+use self::{eval::Frame, types::TypeCache};
+
+/// The garbage collector in which the interpreter allocates objects. This is
+/// the same garbage collector as the one used by the runtime.
+pub type GarbageCollector = MarkSweep<NoopObserver<Event>>;
+
+/// The default number of bytes of stack that nested function calls may use.
+/// Each call is evaluated on the stack of the host, which this limit protects
+/// from overflowing. Threads that are spawned by the standard library have a
+/// stack of 2 MiB by default.
+const DEFAULT_MAX_STACK_SIZE: usize = 1024 * 1024;
+
+/// The signature of a host function that implements an `extern` function.
+type ExternFn<'db> = Box<dyn Fn(&[Value]) -> Value + 'db>;
+
+/// An error that occurs while interpreting Mun code.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InterpretError {
+    /// No function exists with the specified name
+    #[error("function `{0}` does not exist")]
+    UnknownFunction(String),
+
+    /// A function was called with the wrong number of arguments
+    #[error("function `{function}` expects {expected} arguments, but {found} were provided")]
+    ArgumentCount {
+        function: String,
+        expected: usize,
+        found: usize,
+    },
+
+    /// The function contains errors, which are reported by the compiler
+    #[error("function `{0}` contains errors")]
+    InvalidFunction(String),
+
+    /// An `extern` function was called for which the host didn't provide an
+    /// implementation
+    #[error("no implementation was provided for extern function `{0}`")]
+    ExternFunction(String),
+
+    /// The code uses a language feature that the interpreter doesn't support
+    #[error("{0} are not supported by the interpreter")]
+    Unsupported(String),
+
+    /// Function calls were nested too deeply
+    #[error("the maximum stack size of {0} bytes was exceeded")]
+    StackOverflow(usize),
+
+    /// The code performed an invalid operation
+    #[error("{kind} at {function}:{line}:{column}")]
+    Panic {
+        kind: PanicKind,
+        function: String,
+        line: u32,
+        column: u32,
+    },
+}
+
+/// An invalid operation that aborts the interpreted code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicKind {
+    /// An integer was divided by zero
+    DivisionByZero,
+
+    /// The result of an integer operation doesn't fit in its type
+    Overflow,
+
+    /// An array was indexed outside of its bounds
+    IndexOutOfBounds { index: i128, length: usize },
+}
+
+impl fmt::Display for PanicKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PanicKind::DivisionByZero => write!(f, "attempt to divide by zero"),
+            PanicKind::Overflow => write!(f, "attempt to compute a value that overflows its type"),
+            PanicKind::IndexOutOfBounds { index, length } => write!(
+                f,
+                "index {index} is out of bounds for an array of length {length}"
+            ),
+        }
+    }
+}
+
+/// Interprets the functions of the packages in a [`HirDatabase`].
+///
+/// The interpreter never collects garbage. Objects that it allocates remain
+/// alive until the host collects the garbage of [`Interpreter::gc`]. The
+/// values of a function that is being interpreted are not rooted, so garbage
+/// must not be collected while a function runs, e.g. from an `extern`
+/// function.
+pub struct Interpreter<'db> {
+    db: &'db dyn HirDatabase,
+    gc: Arc<GarbageCollector>,
+    types: TypeCache,
+    extern_fns: HashMap<String, ExternFn<'db>>,
+
+    /// Whether functions are free of errors, which is only determined once per
+    /// function
+    valid_functions: HashMap<Function, bool>,
+    call_depth: usize,
+
+    /// The address of the stack when the outermost function was called
+    stack_base: usize,
+    max_stack_size: usize,
+    overflow_checks: bool,
+}
+
+impl<'db> Interpreter<'db> {
+    /// Constructs an interpreter for the code in `db`, with its own garbage
+    /// collector.
+    pub fn new(db: &'db dyn HirDatabase) -> Self {
+        Self::with_gc(db, Arc::default())
+    }
+
+    /// Constructs an interpreter for the code in `db`, that allocates objects
+    /// in `gc`.
+    pub fn with_gc(db: &'db dyn HirDatabase, gc: Arc<GarbageCollector>) -> Self {
+        Self {
+            db,
+            gc,
+            types: TypeCache::default(),
+            extern_fns: HashMap::new(),
+            valid_functions: HashMap::new(),
+            call_depth: 0,
+            stack_base: 0,
+            max_stack_size: DEFAULT_MAX_STACK_SIZE,
+            overflow_checks: true,
+        }
+    }
+
+    /// Sets the maximum number of bytes of stack that nested function calls
+    /// may use. Calls are evaluated on the stack of the host thread, so the
+    /// limit has to be smaller than the stack of the thread that invokes
+    /// functions.
+    pub fn set_max_stack_size(&mut self, size: usize) {
+        self.max_stack_size = size;
+    }
+
+    /// Sets whether integer arithmetic panics when its result overflows. This
+    /// is enabled by default, which corresponds to an unoptimized build. When
+    /// disabled, results wrap around like in an optimized build.
+    pub fn set_overflow_checks(&mut self, enabled: bool) {
+        self.overflow_checks = enabled;
+    }
+
+    /// Returns the garbage collector in which objects are allocated, which the
+    /// host uses to collect garbage between invocations.
+    ///
+    /// Objects are not rooted by the interpreter. Objects that are referenced
+    /// by a returned [`Value`] have to be rooted before the garbage is
+    /// collected, to keep them alive.
+    pub fn gc(&self) -> &Arc<GarbageCollector> {
+        &self.gc
+    }
+
+    /// Provides the implementation of the `extern` function called `name`.
+    pub fn insert_fn(&mut self, name: impl Into<String>, f: impl Fn(&[Value]) -> Value + 'db) {
+        self.extern_fns.insert(name.into(), Box::new(f));
+    }
+
+    /// Returns the function with the specified fully qualified name, e.g.
+    /// `foo::Bar::new`.
+    pub fn find_function(&self, name: &str) -> Option<Function> {
+        Package::all(self.db)
+            .into_iter()
+            .flat_map(|package| package.modules(self.db))
+            .flat_map(|module| {
+                let functions =
+                    module
+                        .declarations(self.db)
+                        .into_iter()
+                        .filter_map(|def| match def {
+                            ModuleDef::Function(function) => Some(function),
+                            _ => None,
+                        });
+                let methods = module.impls(self.db).into_iter().flat_map(|i| {
+                    i.items(self.db).into_iter().map(|item| match item {
+                        mun_hir::AssocItem::Function(function) => function,
+                    })
+                });
+                functions.chain(methods).collect::<Vec<_>>()
+            })
+            .find(|function| function.full_name(self.db) == name)
+    }
+
+    /// Calls the function with the specified fully qualified name.
+    pub fn invoke(&mut self, name: &str, args: Vec<Value>) -> Result<Value, InterpretError> {
+        let function = self
+            .find_function(name)
+            .ok_or_else(|| InterpretError::UnknownFunction(name.to_owned()))?;
+        self.call(function, args)
+    }
+
+    /// Calls `function` with `args`.
+    pub fn call(&mut self, function: Function, args: Vec<Value>) -> Result<Value, InterpretError> {
+        let expected = function.params(self.db).len();
+        if args.len() != expected {
+            return Err(InterpretError::ArgumentCount {
+                function: function.full_name(self.db),
+                expected,
+                found: args.len(),
+            });
+        }
+
+        if function.is_extern(self.db) {
+            let name = function.name(self.db).to_string();
+            let f = self
+                .extern_fns
+                .get(&name)
+                .ok_or(InterpretError::ExternFunction(name))?;
+            return Ok(f(&args));
+        }
+
+        if !self.is_valid(function) {
+            return Err(InterpretError::InvalidFunction(function.full_name(self.db)));
+        }
+
+        let stack_address = stack_address();
+        if self.call_depth == 0 {
+            self.stack_base = stack_address;
+        } else if self.stack_base.abs_diff(stack_address) > self.max_stack_size {
+            return Err(InterpretError::StackOverflow(self.max_stack_size));
+        }
+
+        self.call_depth += 1;
+        let result = Frame::new(self, function, args).run();
+        self.call_depth -= 1;
+        result
+    }
+
+    /// Returns the memory type of the struct `s`, which is identical to the
+    /// type of the struct in compiled assemblies.
+    pub fn struct_type(&mut self, s: Struct) -> Result<Type, InterpretError> {
+        self.types.struct_type(self.db, s)
+    }
+
+    /// Returns true if `function` doesn't contain any errors.
+    fn is_valid(&mut self, function: Function) -> bool {
+        let db = self.db;
+        *self.valid_functions.entry(function).or_insert_with(|| {
+            let mut is_valid = true;
+            let mut sink = DiagnosticSink::new(|_| is_valid = false);
+            function.diagnostics(db, &mut sink);
+            drop(sink);
+            is_valid
+        })
+    }
+}
+
+/// Returns the current address of the stack, which is used to measure how much
+/// stack nested function calls use.
+#[inline(never)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    std::hint::black_box(std::ptr::addr_of!(marker)) as usize
+}
@@ -0,0 +1,468 @@
+use mun_hir_input::WithFixture;
+
+use crate::{InterpretError, Interpreter, InterpreterDatabase, PanicKind, Value};
+
+/// Interprets the function called `name` of the specified `text`.
+fn run(text: &str, name: &str, args: Vec<Value>) -> Result<Value, InterpretError> {
+    let db = InterpreterDatabase::with_files(text);
+    let mut interpreter = Interpreter::new(&db);
+    interpreter.invoke(name, args)
+}
+
+fn int(value: i128) -> Value {
+    Value::Int(value)
+}
+
+#[test]
+fn arithmetic() {
+    let text = r"
+    pub fn fibonacci(n: i64) -> i64 {
+        if n <= 1 {
+            n
+        } else {
+            fibonacci(n - 1) + fibonacci(n - 2)
+        }
+    }
+
+    pub fn mix(a: i32, b: i32) -> i32 {
+        (a * 3 / 2 % 7 - b) << 2 | 1
+    }
+
+    pub fn halve(a: f32) -> f32 { a / 3.0 }
+    pub fn not(a: u8) -> u8 { !a }
+    pub fn negate(a: i16) -> i16 { -a }
+    ";
+
+    assert_eq!(run(text, "fibonacci", vec![int(20)]), Ok(int(6765)));
+    assert_eq!(run(text, "mix", vec![int(9), int(8)]), Ok(int(-7)));
+    assert_eq!(run(text, "not", vec![int(5)]), Ok(int(250)));
+    assert_eq!(run(text, "negate", vec![int(5)]), Ok(int(-5)));
+    assert_eq!(
+        run(text, "halve", vec![Value::Float(1.0)]),
+        Ok(Value::Float(f64::from(1.0f32 / 3.0)))
+    );
+}
+
+#[test]
+fn control_flow() {
+    let text = r"
+    pub fn sum(n: u32) -> u32 {
+        let i = 0;
+        let sum = 0;
+        while i < n {
+            i += 1;
+            if i % 2 == 0 {
+                sum += i;
+            }
+        }
+        sum
+    }
+
+    pub fn first_square_above(n: i64) -> i64 {
+        let i = 0;
+        loop {
+            if i * i > n {
+                break i;
+            }
+            i += 1;
+        }
+    }
+
+    pub fn early(a: bool) -> i32 {
+        if a {
+            return 1;
+        }
+        2
+    }
+
+    pub fn short_circuit(a: i32) -> bool {
+        a == 0 || 10 / a > 2
+    }
+    ";
+
+    assert_eq!(run(text, "sum", vec![int(10)]), Ok(int(30)));
+    assert_eq!(run(text, "first_square_above", vec![int(50)]), Ok(int(8)));
+    assert_eq!(run(text, "early", vec![Value::Bool(true)]), Ok(int(1)));
+    assert_eq!(run(text, "early", vec![Value::Bool(false)]), Ok(int(2)));
+    assert_eq!(
+        run(text, "short_circuit", vec![int(0)]),
+        Ok(Value::Bool(true))
+    );
+    assert_eq!(
+        run(text, "short_circuit", vec![int(4)]),
+        Ok(Value::Bool(false))
+    );
+}
+
+#[test]
+fn structs() {
+    let text = r"
+    struct Counter { count: i64, step: Step }
+    struct(value) Step { size: i64 }
+    struct Pair(i64, i64);
+
+    fn increment(counter: Counter) {
+        counter.count += counter.step.size;
+    }
+
+    pub fn count(n: i64) -> i64 {
+        let counter = Counter { count: 0, step: Step { size: 2 } };
+        let i = 0;
+        while i < n {
+            increment(counter);
+            i += 1;
+        }
+        counter.count
+    }
+
+    pub fn copy() -> i64 {
+        let a = Step { size: 1 };
+        let b = a;
+        b.size = 5;
+        let counter = Counter { count: 0, step: a };
+        counter.step.size = 3;
+        a.size * 100 + b.size * 10 + counter.step.size
+    }
+
+    pub fn tuple() -> i64 {
+        let counter = Counter { step: Step { size: 4 }, count: 7 };
+        let pair = Pair(counter.count, counter.step.size);
+        pair.0 - pair.1
+    }
+    ";
+
+    assert_eq!(run(text, "count", vec![int(5)]), Ok(int(10)));
+    assert_eq!(run(text, "copy", vec![]), Ok(int(153)));
+    assert_eq!(run(text, "tuple", vec![]), Ok(int(3)));
+}
+
+#[test]
+fn struct_types() {
+    let db = InterpreterDatabase::with_files(
+        r"
+    pub struct(value) Foo { a: u8, b: u64, c: Bar }
+    pub struct Bar { d: bool }
+    pub fn foo() -> Foo { Foo { a: 1, b: 2, c: Bar { d: true } } }
+    ",
+    );
+    let mut interpreter = Interpreter::new(&db);
+
+    let Ok(Value::Struct { ty, fields }) = interpreter.invoke("foo", vec![]) else {
+        panic!("expected a value struct");
+    };
+    assert_eq!(ty.name(), "Foo");
+    assert_eq!(ty.value_layout().size(), 24);
+    let offsets = ty
+        .as_struct()
+        .unwrap()
+        .fields()
+        .iter()
+        .map(|field| field.offset())
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, [0, 8, 16]);
+    assert!(ty
+        .as_struct()
+        .unwrap()
+        .fields()
+        .get(2)
+        .unwrap()
+        .ty()
+        .is_reference_type());
+    assert_eq!(fields[..2], [int(1), int(2)]);
+}
+
+#[test]
+fn garbage_is_collected_by_the_host() {
+    let db = InterpreterDatabase::with_files(
+        r"
+    struct Foo { a: i32 }
+    pub fn allocate() -> i32 {
+        let foos = [Foo { a: 1 }, Foo { a: 2 }];
+        foos[0].a + foos[1].a
+    }
+    ",
+    );
+    let mut interpreter = Interpreter::new(&db);
+
+    assert_eq!(interpreter.invoke("allocate", vec![]), Ok(int(3)));
+    assert!(interpreter.gc().collect());
+    assert!(!interpreter.gc().collect());
+}
+
+#[test]
+fn operator_overloading() {
+    let text = r"
+    struct(value) Vec2 { x: f32, y: f32 }
+
+    impl Vec2 {
+        fn add(self, rhs: Self) -> Self { Vec2 { x: self.x + rhs.x, y: self.y + rhs.y } }
+        fn neg(self) -> Self { Vec2 { x: -self.x, y: -self.y } }
+        fn eq(self, rhs: Self) -> bool { self.x == rhs.x && self.y == rhs.y }
+    }
+
+    pub fn sum(n: i32) -> f32 {
+        let v = Vec2 { x: 0.0, y: 0.0 };
+        let i = 0;
+        while i < n {
+            v += -Vec2 { x: 1.0, y: 2.0 };
+            i += 1;
+        }
+        v.y
+    }
+
+    pub fn not_equals(a: f32, b: f32) -> bool {
+        Vec2 { x: a, y: 1.0 } != Vec2 { x: b, y: 1.0 }
+    }
+    ";
+
+    assert_eq!(run(text, "sum", vec![int(3)]), Ok(Value::Float(-6.0)));
+    assert_eq!(
+        run(
+            text,
+            "not_equals",
+            vec![Value::Float(1.0), Value::Float(1.0)]
+        ),
+        Ok(Value::Bool(false))
+    );
+}
+
+#[test]
+fn arrays() {
+    let text = r"
+    pub fn sum(n: i32) -> i32 {
+        let values = [1, 2, 3, n];
+        values[0] = 10;
+        let sum = 0;
+        let i = 0;
+        while i < 4 {
+            sum += values[i];
+            i += 1;
+        }
+        sum
+    }
+
+    struct Foo { value: i32 }
+
+    pub fn objects() -> i32 {
+        let foos = [Foo { value: 1 }, Foo { value: 2 }];
+        foos[1].value = 5;
+        foos[0].value + foos[1].value
+    }
+
+    pub fn out_of_bounds(index: usize) -> i32 {
+        let values = [1, 2, 3];
+        values[index]
+    }
+    ";
+
+    assert_eq!(run(text, "sum", vec![int(4)]), Ok(int(19)));
+    assert_eq!(run(text, "objects", vec![]), Ok(int(6)));
+    assert_eq!(
+        run(text, "out_of_bounds", vec![int(3)]),
+        Err(InterpretError::Panic {
+            kind: PanicKind::IndexOutOfBounds {
+                index: 3,
+                length: 3
+            },
+            function: String::from("out_of_bounds"),
+            line: 23,
+            column: 5,
+        })
+    );
+}
+
+#[test]
+fn overflow() {
+    let text = r"
+    pub fn add(a: i8, b: i8) -> i8 { a + b }
+    pub fn wrapping_add(a: u8, b: u8) -> u8 { u8::wrapping_add(a, b) }
+    pub fn saturating_sub(a: u16, b: u16) -> u16 { u16::saturating_sub(a, b) }
+    pub fn saturating_mul(a: i64, b: i64) -> i64 { i64::saturating_mul(a, b) }
+    pub fn strict_sub(a: u32, b: u32) -> u32 { u32::strict_sub(a, b) }
+    pub fn divide(a: i32, b: i32) -> i32 { a / b }
+    pub fn remainder(a: i32, b: i32) -> i32 { a % b }
+    ";
+    let db = InterpreterDatabase::with_files(text);
+    let mut interpreter = Interpreter::new(&db);
+
+    let panic = |kind, function: &str, column| {
+        Err(InterpretError::Panic {
+            kind,
+            function: function.to_owned(),
+            line: 1,
+            column,
+        })
+    };
+
+    assert_eq!(
+        interpreter.invoke("add", vec![int(100), int(100)]),
+        panic(PanicKind::Overflow, "add", 34)
+    );
+    assert_eq!(
+        interpreter.invoke("wrapping_add", vec![int(250), int(10)]),
+        Ok(int(4))
+    );
+    assert_eq!(
+        interpreter.invoke("saturating_sub", vec![int(5), int(10)]),
+        Ok(int(0))
+    );
+    assert_eq!(
+        interpreter.invoke("saturating_mul", vec![int(i64::MIN.into()), int(2)]),
+        Ok(int(i64::MIN.into()))
+    );
+    assert!(matches!(
+        interpreter.invoke("strict_sub", vec![int(5), int(10)]),
+        Err(InterpretError::Panic {
+            kind: PanicKind::Overflow,
+            ..
+        })
+    ));
+    assert!(matches!(
+        interpreter.invoke("divide", vec![int(5), int(0)]),
+        Err(InterpretError::Panic {
+            kind: PanicKind::DivisionByZero,
+            ..
+        })
+    ));
+    for function in ["divide", "remainder"] {
+        assert!(matches!(
+            interpreter.invoke(function, vec![int(i32::MIN.into()), int(-1)]),
+            Err(InterpretError::Panic {
+                kind: PanicKind::Overflow,
+                ..
+            })
+        ));
+    }
+
+    interpreter.set_overflow_checks(false);
+    assert_eq!(
+        interpreter.invoke("add", vec![int(100), int(100)]),
+        Ok(int(-56))
+    );
+    assert_eq!(
+        interpreter.invoke("divide", vec![int(i32::MIN.into()), int(-1)]),
+        Ok(int(i32::MIN.into()))
+    );
+    assert_eq!(
+        interpreter.invoke("remainder", vec![int(i32::MIN.into()), int(-1)]),
+        Ok(int(0))
+    );
+}
+
+#[test]
+fn constants_and_modules() {
+    let db = InterpreterDatabase::with_files(
+        r"
+    //- /mod.mun
+    pub const SCALE: f64 = 2.5;
+    pub fn scale(a: f64) -> f64 { a * SCALE * foo::Bar::half() }
+
+    //- /foo.mun
+    pub struct Bar;
+    impl Bar {
+        pub fn half() -> f64 { 0.5 }
+    }
+    ",
+    );
+    let mut interpreter = Interpreter::new(&db);
+
+    assert!(interpreter.find_function("foo::Bar::half").is_some());
+    assert_eq!(
+        interpreter.invoke("scale", vec![Value::Float(4.0)]),
+        Ok(Value::Float(5.0))
+    );
+}
+
+#[test]
+fn extern_functions() {
+    let db = InterpreterDatabase::with_files(
+        r"
+    extern fn random() -> i32;
+    pub fn roll() -> i32 { random() % 6 + 1 }
+    ",
+    );
+    let mut interpreter = Interpreter::new(&db);
+    assert_eq!(
+        interpreter.invoke("roll", vec![]),
+        Err(InterpretError::ExternFunction(String::from("random")))
+    );
+
+    interpreter.insert_fn("random", |_| Value::Int(40));
+    assert_eq!(interpreter.invoke("roll", vec![]), Ok(int(5)));
+}
+
+#[test]
+fn compound_assignment_evaluates_place_once() {
+    let db = InterpreterDatabase::with_files(
+        r"
+    extern fn next_index() -> usize;
+    pub fn bump() -> i32 {
+        let values = [1, 2, 3];
+        values[next_index()] += 10;
+        values[0] * 100 + values[1] * 10 + values[2]
+    }
+    ",
+    );
+    let calls = std::cell::Cell::new(0);
+    let mut interpreter = Interpreter::new(&db);
+    interpreter.insert_fn("next_index", |_| {
+        calls.set(calls.get() + 1);
+        Value::Int(calls.get() - 1)
+    });
+
+    assert_eq!(interpreter.invoke("bump", vec![]), Ok(int(1123)));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn errors() {
+    let text = r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    pub fn invalid() -> i32 { true }
+    pub fn recurse(a: i32) -> i32 { recurse(a) }
+    ";
+
+    assert_eq!(
+        run(text, "subtract", vec![]),
+        Err(InterpretError::UnknownFunction(String::from("subtract")))
+    );
+    assert_eq!(
+        run(text, "add", vec![int(1)]),
+        Err(InterpretError::ArgumentCount {
+            function: String::from("add"),
+            expected: 2,
+            found: 1,
+        })
+    );
+    assert_eq!(
+        run(text, "invalid", vec![]),
+        Err(InterpretError::InvalidFunction(String::from("invalid")))
+    );
+    assert_eq!(
+        run(text, "recurse", vec![int(1)]),
+        Err(InterpretError::StackOverflow(1024 * 1024))
+    );
+}
+
+#[test]
+fn deep_recursion() {
+    let text = r"
+    pub fn depth(n: i32) -> i32 { if n == 0 { 0 } else { depth(n - 1) + 1 } }
+    ";
+
+    assert_eq!(run(text, "depth", vec![int(100)]), Ok(int(100)));
+
+    // Hosts that provide a larger stack can allow deeper recursion
+    let result = std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(move || {
+            let db = InterpreterDatabase::with_files(text);
+            let mut interpreter = Interpreter::new(&db);
+            interpreter.set_max_stack_size(32 * 1024 * 1024);
+            interpreter.invoke("depth", vec![int(2000)])
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(result, Ok(int(2000)));
+}
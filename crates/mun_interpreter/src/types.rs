@@ -0,0 +1,127 @@
+use std::{
+    alloc::Layout,
+    collections::{HashMap, HashSet},
+};
+
+use mun_abi as abi;
+use mun_hir::{
+    FloatBitness, HirDatabase, IntBitness, ResolveBitness, Signedness, Struct, StructMemoryKind,
+    Ty, TyKind,
+};
+use mun_memory::{HasStaticType, Type};
+
+use crate::InterpretError;
+
+/// Lowers HIR types to the memory types of the runtime, which describe the
+/// layout of values in memory.
+#[derive(Default)]
+pub(crate) struct TypeCache {
+    structs: HashMap<Struct, Type>,
+
+    /// Structs whose type is currently being lowered, used to detect structs
+    /// that (indirectly) contain themselves.
+    in_progress: HashSet<Struct>,
+}
+
+impl TypeCache {
+    /// Returns the memory type of `ty`.
+    pub fn ty(&mut self, db: &dyn HirDatabase, ty: &Ty) -> Result<Type, InterpretError> {
+        let ty = match ty.interned() {
+            TyKind::Bool => bool::type_info().clone(),
+            TyKind::Int(int_ty) => {
+                let int_ty = int_ty.resolve(&db.target_data_layout());
+                match (int_ty.signedness, int_ty.bitness) {
+                    (Signedness::Signed, IntBitness::X8) => i8::type_info().clone(),
+                    (Signedness::Signed, IntBitness::X16) => i16::type_info().clone(),
+                    (Signedness::Signed, IntBitness::X32) => i32::type_info().clone(),
+                    (Signedness::Signed, IntBitness::X64) => i64::type_info().clone(),
+                    (Signedness::Signed, IntBitness::X128) => i128::type_info().clone(),
+                    (Signedness::Unsigned, IntBitness::X8) => u8::type_info().clone(),
+                    (Signedness::Unsigned, IntBitness::X16) => u16::type_info().clone(),
+                    (Signedness::Unsigned, IntBitness::X32) => u32::type_info().clone(),
+                    (Signedness::Unsigned, IntBitness::X64) => u64::type_info().clone(),
+                    (Signedness::Unsigned, IntBitness::X128) => u128::type_info().clone(),
+                    (_, IntBitness::Xsize) => unreachable!("unresolved integer bitness"),
+                }
+            }
+            TyKind::Float(float_ty) => match float_ty.bitness.resolve(&db.target_data_layout()) {
+                FloatBitness::X32 => f32::type_info().clone(),
+                FloatBitness::X64 => f64::type_info().clone(),
+            },
+            TyKind::Struct(s) => self.struct_type(db, *s)?,
+            TyKind::Array(element_ty) => self.ty(db, element_ty)?.array_type(),
+            _ => {
+                return Err(InterpretError::Unsupported(format!(
+                    "values of type `{}`",
+                    mun_hir::HirDisplay::display(ty, db)
+                )))
+            }
+        };
+        Ok(ty)
+    }
+
+    /// Returns the memory type of the struct `s`. The layout and GUID of the
+    /// type are identical to those generated by the compiler.
+    pub fn struct_type(&mut self, db: &dyn HirDatabase, s: Struct) -> Result<Type, InterpretError> {
+        if let Some(ty) = self.structs.get(&s) {
+            return Ok(ty.clone());
+        }
+
+        if !self.in_progress.insert(s) {
+            return Err(InterpretError::Unsupported(String::from(
+                "recursive struct types",
+            )));
+        }
+        let fields = s
+            .fields(db)
+            .into_iter()
+            .map(|field| {
+                let ty = self.ty(db, &field.ty(db))?;
+                Ok((field, ty))
+            })
+            .collect::<Result<Vec<_>, InterpretError>>();
+        self.in_progress.remove(&s);
+        let fields = fields?;
+
+        let mut layout = Layout::from_size_align(0, 1).expect("empty layout is valid");
+        let mut guid_fields = Vec::with_capacity(fields.len());
+        let mut memory_fields = Vec::with_capacity(fields.len());
+        for (field, ty) in fields {
+            let (extended, offset) = layout
+                .extend(ty.reference_layout())
+                .expect("struct layout does not overflow");
+            layout = extended;
+
+            let name = field.name(db).to_string();
+            guid_fields.push(format!(
+                "{name}: {}",
+                field
+                    .ty(db)
+                    .guid_string(db)
+                    .expect("type should be convertible to a string")
+            ));
+            let offset = u16::try_from(offset).expect("struct field offset exceeds 16 bits");
+            memory_fields.push((name, ty, offset));
+        }
+
+        let name = s.full_name(db);
+        let guid = abi::Guid::from_str(&format!(
+            "struct {name}{{{fields}}}",
+            fields = guid_fields.join(",")
+        ));
+        let memory_kind = match s.data(db.upcast()).memory_kind {
+            StructMemoryKind::Gc => abi::StructMemoryKind::Gc,
+            StructMemoryKind::Value => abi::StructMemoryKind::Value,
+        };
+
+        let ty = Type::new_struct(
+            name,
+            layout.pad_to_align(),
+            guid,
+            memory_fields,
+            memory_kind,
+        );
+        self.structs.insert(s, ty.clone());
+        Ok(ty)
+    }
+}
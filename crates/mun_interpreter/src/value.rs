@@ -0,0 +1,182 @@
+use std::ptr::NonNull;
+
+use mun_memory::{
+    gc::{GcPtr, HasIndirectionPtr},
+    Type,
+};
+
+use crate::GarbageCollector;
+
+/// A value that is produced by interpreting Mun code.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// The empty tuple `()`, which is the value of expressions that don't
+    /// produce a value.
+    Unit,
+
+    /// A `bool`
+    Bool(bool),
+
+    /// An integer of any type. Values of `u128` that don't fit in an `i128`
+    /// are stored as their two's complement bit pattern.
+    Int(i128),
+
+    /// A floating-point number of any type. Values of `f32` are rounded to
+    /// single precision.
+    Float(f64),
+
+    /// A `struct(value)`, which is stored inline.
+    Struct {
+        /// The type of the struct
+        ty: Type,
+        /// The values of the fields, in the order of their declaration
+        fields: Vec<Value>,
+    },
+
+    /// A reference to a garbage collected struct or array.
+    Gc(GcPtr),
+}
+
+impl From<()> for Value {
+    fn from((): ()) -> Self {
+        Value::Unit
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($int:ty),+) => {
+        $(
+            impl From<$int> for Value {
+                fn from(value: $int) -> Self {
+                    Value::Int(value as i128)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! read_int {
+    ($ptr:ident, $ty:ident, $($int:ty),+) => {
+        $(
+            if $ty.equals::<$int>() {
+                return Value::Int($ptr.cast::<$int>().as_ptr().read_unaligned() as i128);
+            }
+        )+
+    };
+}
+
+macro_rules! write_int {
+    ($ptr:ident, $ty:ident, $value:ident, $($int:ty),+) => {
+        $(
+            if $ty.equals::<$int>() {
+                return $ptr.cast::<$int>().as_ptr().write_unaligned($value as $int);
+            }
+        )+
+    };
+}
+
+/// Reads a value of type `ty` from `ptr`.
+///
+/// # Safety
+///
+/// `ptr` must point to an initialized value of type `ty`.
+pub(crate) unsafe fn read(ptr: NonNull<u8>, ty: &Type) -> Value {
+    if ty.is_reference_type() {
+        return Value::Gc(ptr.cast::<GcPtr>().as_ptr().read_unaligned());
+    }
+
+    if let Some(s) = ty.as_struct() {
+        let fields = s
+            .fields()
+            .iter()
+            .map(|field| read(offset(ptr, field.offset()), &field.ty()))
+            .collect();
+        return Value::Struct {
+            ty: ty.clone(),
+            fields,
+        };
+    }
+
+    if ty.equals::<bool>() {
+        return Value::Bool(ptr.cast::<bool>().as_ptr().read_unaligned());
+    }
+    if ty.equals::<f32>() {
+        return Value::Float(f64::from(ptr.cast::<f32>().as_ptr().read_unaligned()));
+    }
+    if ty.equals::<f64>() {
+        return Value::Float(ptr.cast::<f64>().as_ptr().read_unaligned());
+    }
+    read_int!(ptr, ty, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+    unreachable!("values of type `{}` cannot be read", ty.name())
+}
+
+/// Writes `value` of type `ty` to `ptr`. References to garbage collected
+/// objects that are stored are passed to the write barrier of `gc`.
+///
+/// # Safety
+///
+/// `ptr` must point to memory that is valid for writes of type `ty`.
+pub(crate) unsafe fn write(gc: &GarbageCollector, ptr: NonNull<u8>, ty: &Type, value: Value) {
+    match value {
+        Value::Unit => {}
+        Value::Bool(value) => ptr.cast::<bool>().as_ptr().write_unaligned(value),
+        Value::Float(value) if ty.equals::<f32>() => {
+            ptr.cast::<f32>().as_ptr().write_unaligned(value as f32);
+        }
+        Value::Float(value) => ptr.cast::<f64>().as_ptr().write_unaligned(value),
+        Value::Int(value) => {
+            write_int!(ptr, ty, value, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+            unreachable!("integers cannot be written as `{}`", ty.name());
+        }
+        Value::Struct { ty, fields } => {
+            let s = ty.as_struct().expect("value structs have a struct type");
+            for (field, value) in s.fields().iter().zip(fields) {
+                write(gc, offset(ptr, field.offset()), &field.ty(), value);
+            }
+        }
+        Value::Gc(object) => {
+            ptr.cast::<GcPtr>().as_ptr().write_unaligned(object);
+            gc.write_barrier(object);
+        }
+    }
+}
+
+/// Offsets `ptr` by `count` bytes.
+///
+/// # Safety
+///
+/// The resulting pointer must be in bounds of the object that `ptr` points
+/// into.
+pub(crate) unsafe fn offset(ptr: NonNull<u8>, count: usize) -> NonNull<u8> {
+    NonNull::new_unchecked(ptr.as_ptr().add(count))
+}
+
+/// Returns a pointer to the data of the garbage collected struct `object`.
+///
+/// # Safety
+///
+/// `object` must be a live garbage collected struct.
+pub(crate) unsafe fn object_data(object: GcPtr) -> NonNull<u8> {
+    NonNull::new_unchecked(object.deref::<u8>().cast_mut())
+}
@@ -0,0 +1,16 @@
+use mun_test::InterpretTestDriver;
+
+#[test]
+fn update_file() {
+    let mut driver = InterpretTestDriver::from_file("pub fn answer() -> i32 { 41 }");
+    driver.assert_invoke_eq("answer", vec![], 41);
+
+    driver.update_file("pub fn answer() -> i32 { 42 }");
+    driver.assert_invoke_eq("answer", vec![], 42);
+}
+
+#[test]
+#[should_panic(expected = "compiler errors")]
+fn compiler_errors() {
+    InterpretTestDriver::from_file("pub fn answer() -> i32 { true }");
+}
@@ -15,6 +15,7 @@ pub struct SourceAnnotation {
 
 #[derive(Debug)]
 pub struct Diagnostic {
+    pub code: &'static str,
     pub message: String,
     pub range: TextRange,
     pub additional_annotations: Vec<SourceAnnotation>,
@@ -37,6 +38,7 @@ pub(crate) fn diagnostics(db: &AnalysisDatabase, file_id: FileId) -> Vec<Diagnos
     // Add all syntax errors
     let parse = db.parse(file_id);
     result.extend(parse.errors().iter().map(|err| Diagnostic {
+        code: err.code(),
         message: format!("parse error: {err}"),
         range: location_to_range(err.location()),
         additional_annotations: vec![],
@@ -47,6 +49,7 @@ pub(crate) fn diagnostics(db: &AnalysisDatabase, file_id: FileId) -> Vec<Diagnos
     let mut sink = mun_hir::diagnostics::DiagnosticSink::new(|d| {
         result.borrow_mut().push(d.with_diagnostic(db, |d| {
             Diagnostic {
+                code: d.code(),
                 message: format!("{}\n{}", d.title(), d.footer().join("\n"))
                     .trim()
                     .to_owned(),
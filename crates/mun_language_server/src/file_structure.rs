@@ -1,5 +1,5 @@
 use mun_syntax::{
-    ast::{self, NameOwner},
+    ast::{self, NameOwner, TypeAscriptionOwner},
     match_ast, AstNode, SourceFile, SyntaxNode, TextRange, WalkEvent,
 };
 
@@ -129,6 +129,7 @@ fn try_convert_to_structure_node(node: &SyntaxNode) -> Option<StructureNode> {
             },
             ast::StructDef(it) => decl(it, SymbolKind::Struct),
             ast::TypeAliasDef(it) => decl_with_type_ref(&it, it.type_ref(), SymbolKind::TypeAlias),
+            ast::ConstDef(it) => decl_with_type_ref(&it, it.ascribed_type(), SymbolKind::Const),
             _ => None
         }
     }
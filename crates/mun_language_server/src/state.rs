@@ -265,7 +265,7 @@ fn handle_diagnostics(state: LanguageServerSnapshot, sender: Sender<Task>) -> an
                     lsp_diagnostics.push(lsp_types::Diagnostic {
                         range: to_lsp::range(d.range, &line_index),
                         severity: Some(lsp_types::DiagnosticSeverity::ERROR),
-                        code: None,
+                        code: Some(lsp_types::NumberOrString::String(d.code.to_owned())),
                         code_description: None,
                         source: Some("mun".to_string()),
                         message: d.message,
@@ -40,8 +40,24 @@ impl MunLibrary {
     pub unsafe fn new(library_path: &Path) -> Result<Self, InitError> {
         // Although loading a library is technically unsafe, we assume here that this is
         // not the case for munlibs.
-        let library = TempLibrary::new(library_path)?;
+        Self::from_temp_library(TempLibrary::new(library_path)?)
+    }
+
+    /// Loads a munlib library from the contents of a `*.munlib` file.
+    ///
+    /// On Linux, the library is loaded from an anonymous in-memory file. Other
+    /// platforms fall back to writing the contents to a temporary file, see
+    /// [`TempLibrary::from_bytes`].
+    ///
+    /// # Safety
+    ///
+    /// See [`MunLibrary::new`].
+    pub unsafe fn from_bytes(bytes: &[u8]) -> Result<Self, InitError> {
+        Self::from_temp_library(TempLibrary::from_bytes(bytes)?)
+    }
 
+    /// Verifies that a loaded library is a munlib.
+    unsafe fn from_temp_library(library: TempLibrary) -> Result<Self, InitError> {
         // Verify that the `*.munlib` contains all required functions. Note that this is
         // an unsafe operation because the loaded symbols don't actually contain
         // type information. Casting is therefore unsafe.
@@ -102,6 +118,25 @@ impl MunLibrary {
         get_info_fn()
     }
 
+    /// Returns the functions that the shared object exposes to the runtime.
+    /// The returned function pointers are only valid for as long as the
+    /// library is loaded.
+    ///
+    /// # Safety
+    ///
+    /// The symbols in the munlib don't contain type information. There is no
+    /// guarantee that they match the signatures of [`abi::EntryPoints`].
+    pub unsafe fn entry_points(&self) -> abi::EntryPoints {
+        let library = self.0.library();
+        abi::EntryPoints {
+            get_version: *library.get(abi::GET_VERSION_FN_NAME.as_bytes()).unwrap(),
+            get_info: *library.get(abi::GET_INFO_FN_NAME.as_bytes()).unwrap(),
+            set_allocator_handle: *library
+                .get(abi::SET_ALLOCATOR_HANDLE_FN_NAME.as_bytes())
+                .unwrap(),
+        }
+    }
+
     /// Stores the allocator handle inside the shared object. This is used by
     /// the internals of the library to be able to allocate memory.
     ///
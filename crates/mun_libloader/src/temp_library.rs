@@ -1,4 +1,7 @@
-use std::{fs, io, path::Path};
+use std::{fs, io, io::Write, path::Path};
+
+#[cfg(target_os = "linux")]
+use std::{fs::File, os::fd::FromRawFd};
 
 use libloading::Library;
 
@@ -7,8 +10,12 @@ use libloading::Library;
 pub enum InitError {
     #[error("Failed to create a named temp file: {0}.")]
     CreateTempFile(io::Error),
+    #[error("Failed to create an in-memory file: {0}.")]
+    CreateMemoryFile(io::Error),
     #[error("Failed to copy shared library: {0}.")]
     CopyLibrary(io::Error),
+    #[error("Failed to write shared library: {0}.")]
+    WriteLibrary(io::Error),
     #[error("Failed to load temp shared library: {0}")]
     LoadTempLibrary(#[from] libloading::Error),
 }
@@ -21,10 +28,22 @@ pub enum InitError {
 /// loading the library keeps the file open (Windows) or keeping the file is not
 /// required in the first place (*nix).
 pub struct TempLibrary {
-    _tmp_path: tempfile::TempPath,
+    _file: LibraryFile,
     library: Library,
 }
 
+/// The unique file from which a [`TempLibrary`] was loaded.
+enum LibraryFile {
+    /// A temporary file on the filesystem.
+    Temp(tempfile::TempPath),
+    /// An anonymous in-memory file. It is kept open for as long as the library
+    /// is loaded, so another library cannot be loaded from the same path in
+    /// `/proc/self/fd`; the dynamic loader would return the loaded library
+    /// instead.
+    #[cfg(target_os = "linux")]
+    Memory(File),
+}
+
 impl TempLibrary {
     /// Find and load a dynamic library.
     ///
@@ -55,7 +74,66 @@ impl TempLibrary {
         fs::copy(path, &tmp_path).map_err(InitError::CopyLibrary)?;
         let library = Library::new(&tmp_path)?;
         Ok(TempLibrary {
-            _tmp_path: tmp_path,
+            _file: LibraryFile::Temp(tmp_path),
+            library,
+        })
+    }
+
+    /// Loads a dynamic library from the contents of a shared library file.
+    ///
+    /// On Linux, the contents are written to an anonymous in-memory file that
+    /// is loaded through `/proc/self/fd`, so nothing is written to the
+    /// filesystem. Other platforms can only load dynamic libraries from the
+    /// filesystem, so there the contents are written to a unique temporary
+    /// file first.
+    ///
+    /// # Safety
+    ///
+    /// See [`TempLibrary::new`].
+    pub unsafe fn from_bytes(bytes: &[u8]) -> Result<Self, InitError> {
+        #[cfg(target_os = "linux")]
+        {
+            Self::from_memory_file(bytes)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Self::from_temp_file(bytes)
+        }
+    }
+
+    /// Loads a dynamic library from an anonymous in-memory file with the
+    /// specified contents.
+    #[cfg(target_os = "linux")]
+    unsafe fn from_memory_file(bytes: &[u8]) -> Result<Self, InitError> {
+        let fd = libc::memfd_create(b"munlib\0".as_ptr().cast(), libc::MFD_CLOEXEC);
+        if fd < 0 {
+            return Err(InitError::CreateMemoryFile(io::Error::last_os_error()));
+        }
+
+        // SAFETY: `memfd_create` returned a new file descriptor that nothing else owns
+        let mut file = File::from_raw_fd(fd);
+        file.write_all(bytes).map_err(InitError::WriteLibrary)?;
+
+        let library = Library::new(format!("/proc/self/fd/{fd}"))?;
+        Ok(TempLibrary {
+            _file: LibraryFile::Memory(file),
+            library,
+        })
+    }
+
+    /// Loads a dynamic library from a unique temporary file with the specified
+    /// contents.
+    #[cfg(not(target_os = "linux"))]
+    unsafe fn from_temp_file(bytes: &[u8]) -> Result<Self, InitError> {
+        let mut tmp_file = tempfile::NamedTempFile::new().map_err(InitError::CreateTempFile)?;
+        tmp_file
+            .write_all(bytes)
+            .and_then(|()| tmp_file.flush())
+            .map_err(InitError::WriteLibrary)?;
+        let tmp_path = tmp_file.into_temp_path();
+        let library = Library::new(&tmp_path)?;
+        Ok(TempLibrary {
+            _file: LibraryFile::Temp(tmp_path),
             library,
         })
     }
@@ -1,5 +1,7 @@
 pub mod myers;
 
+use std::collections::HashMap;
+
 use self::myers::Change;
 use crate::r#type::{Field, Type};
 
@@ -81,10 +83,60 @@ impl PartialOrd for StructDiff {
     }
 }
 
+/// Field renames that were declared for structs. When the fields of a struct
+/// are diffed, a declared rename takes precedence over the heuristics that
+/// match deleted and inserted fields, even if the type of the field changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldRenames {
+    /// Maps the name of a struct to the old and new names of its renamed fields
+    renames: HashMap<String, HashMap<String, String>>,
+}
+
+impl FieldRenames {
+    /// Declares that the field `old_name` of the struct `struct_name` was
+    /// renamed to `new_name`.
+    pub fn insert(
+        &mut self,
+        struct_name: impl Into<String>,
+        old_name: impl Into<String>,
+        new_name: impl Into<String>,
+    ) {
+        self.renames
+            .entry(struct_name.into())
+            .or_default()
+            .insert(old_name.into(), new_name.into());
+    }
+
+    /// Returns the new name of the field `old_name` of the struct
+    /// `struct_name`, if it was renamed.
+    pub fn get(&self, struct_name: &str, old_name: &str) -> Option<&str> {
+        self.renames
+            .get(struct_name)
+            .and_then(|renames| renames.get(old_name))
+            .map(String::as_str)
+    }
+
+    /// Returns true if no renames were declared.
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty()
+    }
+}
+
 /// Given an `old` and a `new` ordered set of types, computes the difference
 /// based on ordering and equality of struct types. Thus, a diff can consist of
 /// inserted, deleted, moved, and edited (i.e. fields of) struct types.
 pub fn compute_struct_diff(old: &[Type], new: &[Type]) -> Vec<StructDiff> {
+    compute_struct_diff_with_renames(old, new, &FieldRenames::default())
+}
+
+/// Computes the difference between an `old` and a `new` ordered set of types,
+/// like [`compute_struct_diff`]. The fields of edited structs are matched using
+/// the declared `renames`.
+pub fn compute_struct_diff_with_renames(
+    old: &[Type],
+    new: &[Type],
+    renames: &FieldRenames,
+) -> Vec<StructDiff> {
     let diff = myers::compute_diff(old, new);
     let (deletions, insertions) = myers::split_diff(&diff);
 
@@ -99,7 +151,7 @@ pub fn compute_struct_diff(old: &[Type], new: &[Type]) -> Vec<StructDiff> {
         .collect();
 
     let mut mapping: Vec<StructDiff> = Vec::with_capacity(diff.len());
-    append_struct_mapping(deleted_structs, inserted_structs, &mut mapping);
+    append_struct_mapping(deleted_structs, inserted_structs, renames, &mut mapping);
 
     mapping.shrink_to_fit();
     // Sort to guarantee order of execution when deleting and/or inserting
@@ -129,6 +181,7 @@ impl<'a> From<Field<'a>> for UniqueFieldInfo<'a> {
 fn append_struct_mapping(
     deletions: Vec<Change<Type>>,
     insertions: Vec<Change<Type>>,
+    renames: &FieldRenames,
     mapping: &mut Vec<StructDiff>,
 ) {
     struct LengthDescription<'f> {
@@ -208,10 +261,16 @@ fn append_struct_mapping(
         })
         .collect();
 
-    // Sort in ascending order of their `myers::diff_length`.
-    myers_lengths.sort_by(
-        |LengthDescription { length: lhs, .. }, LengthDescription { length: rhs, .. }| lhs.cmp(rhs),
-    );
+    // Sort in ascending order of their `myers::diff_length`, preferring types
+    // with equal names. Otherwise a differently named type with the same fields,
+    // e.g. a struct that describes an old layout, would take the place of the
+    // changed type.
+    myers_lengths.sort_by_key(|description| {
+        (
+            description.old_ty.name() != description.new_ty.name(),
+            description.length,
+        )
+    });
 
     let mut used_deletions = vec![false; deletions.len()];
     let mut used_insertions = vec![false; insertions.len()];
@@ -235,30 +294,34 @@ fn append_struct_mapping(
         used_deletions[deletion_idx] = true;
         used_insertions[insertion_idx] = true;
 
-        // If there is no difference between the old and new fields
-        mapping.push(if length == 0 {
-            // Move the struct
-            StructDiff::Move {
-                old_index,
-                new_index,
-                old_ty,
-                new_ty,
-            }
-        } else {
-            // ASSUMPTION: Don't use recursion, because all types are individually checked
-            // for differences.
-            // TODO: Support value struct vs heap struct?
-            let diff = field_diff(old_fields, new_fields);
-
-            // Edit the struct, potentially moving it in the process.
-            StructDiff::Edit {
-                diff,
-                old_index,
-                new_index,
-                old_ty,
-                new_ty,
-            }
-        });
+        // If there is no difference between the old and new fields. A struct whose memory
+        // kind changed is edited, so the fields that refer to it are converted as well.
+        mapping.push(
+            if length == 0 && old_ty.is_value_type() == new_ty.is_value_type() {
+                // Move the struct
+                StructDiff::Move {
+                    old_index,
+                    new_index,
+                    old_ty,
+                    new_ty,
+                }
+            } else {
+                // ASSUMPTION: Don't use recursion, because all types are individually checked
+                // for differences.
+                let diff = field_diff(old_fields, new_fields, |old_name| {
+                    renames.get(new_ty.name(), old_name)
+                });
+
+                // Edit the struct, potentially moving it in the process.
+                StructDiff::Edit {
+                    diff,
+                    old_index,
+                    new_index,
+                    old_ty,
+                    new_ty,
+                }
+            },
+        );
     }
 
     // Any remaining unused deletions must have been deleted.
@@ -289,7 +352,13 @@ fn append_struct_mapping(
 }
 
 /// Given an `old` and a `new` set of fields, calculates the difference.
-fn field_diff(old: &[UniqueFieldInfo<'_>], new: &[UniqueFieldInfo<'_>]) -> Vec<FieldDiff> {
+/// `renamed` returns the new name of an old field, if it was declared to be
+/// renamed.
+fn field_diff<'r>(
+    old: &[UniqueFieldInfo<'_>],
+    new: &[UniqueFieldInfo<'_>],
+    renamed: impl Fn(&str) -> Option<&'r str>,
+) -> Vec<FieldDiff> {
     let diff = myers::compute_diff(old, new);
     let (deletions, insertions) = myers::split_diff(&diff);
     let mut insertions: Vec<Option<Change<UniqueFieldInfo<'_>>>> =
@@ -303,7 +372,33 @@ fn field_diff(old: &[UniqueFieldInfo<'_>], new: &[UniqueFieldInfo<'_>]) -> Vec<F
         element: old_field,
     } in deletions
     {
-        // is there an insertion with the same field name and type `T`?
+        // was the field declared to be renamed to one of the insertions?
+        if let Some(new_name) = renamed(old_field.name) {
+            let insertion = insertions.iter_mut().find(|insertion| {
+                matches!(insertion, Some(Change { element, .. }) if element.name == new_name)
+            });
+            if let Some(insertion) = insertion {
+                let Change {
+                    index: new_index,
+                    element: new_field,
+                } = insertion.take().expect("insertion must exist");
+
+                // If so, rename the field, converting its type if necessary.
+                mapping.push(FieldDiff::Edit {
+                    old_type: old_field.ty,
+                    new_type: new_field.ty,
+                    old_index: if old_index == new_index {
+                        None
+                    } else {
+                        Some(old_index)
+                    },
+                    new_index,
+                    kind: FieldEditKind::RenamedField,
+                });
+                continue 'outer;
+            }
+        }
+        // Else, is there an insertion with the same field name and type `T`?
         for insertion in insertions.iter_mut() {
             if let Some(Change {
                 index: new_index,
@@ -2,12 +2,14 @@ mod array;
 mod mark_sweep;
 mod ptr;
 mod root_ptr;
+mod snapshot;
 
 use std::{marker::PhantomData, ptr::NonNull};
 
-pub use mark_sweep::MarkSweep;
+pub use mark_sweep::{HeapLimitExceeded, LoadHeapError, MarkSweep};
 pub use ptr::{GcPtr, HasIndirectionPtr, RawGcPtr};
 pub use root_ptr::GcRootPtr;
+pub use snapshot::{HeapSnapshot, ObjectSnapshot};
 
 use crate::r#type::Type;
 
@@ -17,6 +19,32 @@ pub struct Stats {
     pub allocated_memory: usize,
 }
 
+/// Contains stats about the objects of a single type that are currently
+/// allocated, including objects that are no longer reachable but have not been
+/// collected yet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeStats {
+    /// The number of allocated objects
+    pub object_count: usize,
+
+    /// The number of bytes allocated for the objects
+    pub allocated_memory: usize,
+}
+
+/// Limits the amount of memory a GC implementation allocates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapLimits {
+    /// When more than this number of bytes is allocated, the heap should be
+    /// collected at the next opportunity. Allocations never fail because of
+    /// the soft limit; it is only reported by `needs_collection`.
+    pub soft_limit: Option<usize>,
+
+    /// The maximum number of bytes that can be allocated through the fallible
+    /// allocation methods. The infallible methods ignore it, but the memory
+    /// they allocate counts towards it.
+    pub hard_limit: Option<usize>,
+}
+
 /// A trait used to trace an object type.
 pub trait TypeTrace: Send + Sync {
     type Trace: Iterator<Item = GcPtr>;
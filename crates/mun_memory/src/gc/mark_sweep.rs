@@ -1,21 +1,29 @@
+mod heap;
+mod serialize;
+
 use std::{
     alloc::{Layout, LayoutError},
     borrow::Cow,
     collections::{HashMap, VecDeque},
-    pin::Pin,
     ptr::NonNull,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
 };
 
+use heap::Heap;
 use mapping::{Mapping, StructMapping};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+pub use serialize::LoadHeapError;
 
 use crate::{
     cast,
     gc::{
-        array::ArrayHeader, Array as GcArray, Event, GcPtr, GcRuntime, Observer, RawGcPtr, Stats,
-        TypeTrace,
+        array::ArrayHeader, Array as GcArray, Event, GcPtr, GcRuntime, HeapLimits, HeapSnapshot,
+        ObjectSnapshot, Observer, RawGcPtr, Stats, TypeStats, TypeTrace,
+    },
+    mapping::{
+        self, resolve_struct_to_struct_edit, Action, FieldMapping, MappedMemory, MemoryMapper,
+        MigratedObject,
     },
-    mapping::{self, resolve_struct_to_struct_edit, Action, FieldMapping, MemoryMapper},
     r#type::Type,
     TypeKind,
 };
@@ -109,7 +117,10 @@ impl TraceEvent {
                     }))
                 };
             }
-            TypeKind::Array(_) => Some(TraceEvent::Reference(ptr.cast())),
+            TypeKind::Array(_) => {
+                let deref_ptr = unsafe { ptr.cast::<NonNull<ObjectInfo>>().as_ref() };
+                Some(TraceEvent::Reference(*deref_ptr))
+            }
         }
     }
 }
@@ -178,13 +189,59 @@ impl TypeTrace for Type {
 }
 
 /// Implements a simple mark-sweep type garbage collector.
+///
+/// Allocating memory only requires a shared lock on the heap. Collecting
+/// garbage and mapping memory require an exclusive lock.
+///
+/// Next to stop-the-world collections through [`MarkSweep::collect`], garbage
+/// can be collected incrementally through [`MarkSweep::collect_step`]. Every
+/// step performs a bounded amount of work, which bounds the pause times. While
+/// an incremental cycle is marking objects, every reference that is stored in
+/// an object must be passed to [`MarkSweep::write_barrier`].
 pub struct MarkSweep<O>
 where
     O: Observer<Event = Event>,
 {
-    objects: RwLock<HashMap<GcPtr, Pin<Box<ObjectInfo>>>>,
+    heap: RwLock<Heap>,
     observer: O,
-    stats: RwLock<Stats>,
+    allocated_memory: AtomicUsize,
+    limits: HeapLimits,
+    cycle: Mutex<Cycle>,
+
+    /// Whether an incremental cycle is marking objects
+    marking: AtomicBool,
+
+    /// Whether new objects are allocated as marked, because an incremental
+    /// cycle is in progress
+    alloc_marked: AtomicBool,
+
+    /// Objects that were shaded by the write barrier or rooted while marking
+    shaded: Mutex<Vec<GcPtr>>,
+}
+
+/// The state of an incremental collection cycle.
+#[derive(Default)]
+struct Cycle {
+    phase: Phase,
+
+    /// Objects that have been marked but whose references have not been traced
+    /// yet
+    gray: Vec<GcPtr>,
+}
+
+/// The phase of an incremental collection cycle.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Phase {
+    /// No cycle is in progress
+    #[default]
+    Idle,
+
+    /// Objects are being marked. Roots in pages from `next_root_page` onwards
+    /// have not been found yet.
+    Mark { next_root_page: usize },
+
+    /// Unmarked objects are being freed, starting with the page at `next_page`
+    Sweep { next_page: usize },
 }
 
 impl<O> Default for MarkSweep<O>
@@ -192,11 +249,7 @@ where
     O: Observer<Event = Event> + Default,
 {
     fn default() -> Self {
-        MarkSweep {
-            objects: RwLock::new(HashMap::new()),
-            observer: O::default(),
-            stats: RwLock::new(Stats::default()),
-        }
+        Self::with_observer(O::default())
     }
 }
 
@@ -207,20 +260,145 @@ where
     /// Creates a `MarkSweep` memory collector with the specified `Observer`.
     pub fn with_observer(observer: O) -> Self {
         Self {
-            objects: RwLock::new(HashMap::new()),
+            heap: RwLock::default(),
             observer,
-            stats: RwLock::new(Stats::default()),
+            allocated_memory: AtomicUsize::new(0),
+            limits: HeapLimits::default(),
+            cycle: Mutex::default(),
+            marking: AtomicBool::new(false),
+            alloc_marked: AtomicBool::new(false),
+            shaded: Mutex::default(),
         }
     }
 
-    /// Logs an allocation
-    fn log_alloc(&self, handle: GcPtr, size: usize) {
-        {
-            let mut stats = self.stats.write();
-            stats.allocated_memory += size;
+    /// Sets the limits on the amount of memory this collector allocates.
+    pub fn with_limits(mut self, limits: HeapLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns the limits on the amount of memory this collector allocates.
+    pub fn limits(&self) -> HeapLimits {
+        self.limits
+    }
+
+    /// Returns true if more memory is allocated than the soft limit allows,
+    /// which means that the heap should be collected.
+    pub fn needs_collection(&self) -> bool {
+        self.limits.soft_limit.map_or(false, |limit| {
+            self.allocated_memory.load(Ordering::Relaxed) > limit
+        })
+    }
+
+    /// Allocates an object of the given type, unless that would exceed the hard
+    /// limit.
+    pub fn try_alloc(&self, ty: &Type) -> Result<GcPtr, HeapLimitExceeded> {
+        self.reserve(data_layout(ty, 0).size())?;
+        Ok(self.alloc_reserved(ty))
+    }
+
+    /// Allocates an array of the given type, unless that would exceed the hard
+    /// limit. `ty` must be an array type.
+    pub fn try_alloc_array(&self, ty: &Type, n: usize) -> Result<ArrayHandle, HeapLimitExceeded> {
+        self.reserve(data_layout(ty, n).size())?;
+        Ok(self.alloc_array_reserved(ty, n))
+    }
+
+    /// Accounts for another `size` bytes of allocated memory, unless that would
+    /// exceed the hard limit. The limit is checked and the memory is accounted
+    /// for atomically, so concurrent allocations cannot exceed the limit
+    /// together.
+    fn reserve(&self, size: usize) -> Result<(), HeapLimitExceeded> {
+        let Some(limit) = self.limits.hard_limit else {
+            self.allocated_memory.fetch_add(size, Ordering::Relaxed);
+            return Ok(());
+        };
+
+        match self.allocated_memory.fetch_update(
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+            |allocated| {
+                allocated
+                    .checked_add(size)
+                    .filter(|&allocated| allocated <= limit)
+            },
+        ) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(HeapLimitExceeded),
         }
+    }
+
+    /// Allocates an object of the given type, for which memory has already
+    /// been accounted.
+    fn alloc_reserved(&self, ty: &Type) -> GcPtr {
+        assert!(ty.is_concrete());
 
+        let heap = self.heap.read();
+        let object = alloc_obj(&heap, ty.clone(), self.alloc_marked.load(Ordering::Relaxed));
+        drop(heap);
+
+        // We want to return a pointer to the `ObjectInfo`, to be used as handle.
+        let handle = object.into();
         self.observer.event(Event::Allocation(handle));
+        handle
+    }
+
+    /// Allocates an array of the given type, for which memory has already been
+    /// accounted. `ty` must be an array type.
+    fn alloc_array_reserved(&self, ty: &Type, n: usize) -> ArrayHandle {
+        let heap = self.heap.read();
+        let object = alloc_array(
+            &heap,
+            ty.clone(),
+            n,
+            self.alloc_marked.load(Ordering::Relaxed),
+        );
+        drop(heap);
+
+        // We want to return a pointer to the `ObjectInfo`, to be used as handle.
+        self.observer.event(Event::Allocation(object.into()));
+        ArrayHandle { obj: object }
+    }
+
+    /// Returns stats about the currently allocated objects of each type.
+    pub fn type_stats(&self) -> HashMap<Type, TypeStats> {
+        let mut heap = self.heap.write();
+        let mut stats = HashMap::<Type, TypeStats>::new();
+        for object in heap.objects() {
+            // Safety: we hold an exclusive lock on the heap, so the object cannot change
+            let object = unsafe { object.as_ref() };
+            let type_stats = stats.entry(object.ty.clone()).or_default();
+            type_stats.object_count += 1;
+            type_stats.allocated_memory += object.layout().size();
+        }
+        stats
+    }
+
+    /// Returns a snapshot of all objects on the heap, including objects that
+    /// are no longer reachable but have not been collected yet.
+    pub fn snapshot(&self) -> HeapSnapshot {
+        let mut heap = self.heap.write();
+        let objects = heap
+            .objects()
+            .map(|object| {
+                // Safety: we hold an exclusive lock on the heap, so the object cannot change
+                let object_info = unsafe { object.as_ref() };
+                ObjectSnapshot {
+                    handle: object.into(),
+                    ty: object_info.ty.clone(),
+                    size: object_info.layout().size(),
+                    roots: object_info.roots.load(Ordering::Relaxed),
+                    references: object_info
+                        .ty
+                        .trace(object.into())
+                        // Objects allocated from outside of Mun may not be initialized yet
+                        .filter(|reference| !reference.as_ptr().is_null())
+                        .collect(),
+                }
+            })
+            .collect();
+
+        HeapSnapshot::new(objects)
     }
 
     /// Returns the observer
@@ -229,17 +407,22 @@ where
     }
 }
 
-fn alloc_obj(ty: Type) -> Pin<Box<ObjectInfo>> {
-    let ptr = NonNull::new(unsafe { std::alloc::alloc_zeroed(ty.value_layout()) })
-        .expect("failed to allocate memory for new object");
-    Box::pin(ObjectInfo {
+/// Allocates a zero-initialized object of type `ty` on the heap. If `marked` is
+/// set, the object is marked as reachable.
+fn alloc_obj(heap: &Heap, ty: Type, marked: bool) -> NonNull<ObjectInfo> {
+    heap.alloc(data_layout(&ty, 0), marked, |ptr| ObjectInfo {
         data: ObjectInfoData { ptr },
+        roots: AtomicU32::new(0),
         ty,
-        roots: 0,
-        color: Color::White,
     })
 }
 
+/// An error that is returned when an allocation would exceed the hard heap
+/// limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("the allocation exceeds the hard heap limit")]
+pub struct HeapLimitExceeded;
+
 /// An error that might occur when requesting memory layout of a type
 #[derive(Debug)]
 pub enum MemoryLayoutError {
@@ -424,37 +607,29 @@ fn repeat_layout(layout: Layout, n: usize) -> Result<Layout, MemoryLayoutError>
     Layout::from_size_align(alloc_size, layout.align()).map_err(Into::into)
 }
 
-/// Allocates memory for an array type with `length` elements. `array_ty` must
-/// be an array type.
-fn alloc_array(ty: Type, length: usize) -> Pin<Box<ObjectInfo>> {
-    Box::pin(ObjectInfo {
+/// Allocates an array of type `ty` with `length` zero-initialized elements on
+/// the heap. `ty` must be an array type. If `marked` is set, the array is
+/// marked as reachable.
+fn alloc_array(heap: &Heap, ty: Type, length: usize, marked: bool) -> NonNull<ObjectInfo> {
+    heap.alloc(data_layout(&ty, length), marked, |ptr| ObjectInfo {
         data: ObjectInfoData {
-            array: array_header(&ty, length),
+            array: init_array_header(ptr, length),
         },
+        roots: AtomicU32::new(0),
         ty,
-        roots: 0,
-        color: Color::White,
     })
 }
 
-/// Constructs an array header for an array type with `length` elements.
-fn array_header(ty: &Type, length: usize) -> NonNull<ArrayHeader> {
-    let array_ty = ty
-        .as_array()
-        .expect("array type doesnt have an element type");
-
-    // Allocate memory for the array data
-    let header_layout = Layout::new::<ArrayHeader>();
-    let element_ty_layout = array_ty.element_type().reference_layout();
-    let elements_layout = repeat_layout(element_ty_layout, length)
-        .expect("unable to create a memory layout for array elemets");
-    let (layout, _) = header_layout
-        .extend(elements_layout)
-        .expect("unable to create memory layout for array");
-
-    let mut array_header: NonNull<ArrayHeader> =
-        NonNull::new(unsafe { std::alloc::alloc_zeroed(layout).cast() })
-            .expect("error allocating memory for array");
+/// Allocates the data of an array of type `ty` with `length` zero-initialized
+/// elements on the heap. `ty` must be an array type.
+fn alloc_array_data(heap: &Heap, ty: &Type, length: usize) -> NonNull<ArrayHeader> {
+    init_array_header(heap.alloc_data(data_layout(ty, length)), length)
+}
+
+/// Initializes the header of newly allocated array data with room for `length`
+/// elements.
+fn init_array_header(data: NonNull<u8>, length: usize) -> NonNull<ArrayHeader> {
+    let mut array_header = data.cast::<ArrayHeader>();
     let array = unsafe { array_header.as_mut() };
     array.length = length;
     array.capacity = length;
@@ -469,43 +644,19 @@ where
     type Array = ArrayHandle;
 
     fn alloc(&self, ty: &Type) -> GcPtr {
-        assert!(ty.is_concrete());
-
-        let object = alloc_obj(ty.clone());
-        let size = object.layout().size();
-
-        // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-        let handle = (&*object.as_ref() as *const _ as RawGcPtr).into();
-
-        {
-            let mut objects = self.objects.write();
-            objects.insert(handle, object);
-        }
-
-        self.log_alloc(handle, size);
-        handle
+        self.allocated_memory
+            .fetch_add(data_layout(ty, 0).size(), Ordering::Relaxed);
+        self.alloc_reserved(ty)
     }
 
     fn alloc_array(&self, ty: &Type, n: usize) -> Self::Array {
-        let object = alloc_array(ty.clone(), n);
-        let size = object.layout().size();
-
-        // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-        let handle = (&*object.as_ref() as *const _ as RawGcPtr).into();
-
-        {
-            let mut objects = self.objects.write();
-            objects.insert(handle, object);
-        }
-
-        self.log_alloc(handle, size);
-        ArrayHandle {
-            obj: unsafe { NonNull::new_unchecked(handle.into()) },
-        }
+        self.allocated_memory
+            .fetch_add(data_layout(ty, n).size(), Ordering::Relaxed);
+        self.alloc_array_reserved(ty, n)
     }
 
     fn ptr_type(&self, handle: GcPtr) -> Type {
-        let _lock = self.objects.read();
+        let _lock = self.heap.read();
 
         // Convert the handle to our internal representation
         let object_info: *const ObjectInfo = handle.into();
@@ -515,7 +666,7 @@ where
     }
 
     fn array(&self, handle: GcPtr) -> Option<Self::Array> {
-        let _lock = self.objects.read();
+        let _lock = self.heap.read();
         let obj: NonNull<ObjectInfo> =
             NonNull::new(handle.into()).expect("cannot have a null handle here");
         unsafe {
@@ -528,25 +679,32 @@ where
     }
 
     fn root(&self, handle: GcPtr) {
-        let _lock = self.objects.write();
+        let _lock = self.heap.read();
 
         // Convert the handle to our internal representation
-        let object_info: *mut ObjectInfo = handle.into();
+        let object_info: *const ObjectInfo = handle.into();
+
+        unsafe { (*object_info).roots.fetch_add(1, Ordering::Relaxed) };
 
-        unsafe { (*object_info).roots += 1 };
+        // Roots that are added while marking might not be found anymore
+        if self.marking.load(Ordering::Relaxed) {
+            self.shaded.lock().push(handle);
+        }
     }
 
     fn unroot(&self, handle: GcPtr) {
-        let _lock = self.objects.write();
+        let _lock = self.heap.read();
 
         // Convert the handle to our internal representation
-        let object_info: *mut ObjectInfo = handle.into();
+        let object_info: *const ObjectInfo = handle.into();
 
-        unsafe { (*object_info).roots -= 1 };
+        unsafe { (*object_info).roots.fetch_sub(1, Ordering::Relaxed) };
     }
 
     fn stats(&self) -> Stats {
-        self.stats.read().clone()
+        Stats {
+            allocated_memory: self.allocated_memory.load(Ordering::Relaxed),
+        }
     }
 }
 
@@ -556,69 +714,182 @@ where
 {
     /// Collects all memory that is no longer referenced by rooted objects.
     /// Returns `true` if memory was reclaimed, `false` otherwise.
+    ///
+    /// An incremental cycle that is in progress is aborted.
     pub fn collect(&self) -> bool {
+        let mut heap = self.heap.write();
+        self.abort_cycle();
+
         self.observer.event(Event::Start);
 
-        let mut objects = self.objects.write();
+        // Objects that were allocated during an incremental cycle might still be marked
+        heap.clear_marks();
 
         // Get all roots
-        let mut roots = objects
-            .iter()
-            .filter_map(|(_, obj)| {
-                if obj.roots > 0 {
-                    Some(obj.as_ref().get_ref() as *const _ as *mut ObjectInfo)
-                } else {
-                    None
-                }
-            })
+        let mut roots = heap
+            .objects()
+            .filter(|obj| unsafe { obj.as_ref() }.roots.load(Ordering::Relaxed) > 0)
             .collect::<VecDeque<_>>();
+        for root in roots.iter() {
+            unsafe { Heap::mark(*root) };
+        }
 
         // Iterate over all roots
         while let Some(next) = roots.pop_front() {
-            let handle = (next as *const _ as RawGcPtr).into();
-
             // Trace all other objects
-            for reference in unsafe { (*next).ty.trace(handle) } {
-                let ref_ptr = objects
-                    .get_mut(&reference)
-                    .expect("found invalid reference");
-                if ref_ptr.color == Color::White {
-                    let ptr = ref_ptr.as_ref().get_ref() as *const _ as *mut ObjectInfo;
-                    unsafe { (*ptr).color = Color::Gray };
-                    roots.push_back(ptr);
+            for reference in unsafe { next.as_ref().ty.trace(next.into()) } {
+                let reference = NonNull::new(reference.into()).expect("found invalid reference");
+                if unsafe { Heap::mark(reference) } {
+                    roots.push_back(reference);
                 }
             }
-
-            // This object has been traced
-            unsafe {
-                (*next).color = Color::Black;
-            }
         }
 
         // Sweep all non-reachable objects
-        let size_before = objects.len();
-        objects.retain(|h, obj| {
-            if obj.color == Color::Black {
-                unsafe {
-                    obj.as_mut().get_unchecked_mut().color = Color::White;
+        let mut reclaimed = false;
+        heap.sweep(|object| {
+            self.log_dealloc(object);
+            reclaimed = true;
+        });
+
+        self.observer.event(Event::End);
+
+        reclaimed
+    }
+
+    /// Performs a step of an incremental collection cycle, starting a new cycle
+    /// if none is in progress. A step processes roughly `budget` objects, where
+    /// finding roots, tracing an object and sweeping an object all count as
+    /// processing it. Returns `true` if the step completed the cycle.
+    ///
+    /// Objects that become unreachable during a cycle are only collected by the
+    /// next cycle. Between steps, every reference that is stored in an object
+    /// must be passed to [`MarkSweep::write_barrier`].
+    pub fn collect_step(&self, budget: usize) -> bool {
+        let mut heap = self.heap.write();
+        let mut cycle = self.cycle.lock();
+
+        if cycle.phase == Phase::Idle {
+            self.observer.event(Event::Start);
+
+            // Objects that were allocated during the previous cycle might still be marked
+            heap.clear_marks();
+            self.shaded.lock().clear();
+            cycle.phase = Phase::Mark { next_root_page: 0 };
+            self.marking.store(true, Ordering::Relaxed);
+            self.alloc_marked.store(true, Ordering::Relaxed);
+        }
+
+        let mut work = 0;
+        while work < budget {
+            match cycle.phase {
+                Phase::Idle => unreachable!("a cycle is in progress"),
+                Phase::Mark { next_root_page } => {
+                    // Shaded objects are traced again, even if they were traced already
+                    for object in self.shaded.lock().drain(..) {
+                        unsafe { Heap::mark(object_info(object)) };
+                        cycle.gray.push(object);
+                    }
+
+                    if let Some(object) = cycle.gray.pop() {
+                        let object = object_info(object);
+                        for reference in unsafe { object.as_ref().ty.trace(object.into()) } {
+                            if unsafe { Heap::mark(object_info(reference)) } {
+                                cycle.gray.push(reference);
+                            }
+                        }
+                        work += 1;
+                    } else if next_root_page < heap.page_count() {
+                        let mut object_count = 0;
+                        for object in heap.page_objects(next_root_page) {
+                            object_count += 1;
+                            if unsafe { object.as_ref() }.roots.load(Ordering::Relaxed) > 0
+                                && unsafe { Heap::mark(object) }
+                            {
+                                cycle.gray.push(object.into());
+                            }
+                        }
+                        cycle.phase = Phase::Mark {
+                            next_root_page: next_root_page + 1,
+                        };
+                        work += object_count.max(1);
+                    } else {
+                        // All reachable objects have been marked
+                        self.marking.store(false, Ordering::Relaxed);
+                        cycle.phase = Phase::Sweep { next_page: 0 };
+                    }
                 }
-                true
-            } else {
-                let value_memory_layout = obj.layout();
-                unsafe { std::alloc::dealloc(obj.data.ptr.as_mut(), value_memory_layout) };
-                self.observer.event(Event::Deallocation(*h));
-                {
-                    let mut stats = self.stats.write();
-                    stats.allocated_memory -= value_memory_layout.size();
+                Phase::Sweep { next_page } => {
+                    if next_page < heap.page_count() {
+                        let object_count =
+                            heap.sweep_page(next_page, |object| self.log_dealloc(object));
+                        cycle.phase = Phase::Sweep {
+                            next_page: next_page + 1,
+                        };
+                        work += object_count.max(1);
+                    } else {
+                        self.alloc_marked.store(false, Ordering::Relaxed);
+                        cycle.phase = Phase::Idle;
+                        self.observer.event(Event::End);
+                        return true;
+                    }
                 }
-                false
             }
-        });
-        let size_after = objects.len();
+        }
 
-        self.observer.event(Event::End);
+        false
+    }
+
+    /// Returns true if an incremental collection cycle is in progress.
+    pub fn is_collecting(&self) -> bool {
+        self.cycle.lock().phase != Phase::Idle
+    }
+
+    /// Returns the flag that is set while an incremental cycle is marking
+    /// objects. Only then does [`MarkSweep::write_barrier`] need to be called,
+    /// so code can check the flag to skip the call.
+    pub fn marking_flag(&self) -> &AtomicBool {
+        &self.marking
+    }
+
+    /// Informs the collector that a reference to `obj` was stored in another
+    /// object, or that references were stored in `obj` itself. This ensures
+    /// that an incremental cycle that is marking objects traces `obj` and does
+    /// not miss any of the objects it references. This is cheap if no cycle is
+    /// marking objects.
+    pub fn write_barrier(&self, obj: GcPtr) {
+        if !self.marking.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // Steps hold an exclusive lock, so marking cannot end while we hold the lock
+        let _lock = self.heap.read();
+        if self.marking.load(Ordering::Relaxed) {
+            self.shaded.lock().push(obj);
+        }
+    }
+
+    /// Aborts the incremental collection cycle that is in progress, if any.
+    /// Objects that are marked remain marked until the next cycle starts.
+    ///
+    /// The caller must hold an exclusive lock on the heap.
+    fn abort_cycle(&self) {
+        let mut cycle = self.cycle.lock();
+        if cycle.phase != Phase::Idle {
+            cycle.phase = Phase::Idle;
+            cycle.gray.clear();
+            self.shaded.lock().clear();
+            self.marking.store(false, Ordering::Relaxed);
+            self.alloc_marked.store(false, Ordering::Relaxed);
+            self.observer.event(Event::End);
+        }
+    }
 
-        size_before != size_after
+    /// Logs that an object is about to be deallocated
+    fn log_dealloc(&self, object: NonNull<ObjectInfo>) {
+        let size = unsafe { object.as_ref() }.layout().size();
+        self.observer.event(Event::Deallocation(object.into()));
+        self.allocated_memory.fetch_sub(size, Ordering::Relaxed);
     }
 }
 
@@ -626,7 +897,7 @@ impl<O> MemoryMapper for MarkSweep<O>
 where
     O: Observer<Event = Event>,
 {
-    fn map_memory(&self, mapping: Mapping) -> Vec<GcPtr> {
+    fn map_memory(&self, mapping: Mapping) -> MappedMemory {
         unsafe fn get_field_ptr(struct_ptr: NonNull<u8>, offset: usize) -> NonNull<u8> {
             let mut ptr = struct_ptr.as_ptr() as usize;
             ptr += offset;
@@ -634,7 +905,8 @@ where
         }
 
         fn map_array(
-            new_allocations: &mut Vec<Pin<Box<ObjectInfo>>>,
+            heap: &Heap,
+            new_allocations: &mut Vec<NonNull<ObjectInfo>>,
             conversions: &HashMap<Type, StructMapping>,
             mut src_object: NonNull<ObjectInfo>,
             element_action: &Action,
@@ -643,12 +915,11 @@ where
             let src_array = ArrayHandle { obj: src_object };
 
             // Initialize the array
-            let new_header = array_header(new_ty, src_array.length());
+            let new_header = alloc_array_data(heap, new_ty, src_array.length());
 
             let mut dest_obj = ObjectInfo {
                 data: ObjectInfoData { array: new_header },
-                roots: unsafe { src_object.as_ref().roots },
-                color: unsafe { src_object.as_ref().color },
+                roots: AtomicU32::new(0),
                 ty: new_ty.clone(),
             };
 
@@ -662,6 +933,7 @@ where
                 .zip(dest_array.elements())
                 .for_each(|(src, dest)| {
                     map_type(
+                        heap,
                         new_allocations,
                         conversions,
                         src,
@@ -673,13 +945,15 @@ where
 
             unsafe {
                 let src_obj = src_object.as_mut();
-                std::alloc::dealloc(src_obj.data.ptr.as_mut(), src_obj.layout());
-                *src_obj = dest_obj;
+                heap.free_data(src_obj.data.ptr, src_obj.layout());
+                src_obj.data = dest_obj.data;
+                src_obj.ty = dest_obj.ty;
             };
         }
 
         fn map_type(
-            new_allocations: &mut Vec<Pin<Box<ObjectInfo>>>,
+            heap: &Heap,
+            new_allocations: &mut Vec<NonNull<ObjectInfo>>,
             conversions: &HashMap<Type, StructMapping>,
             src: NonNull<u8>,
             dest: NonNull<u8>,
@@ -689,10 +963,10 @@ where
             match action {
                 mapping::Action::ArrayAlloc => {
                     // Initialize the array with no values
-                    let object = alloc_array(new_ty.clone(), 0);
+                    let object = alloc_array(heap, new_ty.clone(), 0, false);
 
                     // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-                    let handle = (&*object.as_ref() as *const _ as RawGcPtr).into();
+                    let handle = object.into();
 
                     // Write handle to field
                     let mut dest_handle = dest.cast::<GcPtr>();
@@ -705,16 +979,13 @@ where
                     old_offset,
                 } => {
                     // Initialize the array with a single value
-                    let mut object = alloc_array(new_ty.clone(), 1);
+                    let object = alloc_array(heap, new_ty.clone(), 1, false);
 
-                    let array_handle = ArrayHandle {
-                        obj: unsafe {
-                            NonNull::new_unchecked(&mut *object.as_mut() as *mut ObjectInfo)
-                        },
-                    };
+                    let array_handle = ArrayHandle { obj: object };
 
                     // Map single element to array
                     map_type(
+                        heap,
                         new_allocations,
                         conversions,
                         unsafe { get_field_ptr(src, *old_offset) },
@@ -724,7 +995,7 @@ where
                     );
 
                     // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-                    let handle = (&*object.as_ref() as *const _ as RawGcPtr).into();
+                    let handle = object.into();
 
                     // Write handle to field
                     let mut dest_handle = dest.cast::<GcPtr>();
@@ -742,6 +1013,7 @@ where
                     let src_obj = unsafe { *src_ptr.cast::<NonNull<ObjectInfo>>().as_ref() };
 
                     map_array(
+                        heap,
                         new_allocations,
                         conversions,
                         src_obj,
@@ -794,6 +1066,7 @@ where
                     if array_handle.header().length > 0 {
                         // Map single element from array
                         map_type(
+                            heap,
                             new_allocations,
                             conversions,
                             array_handle.data(),
@@ -806,10 +1079,10 @@ where
                     }
                 }
                 mapping::Action::StructAlloc => {
-                    let object = alloc_obj(new_ty.clone());
+                    let object = alloc_obj(heap, new_ty.clone(), false);
 
                     // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-                    let handle = (&*object.as_ref() as *const _ as RawGcPtr).into();
+                    let handle = object.into();
 
                     // Write handle to field
                     let mut dest_handle = dest.cast::<GcPtr>();
@@ -833,6 +1106,7 @@ where
 
                     // Map heap-allocated struct to in-memory struct
                     map_struct(
+                        heap,
                         new_allocations,
                         conversions,
                         &conversion.field_mapping,
@@ -842,7 +1116,7 @@ where
                     );
                 }
                 mapping::Action::StructMapFromValue { old_ty, old_offset } => {
-                    let object = alloc_obj(new_ty.clone());
+                    let object = alloc_obj(heap, new_ty.clone(), false);
 
                     let conversion = conversions.get(old_ty).unwrap_or_else(|| {
                         panic!(
@@ -852,6 +1126,7 @@ where
 
                     // Map in-memory struct to heap-allocated struct
                     map_struct(
+                        heap,
                         new_allocations,
                         conversions,
                         &conversion.field_mapping,
@@ -861,7 +1136,7 @@ where
                     );
 
                     // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-                    let handle = (&*object.as_ref() as *const _ as RawGcPtr).into();
+                    let handle = object.into();
 
                     // Write handle to field
                     let mut dest_handle = dest.cast::<GcPtr>();
@@ -877,6 +1152,7 @@ where
                     });
 
                     map_struct(
+                        heap,
                         new_allocations,
                         conversions,
                         &conversion.field_mapping,
@@ -892,7 +1168,8 @@ where
 
         #[allow(clippy::mutable_key_type)]
         fn map_struct(
-            new_allocations: &mut Vec<Pin<Box<ObjectInfo>>>,
+            heap: &Heap,
+            new_allocations: &mut Vec<NonNull<ObjectInfo>>,
             conversions: &HashMap<Type, StructMapping>,
             mapping: &[FieldMapping],
             src: NonNull<u8>,
@@ -906,6 +1183,7 @@ where
             {
                 let field_dest = unsafe { get_field_ptr(dest, *new_offset) };
                 map_type(
+                    heap,
                     new_allocations,
                     conversions,
                     src,
@@ -916,53 +1194,83 @@ where
             }
         }
 
-        let mut objects = self.objects.write();
+        let mut heap = self.heap.write();
+
+        // Mapping changes the types of objects, so the references of marked objects may no longer
+        // be traced correctly.
+        self.abort_cycle();
+
+        // Objects that are allocated during mapping don't need to be mapped themselves
+        let objects = heap.objects().collect::<Vec<_>>();
+        let heap = &*heap;
 
         // Determine which types are still allocated with deleted types
         let deleted = objects
             .iter()
-            .filter_map(|(ptr, object_info)| {
-                if mapping.deletions.contains(&object_info.ty) {
-                    Some(*ptr)
+            .filter_map(|object_info| {
+                // Safety: we hold an exclusive lock on the heap, so no one else can access it
+                if mapping
+                    .deletions
+                    .contains(unsafe { &object_info.as_ref().ty })
+                {
+                    Some((*object_info).into())
                 } else {
                     None
                 }
             })
             .collect();
 
+        let mut new_allocations = Vec::new();
+
+        // Preserve the old contents of objects that have a migration, before they are mapped
+        let migrated = objects
+            .iter()
+            .filter_map(|object_info| {
+                let object = unsafe { object_info.as_ref() };
+                let migration = mapping.migrations.get(&object.ty)?;
+
+                let copy = alloc_obj(heap, migration.new_ty.clone(), false);
+                unsafe { copy.as_ref() }.roots.store(1, Ordering::Relaxed);
+                map_struct(
+                    heap,
+                    &mut new_allocations,
+                    &mapping.struct_mappings,
+                    &migration.field_mapping,
+                    unsafe { object.data.ptr },
+                    unsafe { copy.as_ref().data.ptr },
+                );
+                new_allocations.push(copy);
+
+                Some(MigratedObject {
+                    object: (*object_info).into(),
+                    old_contents: copy.into(),
+                })
+            })
+            .collect();
+
         // Update type pointers of types that didn't change
         for (old_ty, new_ty) in mapping.identical {
-            for object_info in objects.values_mut() {
+            for object_info in objects.iter() {
+                let object_info = unsafe { &mut *object_info.as_ptr() };
                 if object_info.ty == old_ty {
-                    object_info.set(ObjectInfo {
-                        data: ObjectInfoData {
-                            ptr: unsafe { object_info.data.ptr },
-                        },
-                        roots: object_info.roots,
-                        color: object_info.color,
-                        ty: new_ty.clone(),
-                    });
+                    object_info.ty = new_ty.clone();
                 }
             }
         }
 
-        let mut new_allocations = Vec::new();
-
         // Map struct types
         objects
-            .values_mut()
+            .iter()
+            .map(|object_info| unsafe { &mut *object_info.as_ptr() })
             .filter(|object_info| object_info.ty.is_struct())
             .for_each(|object_info| {
                 if let Some(conversion) = mapping.struct_mappings.get(&object_info.ty) {
-                    let old_layout = object_info.ty.value_layout();
+                    let old_layout = object_info.layout();
                     let src = unsafe { object_info.data.ptr };
-                    let dest = unsafe {
-                        NonNull::new_unchecked(std::alloc::alloc_zeroed(
-                            conversion.new_ty.value_layout(),
-                        ))
-                    };
+                    let dest = heap.alloc_data(data_layout(&conversion.new_ty, 0));
 
                     map_struct(
+                        heap,
                         &mut new_allocations,
                         &mapping.struct_mappings,
                         &conversion.field_mapping,
@@ -970,23 +1278,19 @@ where
                         dest,
                     );
 
-                    unsafe { std::alloc::dealloc(src.as_ptr(), old_layout) };
+                    unsafe { heap.free_data(src, old_layout) };
 
-                    object_info.set(ObjectInfo {
-                        data: ObjectInfoData { ptr: dest },
-                        roots: object_info.roots,
-                        color: object_info.color,
-                        ty: conversion.new_ty.clone(),
-                    });
+                    object_info.data = ObjectInfoData { ptr: dest };
+                    object_info.ty = conversion.new_ty.clone();
                 }
             });
 
         // Map rooted array types
         objects
-            .values_mut()
-            .filter(|object_info| object_info.ty.is_array())
+            .iter()
+            .filter(|object_info| unsafe { object_info.as_ref() }.ty.is_array())
             .for_each(|object_info| {
-                let mut ty = object_info.ty.clone();
+                let mut ty = unsafe { object_info.as_ref() }.ty.clone();
                 let mut stack = Vec::new();
 
                 while let Some(array) = ty.as_array() {
@@ -1012,61 +1316,44 @@ where
                             resolve_struct_to_struct_edit(&old_element_ty, &new_element_ty, 0);
 
                         map_array(
+                            heap,
                             &mut new_allocations,
                             &mapping.struct_mappings,
-                            unsafe {
-                                NonNull::new_unchecked(
-                                    &mut *object_info.as_mut() as *mut ObjectInfo
-                                )
-                            },
+                            *object_info,
                             &element_action,
                             &new_ty,
                         );
                     } else {
                         // Update the type of arrays of arrays
-                        object_info.as_mut().ty = conversion.new_ty.clone();
+                        unsafe { (*object_info.as_ptr()).ty = new_ty };
                     }
                 }
             });
 
-        // Retroactively store newly allocated objects
-        // This cannot be done while mapping because we hold a mutable reference to
-        // objects
-        for object in new_allocations {
-            let size = object.layout().size();
-            // We want to return a pointer to the `ObjectInfo`, to
-            // be used as handle.
-            let handle = (&*object.as_ref() as *const _ as RawGcPtr).into();
-            objects.insert(handle, object);
-
-            self.log_alloc(handle, size);
+        for object in new_allocations.iter() {
+            self.observer.event(Event::Allocation((*object).into()));
         }
 
-        deleted
-    }
-}
-
-/// Coloring used in the Mark Sweep phase.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Color {
-    /// A white object has not been seen yet by the mark phase
-    White,
-
-    /// A gray object has been seen by the mark phase but has not yet been
-    /// visited
-    Gray,
+        // Mapping changes the size of existing objects, so recompute the allocated memory
+        let allocated_memory = objects
+            .iter()
+            .chain(new_allocations.iter())
+            .map(|object| unsafe { object.as_ref() }.layout().size())
+            .sum();
+        self.allocated_memory
+            .store(allocated_memory, Ordering::Relaxed);
 
-    /// A black object has been visited by the mark phase
-    Black,
+        MappedMemory { deleted, migrated }
+    }
 }
 
 /// An indirection table that stores the address to the actual memory, the type
-/// of the object and meta information.
+/// of the object and meta information. Whether an object has been marked by
+/// the collector is stored in a bitmap of the page that contains the header.
 #[repr(C)]
 struct ObjectInfo {
     pub data: ObjectInfoData,
-    pub roots: u32,
-    pub color: Color,
+    pub roots: AtomicU32,
     pub ty: Type,
 }
 
@@ -1111,22 +1398,40 @@ impl From<NonNull<ObjectInfo>> for GcPtr {
     }
 }
 
+/// Returns the object info that `ptr` points to.
+fn object_info(ptr: GcPtr) -> NonNull<ObjectInfo> {
+    NonNull::new(ptr.into()).expect("found invalid reference")
+}
+
 impl ObjectInfo {
     /// Returns the layout of the data pointed to by data
     pub fn layout(&self) -> Layout {
-        match self.ty.kind() {
-            TypeKind::Struct(_) | TypeKind::Primitive(_) | TypeKind::Pointer(_) => {
-                self.ty.value_layout()
-            }
-            TypeKind::Array(array) => {
-                let elem_count = unsafe { self.data.array.as_ref().capacity };
-                let elem_layout = repeat_layout(array.element_type().value_layout(), elem_count)
-                    .expect("unable to determine layout of array elements");
-                let (layout, _) = Layout::new::<ArrayHeader>()
-                    .extend(elem_layout)
-                    .expect("unable to determine layout of array");
-                layout
-            }
+        let capacity = if self.ty.is_array() {
+            unsafe { self.data.array.as_ref().capacity }
+        } else {
+            0
+        };
+        data_layout(&self.ty, capacity)
+    }
+}
+
+/// Returns the layout of the data of an object of type `ty`. For arrays,
+/// `capacity` specifies the number of elements; it is ignored otherwise.
+fn data_layout(ty: &Type, capacity: usize) -> Layout {
+    try_data_layout(ty, capacity).expect("unable to determine layout of object")
+}
+
+/// Returns the layout of the data of an object of type `ty`, or an error if an
+/// array of `capacity` elements is too large.
+fn try_data_layout(ty: &Type, capacity: usize) -> Result<Layout, MemoryLayoutError> {
+    match ty.kind() {
+        TypeKind::Struct(_) | TypeKind::Primitive(_) | TypeKind::Pointer(_) => {
+            Ok(ty.value_layout())
+        }
+        TypeKind::Array(array) => {
+            let elem_layout = repeat_layout(array.element_type().reference_layout(), capacity)?;
+            let (layout, _) = Layout::new::<ArrayHeader>().extend(elem_layout)?;
+            Ok(layout)
         }
     }
 }
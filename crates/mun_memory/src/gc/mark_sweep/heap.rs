@@ -0,0 +1,436 @@
+//! The heap that backs the [`super::MarkSweep`] collector.
+//!
+//! Object headers ([`ObjectInfo`]) are stored in pages with a fixed number of
+//! slots. Because headers never move, a pointer to a header is used as the
+//! [`GcPtr`](crate::gc::GcPtr) of an object. Headers are not stored inline with
+//! the data of an object, because the data is reallocated when hot reloading
+//! changes the layout of its type, while its `GcPtr` must remain valid.
+//!
+//! Every header page also contains a bitmap of the slots that are in use and a
+//! bitmap of the objects that have been marked by the collector. Header pages
+//! are aligned to their size, which enables finding the page of a header by
+//! masking its address.
+//!
+//! The data of an object is allocated from pages of segregated size classes.
+//! Only data that is larger than the largest size class, or that requires a
+//! larger alignment than the pages provide, is allocated with the global
+//! allocator.
+//!
+//! Pages are never returned to the global allocator; freed slots are reused by
+//! subsequent allocations. A program that allocates many short-lived objects
+//! every frame would otherwise release and reallocate the same pages after
+//! every collection. It also keeps the indices of header pages stable, which
+//! incremental collection cycles use to resume marking and sweeping.
+
+use std::{
+    alloc::Layout,
+    mem::MaybeUninit,
+    ptr::{self, NonNull},
+};
+
+use parking_lot::Mutex;
+
+use super::ObjectInfo;
+
+/// The number of header slots in a header page.
+const HEADERS_PER_PAGE: usize = 256;
+
+/// The number of words in the bitmaps of a header page.
+const BITMAP_WORDS: usize = HEADERS_PER_PAGE / u64::BITS as usize;
+
+/// The sizes of the size classes from which object data is allocated.
+const SIZE_CLASSES: [usize; 24] = [
+    16, 32, 48, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384, 448, 512, 640, 768, 896, 1024,
+    1280, 1536, 1792, 2048,
+];
+
+/// The alignment of all slots in size class pages.
+const SIZE_CLASS_ALIGN: usize = 16;
+
+/// The number of bytes in a size class page.
+const DATA_PAGE_SIZE: usize = 16 * 1024;
+
+/// A page of object headers.
+#[repr(C)]
+struct HeaderPage {
+    /// The slots that contain an object header
+    used: [u64; BITMAP_WORDS],
+
+    /// The objects that have been marked as reachable by the collector
+    marked: [u64; BITMAP_WORDS],
+
+    headers: [MaybeUninit<ObjectInfo>; HEADERS_PER_PAGE],
+}
+
+impl HeaderPage {
+    /// Returns the layout of a header page. Pages are aligned to their size, so
+    /// the page of a header can be found by masking its address.
+    fn layout() -> Layout {
+        let size = std::mem::size_of::<HeaderPage>().next_power_of_two();
+        Layout::from_size_align(size, size).expect("invalid header page layout")
+    }
+
+    /// Returns the page that contains `header` and the index of its slot.
+    ///
+    /// # Safety
+    ///
+    /// `header` must point to a slot in a header page.
+    unsafe fn of(header: NonNull<ObjectInfo>) -> (NonNull<HeaderPage>, usize) {
+        let address = header.as_ptr() as usize;
+        let page = (address & !(Self::layout().align() - 1)) as *mut HeaderPage;
+        let first_header = ptr::addr_of_mut!((*page).headers).cast::<ObjectInfo>();
+        let index = header.as_ptr().offset_from(first_header) as usize;
+        (NonNull::new_unchecked(page), index)
+    }
+}
+
+/// Returns the word and the mask of the bit that corresponds to `index` in a
+/// bitmap.
+fn bit(index: usize) -> (usize, u64) {
+    let bits = u64::BITS as usize;
+    (index / bits, 1 << (index % bits))
+}
+
+/// Allocates object headers from header pages.
+#[derive(Default)]
+struct HeaderArena {
+    pages: Vec<NonNull<HeaderPage>>,
+
+    /// Header slots that are not in use
+    free: Vec<NonNull<ObjectInfo>>,
+}
+
+impl HeaderArena {
+    /// Allocates an uninitialized header slot. If `marked` is set, the object
+    /// is marked as reachable.
+    fn alloc(&mut self, marked: bool) -> NonNull<ObjectInfo> {
+        if self.free.is_empty() {
+            self.add_page();
+        }
+        let header = self.free.pop().expect("a new page has free slots");
+
+        // Safety: all slots in the free list are part of a header page
+        unsafe {
+            let (mut page, index) = HeaderPage::of(header);
+            let (word, mask) = bit(index);
+            page.as_mut().used[word] |= mask;
+            if marked {
+                page.as_mut().marked[word] |= mask;
+            }
+        }
+
+        header
+    }
+
+    /// Adds a new page and adds its slots to the free list.
+    fn add_page(&mut self) {
+        // Safety: a zeroed page has empty bitmaps and uninitialized headers
+        let page = NonNull::new(unsafe { std::alloc::alloc_zeroed(HeaderPage::layout()) })
+            .expect("failed to allocate memory for object headers")
+            .cast::<HeaderPage>();
+        self.pages.push(page);
+
+        // Hand out the slots in order of their address
+        let first_header =
+            unsafe { ptr::addr_of_mut!((*page.as_ptr()).headers) }.cast::<ObjectInfo>();
+        self.free.extend((0..HEADERS_PER_PAGE).rev().map(|index| {
+            // Safety: the index is within the bounds of the page
+            unsafe { NonNull::new_unchecked(first_header.add(index)) }
+        }));
+    }
+
+    /// Returns the headers of all objects.
+    fn objects(&self) -> impl Iterator<Item = NonNull<ObjectInfo>> + '_ {
+        self.pages.iter().flat_map(|page| page_objects(*page))
+    }
+}
+
+/// Returns the headers of all objects in a page.
+fn page_objects(page: NonNull<HeaderPage>) -> impl Iterator<Item = NonNull<ObjectInfo>> {
+    // Safety: pages stay alive for as long as the arena
+    let page = page.as_ptr();
+    let first_header = unsafe { ptr::addr_of_mut!((*page).headers) }.cast::<ObjectInfo>();
+    (0..BITMAP_WORDS).flat_map(move |word| {
+        let used = unsafe { (*page).used[word] };
+        SetBits(used).map(move |bit| {
+            let index = word * u64::BITS as usize + bit;
+            unsafe { NonNull::new_unchecked(first_header.add(index)) }
+        })
+    })
+}
+
+/// An iterator over the indices of the set bits in a word.
+struct SetBits(u64);
+
+impl Iterator for SetBits {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            None
+        } else {
+            let index = self.0.trailing_zeros() as usize;
+            self.0 &= self.0 - 1;
+            Some(index)
+        }
+    }
+}
+
+impl Drop for HeaderArena {
+    fn drop(&mut self) {
+        for page in &self.pages {
+            // Safety: the page was allocated with this layout
+            unsafe { std::alloc::dealloc(page.as_ptr().cast(), HeaderPage::layout()) };
+        }
+    }
+}
+
+/// Allocates object data of a single size class.
+struct SizeClass {
+    /// The size of every slot
+    slot_size: usize,
+
+    pages: Vec<NonNull<u8>>,
+
+    /// Slots that are not in use
+    free: Vec<NonNull<u8>>,
+}
+
+impl SizeClass {
+    fn new(slot_size: usize) -> Self {
+        Self {
+            slot_size,
+            pages: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    fn page_layout() -> Layout {
+        Layout::from_size_align(DATA_PAGE_SIZE, SIZE_CLASS_ALIGN).expect("invalid page layout")
+    }
+
+    /// Allocates a slot. The contents of the slot are unspecified.
+    fn alloc(&mut self) -> NonNull<u8> {
+        if let Some(slot) = self.free.pop() {
+            return slot;
+        }
+
+        let page = NonNull::new(unsafe { std::alloc::alloc(Self::page_layout()) })
+            .expect("failed to allocate memory for object data");
+        self.pages.push(page);
+
+        // Hand out the slots in order of their address
+        let slot_count = DATA_PAGE_SIZE / self.slot_size;
+        self.free.extend((1..slot_count).rev().map(|index| {
+            // Safety: the offset is within the bounds of the page
+            unsafe { NonNull::new_unchecked(page.as_ptr().add(index * self.slot_size)) }
+        }));
+        page
+    }
+
+    /// Returns a slot to the size class.
+    fn free(&mut self, slot: NonNull<u8>) {
+        self.free.push(slot);
+    }
+}
+
+impl Drop for SizeClass {
+    fn drop(&mut self) {
+        for page in &self.pages {
+            // Safety: the page was allocated with this layout
+            unsafe { std::alloc::dealloc(page.as_ptr(), Self::page_layout()) };
+        }
+    }
+}
+
+/// Returns the index of the size class that data with the specified layout is
+/// allocated from, or `None` if it must be allocated with the global allocator.
+fn size_class(layout: Layout) -> Option<usize> {
+    if layout.align() > SIZE_CLASS_ALIGN {
+        return None;
+    }
+
+    let index = SIZE_CLASSES.partition_point(|size| *size < layout.size());
+    (index < SIZE_CLASSES.len()).then_some(index)
+}
+
+/// The memory in which the objects of a [`super::MarkSweep`] collector are
+/// stored.
+///
+/// Allocating and freeing memory only requires a shared reference, so multiple
+/// threads can allocate at the same time. Only threads that allocate data of
+/// the same size class contend for the same lock. Functions that inspect all
+/// objects require an exclusive reference.
+pub(super) struct Heap {
+    headers: Mutex<HeaderArena>,
+    size_classes: [Mutex<SizeClass>; SIZE_CLASSES.len()],
+}
+
+/// The heap only contains pointers to memory that it owns.
+unsafe impl Send for Heap {}
+
+unsafe impl Sync for Heap {}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self {
+            headers: Mutex::default(),
+            size_classes: SIZE_CLASSES.map(|size| Mutex::new(SizeClass::new(size))),
+        }
+    }
+}
+
+impl Heap {
+    /// Allocates an object with the specified header. The data of the object
+    /// is zero-initialized and must have the specified layout. If `marked` is
+    /// set, the object is marked as reachable.
+    pub fn alloc(
+        &self,
+        layout: Layout,
+        marked: bool,
+        header: impl FnOnce(NonNull<u8>) -> ObjectInfo,
+    ) -> NonNull<ObjectInfo> {
+        let data = self.alloc_data(layout);
+        let slot = self.headers.lock().alloc(marked);
+
+        // Safety: the slot was just allocated, so it's not used by another object
+        unsafe { slot.as_ptr().write(header(data)) };
+        slot
+    }
+
+    /// Frees an object, including its data.
+    ///
+    /// # Safety
+    ///
+    /// The object must have been allocated from this heap and cannot be used
+    /// afterwards.
+    pub unsafe fn free(&mut self, object: NonNull<ObjectInfo>) {
+        let header = object.as_ptr().read();
+        self.free_data(header.data.ptr, header.layout());
+
+        let (mut page, index) = HeaderPage::of(object);
+        let (word, mask) = bit(index);
+        page.as_mut().used[word] &= !mask;
+        self.headers.get_mut().free.push(object);
+    }
+
+    /// Allocates zero-initialized memory for the data of an object.
+    pub fn alloc_data(&self, layout: Layout) -> NonNull<u8> {
+        match size_class(layout) {
+            Some(index) => {
+                let data = self.size_classes[index].lock().alloc();
+                // Safety: the slot is at least as big as the layout
+                unsafe { ptr::write_bytes(data.as_ptr(), 0, layout.size()) };
+                data
+            }
+            // Safety: layouts without a size class are never zero-sized
+            None => NonNull::new(unsafe { std::alloc::alloc_zeroed(layout) })
+                .expect("failed to allocate memory for object data"),
+        }
+    }
+
+    /// Frees the data of an object.
+    ///
+    /// # Safety
+    ///
+    /// The data must have been allocated by [`Heap::alloc_data`] with the same
+    /// layout.
+    pub unsafe fn free_data(&self, data: NonNull<u8>, layout: Layout) {
+        match size_class(layout) {
+            Some(index) => self.size_classes[index].lock().free(data),
+            None => std::alloc::dealloc(data.as_ptr(), layout),
+        }
+    }
+
+    /// Returns the headers of all objects.
+    pub fn objects(&mut self) -> impl Iterator<Item = NonNull<ObjectInfo>> + '_ {
+        self.headers.get_mut().objects()
+    }
+
+    /// Marks an object as reachable. Returns `true` if the object was not
+    /// marked yet.
+    ///
+    /// # Safety
+    ///
+    /// The object must have been allocated from a heap to which the caller
+    /// holds an exclusive reference.
+    pub unsafe fn mark(object: NonNull<ObjectInfo>) -> bool {
+        let (mut page, index) = HeaderPage::of(object);
+        let (word, mask) = bit(index);
+        let marked = &mut page.as_mut().marked[word];
+        let newly_marked = *marked & mask == 0;
+        *marked |= mask;
+        newly_marked
+    }
+
+    /// Returns the number of header pages. Pages are never removed, so the
+    /// index of a page never changes.
+    pub fn page_count(&mut self) -> usize {
+        self.headers.get_mut().pages.len()
+    }
+
+    /// Returns the headers of all objects in the page with the specified
+    /// index.
+    pub fn page_objects(&mut self, page: usize) -> impl Iterator<Item = NonNull<ObjectInfo>> {
+        page_objects(self.headers.get_mut().pages[page])
+    }
+
+    /// Unmarks all objects.
+    pub fn clear_marks(&mut self) {
+        for page in &self.headers.get_mut().pages {
+            // Safety: pages stay alive for as long as the heap
+            unsafe { (*page.as_ptr()).marked = [0; BITMAP_WORDS] };
+        }
+    }
+
+    /// Frees all objects that have not been marked, calling `on_free` for each
+    /// of them before they are freed. Afterwards, all objects are unmarked.
+    pub fn sweep(&mut self, mut on_free: impl FnMut(NonNull<ObjectInfo>)) {
+        for page in 0..self.page_count() {
+            self.sweep_page(page, &mut on_free);
+        }
+    }
+
+    /// Frees all objects in the page with the specified index that have not
+    /// been marked, calling `on_free` for each of them before they are freed.
+    /// Afterwards, all objects in the page are unmarked. Returns the number of
+    /// objects that were in the page.
+    pub fn sweep_page(
+        &mut self,
+        page: usize,
+        mut on_free: impl FnMut(NonNull<ObjectInfo>),
+    ) -> usize {
+        // Safety: pages stay alive for as long as the heap
+        let page = unsafe { &mut *self.headers.get_mut().pages[page].as_ptr() };
+        let first_header = ptr::addr_of_mut!(page.headers).cast::<ObjectInfo>();
+        let mut object_count = 0;
+        let mut unreachable = Vec::new();
+        for word in 0..BITMAP_WORDS {
+            object_count += page.used[word].count_ones() as usize;
+            let dead = page.used[word] & !page.marked[word];
+            unreachable.extend(SetBits(dead).map(|bit| {
+                let index = word * u64::BITS as usize + bit;
+                unsafe { NonNull::new_unchecked(first_header.add(index)) }
+            }));
+            page.marked[word] = 0;
+        }
+
+        for object in unreachable {
+            on_free(object);
+            // Safety: the object is unreachable, so it can no longer be used
+            unsafe { self.free(object) };
+        }
+
+        object_count
+    }
+}
+
+impl Drop for Heap {
+    fn drop(&mut self) {
+        let objects = self.objects().collect::<Vec<_>>();
+        for object in objects {
+            // Safety: the heap is being destroyed, so objects can no longer be used
+            unsafe { self.free(object) };
+        }
+    }
+}
@@ -0,0 +1,665 @@
+//! Saving and loading of objects in a versioned binary format.
+//!
+//! A heap file consists of:
+//!
+//! * a header with [`MAGIC`] and [`VERSION`],
+//! * a table of all types that are used, in which structs are described by
+//!   their name and the names and types of their fields,
+//! * a table of all objects, with their type and array length,
+//! * the data of all objects, and
+//! * the indices of the root objects.
+//!
+//! Fields are stored in the order of their type description, so memory offsets
+//! are never stored. References are stored as one-based indices into the table
+//! of objects, or zero for null references. Integers are stored in
+//! little-endian byte order, primitive values are stored as they are in memory.
+//!
+//! When a file is loaded, objects are first allocated with the types described
+//! in the file. Types that have changed since the file was saved are then
+//! converted to their current version through a [`Mapping`].
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Read, Write},
+    ptr::NonNull,
+};
+
+use super::{object_info, try_data_layout, ArrayHandle, HeapLimitExceeded, MarkSweep};
+use crate::{
+    abi,
+    gc::{Array, Event, GcPtr, GcRuntime, Observer, TypeTrace},
+    mapping::{Mapping, MemoryMapper},
+    r#type::Type,
+    type_table::TypeTable,
+    StructTypeBuilder, TypeKind,
+};
+
+/// The bytes with which every heap file starts.
+const MAGIC: [u8; 8] = *b"MUNHEAP\0";
+
+/// The version of the file format.
+const VERSION: u32 = 1;
+
+const TYPE_PRIMITIVE: u8 = 0;
+const TYPE_STRUCT: u8 = 1;
+const TYPE_ARRAY: u8 = 2;
+
+const MEMORY_KIND_GC: u8 = 0;
+const MEMORY_KIND_VALUE: u8 = 1;
+
+/// An error that can occur when loading objects from a heap file.
+#[derive(Debug, thiserror::Error)]
+pub enum LoadHeapError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("the file is not a heap file")]
+    InvalidMagic,
+    #[error("heap files of version {0} are not supported")]
+    UnsupportedVersion(u32),
+    #[error("the primitive type '{0}' is unknown")]
+    UnknownPrimitive(String),
+    #[error("the heap file is corrupt")]
+    Corrupt,
+    #[error(transparent)]
+    HeapLimitExceeded(#[from] HeapLimitExceeded),
+}
+
+impl<O> MarkSweep<O>
+where
+    O: Observer<Event = Event>,
+{
+    /// Writes all objects that are reachable from `roots` to `writer`.
+    ///
+    /// Objects are stored together with descriptions of their types, which
+    /// refer to fields by name. This enables [`MarkSweep::load_heap`] to load
+    /// the objects after their types have changed.
+    pub fn save_heap(&self, roots: &[GcPtr], writer: impl io::Write) -> io::Result<()> {
+        let _heap = self.heap.read();
+
+        let mut objects = Vec::new();
+        let mut object_indices = HashMap::new();
+        let mut queue = VecDeque::new();
+        for root in roots.iter() {
+            if !object_indices.contains_key(root) {
+                object_indices.insert(*root, objects.len());
+                objects.push(*root);
+                queue.push_back(*root);
+            }
+        }
+
+        while let Some(object) = queue.pop_front() {
+            // Safety: we hold a lock on the heap, so the object cannot be collected or mapped
+            let ty = unsafe { &object_info(object).as_ref().ty };
+            for reference in ty.trace(object) {
+                if !reference.as_ptr().is_null() && !object_indices.contains_key(&reference) {
+                    object_indices.insert(reference, objects.len());
+                    objects.push(reference);
+                    queue.push_back(reference);
+                }
+            }
+        }
+
+        let mut types = TypeIndices::default();
+        for object in objects.iter() {
+            types.insert(unsafe { &object_info(*object).as_ref().ty })?;
+        }
+
+        let mut writer = io::BufWriter::new(writer);
+        writer.write_all(&MAGIC)?;
+        write_u32(&mut writer, VERSION)?;
+
+        write_len(&mut writer, types.types.len())?;
+        for ty in types.types.iter() {
+            match ty.kind() {
+                TypeKind::Primitive(_) => {
+                    writer.write_all(&[TYPE_PRIMITIVE])?;
+                    write_str(&mut writer, ty.name())?;
+                }
+                TypeKind::Struct(s) => {
+                    writer.write_all(&[TYPE_STRUCT])?;
+                    write_str(&mut writer, ty.name())?;
+                    writer.write_all(&[if s.is_gc_struct() {
+                        MEMORY_KIND_GC
+                    } else {
+                        MEMORY_KIND_VALUE
+                    }])?;
+                    write_len(&mut writer, s.fields().len())?;
+                    for field in s.fields().iter() {
+                        write_str(&mut writer, field.name())?;
+                        write_len(&mut writer, types.indices[&field.ty()])?;
+                    }
+                }
+                TypeKind::Array(array) => {
+                    writer.write_all(&[TYPE_ARRAY])?;
+                    write_len(&mut writer, types.indices[&array.element_type()])?;
+                }
+                TypeKind::Pointer(_) => unreachable!("pointers are rejected by `TypeIndices`"),
+            }
+        }
+
+        write_len(&mut writer, objects.len())?;
+        for object in objects.iter() {
+            let ty = unsafe { &object_info(*object).as_ref().ty };
+            write_len(&mut writer, types.indices[ty])?;
+            let length = if ty.is_array() {
+                ArrayHandle {
+                    obj: object_info(*object),
+                }
+                .length()
+            } else {
+                0
+            };
+            writer.write_all(&(length as u64).to_le_bytes())?;
+        }
+
+        let mut object_writer = ObjectWriter {
+            writer: &mut writer,
+            object_indices: &object_indices,
+        };
+        for object in objects.iter() {
+            let object_info = object_info(*object);
+            let ty = unsafe { &object_info.as_ref().ty };
+            match ty.kind() {
+                TypeKind::Struct(_) => {
+                    object_writer.write_fields(ty, unsafe { object_info.as_ref().data.ptr })?;
+                }
+                TypeKind::Array(array) => {
+                    let element_ty = array.element_type();
+                    for element in (ArrayHandle { obj: object_info }).elements() {
+                        object_writer.write_value(&element_ty, element)?;
+                    }
+                }
+                TypeKind::Primitive(_) | TypeKind::Pointer(_) => {
+                    unreachable!("only structs and arrays are allocated")
+                }
+            }
+        }
+
+        write_len(&mut writer, roots.len())?;
+        for root in roots.iter() {
+            write_len(&mut writer, object_indices[root])?;
+        }
+
+        writer.flush()
+    }
+
+    /// Loads the objects that were saved with [`MarkSweep::save_heap`] from
+    /// `reader`, and returns the root objects in the order in which they were
+    /// saved.
+    ///
+    /// Types are looked up by name in `type_table`. Objects of structs that
+    /// have changed since the file was saved are mapped to the current version
+    /// of the struct, in the same way as during a hot reload. Objects of
+    /// structs that no longer exist keep the type that is described in the
+    /// file.
+    ///
+    /// Loaded objects count towards the hard heap limit. If the objects don't
+    /// fit, [`LoadHeapError::HeapLimitExceeded`] is returned and none of the
+    /// objects remain rooted.
+    ///
+    /// The returned roots are rooted, so they must be unrooted before they can
+    /// be collected.
+    pub fn load_heap(
+        &self,
+        type_table: &TypeTable,
+        reader: impl io::Read,
+    ) -> Result<Vec<GcPtr>, LoadHeapError> {
+        let mut objects = Vec::new();
+        let result = self.load_objects(type_table, &mut io::BufReader::new(reader), &mut objects);
+
+        // Objects are rooted while they are loaded. Afterwards, only the roots remain rooted.
+        if let Ok(roots) = &result {
+            for root in roots.iter() {
+                self.root(*root);
+            }
+        }
+        for object in objects {
+            self.unroot(object);
+        }
+
+        result
+    }
+
+    /// Allocates and initializes all objects in a heap file. Every allocated
+    /// object is rooted and added to `objects`.
+    fn load_objects(
+        &self,
+        type_table: &TypeTable,
+        reader: &mut impl io::Read,
+        objects: &mut Vec<GcPtr>,
+    ) -> Result<Vec<GcPtr>, LoadHeapError> {
+        let mut magic = [0u8; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(LoadHeapError::InvalidMagic);
+        }
+        let version = read_u32(reader)?;
+        if version != VERSION {
+            return Err(LoadHeapError::UnsupportedVersion(version));
+        }
+
+        let type_count = read_len(reader)?;
+        let mut descriptions = Vec::new();
+        for _ in 0..type_count {
+            let description = match read_u8(reader)? {
+                TYPE_PRIMITIVE => TypeDescription::Primitive(read_string(reader)?),
+                TYPE_STRUCT => {
+                    let name = read_string(reader)?;
+                    let memory_kind = match read_u8(reader)? {
+                        MEMORY_KIND_GC => abi::StructMemoryKind::Gc,
+                        MEMORY_KIND_VALUE => abi::StructMemoryKind::Value,
+                        _ => return Err(LoadHeapError::Corrupt),
+                    };
+                    let field_count = read_len(reader)?;
+                    let fields = (0..field_count)
+                        .map(|_| Ok((read_string(reader)?, read_index(reader, type_count)?)))
+                        .collect::<Result<_, LoadHeapError>>()?;
+                    TypeDescription::Struct {
+                        name,
+                        memory_kind,
+                        fields,
+                    }
+                }
+                TYPE_ARRAY => TypeDescription::Array(read_index(reader, type_count)?),
+                _ => return Err(LoadHeapError::Corrupt),
+            };
+            descriptions.push(description);
+        }
+
+        let mut resolver = TypeResolver {
+            descriptions: &descriptions,
+            type_table,
+            types: vec![None; type_count],
+            resolving: vec![false; type_count],
+        };
+
+        let object_count = read_len(reader)?;
+        let mut object_types = Vec::new();
+        for _ in 0..object_count {
+            let description = read_index(reader, type_count)?;
+            let length =
+                usize::try_from(read_u64(reader)?).map_err(|_error| LoadHeapError::Corrupt)?;
+            let ty = resolver.resolve(description)?;
+            let handle = match &descriptions[description] {
+                TypeDescription::Struct {
+                    memory_kind: abi::StructMemoryKind::Gc,
+                    ..
+                } if length == 0 => self.try_alloc(&ty)?,
+                TypeDescription::Array(_) => {
+                    // The length must not overflow the size of the array
+                    try_data_layout(&ty, length).map_err(|_error| LoadHeapError::Corrupt)?;
+                    self.try_alloc_array(&ty, length)?.as_raw()
+                }
+                _ => return Err(LoadHeapError::Corrupt),
+            };
+            self.root(handle);
+            objects.push(handle);
+            object_types.push((description, ty));
+        }
+
+        let mut object_reader = ObjectReader {
+            reader: &mut *reader,
+            descriptions: &descriptions,
+            objects,
+            object_types: &object_types,
+        };
+        for (index, (description, ty)) in object_types.iter().enumerate() {
+            let object_info = object_info(object_reader.objects[index]);
+            match &descriptions[*description] {
+                TypeDescription::Struct { .. } => {
+                    object_reader
+                        .read_fields(*description, ty, unsafe { object_info.as_ref().data.ptr })?;
+                }
+                TypeDescription::Array(element_description) => {
+                    let element_ty = ty.as_array().expect("must be an array").element_type();
+                    for element in (ArrayHandle { obj: object_info }).elements() {
+                        object_reader.read_value(*element_description, &element_ty, element)?;
+                    }
+                }
+                TypeDescription::Primitive(_) => {
+                    unreachable!("only structs and arrays are allocated")
+                }
+            }
+        }
+
+        let root_count = read_len(reader)?;
+        let roots = (0..root_count)
+            .map(|_| Ok(objects[read_index(reader, object_count)?]))
+            .collect::<Result<Vec<_>, LoadHeapError>>()?;
+
+        // Convert objects of structs that have changed to the current version of the struct
+        let (old_types, new_types): (Vec<_>, Vec<_>) = resolver
+            .types
+            .iter()
+            .flatten()
+            .filter(|ty| {
+                ty.is_struct() && type_table.find_type_info_by_name(ty.name()).as_ref() != Some(*ty)
+            })
+            .map(|ty| (ty.clone(), type_table.find_type_info_by_name(ty.name())))
+            .unzip();
+        if !old_types.is_empty() {
+            let new_types = new_types.into_iter().flatten().collect::<Vec<_>>();
+            self.map_memory(Mapping::new(&old_types, &new_types));
+        }
+
+        Ok(roots)
+    }
+}
+
+/// Assigns an index to every type that is used by the saved objects.
+#[derive(Default)]
+struct TypeIndices {
+    types: Vec<Type>,
+    indices: HashMap<Type, usize>,
+}
+
+impl TypeIndices {
+    /// Assigns an index to `ty` and the types of its fields or elements.
+    fn insert(&mut self, ty: &Type) -> io::Result<()> {
+        if self.indices.contains_key(ty) {
+            return Ok(());
+        }
+
+        self.indices.insert(ty.clone(), self.types.len());
+        self.types.push(ty.clone());
+        match ty.kind() {
+            TypeKind::Primitive(_) => {}
+            TypeKind::Struct(s) => {
+                for field in s.fields().iter() {
+                    self.insert(&field.ty())?;
+                }
+            }
+            TypeKind::Array(array) => self.insert(&array.element_type())?,
+            TypeKind::Pointer(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("cannot save a value of pointer type '{ty}'"),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the data of objects.
+struct ObjectWriter<'a, W: io::Write> {
+    writer: &'a mut W,
+    object_indices: &'a HashMap<GcPtr, usize>,
+}
+
+impl<'a, W: io::Write> ObjectWriter<'a, W> {
+    /// Writes the fields of the struct of type `ty` that is stored at `ptr`.
+    fn write_fields(&mut self, ty: &Type, ptr: NonNull<u8>) -> io::Result<()> {
+        for field in ty.as_struct().expect("must be a struct").fields().iter() {
+            let field_ptr = unsafe { NonNull::new_unchecked(ptr.as_ptr().add(field.offset())) };
+            self.write_value(&field.ty(), field_ptr)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the value of type `ty` that is stored at `ptr`.
+    fn write_value(&mut self, ty: &Type, ptr: NonNull<u8>) -> io::Result<()> {
+        match ty.kind() {
+            TypeKind::Primitive(_) => {
+                let bytes =
+                    unsafe { std::slice::from_raw_parts(ptr.as_ptr(), ty.value_layout().size()) };
+                self.writer.write_all(bytes)
+            }
+            TypeKind::Struct(s) if s.is_value_struct() => self.write_fields(ty, ptr),
+            TypeKind::Struct(_) | TypeKind::Array(_) => {
+                let reference = unsafe { *ptr.cast::<GcPtr>().as_ref() };
+                let index = if reference.as_ptr().is_null() {
+                    0
+                } else {
+                    self.object_indices[&reference] + 1
+                };
+                write_len(self.writer, index)
+            }
+            TypeKind::Pointer(_) => unreachable!("pointers are rejected by `TypeIndices`"),
+        }
+    }
+}
+
+/// The description of a type in a heap file.
+enum TypeDescription {
+    Primitive(String),
+    Struct {
+        name: String,
+        memory_kind: abi::StructMemoryKind,
+        fields: Vec<(String, usize)>,
+    },
+    Array(usize),
+}
+
+/// Finds or constructs the types that are described in a heap file.
+struct TypeResolver<'a> {
+    descriptions: &'a [TypeDescription],
+    type_table: &'a TypeTable,
+    types: Vec<Option<Type>>,
+
+    /// Whether the fields of a struct are currently being resolved
+    resolving: Vec<bool>,
+}
+
+impl<'a> TypeResolver<'a> {
+    /// Returns the type that is described at `index`. If a struct is described
+    /// that differs from the struct with the same name in the type table, a new
+    /// struct type is constructed.
+    fn resolve(&mut self, index: usize) -> Result<Type, LoadHeapError> {
+        if let Some(ty) = &self.types[index] {
+            return Ok(ty.clone());
+        }
+
+        let ty = match &self.descriptions[index] {
+            TypeDescription::Primitive(name) => self
+                .type_table
+                .find_type_info_by_name(name)
+                .filter(Type::is_primitive)
+                .ok_or_else(|| LoadHeapError::UnknownPrimitive(name.clone()))?,
+            TypeDescription::Struct {
+                name,
+                memory_kind,
+                fields,
+            } => {
+                // Value structs cannot contain themselves
+                if self.resolving[index] {
+                    return Err(LoadHeapError::Corrupt);
+                }
+
+                self.resolving[index] = true;
+                let fields = fields
+                    .iter()
+                    .map(|(name, description)| {
+                        Ok((name.clone(), self.resolve_field(*description)?))
+                    })
+                    .collect::<Result<Vec<_>, LoadHeapError>>()?;
+                self.resolving[index] = false;
+
+                let current = self.type_table.find_type_info_by_name(name);
+                match current {
+                    Some(current) if is_same_struct(&current, *memory_kind, &fields) => current,
+                    _ => StructTypeBuilder::new(name.clone())
+                        .set_memory_kind(*memory_kind)
+                        .add_fields(fields)
+                        .finish(),
+                }
+            }
+            TypeDescription::Array(element) => self.resolve_field(*element)?.array_type(),
+        };
+
+        self.types[index] = Some(ty.clone());
+        Ok(ty)
+    }
+
+    /// Returns the type that is described at `index`, for use as the type of a
+    /// field or array element.
+    ///
+    /// The type of a reference to a garbage collected struct does not affect
+    /// the layout of its parent, so the current version of the struct is used
+    /// if it still is a garbage collected struct. This also prevents cycles when
+    /// structs reference each other.
+    fn resolve_field(&mut self, index: usize) -> Result<Type, LoadHeapError> {
+        if let TypeDescription::Struct {
+            name,
+            memory_kind: abi::StructMemoryKind::Gc,
+            ..
+        } = &self.descriptions[index]
+        {
+            let current = self
+                .type_table
+                .find_type_info_by_name(name)
+                .filter(|ty| ty.as_struct().map_or(false, |s| s.is_gc_struct()));
+            if let Some(current) = current {
+                return Ok(current);
+            } else if self.resolving[index] {
+                return Ok(StructTypeBuilder::new(name.clone()).finish());
+            }
+        }
+
+        self.resolve(index)
+    }
+}
+
+/// Returns whether `ty` is a struct with the specified memory kind and fields.
+fn is_same_struct(
+    ty: &Type,
+    memory_kind: abi::StructMemoryKind,
+    fields: &[(String, Type)],
+) -> bool {
+    let Some(s) = ty.as_struct() else {
+        return false;
+    };
+
+    s.memory_kind() == memory_kind
+        && s.fields().len() == fields.len()
+        && s.fields()
+            .iter()
+            .zip(fields.iter())
+            .all(|(field, (name, ty))| field.name() == name && field.ty() == *ty)
+}
+
+/// Reads the data of objects.
+struct ObjectReader<'a, R: io::Read> {
+    reader: &'a mut R,
+    descriptions: &'a [TypeDescription],
+    objects: &'a [GcPtr],
+    object_types: &'a [(usize, Type)],
+}
+
+impl<'a, R: io::Read> ObjectReader<'a, R> {
+    /// Reads the fields of the struct described at `description` into `ptr`,
+    /// which stores a struct of type `ty`.
+    fn read_fields(
+        &mut self,
+        description: usize,
+        ty: &Type,
+        ptr: NonNull<u8>,
+    ) -> Result<(), LoadHeapError> {
+        let TypeDescription::Struct {
+            fields: descriptions,
+            ..
+        } = &self.descriptions[description]
+        else {
+            unreachable!("must be a struct")
+        };
+
+        let fields = ty.as_struct().expect("must be a struct").fields();
+        for ((_, description), field) in descriptions.iter().zip(fields.iter()) {
+            let field_ptr = unsafe { NonNull::new_unchecked(ptr.as_ptr().add(field.offset())) };
+            self.read_value(*description, &field.ty(), field_ptr)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the value described at `description` into `ptr`, which stores a
+    /// value of type `ty`.
+    fn read_value(
+        &mut self,
+        description: usize,
+        ty: &Type,
+        ptr: NonNull<u8>,
+    ) -> Result<(), LoadHeapError> {
+        match &self.descriptions[description] {
+            TypeDescription::Primitive(_) => {
+                let bytes = unsafe {
+                    std::slice::from_raw_parts_mut(ptr.as_ptr(), ty.value_layout().size())
+                };
+                self.reader.read_exact(bytes)?;
+            }
+            TypeDescription::Struct {
+                memory_kind: abi::StructMemoryKind::Value,
+                ..
+            } => self.read_fields(description, ty, ptr)?,
+            TypeDescription::Struct { .. } | TypeDescription::Array(_) => {
+                let index = read_index(self.reader, self.objects.len() + 1)?;
+                if index > 0 {
+                    // The referenced object must be of the type that the field expects
+                    if self.object_types[index - 1].0 != description {
+                        return Err(LoadHeapError::Corrupt);
+                    }
+                    unsafe { *ptr.cast::<GcPtr>().as_mut() = self.objects[index - 1] };
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_u32(writer: &mut impl io::Write, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+/// Writes a length or index, which are stored as 32-bit integers.
+fn write_len(writer: &mut impl io::Write, len: usize) -> io::Result<()> {
+    let len = u32::try_from(len).map_err(|_error| {
+        io::Error::new(io::ErrorKind::InvalidInput, "too many objects to save")
+    })?;
+    write_u32(writer, len)
+}
+
+fn write_str(writer: &mut impl io::Write, value: &str) -> io::Result<()> {
+    write_len(writer, value.len())?;
+    writer.write_all(value.as_bytes())
+}
+
+fn read_u8(reader: &mut impl io::Read) -> io::Result<u8> {
+    let mut bytes = [0u8; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u32(reader: &mut impl io::Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl io::Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_len(reader: &mut impl io::Read) -> io::Result<usize> {
+    read_u32(reader).map(|len| len as usize)
+}
+
+/// Reads an index that must be smaller than `count`.
+fn read_index(reader: &mut impl io::Read, count: usize) -> Result<usize, LoadHeapError> {
+    let index = read_len(reader)?;
+    if index < count {
+        Ok(index)
+    } else {
+        Err(LoadHeapError::Corrupt)
+    }
+}
+
+fn read_string(reader: &mut impl io::Read) -> Result<String, LoadHeapError> {
+    let len = read_len(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    String::from_utf8(bytes).map_err(|_error| LoadHeapError::Corrupt)
+}
@@ -0,0 +1,105 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{gc::GcPtr, r#type::Type};
+
+/// A snapshot of all objects that were allocated by a garbage collector at a
+/// certain point in time. This includes objects that are no longer reachable,
+/// but that have not been collected yet.
+#[derive(Debug, Clone, Default)]
+pub struct HeapSnapshot {
+    objects: Vec<ObjectSnapshot>,
+
+    /// The index of every object in `objects`
+    indices: HashMap<GcPtr, usize>,
+}
+
+/// The state of a single object in a [`HeapSnapshot`].
+#[derive(Debug, Clone)]
+pub struct ObjectSnapshot {
+    /// The handle of the object
+    pub handle: GcPtr,
+
+    /// The type of the object
+    pub ty: Type,
+
+    /// The number of bytes allocated for the object
+    pub size: usize,
+
+    /// The number of times the object is rooted
+    pub roots: u32,
+
+    /// The objects that are referenced by this object
+    pub references: Vec<GcPtr>,
+}
+
+impl HeapSnapshot {
+    /// Constructs a snapshot from the specified objects.
+    pub fn new(objects: Vec<ObjectSnapshot>) -> Self {
+        let indices = objects
+            .iter()
+            .enumerate()
+            .map(|(index, object)| (object.handle, index))
+            .collect();
+
+        Self { objects, indices }
+    }
+
+    /// Returns all objects in the snapshot.
+    pub fn objects(&self) -> &[ObjectSnapshot] {
+        &self.objects
+    }
+
+    /// Returns the object with the specified handle, if it is part of the
+    /// snapshot.
+    pub fn get(&self, handle: GcPtr) -> Option<&ObjectSnapshot> {
+        self.indices.get(&handle).map(|index| &self.objects[*index])
+    }
+
+    /// Returns all objects that are rooted.
+    pub fn roots(&self) -> impl Iterator<Item = &ObjectSnapshot> {
+        self.objects.iter().filter(|object| object.roots > 0)
+    }
+
+    /// Returns the shortest chain of references from a rooted object to the
+    /// object with the specified handle, which explains why the object is kept
+    /// alive. The chain starts with the rooted object and ends with the object
+    /// itself. Returns `None` if the object is not reachable from any root.
+    pub fn path_to_root(&self, handle: GcPtr) -> Option<Vec<GcPtr>> {
+        let target = *self.indices.get(&handle)?;
+
+        // Search breadth-first from all roots at once, remembering from which object every object
+        // was first reached.
+        let mut reached_from = vec![None; self.objects.len()];
+        let mut queue = VecDeque::new();
+        for (index, object) in self.objects.iter().enumerate() {
+            if object.roots > 0 {
+                reached_from[index] = Some(index);
+                queue.push_back(index);
+            }
+        }
+
+        while let Some(index) = queue.pop_front() {
+            if index == target {
+                let mut path = vec![self.objects[index].handle];
+                let mut current = index;
+                while let Some(previous) = reached_from[current].filter(|prev| *prev != current) {
+                    path.push(self.objects[previous].handle);
+                    current = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for reference in self.objects[index].references.iter() {
+                if let Some(&next) = self.indices.get(reference) {
+                    if reached_from[next].is_none() {
+                        reached_from[next] = Some(index);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -4,7 +4,7 @@ use itertools::Itertools;
 use mun_abi::Guid;
 
 use crate::{
-    diff::{compute_struct_diff, FieldDiff, StructDiff},
+    diff::{compute_struct_diff_with_renames, FieldDiff, FieldRenames, StructDiff},
     gc::GcPtr,
     r#type::Type,
     ArrayType, Field, TypeKind,
@@ -20,6 +20,9 @@ pub struct Mapping {
     pub struct_mappings: HashMap<Type, StructMapping>,
     /// The types that didn't change
     pub identical: Vec<(Type, Type)>,
+    /// The mappings that preserve the old contents of changed structs for
+    /// user-defined migrations, by old struct type
+    pub migrations: HashMap<Type, StructMapping>,
 }
 
 /// The struct mapping needed to convert an old into a new struct of unique and
@@ -83,9 +86,15 @@ pub enum Action {
 }
 
 impl Mapping {
-    #[allow(clippy::mutable_key_type)]
     pub fn new(old: &[Type], new: &[Type]) -> Self {
-        let diff = compute_struct_diff(old, new);
+        Self::with_renames(old, new, &FieldRenames::default())
+    }
+
+    /// Constructs the mapping from `old` to `new` types, matching the fields of
+    /// changed structs using the declared `renames`.
+    #[allow(clippy::mutable_key_type)]
+    pub fn with_renames(old: &[Type], new: &[Type], renames: &FieldRenames) -> Self {
+        let diff = compute_struct_diff_with_renames(old, new, renames);
 
         let mut conversions = HashMap::new();
         let mut deletions = HashSet::new();
@@ -167,10 +176,92 @@ impl Mapping {
             deletions,
             struct_mappings: conversions,
             identical,
+            migrations: HashMap::new(),
+        }
+    }
+
+    /// Preserves the old contents of all objects whose struct type changed into
+    /// `new_ty`. Before the objects are mapped, a copy of each object is
+    /// converted into `layout_ty`, which describes the old layout of the
+    /// struct. Fields are copied by name, fields that don't exist in the old
+    /// struct are zero-initialized.
+    ///
+    /// Returns false if no struct changed into `new_ty`.
+    pub fn add_migration(&mut self, new_ty: &Type, layout_ty: &Type) -> bool {
+        let old_ty = self
+            .struct_mappings
+            .iter()
+            .find(|(_, conversion)| conversion.new_ty == *new_ty)
+            .map(|(old_ty, _)| old_ty.clone());
+
+        if let Some(old_ty) = old_ty {
+            let migration = migration_mapping(&old_ty, layout_ty);
+            self.migrations.insert(old_ty, migration);
+            true
+        } else {
+            false
         }
     }
 }
 
+/// Calculates the mapping of an old struct into a struct that describes its
+/// old layout, by matching fields by name.
+fn migration_mapping(old_ty: &Type, layout_ty: &Type) -> StructMapping {
+    let old_fields = old_ty
+        .as_struct()
+        .into_iter()
+        .flat_map(|s| s.fields().iter())
+        .collect_vec();
+
+    let field_mapping = layout_ty
+        .as_struct()
+        .into_iter()
+        .flat_map(|s| s.fields().iter())
+        .map(|new_field| {
+            let new_ty = new_field.ty();
+            let action = match old_fields
+                .iter()
+                .find(|old_field| old_field.name() == new_field.name())
+            {
+                Some(old_field) => match resolve_edit(&old_field.ty(), &new_ty, old_field.offset())
+                {
+                    // Arrays are mapped in-place, so the array is shared with the copy. It
+                    // is mapped along with the other arrays.
+                    Action::ArrayMap { old_offset, .. } => Action::Copy {
+                        old_offset,
+                        size: std::mem::size_of::<GcPtr>(),
+                    },
+                    action => action,
+                },
+                None => insert_action(&new_ty),
+            };
+
+            FieldMapping {
+                new_ty,
+                new_offset: new_field.offset(),
+                action,
+            }
+        })
+        .collect();
+
+    StructMapping {
+        field_mapping,
+        new_ty: layout_ty.clone(),
+    }
+}
+
+/// Returns the action that initializes a newly inserted field of type
+/// `new_type`.
+fn insert_action(new_type: &Type) -> Action {
+    if new_type.is_struct() && !new_type.is_value_type() {
+        Action::StructAlloc
+    } else if new_type.is_array() {
+        Action::ArrayAlloc
+    } else {
+        Action::ZeroInitialize
+    }
+}
+
 /// Given a set of `old_fields` of type `T` and their corresponding `diff`,
 /// calculates the mapping `new_index -> Option<FieldMappingDesc>` for each new
 /// field.
@@ -233,16 +324,7 @@ pub unsafe fn field_mapping(old_ty: &Type, new_ty: &Type, diff: &[FieldDiff]) ->
                     .expect("The old field must exist.");
                 (*new_index, resolve_edit(old_type, new_type, old_offset))
             }),
-            FieldDiff::Insert { index, new_type } => Some((
-                *index,
-                if new_type.is_struct() && !new_type.is_value_type() {
-                    Action::StructAlloc
-                } else if new_type.is_array() {
-                    Action::ArrayAlloc
-                } else {
-                    Action::ZeroInitialize
-                },
-            )),
+            FieldDiff::Insert { index, new_type } => Some((*index, insert_action(new_type))),
             FieldDiff::Move {
                 ty,
                 old_index,
@@ -512,12 +594,28 @@ fn resolve_array_to_array_edit(
     }
 }
 
+/// The objects that are affected by mapping memory.
+#[derive(Debug, Default)]
+pub struct MappedMemory {
+    /// The objects of types that were deleted. The corresponding types have to
+    /// remain in-memory until the objects have been deallocated.
+    pub deleted: Vec<GcPtr>,
+    /// The objects that have a migration
+    pub migrated: Vec<MigratedObject>,
+}
+
+/// An object whose struct type changed and that has a migration.
+#[derive(Debug, Clone, Copy)]
+pub struct MigratedObject {
+    /// The mapped object
+    pub object: GcPtr,
+    /// A rooted copy of the object's contents before it was mapped, in the
+    /// layout of the migration. It has to be unrooted once the migration ran.
+    pub old_contents: GcPtr,
+}
+
 /// A trait used to map allocated memory using type differences.
 pub trait MemoryMapper {
     /// Maps its allocated memory using the provided `mapping`.
-    ///
-    /// A `Vec<GcPtr>` is returned containing all objects of types that were
-    /// deleted. The corresponding types have to remain in-memory until the
-    /// objects have been deallocated.
-    fn map_memory(&self, mapping: Mapping) -> Vec<GcPtr>;
+    fn map_memory(&self, mapping: Mapping) -> MappedMemory;
 }
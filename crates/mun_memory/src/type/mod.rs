@@ -1132,15 +1132,9 @@ impl StructTypeBuilder {
 
     /// Adds a field to the struct
     pub fn add_field(mut self, name: impl Into<String>, ty: Type) -> Self {
-        let field_layout = if ty.is_value_type() {
-            ty.value_layout()
-        } else {
-            Layout::new::<std::ffi::c_void>()
-        };
-
         let (new_layout, offset) = self
             .layout
-            .extend(field_layout)
+            .extend(ty.reference_layout())
             .expect("cannot extend struct layout");
         self.fields.push((name.into(), ty, offset));
         self.layout = new_layout;
@@ -1172,9 +1166,10 @@ impl StructTypeBuilder {
             abi::Guid::from_str(&guid_string)
         };
 
+        // Like compiled structs, the size of the struct is a multiple of its alignment
         Type::new_struct(
             self.name,
-            self.layout,
+            self.layout.pad_to_align(),
             guid,
             self.fields
                 .into_iter()
@@ -1,6 +1,9 @@
 use mun_abi::StructMemoryKind;
 use mun_memory::{
-    diff::{compute_struct_diff, FieldDiff, FieldEditKind, StructDiff},
+    diff::{
+        compute_struct_diff, compute_struct_diff_with_renames, FieldDiff, FieldEditKind,
+        FieldRenames, StructDiff,
+    },
     type_table::TypeTable,
     HasStaticType, StructTypeBuilder, Type,
 };
@@ -528,3 +531,107 @@ fn rename_field2() {
     );
     assert_eq_struct(&apply_diff(old, diff), &[struct2]);
 }
+
+#[test]
+fn declared_rename_with_changed_type() {
+    let type_table = TypeTable::default();
+
+    let struct1 = fake_struct!(type_table, "struct1",
+        "hp" => f32, "mana" => f32
+    );
+    let struct2 = fake_struct!(type_table, "struct1",
+        "mana" => f32, "health" => f64
+    );
+
+    let old = &[struct1.clone()];
+    let new = &[struct2.clone()];
+
+    // Without a declared rename, the field is deleted and a new one is inserted
+    let diff = compute_struct_diff(old, new);
+    assert_eq!(
+        diff,
+        vec![StructDiff::Edit {
+            diff: vec![
+                FieldDiff::Delete { index: 0 },
+                FieldDiff::Insert {
+                    index: 1,
+                    new_type: f64::type_info().clone()
+                }
+            ],
+            old_index: 0,
+            new_index: 0,
+            old_ty: struct1.clone(),
+            new_ty: struct2.clone()
+        }]
+    );
+
+    let mut renames = FieldRenames::default();
+    renames.insert("struct1", "hp", "health");
+
+    let diff = compute_struct_diff_with_renames(old, new, &renames);
+    assert_eq!(
+        diff,
+        vec![StructDiff::Edit {
+            diff: vec![FieldDiff::Edit {
+                old_type: f32::type_info().clone(),
+                new_type: f64::type_info().clone(),
+                old_index: Some(0),
+                new_index: 1,
+                kind: FieldEditKind::RenamedField,
+            }],
+            old_index: 0,
+            new_index: 0,
+            old_ty: struct1,
+            new_ty: struct2.clone()
+        }]
+    );
+    assert_eq_struct(&apply_diff(old, diff), &[struct2]);
+}
+
+#[test]
+fn declared_rename_takes_precedence() {
+    let type_table = TypeTable::default();
+
+    let struct1 = fake_struct!(type_table, "struct1",
+        "a" => i64, "b" => i64
+    );
+    let struct2 = fake_struct!(type_table, "struct1",
+        "c" => i64, "d" => i64
+    );
+
+    let old = &[struct1.clone()];
+    let new = &[struct2.clone()];
+
+    // The closest field of the same type would be `c`
+    let mut renames = FieldRenames::default();
+    renames.insert("struct1", "a", "d");
+    renames.insert("struct1", "b", "c");
+
+    let diff = compute_struct_diff_with_renames(old, new, &renames);
+    assert_eq!(
+        diff,
+        vec![StructDiff::Edit {
+            diff: vec![
+                FieldDiff::Edit {
+                    old_type: i64::type_info().clone(),
+                    new_type: i64::type_info().clone(),
+                    old_index: Some(0),
+                    new_index: 1,
+                    kind: FieldEditKind::RenamedField,
+                },
+                FieldDiff::Edit {
+                    old_type: i64::type_info().clone(),
+                    new_type: i64::type_info().clone(),
+                    old_index: Some(1),
+                    new_index: 0,
+                    kind: FieldEditKind::RenamedField,
+                }
+            ],
+            old_index: 0,
+            new_index: 0,
+            old_ty: struct1,
+            new_ty: struct2.clone()
+        }]
+    );
+    assert_eq_struct(&apply_diff(old, diff), &[struct2]);
+}
@@ -90,7 +90,11 @@ fn apply_struct_mapping(
     fn edit_field(kind: &FieldEditKind, old_field: &mut (String, Type), new_field: Field<'_>) {
         match *kind {
             FieldEditKind::ChangedTyped => old_field.1 = new_field.ty(),
-            FieldEditKind::RenamedField => old_field.0 = new_field.name().to_owned(),
+            // A declared rename can also change the type of the field
+            FieldEditKind::RenamedField => {
+                old_field.0 = new_field.name().to_owned();
+                old_field.1 = new_field.ty();
+            }
         }
     }
 
@@ -100,17 +104,33 @@ fn apply_struct_mapping(
         .map(|f| (f.name().to_owned(), f.ty()))
         .collect();
 
-    for diff in mapping.iter().rev() {
-        match diff {
-            FieldDiff::Delete { index } => {
-                fields.remove(*index);
-            }
+    // Fields that are edited and moved, by old index
+    let moved_edits: Vec<_> = mapping
+        .iter()
+        .filter_map(|diff| match diff {
+            FieldDiff::Edit {
+                old_index: Some(old_index),
+                ..
+            } => Some((*old_index, fields[*old_index].clone())),
+            _ => None,
+        })
+        .collect();
 
-            FieldDiff::Move { old_index, .. } => {
-                fields.remove(*old_index);
-            }
-            _ => (),
-        }
+    let mut removals: Vec<_> = mapping
+        .iter()
+        .filter_map(|diff| match diff {
+            FieldDiff::Delete { index } => Some(*index),
+            FieldDiff::Move { old_index, .. }
+            | FieldDiff::Edit {
+                old_index: Some(old_index),
+                ..
+            } => Some(*old_index),
+            _ => None,
+        })
+        .collect();
+    removals.sort_unstable();
+    for index in removals.into_iter().rev() {
+        fields.remove(index);
     }
 
     // Sort elements in ascending order of their insertion indices.
@@ -123,12 +143,16 @@ fn apply_struct_mapping(
                 kind,
                 ..
             } => {
-                let combined = fields.get_mut(*old_index).unwrap();
+                let (_, field) = moved_edits
+                    .iter()
+                    .find(|(index, _)| index == old_index)
+                    .unwrap();
+                let mut combined = field.clone();
                 let new_field = new_struct.fields().get(*new_index).unwrap();
 
-                edit_field(kind, combined, new_field);
+                edit_field(kind, &mut combined, new_field);
 
-                Some((*new_index, combined.clone()))
+                Some((*new_index, combined))
             }
             FieldDiff::Insert { index, .. } => {
                 let new_field = new_struct.fields().get(*index).unwrap();
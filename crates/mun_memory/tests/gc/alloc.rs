@@ -1,7 +1,10 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc, thread};
 
 use mun_memory::{
-    gc::{Event, GcRootPtr, GcRuntime, MarkSweep},
+    gc::{
+        Array, Event, GcRootPtr, GcRuntime, HasIndirectionPtr, HeapLimitExceeded, HeapLimits,
+        MarkSweep, TypeStats,
+    },
     HasStaticType,
 };
 
@@ -68,3 +71,245 @@ fn collect_rooted() {
     assert_eq!(events.next(), Some(Event::End));
     assert_eq!(events.next(), None);
 }
+
+#[test]
+fn hard_limit() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default().with_limits(HeapLimits {
+        soft_limit: None,
+        hard_limit: Some(16),
+    });
+
+    let first = runtime.try_alloc(i64::type_info()).unwrap();
+    let second = runtime.try_alloc(i64::type_info()).unwrap();
+    assert_eq!(runtime.try_alloc(i64::type_info()), Err(HeapLimitExceeded));
+    assert!(runtime
+        .try_alloc_array(&i64::type_info().array_type(), 0)
+        .is_err());
+    assert_eq!(runtime.stats().allocated_memory, 16);
+
+    // Collecting memory makes room for new allocations
+    runtime.collect();
+    let third = runtime.try_alloc(i64::type_info()).unwrap();
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(first)));
+    assert_eq!(events.next(), Some(Event::Allocation(second)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert!(matches!(events.next(), Some(Event::Deallocation(_))));
+    assert!(matches!(events.next(), Some(Event::Deallocation(_))));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Allocation(third)));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn hard_limit_concurrent() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default().with_limits(HeapLimits {
+        soft_limit: None,
+        hard_limit: Some(800),
+    });
+
+    // Concurrent allocations cannot exceed the limit together
+    let allocated: usize = thread::scope(|scope| {
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                scope.spawn(|| {
+                    (0..50)
+                        .filter(|_| runtime.try_alloc(i64::type_info()).is_ok())
+                        .count()
+                })
+            })
+            .collect();
+        threads.into_iter().map(|t| t.join().unwrap()).sum()
+    });
+    assert_eq!(allocated, 100);
+    assert_eq!(runtime.stats().allocated_memory, 800);
+}
+
+#[test]
+fn soft_limit() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default().with_limits(HeapLimits {
+        soft_limit: Some(8),
+        hard_limit: None,
+    });
+
+    runtime.alloc(i64::type_info());
+    assert!(!runtime.needs_collection());
+    runtime.alloc(i64::type_info());
+    assert!(runtime.needs_collection());
+
+    runtime.collect();
+    assert!(!runtime.needs_collection());
+}
+
+#[test]
+fn type_stats() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default();
+    runtime.alloc(i64::type_info());
+    runtime.alloc(i64::type_info());
+    runtime.alloc(f32::type_info());
+    let array = runtime.alloc_array(&i32::type_info().array_type(), 4);
+
+    let stats = runtime.type_stats();
+    assert_eq!(stats.len(), 3);
+    assert_eq!(
+        stats[i64::type_info()],
+        TypeStats {
+            object_count: 2,
+            allocated_memory: 16,
+        }
+    );
+    assert_eq!(
+        stats[f32::type_info()],
+        TypeStats {
+            object_count: 1,
+            allocated_memory: 4,
+        }
+    );
+    let array_stats = &stats[&runtime.ptr_type(array.as_raw())];
+    assert_eq!(array_stats.object_count, 1);
+    assert!(array_stats.allocated_memory >= 16);
+
+    let total: usize = stats.values().map(|stats| stats.allocated_memory).sum();
+    assert_eq!(total, runtime.stats().allocated_memory);
+
+    runtime.collect();
+    assert!(runtime.type_stats().is_empty());
+}
+
+#[test]
+fn reuse_memory() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default();
+
+    // Allocate enough objects to fill multiple pages
+    let handles = (0..1000)
+        .map(|_| runtime.alloc(i64::type_info()))
+        .collect::<HashSet<_>>();
+    assert_eq!(handles.len(), 1000);
+    assert_eq!(runtime.stats().allocated_memory, 8000);
+
+    // Newly allocated objects are zero-initialized
+    for mut handle in handles.iter().copied() {
+        assert_eq!(unsafe { *handle.deref::<i64>() }, 0);
+        unsafe { *handle.deref_mut::<i64>() = 5 };
+    }
+
+    runtime.collect();
+    assert_eq!(runtime.stats().allocated_memory, 0);
+
+    // Freed memory is reused and zero-initialized again
+    let reused = (0..1000)
+        .map(|_| runtime.alloc(i64::type_info()))
+        .collect::<HashSet<_>>();
+    assert_eq!(reused, handles);
+    for handle in reused.iter() {
+        assert_eq!(unsafe { *handle.deref::<i64>() }, 0);
+    }
+}
+
+#[test]
+fn large_array() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default();
+
+    // Arrays larger than the largest size class are allocated separately
+    let mut array = runtime.alloc_array(&i64::type_info().array_type(), 1000);
+    assert_eq!(array.capacity(), 1000);
+    assert!(runtime.stats().allocated_memory > 8000);
+    unsafe { array.set_length(1000) };
+    assert!(array
+        .elements()
+        .all(|element| unsafe { *element.cast::<i64>().as_ref() } == 0));
+
+    runtime.collect();
+    assert_eq!(runtime.stats().allocated_memory, 0);
+}
+
+#[test]
+fn alloc_concurrently() {
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+
+    let threads = (0..4)
+        .map(|_| {
+            let runtime = runtime.clone();
+            std::thread::spawn(move || {
+                (0..1000)
+                    .map(|_| GcRootPtr::new(&runtime, runtime.alloc(i64::type_info())).unroot())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let handles = threads
+        .into_iter()
+        .flat_map(|thread| thread.join().unwrap())
+        .collect::<HashSet<_>>();
+    assert_eq!(handles.len(), 4000);
+    assert_eq!(runtime.stats().allocated_memory, 32000);
+
+    runtime.collect();
+    assert_eq!(runtime.stats().allocated_memory, 0);
+}
+
+#[test]
+fn alloc_arrays_concurrently() {
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let array_type = i64::type_info().array_type();
+
+    let threads = (0..4usize)
+        .map(|thread_idx| {
+            let runtime = runtime.clone();
+            let array_type = array_type.clone();
+            std::thread::spawn(move || {
+                (0..100)
+                    .map(|i| {
+                        let length = thread_idx * 100 + i;
+                        let array = runtime.alloc_array(&array_type, length);
+                        assert_eq!(array.capacity(), length);
+                        array.as_raw()
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let handles = threads
+        .into_iter()
+        .flat_map(|thread| thread.join().unwrap())
+        .collect::<HashSet<_>>();
+    assert_eq!(handles.len(), 400);
+
+    runtime.collect();
+    assert_eq!(runtime.stats().allocated_memory, 0);
+}
+
+#[test]
+fn collect_step() {
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+    for _ in 0..1000 {
+        runtime.alloc(i64::type_info());
+    }
+
+    // Every step only processes a limited number of objects
+    let mut steps = 1;
+    while !runtime.collect_step(100) {
+        steps += 1;
+    }
+    assert!(steps > 1);
+    assert_eq!(runtime.stats().allocated_memory, 8);
+
+    // A step without limits completes a cycle
+    runtime.alloc(i64::type_info());
+    assert!(runtime.collect_step(usize::MAX));
+    assert_eq!(runtime.stats().allocated_memory, 8);
+
+    // A full collection aborts an incremental cycle
+    runtime.alloc(i64::type_info());
+    assert!(!runtime.collect_step(1));
+    assert!(runtime.is_collecting());
+    runtime.collect();
+    assert!(!runtime.is_collecting());
+    assert_eq!(runtime.stats().allocated_memory, 8);
+
+    drop(rooted);
+}
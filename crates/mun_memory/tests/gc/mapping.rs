@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use mun_memory::{
+    gc::{Event, GcPtr, GcRootPtr, GcRuntime, HasIndirectionPtr, MarkSweep},
+    mapping::{Mapping, MemoryMapper},
+    type_table::TypeTable,
+    Type,
+};
+
+use super::util::EventAggregator;
+use crate::fake_struct;
+
+/// Returns a pointer to the field `name` of the struct `obj`.
+unsafe fn field_ptr<T>(obj: GcPtr, ty: &Type, name: &str) -> *mut T {
+    let offset = ty
+        .as_struct()
+        .unwrap()
+        .fields()
+        .find_by_name(name)
+        .unwrap()
+        .offset();
+    obj.deref::<u8>().add(offset) as *mut T
+}
+
+#[test]
+fn map_memory_with_migration() {
+    let type_table = TypeTable::default();
+    let old_player_type_info = fake_struct!(type_table, "core::Player", "health" => f32);
+    let new_player_type_info =
+        fake_struct!(type_table, "core::Player", "current" => f32, "max" => f32);
+    let layout_type_info =
+        fake_struct!(type_table, "core::PlayerV1", "health" => f32, "armor" => i32);
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let player_ptr = GcRootPtr::new(&runtime, runtime.alloc(&old_player_type_info));
+    unsafe { *field_ptr::<f32>(player_ptr.handle(), &old_player_type_info, "health") = 10.0 };
+
+    let mut mapping = Mapping::new(
+        &[old_player_type_info],
+        &[new_player_type_info.clone(), layout_type_info.clone()],
+    );
+    assert!(mapping.add_migration(&new_player_type_info, &layout_type_info));
+    assert!(!mapping.add_migration(&layout_type_info, &layout_type_info));
+
+    let mapped = runtime.map_memory(mapping);
+    assert!(mapped.deleted.is_empty());
+    assert_eq!(mapped.migrated.len(), 1);
+
+    let migrated = mapped.migrated[0];
+    assert_eq!(migrated.object, player_ptr.handle());
+    assert_eq!(runtime.ptr_type(migrated.object), new_player_type_info);
+    assert_eq!(runtime.ptr_type(migrated.old_contents), layout_type_info);
+    unsafe {
+        // Fields are copied by name
+        assert_eq!(
+            *field_ptr::<f32>(migrated.old_contents, &layout_type_info, "health"),
+            10.0
+        );
+        assert_eq!(
+            *field_ptr::<i32>(migrated.old_contents, &layout_type_info, "armor"),
+            0
+        );
+    }
+
+    // The copy is rooted until the migration ran
+    runtime.collect();
+    assert_eq!(runtime.snapshot().roots().count(), 2);
+    runtime.unroot(migrated.old_contents);
+    runtime.collect();
+    assert_eq!(runtime.snapshot().objects().len(), 1);
+}
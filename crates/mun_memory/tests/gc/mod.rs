@@ -1,4 +1,7 @@
 mod alloc;
+mod mapping;
+mod save;
+mod snapshot;
 mod structs;
 #[macro_use]
 mod util;
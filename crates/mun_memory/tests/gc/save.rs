@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use mun_memory::{
+    gc::{
+        Array, Event, GcPtr, GcRootPtr, GcRuntime, HasIndirectionPtr, HeapLimits, LoadHeapError,
+        MarkSweep,
+    },
+    type_table::TypeTable,
+    StructTypeBuilder, Type,
+};
+
+use super::util::EventAggregator;
+use crate::fake_struct;
+
+/// Returns a pointer to the field `name` of the struct `obj`.
+unsafe fn field_ptr<T>(obj: GcPtr, ty: &Type, name: &str) -> *mut T {
+    let offset = ty
+        .as_struct()
+        .unwrap()
+        .fields()
+        .find_by_name(name)
+        .unwrap()
+        .offset();
+    obj.deref::<u8>().add(offset) as *mut T
+}
+
+#[test]
+fn save_and_load() {
+    let mut type_table = TypeTable::default();
+
+    let bar_type_info = fake_struct!(type_table, "core::Bar", "a" => i64);
+    type_table.insert_type(bar_type_info.clone());
+
+    let foo_type_info = fake_struct!(type_table, "core::Foo", "bar" => Bar);
+    type_table.insert_type(foo_type_info.clone());
+
+    let baz_type_info = StructTypeBuilder::new("core::Baz")
+        .add_field("foo", foo_type_info.clone())
+        .add_field("bars", bar_type_info.array_type())
+        .finish();
+    type_table.insert_type(baz_type_info.clone());
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let baz_ptr = GcRootPtr::new(&runtime, runtime.alloc(&baz_type_info));
+    let foo_handle = runtime.alloc(&foo_type_info);
+    let bar_handle = runtime.alloc(&bar_type_info);
+    let mut bars = runtime.alloc_array(&bar_type_info.array_type(), 2);
+    unsafe {
+        *field_ptr(baz_ptr.handle(), &baz_type_info, "foo") = foo_handle;
+        *field_ptr(baz_ptr.handle(), &baz_type_info, "bars") = bars.as_raw();
+        *field_ptr(foo_handle, &foo_type_info, "bar") = bar_handle;
+        *field_ptr::<i64>(bar_handle, &bar_type_info, "a") = 5;
+
+        // Both elements reference the same object
+        bars.set_length(2);
+        for element in bars.elements() {
+            *element.cast::<GcPtr>().as_mut() = bar_handle;
+        }
+    }
+
+    let mut file = Vec::new();
+    runtime.save_heap(&[baz_ptr.handle()], &mut file).unwrap();
+
+    let loaded_runtime = MarkSweep::<EventAggregator<Event>>::default();
+    let roots = loaded_runtime
+        .load_heap(&type_table, file.as_slice())
+        .unwrap();
+    assert_eq!(roots.len(), 1);
+    let baz_handle = roots[0];
+    assert_eq!(loaded_runtime.ptr_type(baz_handle), baz_type_info);
+
+    let foo_handle: GcPtr = unsafe { *field_ptr(baz_handle, &baz_type_info, "foo") };
+    assert_eq!(loaded_runtime.ptr_type(foo_handle), foo_type_info);
+    let bar_handle: GcPtr = unsafe { *field_ptr(foo_handle, &foo_type_info, "bar") };
+    assert_eq!(
+        unsafe { *field_ptr::<i64>(bar_handle, &bar_type_info, "a") },
+        5
+    );
+
+    let bars_handle: GcPtr = unsafe { *field_ptr(baz_handle, &baz_type_info, "bars") };
+    let bars = loaded_runtime.array(bars_handle).unwrap();
+    assert_eq!(bars.length(), 2);
+    for element in bars.elements() {
+        assert_eq!(unsafe { *element.cast::<GcPtr>().as_ref() }, bar_handle);
+    }
+
+    // Only the root remains rooted
+    assert_eq!(loaded_runtime.snapshot().roots().count(), 1);
+    loaded_runtime.unroot(baz_handle);
+    loaded_runtime.collect();
+    assert_eq!(loaded_runtime.stats().allocated_memory, 0);
+}
+
+#[test]
+fn load_changed_struct() {
+    let mut old_type_table = TypeTable::default();
+    let old_foo_type_info = fake_struct!(old_type_table, "core::Foo", "a" => i64, "b" => i32);
+    old_type_table.insert_type(old_foo_type_info.clone());
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let foo_ptr = GcRootPtr::new(&runtime, runtime.alloc(&old_foo_type_info));
+    unsafe {
+        *field_ptr::<i64>(foo_ptr.handle(), &old_foo_type_info, "a") = 3;
+        *field_ptr::<i32>(foo_ptr.handle(), &old_foo_type_info, "b") = -7;
+    }
+
+    let mut file = Vec::new();
+    runtime.save_heap(&[foo_ptr.handle()], &mut file).unwrap();
+
+    // Change the type of `b`, move `a`, and insert `c`
+    let mut new_type_table = TypeTable::default();
+    let new_foo_type_info =
+        fake_struct!(new_type_table, "core::Foo", "b" => i64, "c" => f64, "a" => i64);
+    new_type_table.insert_type(new_foo_type_info.clone());
+
+    let loaded_runtime = MarkSweep::<EventAggregator<Event>>::default();
+    let roots = loaded_runtime
+        .load_heap(&new_type_table, file.as_slice())
+        .unwrap();
+    let foo_handle = roots[0];
+    assert_eq!(loaded_runtime.ptr_type(foo_handle), new_foo_type_info);
+    unsafe {
+        assert_eq!(*field_ptr::<i64>(foo_handle, &new_foo_type_info, "a"), 3);
+        assert_eq!(*field_ptr::<i64>(foo_handle, &new_foo_type_info, "b"), -7);
+        assert_eq!(*field_ptr::<f64>(foo_handle, &new_foo_type_info, "c"), 0.0);
+    }
+}
+
+#[test]
+fn load_invalid_file() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default();
+    let type_table = TypeTable::default();
+
+    assert!(matches!(
+        runtime.load_heap(&type_table, b"NOTAHEAP".as_slice()),
+        Err(LoadHeapError::InvalidMagic)
+    ));
+    assert!(matches!(
+        runtime.load_heap(&type_table, b"MUNHEAP\0\x02\0\0\0".as_slice()),
+        Err(LoadHeapError::UnsupportedVersion(2))
+    ));
+    assert!(matches!(
+        runtime.load_heap(&type_table, b"MUNHEAP\0\x01\0\0\0\x01".as_slice()),
+        Err(LoadHeapError::Io(_))
+    ));
+}
+
+/// Returns a heap file that describes a single object with the specified array
+/// `length`, but not its data. The object is an array of `i64` if `is_array` is
+/// set, or a garbage collected struct without fields otherwise.
+fn single_object_file(is_array: bool, length: u64) -> Vec<u8> {
+    let mut file = b"MUNHEAP\0".to_vec();
+    file.extend(1u32.to_le_bytes());
+
+    // The table of types
+    file.extend(2u32.to_le_bytes());
+    file.push(0);
+    file.extend(9u32.to_le_bytes());
+    file.extend(b"core::i64");
+    if is_array {
+        file.push(2);
+        file.extend(0u32.to_le_bytes());
+    } else {
+        file.push(1);
+        file.extend(9u32.to_le_bytes());
+        file.extend(b"core::Foo");
+        file.push(0);
+        file.extend(0u32.to_le_bytes());
+    }
+
+    // The table of objects
+    file.extend(1u32.to_le_bytes());
+    file.extend(1u32.to_le_bytes());
+    file.extend(length.to_le_bytes());
+    file
+}
+
+#[test]
+fn load_invalid_length() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default();
+    let type_table = TypeTable::default();
+
+    // The size of the array overflows
+    assert!(matches!(
+        runtime.load_heap(
+            &type_table,
+            single_object_file(true, u64::MAX / 4).as_slice()
+        ),
+        Err(LoadHeapError::Corrupt)
+    ));
+
+    // Only arrays have a length
+    assert!(matches!(
+        runtime.load_heap(&type_table, single_object_file(false, 1).as_slice()),
+        Err(LoadHeapError::Corrupt)
+    ));
+
+    // Nothing remains allocated
+    runtime.collect();
+    assert_eq!(runtime.stats().allocated_memory, 0);
+}
+
+#[test]
+fn load_exceeds_hard_limit() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default().with_limits(HeapLimits {
+        soft_limit: None,
+        hard_limit: Some(1024),
+    });
+    let type_table = TypeTable::default();
+
+    // The array is never read, because it is too large
+    assert!(matches!(
+        runtime.load_heap(&type_table, single_object_file(true, 1 << 20).as_slice()),
+        Err(LoadHeapError::HeapLimitExceeded(_))
+    ));
+    assert_eq!(runtime.stats().allocated_memory, 0);
+}
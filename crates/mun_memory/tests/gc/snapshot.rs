@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use mun_memory::{
+    gc::{Array, Event, GcPtr, GcRootPtr, GcRuntime, HasIndirectionPtr, MarkSweep},
+    type_table::TypeTable,
+    StructTypeBuilder,
+};
+
+use super::util::EventAggregator;
+use crate::fake_struct;
+
+#[test]
+fn snapshot() {
+    let mut type_table = TypeTable::default();
+
+    let bar_type_info = fake_struct!(type_table, "core::Bar", "a" => i64);
+    type_table.insert_type(bar_type_info.clone());
+
+    let foo_type_info = fake_struct!(type_table, "core::Foo", "bar" => Bar);
+    let baz_type_info = StructTypeBuilder::new("core::Baz")
+        .add_field("bars", bar_type_info.array_type())
+        .finish();
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let mut foo_ptr = GcRootPtr::new(&runtime, runtime.alloc(&foo_type_info));
+    let mut baz_ptr = GcRootPtr::new(&runtime, runtime.alloc(&baz_type_info));
+    let bar_handle = runtime.alloc(&bar_type_info);
+    let element_handle = runtime.alloc(&bar_type_info);
+    let orphan_handle = runtime.alloc(&bar_type_info);
+    let mut array = runtime.alloc_array(&bar_type_info.array_type(), 1);
+    unsafe {
+        *foo_ptr.deref_mut::<GcPtr>() = bar_handle;
+        *baz_ptr.deref_mut::<GcPtr>() = array.as_raw();
+        array.set_length(1);
+        *array.elements().next().unwrap().cast::<GcPtr>().as_mut() = element_handle;
+    }
+
+    let snapshot = runtime.snapshot();
+    assert_eq!(snapshot.objects().len(), 6);
+
+    let foo_object = snapshot.get(foo_ptr.handle()).unwrap();
+    assert_eq!(foo_object.ty, foo_type_info);
+    assert_eq!(foo_object.roots, 1);
+    assert_eq!(foo_object.references, vec![bar_handle]);
+
+    let bar = snapshot.get(bar_handle).unwrap();
+    assert_eq!(bar.size, 8);
+    assert_eq!(bar.roots, 0);
+    assert!(bar.references.is_empty());
+
+    let baz_object = snapshot.get(baz_ptr.handle()).unwrap();
+    assert_eq!(baz_object.references, vec![array.as_raw()]);
+    let array_snapshot = snapshot.get(array.as_raw()).unwrap();
+    assert_eq!(array_snapshot.references, vec![element_handle]);
+
+    assert_eq!(snapshot.roots().count(), 2);
+
+    // Find out why objects are kept alive
+    assert_eq!(
+        snapshot.path_to_root(element_handle),
+        Some(vec![baz_ptr.handle(), array.as_raw(), element_handle])
+    );
+    assert_eq!(
+        snapshot.path_to_root(foo_ptr.handle()),
+        Some(vec![foo_ptr.handle()])
+    );
+    assert_eq!(snapshot.path_to_root(orphan_handle), None);
+
+    // Only the orphan is collected
+    runtime.collect();
+    let mut events = runtime.observer().take_all().into_iter().rev();
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Deallocation(orphan_handle)));
+    assert_eq!(events.next(), Some(Event::Start));
+}
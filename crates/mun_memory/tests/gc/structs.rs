@@ -120,3 +120,137 @@ fn trace_cycle() {
     assert_eq!(events.next(), Some(Event::End));
     assert_eq!(events.next(), None);
 }
+
+#[test]
+fn incremental_write_barrier() {
+    let mut type_table = TypeTable::default();
+
+    let bar_type_info = fake_struct!(type_table, "core::Bar", "a" => i64);
+    type_table.insert_type(bar_type_info.clone());
+
+    let foo_type_info = fake_struct!(type_table, "core::Foo", "bar" => Bar);
+    type_table.insert_type(foo_type_info.clone());
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let mut foo_ptr = GcRootPtr::new(&runtime, runtime.alloc(&foo_type_info));
+    let bar_handle = runtime.alloc(&bar_type_info);
+    let baz_handle = runtime.alloc(&bar_type_info);
+    unsafe {
+        (*foo_ptr.deref_mut::<FooObject>()).bar = bar_handle;
+    }
+
+    // Find the roots and trace foo, which marks bar
+    assert!(!runtime.collect_step(3));
+    assert!(!runtime.collect_step(1));
+    assert!(runtime.is_collecting());
+
+    // Replace bar by baz, which has not been marked yet
+    unsafe {
+        (*foo_ptr.deref_mut::<FooObject>()).bar = baz_handle;
+    }
+    runtime.write_barrier(baz_handle);
+
+    // Finish the cycle, baz must survive and bar is only collected by the next cycle
+    while !runtime.collect_step(1) {}
+    assert!(!runtime.is_collecting());
+    runtime.collect();
+
+    let foo_instance = foo_ptr.unroot();
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(foo_instance)));
+    assert_eq!(events.next(), Some(Event::Allocation(bar_handle)));
+    assert_eq!(events.next(), Some(Event::Allocation(baz_handle)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(bar_handle)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn incremental_write_barrier_on_container() {
+    let mut type_table = TypeTable::default();
+
+    let bar_type_info = fake_struct!(type_table, "core::Bar", "a" => i64);
+    type_table.insert_type(bar_type_info.clone());
+
+    let foo_type_info = fake_struct!(type_table, "core::Foo", "bar" => Bar);
+    type_table.insert_type(foo_type_info.clone());
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let mut foo_ptr = GcRootPtr::new(&runtime, runtime.alloc(&foo_type_info));
+    let bar_handle = runtime.alloc(&bar_type_info);
+    let baz_handle = runtime.alloc(&bar_type_info);
+    unsafe {
+        (*foo_ptr.deref_mut::<FooObject>()).bar = bar_handle;
+    }
+
+    // Find the roots and trace foo, which marks bar
+    assert!(!runtime.collect_step(3));
+    assert!(!runtime.collect_step(1));
+    assert!(runtime.is_collecting());
+
+    // Replace bar by baz, which has not been marked yet
+    unsafe {
+        (*foo_ptr.deref_mut::<FooObject>()).bar = baz_handle;
+    }
+    runtime.write_barrier(foo_ptr.handle());
+
+    // Finish the cycle, baz must survive and bar is only collected by the next cycle
+    while !runtime.collect_step(1) {}
+    assert!(!runtime.is_collecting());
+    runtime.collect();
+
+    let foo_instance = foo_ptr.unroot();
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(foo_instance)));
+    assert_eq!(events.next(), Some(Event::Allocation(bar_handle)));
+    assert_eq!(events.next(), Some(Event::Allocation(baz_handle)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(bar_handle)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn incremental_alloc_during_cycle() {
+    let mut type_table = TypeTable::default();
+
+    let bar_type_info = fake_struct!(type_table, "core::Bar", "a" => i64);
+    type_table.insert_type(bar_type_info.clone());
+
+    let foo_type_info = fake_struct!(type_table, "core::Foo", "bar" => Bar);
+    type_table.insert_type(foo_type_info.clone());
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let mut foo_ptr = GcRootPtr::new(&runtime, runtime.alloc(&foo_type_info));
+    let bar_handle = runtime.alloc(&bar_type_info);
+    unsafe {
+        (*foo_ptr.deref_mut::<FooObject>()).bar = bar_handle;
+    }
+
+    // Finish marking, which leaves only the sweep phase
+    assert!(!runtime.collect_step(4));
+
+    // Objects that are allocated during a cycle survive it
+    let baz_handle = runtime.alloc(&bar_type_info);
+    while !runtime.collect_step(1) {}
+
+    // The next cycle collects them if they are unreachable
+    while !runtime.collect_step(1) {}
+
+    let foo_instance = foo_ptr.unroot();
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(foo_instance)));
+    assert_eq!(events.next(), Some(Event::Allocation(bar_handle)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Allocation(baz_handle)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(baz_handle)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
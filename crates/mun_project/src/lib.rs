@@ -1,4 +1,4 @@
-pub use manifest::{Manifest, ManifestMetadata, PackageId};
+pub use manifest::{Manifest, ManifestMetadata, ModuleGroup, ModuleGrouping, PackageId};
 pub use package::Package;
 pub use project_manifest::ProjectManifest;
 
@@ -8,6 +8,7 @@ mod toml;
 pub struct Manifest {
     package_id: PackageId,
     metadata: ManifestMetadata,
+    module_grouping: ModuleGrouping,
 }
 
 /// General metadata for a package.
@@ -16,6 +17,33 @@ pub struct ManifestMetadata {
     pub authors: Vec<String>,
 }
 
+/// Describes how the modules of a package are grouped into munlibs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ModuleGrouping {
+    /// Every module is compiled into its own munlib. This enables the most
+    /// fine-grained hot reloading.
+    #[default]
+    PerModule,
+
+    /// All modules of the package are compiled into a single munlib.
+    PerPackage,
+
+    /// Modules are compiled into the named groups. Modules that are not part
+    /// of any group are compiled into their own munlib.
+    Groups(Vec<ModuleGroup>),
+}
+
+/// A named group of modules that is compiled into a single munlib.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleGroup {
+    /// The name of the group, which is also the name of its munlib
+    pub name: String,
+
+    /// The paths of the modules in the group, e.g. `foo::bar`. The root module
+    /// of the package is referred to by an empty path.
+    pub modules: Vec<String>,
+}
+
 /// Unique identifier of a package and version
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PackageId {
@@ -52,6 +80,11 @@ impl Manifest {
     pub fn metadata(&self) -> &ManifestMetadata {
         &self.metadata
     }
+
+    /// Returns how the modules of the package are grouped into munlibs
+    pub fn module_grouping(&self) -> &ModuleGrouping {
+        &self.module_grouping
+    }
 }
 
 impl PackageId {
@@ -87,7 +120,7 @@ impl std::str::FromStr for Manifest {
 mod tests {
     use std::str::FromStr;
 
-    use crate::Manifest;
+    use crate::{Manifest, ModuleGroup, ModuleGrouping};
 
     #[test]
     fn parse() {
@@ -108,5 +141,95 @@ mod tests {
         );
         assert_eq!(manifest.metadata().authors, vec!["Mun Team"]);
         assert_eq!(format!("{}", manifest.package_id()), "test v0.2.0");
+        assert_eq!(manifest.module_grouping(), &ModuleGrouping::PerModule);
+    }
+
+    #[test]
+    fn parse_module_grouping() {
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+
+        [build]
+        module-grouping = "per-package"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(manifest.module_grouping(), &ModuleGrouping::PerPackage);
+
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+
+        [build.module-groups]
+        core = ["package", "package::math", "math::vector"]
+        game = ["game"]
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.module_grouping(),
+            &ModuleGrouping::Groups(vec![
+                ModuleGroup {
+                    name: String::from("core"),
+                    modules: vec![
+                        String::new(),
+                        String::from("math"),
+                        String::from("math::vector")
+                    ],
+                },
+                ModuleGroup {
+                    name: String::from("game"),
+                    modules: vec![String::from("game")],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_invalid_module_groups() {
+        let duplicate_module = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+
+        [build.module-groups]
+        core = ["math"]
+        game = ["package::math"]
+        "#,
+        );
+        assert!(duplicate_module.is_err());
+
+        let conflicting_grouping = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+
+        [build]
+        module-grouping = "per-package"
+
+        [build.module-groups]
+        core = ["math"]
+        "#,
+        );
+        assert!(conflicting_grouping.is_err());
+
+        let invalid_name = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+
+        [build.module-groups]
+        "core/math" = ["math"]
+        "#,
+        );
+        assert!(invalid_name.is_err());
     }
 }
@@ -1,12 +1,16 @@
+use std::collections::BTreeMap;
+
+use rustc_hash::FxHashSet;
 use serde_derive::{Deserialize, Serialize};
 
-use super::{Manifest, ManifestMetadata, PackageId};
+use super::{Manifest, ManifestMetadata, ModuleGroup, ModuleGrouping, PackageId};
 
 /// A manifest as specified in a mun.toml file.
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct TomlManifest {
     package: TomlProject,
+    build: Option<TomlBuild>,
 }
 
 /// Represents the `package` section of a mun.toml file.
@@ -17,6 +21,22 @@ pub struct TomlProject {
     authors: Option<Vec<String>>,
 }
 
+/// Represents the `build` section of a mun.toml file.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlBuild {
+    module_grouping: Option<TomlModuleGrouping>,
+    module_groups: Option<BTreeMap<String, Vec<String>>>,
+}
+
+/// The predefined strategies to group modules into munlibs.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum TomlModuleGrouping {
+    PerModule,
+    PerPackage,
+}
+
 impl TomlManifest {
     /// Convert this toml manifest into a "real" manifest.
     pub fn into_real_manifest(self) -> Result<Manifest, anyhow::Error> {
@@ -25,6 +45,11 @@ impl TomlManifest {
             anyhow::bail!("package name cannot be an empty string");
         }
 
+        let module_grouping = match self.build {
+            Some(build) => build.into_module_grouping()?,
+            None => ModuleGrouping::default(),
+        };
+
         Ok(Manifest {
             package_id: PackageId {
                 name: name.to_owned(),
@@ -33,6 +58,72 @@ impl TomlManifest {
             metadata: ManifestMetadata {
                 authors: self.package.authors.unwrap_or_default(),
             },
+            module_grouping,
         })
     }
 }
+
+impl TomlBuild {
+    /// Determines how modules are grouped from the `module-grouping` and
+    /// `module-groups` settings.
+    fn into_module_grouping(self) -> Result<ModuleGrouping, anyhow::Error> {
+        let groups = match (self.module_grouping, self.module_groups) {
+            (Some(_), Some(_)) => {
+                anyhow::bail!("`module-grouping` and `module-groups` cannot both be specified")
+            }
+            (Some(TomlModuleGrouping::PerModule) | None, None) => {
+                return Ok(ModuleGrouping::PerModule)
+            }
+            (Some(TomlModuleGrouping::PerPackage), None) => return Ok(ModuleGrouping::PerPackage),
+            (None, Some(groups)) => groups,
+        };
+
+        let mut grouped_modules = FxHashSet::default();
+        let groups = groups
+            .into_iter()
+            .map(|(name, modules)| {
+                if name.is_empty() || name.contains(['/', '\\', '$']) {
+                    anyhow::bail!("invalid module group name '{}'", name);
+                }
+
+                let modules = modules
+                    .iter()
+                    .map(|path| {
+                        let path = normalize_module_path(path);
+                        if !grouped_modules.insert(path.clone()) {
+                            anyhow::bail!(
+                                "module '{}' is part of multiple module groups",
+                                display_module_path(&path)
+                            );
+                        }
+                        Ok(path)
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(ModuleGroup { name, modules })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(ModuleGrouping::Groups(groups))
+    }
+}
+
+/// Strips the optional `package` prefix from a module path, so the root
+/// module is referred to by an empty path.
+fn normalize_module_path(path: &str) -> String {
+    let path = path.trim();
+    if path == "package" {
+        String::new()
+    } else {
+        path.strip_prefix("package::").unwrap_or(path).to_owned()
+    }
+}
+
+/// Returns the path of a module as it would be written in Mun code.
+fn display_module_path(path: &str) -> String {
+    if path.is_empty() {
+        String::from("package")
+    } else {
+        format!("package::{path}")
+    }
+}
@@ -18,7 +18,7 @@ use crate::{
 /// Represents a Mun struct pointer.
 #[repr(transparent)]
 #[derive(Clone)]
-pub struct RawStruct(GcPtr);
+pub struct RawStruct(pub(crate) GcPtr);
 
 impl RawStruct {
     /// Returns a pointer to the struct memory.
@@ -38,7 +38,7 @@ pub struct StructRef<'s> {
 
 impl<'s> StructRef<'s> {
     /// Creates a `StructRef` that wraps a raw Mun struct.
-    fn new<'r>(raw: RawStruct, runtime: &'r Runtime) -> Self
+    pub(crate) fn new<'r>(raw: RawStruct, runtime: &'r Runtime) -> Self
     where
         'r: 's,
     {
@@ -154,6 +154,7 @@ impl<'s> StructRef<'s> {
         let field_ptr = unsafe { self.get_field_ptr_unchecked::<T::MunType>(field_info.offset()) };
         let old = Marshal::marshal_from_ptr(field_ptr, self.runtime, &field_info.ty());
         Marshal::marshal_to_ptr(value, field_ptr, &field_info.ty());
+        self.runtime.gc.write_barrier(self.raw.0);
         Ok(old)
     }
 
@@ -193,6 +194,7 @@ impl<'s> StructRef<'s> {
         // SAFETY: The offset in the ABI is always valid.
         let field_ptr = unsafe { self.get_field_ptr_unchecked::<T::MunType>(field_info.offset()) };
         Marshal::marshal_to_ptr(value, field_ptr, &field_info.ty());
+        self.runtime.gc.write_barrier(self.raw.0);
         Ok(())
     }
 }
@@ -7,16 +7,40 @@ use std::{
 };
 
 use itertools::Itertools;
-use log::error;
+use log::{error, warn};
 use mun_abi as abi;
 use mun_libloader::{MunLibrary, TempLibrary};
 use mun_memory::{
+    diff::FieldRenames,
+    gc::{GcPtr, GcRuntime},
     mapping::{Mapping, MemoryMapper},
     type_table::TypeTable,
     Type,
 };
 
-use crate::{garbage_collector::GarbageCollector, DispatchTable};
+use crate::{garbage_collector::Allocator, reload::MappedTypeReport, DispatchTable};
+
+/// A migration that has to be invoked for an object whose struct type changed.
+pub(crate) struct PendingMigration {
+    /// The name of the migration function
+    pub function_name: String,
+    /// The object to migrate
+    pub object: GcPtr,
+    /// A rooted copy of the object's contents before its type changed
+    pub old_contents: GcPtr,
+}
+
+/// The result of relinking assemblies, next to the new dispatch and type
+/// tables.
+#[derive(Default)]
+pub(crate) struct Relinked {
+    /// The paths of the relinked assemblies
+    pub assemblies: Vec<PathBuf>,
+    /// The migrations that have to be invoked
+    pub migrations: Vec<PendingMigration>,
+    /// The structs whose objects were mapped
+    pub mapped_types: Vec<MappedTypeReport>,
+}
 
 /// An error that occurs upon loading of a Mun library.
 #[derive(Debug, thiserror::Error)]
@@ -29,6 +53,12 @@ pub enum LoadError {
     MismatchedAbiVersions { expected: u32, actual: u32 },
     #[error(transparent)]
     Other(#[from] io::Error),
+    #[cfg(feature = "jit")]
+    #[error("Failed to compile assembly:\n{0}")]
+    CompileError(String),
+    #[cfg(feature = "jit")]
+    #[error(transparent)]
+    Jit(#[from] mun_codegen::JitError),
 }
 
 /// An error that occurs upon linking of a Mun assembly.
@@ -48,6 +78,23 @@ pub enum LinkError {
     MissingTypes(Vec<String>),
 }
 
+/// An error that occurs upon unloading of a Mun assembly.
+#[derive(Debug, thiserror::Error)]
+pub enum UnloadError {
+    /// No assembly was loaded from the library
+    #[error("No assembly is loaded from `{0}`")]
+    NotLoaded(PathBuf),
+    /// Another assembly depends on the assembly
+    #[error("The assembly is a dependency of `{0}`")]
+    Dependency(PathBuf),
+    /// Functions that remain in the dispatch table use the assembly's types
+    #[error("Functions still use the assembly's types: {0:?}")]
+    ReferencedByFunctions(Vec<String>),
+    /// Objects of the assembly's types are still alive
+    #[error("Objects of the assembly's types are still alive: {0:?}")]
+    LiveObjects(Vec<String>),
+}
+
 /// An error that occurs upon linking of a Mun function prototype.
 #[derive(Debug, thiserror::Error)]
 pub enum LinkFunctionsError {
@@ -85,13 +132,24 @@ pub enum LinkFunctionsError {
     },
 }
 
+/// Keeps the code of an assembly loaded.
+enum AssemblyCode {
+    /// The code is part of a loaded munlib
+    Library(TempLibrary),
+    /// The code is statically linked into the host
+    Static,
+    /// The code was compiled into the running process
+    #[cfg(feature = "jit")]
+    Jit(mun_codegen::JitAssembly),
+}
+
 /// An assembly is a hot reloadable compilation unit, consisting of one or more
 /// Mun modules.
 pub struct Assembly {
     library_path: PathBuf,
-    library: TempLibrary,
+    code: AssemblyCode,
     info: abi::AssemblyInfo<'static>,
-    allocator: Arc<GarbageCollector>,
+    allocator: Arc<Allocator>,
 }
 
 impl Assembly {
@@ -112,10 +170,88 @@ impl Assembly {
     /// unloaded.
     ///
     /// See [`libloading::Library::new`] for more information.
-    pub unsafe fn load(library_path: &Path, gc: Arc<GarbageCollector>) -> Result<Self, LoadError> {
-        let mut library = MunLibrary::new(library_path)?;
+    pub unsafe fn load(library_path: &Path, allocator: Arc<Allocator>) -> Result<Self, LoadError> {
+        Self::from_library(library_path, MunLibrary::new(library_path)?, allocator)
+    }
+
+    /// Loads an assembly and its information from the contents of a munlib.
+    /// The `library_path` identifies the assembly, but the file it refers to
+    /// does not have to exist. The resulting `Assembly` is ensured to be
+    /// linkable.
+    ///
+    /// # Safety
+    ///
+    /// See [`Assembly::load`].
+    pub unsafe fn load_from_bytes(
+        library_path: &Path,
+        bytes: &[u8],
+        allocator: Arc<Allocator>,
+    ) -> Result<Self, LoadError> {
+        Self::from_library(library_path, MunLibrary::from_bytes(bytes)?, allocator)
+    }
+
+    /// Constructs an assembly from a loaded munlib.
+    unsafe fn from_library(
+        library_path: &Path,
+        library: MunLibrary,
+        allocator: Arc<Allocator>,
+    ) -> Result<Self, LoadError> {
+        let entry_points = library.entry_points();
+        Self::from_entry_points(
+            library_path,
+            entry_points,
+            AssemblyCode::Library(library.into_inner()),
+            allocator,
+        )
+    }
+
+    /// Constructs an assembly from the entry points of a static library that
+    /// is linked into the host. The `library_path` identifies the assembly,
+    /// but the file it refers to does not have to exist.
+    ///
+    /// # Safety
+    ///
+    /// The entry points are invoked to initialize the assembly. There is no
+    /// guarantee that their execution won't result in undefined behavior.
+    pub unsafe fn load_static(
+        library_path: &Path,
+        entry_points: abi::EntryPoints,
+        allocator: Arc<Allocator>,
+    ) -> Result<Self, LoadError> {
+        Self::from_entry_points(library_path, entry_points, AssemblyCode::Static, allocator)
+    }
+
+    /// Constructs an assembly from an assembly that was compiled into the
+    /// running process. The `library_path` identifies the assembly, but the
+    /// file it refers to does not have to exist.
+    ///
+    /// # Safety
+    ///
+    /// See [`Assembly::load_static`].
+    #[cfg(feature = "jit")]
+    pub(crate) unsafe fn load_jit(
+        library_path: &Path,
+        assembly: mun_codegen::JitAssembly,
+        allocator: Arc<Allocator>,
+    ) -> Result<Self, LoadError> {
+        let entry_points = assembly.entry_points();
+        Self::from_entry_points(
+            library_path,
+            entry_points,
+            AssemblyCode::Jit(assembly),
+            allocator,
+        )
+    }
 
-        let version = library.get_abi_version();
+    /// Constructs an assembly from its entry points, whose code is kept
+    /// alive by `code`.
+    unsafe fn from_entry_points(
+        library_path: &Path,
+        entry_points: abi::EntryPoints,
+        code: AssemblyCode,
+        allocator: Arc<Allocator>,
+    ) -> Result<Self, LoadError> {
+        let version = (entry_points.get_version)();
         if abi::ABI_VERSION != version {
             return Err(LoadError::MismatchedAbiVersions {
                 expected: abi::ABI_VERSION,
@@ -123,14 +259,14 @@ impl Assembly {
             });
         }
 
-        let allocator_ptr = Arc::into_raw(gc.clone()) as *mut std::ffi::c_void;
-        library.set_allocator_handle(allocator_ptr);
+        let allocator_ptr = Arc::into_raw(allocator.clone()) as *mut std::ffi::c_void;
+        (entry_points.set_allocator_handle)(allocator_ptr);
 
         let assembly = Assembly {
-            info: library.get_info(),
+            info: (entry_points.get_info)(),
             library_path: library_path.to_path_buf(),
-            library: library.into_inner(),
-            allocator: gc,
+            code,
+            allocator,
         };
 
         Ok(assembly)
@@ -307,15 +443,71 @@ impl Assembly {
         Ok((dispatch_table, type_table))
     }
 
+    /// Returns the migrations that the assembly defines for the struct types in
+    /// `types`. Every migration is returned as the struct type, the name of the
+    /// migration function and the struct that describes the old layout.
+    ///
+    /// A migration is a method of the struct called `migrate`, that takes the
+    /// object as `self` and a struct that describes the old layout of the
+    /// object, e.g.: `pub fn migrate(self, old: PlayerV1)`. The method is
+    /// looked up by its fully qualified path, so structs with the same name in
+    /// different modules don't share their migrations.
+    fn migrations(&self, types: &[Type], type_table: &TypeTable) -> Vec<(Type, String, Type)> {
+        types
+            .iter()
+            .filter(|ty| ty.is_struct())
+            .filter_map(|ty| {
+                let fn_name = format!("{}::migrate", ty.name());
+                let fn_def = self
+                    .info
+                    .symbols
+                    .functions()
+                    .iter()
+                    .find(|fn_def| fn_def.prototype.name() == fn_name)?;
+
+                let signature = &fn_def.prototype.signature;
+                let arg_types = signature
+                    .arg_types()
+                    .iter()
+                    .map(|type_id| type_table.find_type_info_by_id(type_id))
+                    .collect::<Vec<_>>();
+                match arg_types.as_slice() {
+                    [Some(self_ty), Some(layout_ty)]
+                        if self_ty == ty
+                            && !self_ty.is_value_type()
+                            && layout_ty.is_struct()
+                            && !layout_ty.is_value_type()
+                            && signature.return_type().is_none() =>
+                    {
+                        Some((ty.clone(), fn_name, layout_ty.clone()))
+                    }
+                    _ => {
+                        warn!(
+                            "`{fn_name}` is not a valid migration. A migration must have the \
+                            signature `fn migrate(self, old: OldLayout)` and both structs must be \
+                            gc structs."
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
     /// Tries to link the `unlinked_assemblies`, resulting in a new
     /// [`DispatchTable`] on success. This leaves the original
     /// `dispatch_table` intact, in case of linking errors.
+    ///
+    /// Objects whose struct types changed are mapped to their new types, using
+    /// the declared `field_renames`. The returned migrations have to be invoked
+    /// once the new dispatch table is in use.
     pub(super) fn relink_all(
         unlinked_assemblies: &mut HashMap<PathBuf, Assembly>,
         linked_assemblies: &mut HashMap<PathBuf, Assembly>,
         dispatch_table: &DispatchTable,
         type_table: &TypeTable,
-    ) -> Result<(DispatchTable, TypeTable), LinkError> {
+        field_renames: &FieldRenames,
+    ) -> Result<(DispatchTable, TypeTable, Relinked), LinkError> {
         let mut dependencies: HashMap<String, Vec<String>> = unlinked_assemblies
             .values()
             .map(|assembly| {
@@ -339,6 +531,8 @@ impl Assembly {
         // Clone the dispatch table, such that we can roll back if linking fails
         let mut dispatch_table = dispatch_table.clone();
 
+        let mut relinked = Relinked::default();
+
         while let Some(mut entry) = assemblies_to_link.pop_front() {
             let (ref old_assembly, ref mut new_assembly) = entry;
 
@@ -389,10 +583,53 @@ impl Assembly {
 
             // Memory map allocated object
             if let Some((old_assembly, old_types)) = old_types {
-                let mapping = Mapping::new(&old_types, &new_types);
-                let _deleted_objects = old_assembly.allocator.map_memory(mapping);
+                let mut mapping = Mapping::with_renames(&old_types, &new_types, field_renames);
+
+                let mut migration_fns = HashMap::new();
+                for (ty, fn_name, layout_ty) in new_assembly.migrations(&new_types, &type_table) {
+                    if mapping.add_migration(&ty, &layout_ty) {
+                        migration_fns.insert(ty, fn_name);
+                    }
+                }
+
+                let gc = old_assembly.allocator.gc();
+
+                // Collect garbage first, so only objects that are still reachable are mapped
+                // and reported
+                gc.collect();
+                let type_stats = gc.type_stats();
+                let mut mapped_types = mapping
+                    .struct_mappings
+                    .iter()
+                    .map(|(old_ty, conversion)| {
+                        let object_count =
+                            type_stats.get(old_ty).map_or(0, |stats| stats.object_count);
+                        MappedTypeReport::new(old_ty, conversion, object_count)
+                    })
+                    .collect::<Vec<_>>();
+
+                let mapped = gc.map_memory(mapping);
                 // DISCUSSION: Do we need to maintain an assembly for the type
                 // LUT of allocated objects with deleted types?
+
+                for migrated in mapped.migrated {
+                    let ty = gc.ptr_type(migrated.object);
+                    if let Some(report) = mapped_types
+                        .iter_mut()
+                        .find(|report| report.name == ty.name())
+                    {
+                        report.migrated_count += 1;
+                    }
+
+                    relinked.migrations.push(PendingMigration {
+                        function_name: migration_fns[&ty].clone(),
+                        object: migrated.object,
+                        old_contents: migrated.old_contents,
+                    });
+                }
+
+                mapped_types.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+                relinked.mapped_types.extend(mapped_types);
             }
 
             // Remove the old assembly's functions from the dispatch table
@@ -441,12 +678,72 @@ impl Assembly {
             );
 
             let new_path = new_assembly.library_path.clone();
+            relinked.assemblies.push(new_path.clone());
             linked_assemblies.insert(new_path, new_assembly);
         }
 
         // Collect types
         Type::collect_unreferenced_type_data();
 
+        relinked.assemblies.sort();
+
+        Ok((dispatch_table, type_table, relinked))
+    }
+
+    /// Removes the assembly's functions and types from the dispatch and type
+    /// table, after validating that no remaining functions and no live
+    /// objects use its types.
+    pub(super) fn unlink(
+        &self,
+        dispatch_table: &DispatchTable,
+        type_table: &TypeTable,
+    ) -> Result<(DispatchTable, TypeTable), UnloadError> {
+        let mut type_table = type_table.clone();
+        let types = self
+            .info
+            .symbols
+            .types()
+            .iter()
+            .filter_map(|type_info| type_table.remove_type_by_type_info(type_info))
+            .collect::<Vec<_>>();
+
+        let mut dispatch_table = dispatch_table.clone();
+        dispatch_table.remove_module(&self.info.symbols);
+
+        let referencing_functions = dispatch_table
+            .get_fn_names()
+            .filter(|name| {
+                let signature = &dispatch_table
+                    .get_fn(name)
+                    .expect("function must exist")
+                    .prototype
+                    .signature;
+                signature
+                    .arg_types
+                    .iter()
+                    .chain(std::iter::once(&signature.return_type))
+                    .any(|ty| uses_any_type(ty, &types))
+            })
+            .map(ToOwned::to_owned)
+            .sorted()
+            .collect::<Vec<_>>();
+        if !referencing_functions.is_empty() {
+            return Err(UnloadError::ReferencedByFunctions(referencing_functions));
+        }
+
+        let gc = self.allocator.gc();
+        gc.collect();
+        let live_types = gc
+            .type_stats()
+            .into_iter()
+            .filter(|(ty, stats)| stats.object_count > 0 && uses_any_type(ty, &types))
+            .map(|(ty, _)| ty.name().to_owned())
+            .sorted()
+            .collect::<Vec<_>>();
+        if !live_types.is_empty() {
+            return Err(UnloadError::LiveObjects(live_types));
+        }
+
         Ok((dispatch_table, type_table))
     }
 
@@ -467,8 +764,28 @@ impl Assembly {
     }
 
     /// Converts the `Assembly` into a `TempLibrary`, consuming the input in the
-    /// process.
-    pub fn into_library(self) -> TempLibrary {
-        self.library
+    /// process. Returns `None` if the assembly is statically linked into the
+    /// host.
+    pub fn into_library(self) -> Option<TempLibrary> {
+        match self.code {
+            AssemblyCode::Library(library) => Some(library),
+            AssemblyCode::Static => None,
+            #[cfg(feature = "jit")]
+            AssemblyCode::Jit(_) => None,
+        }
+    }
+}
+
+/// Returns true if `ty` is one of `types`, or an array of or a pointer to one
+/// of them.
+fn uses_any_type(ty: &Type, types: &[Type]) -> bool {
+    if types.contains(ty) {
+        true
+    } else if let Some(array) = ty.as_array() {
+        uses_any_type(&array.element_type(), types)
+    } else if let Some(pointer) = ty.as_pointer() {
+        uses_any_type(&pointer.pointee(), types)
+    } else {
+        false
     }
 }
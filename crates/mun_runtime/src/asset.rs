@@ -0,0 +1,302 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{channel, Receiver},
+        Arc,
+    },
+};
+
+use log::debug;
+use mun_abi as abi;
+use mun_project::LOCKFILE_NAME;
+use notify::{event::ModifyKind, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+
+use crate::{assembly::LoadError, garbage_collector::Allocator, Assembly};
+
+/// Provides the contents of munlibs to a [`crate::Runtime`], instead of the
+/// runtime loading them from the filesystem.
+///
+/// Assemblies are identified by a path, which does not have to refer to a
+/// file. It could for instance be the path of an entry in a packed archive.
+///
+/// On Linux, munlibs are loaded from anonymous in-memory files. Other
+/// platforms can only load shared libraries from the filesystem, so there
+/// every munlib is written to a temporary file before it is loaded.
+pub trait AssetProvider: Send {
+    /// Returns the contents of the munlib at `path`.
+    fn load(&mut self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Returns the path of the assembly called `dependency`, on which the
+    /// assembly at `dependant` depends. By default, a dependency is resolved
+    /// relative to the directory of its dependant, with the same extension.
+    fn resolve_dependency(&self, dependant: &Path, dependency: &str) -> PathBuf {
+        resolve_dependency(dependant, dependency)
+    }
+
+    /// Returns the paths of the assemblies that changed since the last call.
+    /// This is polled by [`crate::Runtime::update`], which reloads the
+    /// returned assemblies.
+    fn changed_assemblies(&mut self) -> Vec<PathBuf>;
+}
+
+/// Returns the path of the assembly called `dependency`, relative to the
+/// directory of the assembly at `dependant` and with the same extension.
+pub(crate) fn resolve_dependency(dependant: &Path, dependency: &str) -> PathBuf {
+    let mut path = dependant
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(dependency);
+    if let Some(extension) = dependant.extension() {
+        path = path.with_extension(extension);
+    }
+    path
+}
+
+/// An [`AssetProvider`] that holds the contents of munlibs in memory.
+///
+/// Clones share the same munlibs, so a clone can be used to insert updated
+/// munlibs after the original has been passed to the runtime. The runtime
+/// reloads an assembly after its munlib is replaced.
+#[derive(Clone, Default)]
+pub struct InMemoryAssets {
+    inner: Arc<Mutex<InMemoryAssetsInner>>,
+}
+
+#[derive(Default)]
+struct InMemoryAssetsInner {
+    munlibs: HashMap<PathBuf, Vec<u8>>,
+    changed: BTreeSet<PathBuf>,
+}
+
+impl InMemoryAssets {
+    /// Inserts the contents of the munlib at `path`. If the path was already
+    /// inserted, the assembly is reloaded during the next update of the
+    /// runtime.
+    pub fn insert(&self, path: impl Into<PathBuf>, bytes: Vec<u8>) {
+        let path = path.into();
+        let mut inner = self.inner.lock();
+        if inner.munlibs.insert(path.clone(), bytes).is_some() {
+            inner.changed.insert(path);
+        }
+    }
+}
+
+impl AssetProvider for InMemoryAssets {
+    fn load(&mut self, path: &Path) -> io::Result<Vec<u8>> {
+        self.inner.lock().munlibs.get(path).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no munlib at `{}`", path.display()),
+            )
+        })
+    }
+
+    fn changed_assemblies(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.inner.lock().changed)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// The source from which a runtime loads its assemblies.
+pub(crate) enum AssemblySource {
+    /// Assemblies are loaded from the filesystem, and reloaded when their
+    /// munlibs change on disk.
+    FileSystem(FileWatcher),
+    /// Assemblies are loaded from an asset provider, and reloaded when the
+    /// provider reports that they changed.
+    Provider(Mutex<Box<dyn AssetProvider>>),
+    /// The assembly is statically linked into the host, and is never
+    /// reloaded.
+    Static(abi::EntryPoints),
+    /// Assemblies are compiled into the running process by a JIT, and
+    /// recompiled when the source files of the compiler driver change.
+    #[cfg(feature = "jit")]
+    Jit(crate::jit::JitSource),
+}
+
+impl AssemblySource {
+    /// Constructs a source that loads the statically linked assembly with
+    /// `entry_points`, or otherwise assemblies from `provider` or from the
+    /// filesystem if there is no provider.
+    pub fn new(
+        provider: Option<Box<dyn AssetProvider>>,
+        entry_points: Option<abi::EntryPoints>,
+    ) -> Result<Self, notify::Error> {
+        Ok(match (entry_points, provider) {
+            (Some(entry_points), _) => AssemblySource::Static(entry_points),
+            (None, Some(provider)) => AssemblySource::Provider(Mutex::new(provider)),
+            (None, None) => AssemblySource::FileSystem(FileWatcher::new()?),
+        })
+    }
+
+    /// Returns the path that identifies the assembly at `library_path`.
+    pub fn identify(&self, library_path: &Path) -> io::Result<PathBuf> {
+        match self {
+            AssemblySource::FileSystem(_) => library_path.canonicalize(),
+            AssemblySource::Provider(_) | AssemblySource::Static(_) => {
+                Ok(library_path.to_path_buf())
+            }
+            #[cfg(feature = "jit")]
+            AssemblySource::Jit(_) => Ok(library_path.to_path_buf()),
+        }
+    }
+
+    /// Loads the assembly at `library_path`.
+    ///
+    /// # Safety
+    ///
+    /// See [`Assembly::load`].
+    pub unsafe fn load(
+        &mut self,
+        library_path: &Path,
+        allocator: Arc<Allocator>,
+    ) -> Result<Assembly, LoadError> {
+        match self {
+            AssemblySource::FileSystem(_) => Assembly::load(library_path, allocator),
+            AssemblySource::Provider(provider) => {
+                let bytes = provider.get_mut().load(library_path)?;
+                Assembly::load_from_bytes(library_path, &bytes, allocator)
+            }
+            AssemblySource::Static(entry_points) => {
+                Assembly::load_static(library_path, *entry_points, allocator)
+            }
+            #[cfg(feature = "jit")]
+            AssemblySource::Jit(source) => source.load(library_path, allocator),
+        }
+    }
+
+    /// Returns the path of the assembly called `dependency`, on which the
+    /// assembly at `dependant` depends.
+    pub fn resolve_dependency(&mut self, dependant: &Path, dependency: &str) -> PathBuf {
+        match self {
+            AssemblySource::FileSystem(_) | AssemblySource::Static(_) => {
+                resolve_dependency(dependant, dependency)
+            }
+            AssemblySource::Provider(provider) => {
+                provider.get_mut().resolve_dependency(dependant, dependency)
+            }
+            #[cfg(feature = "jit")]
+            AssemblySource::Jit(_) => resolve_dependency(dependant, dependency),
+        }
+    }
+
+    /// Starts watching the loaded assembly at `library_path` for changes.
+    pub fn watch(&mut self, library_path: &Path) {
+        if let AssemblySource::FileSystem(watcher) = self {
+            watcher
+                .watcher
+                .watch(library_path.parent().unwrap(), RecursiveMode::NonRecursive)
+                .expect("Path must exist as we just loaded the library");
+        }
+    }
+
+    /// Stops watching the removed assembly at `library_path` for changes. Its
+    /// directory remains watched if it contains any of the `remaining`
+    /// assemblies.
+    pub fn unwatch<'a>(
+        &mut self,
+        library_path: &Path,
+        mut remaining: impl Iterator<Item = &'a PathBuf>,
+    ) {
+        if let AssemblySource::FileSystem(watcher) = self {
+            let directory = library_path.parent().unwrap();
+            if !remaining.any(|path| path.parent() == Some(directory)) {
+                // The directory might have been removed, which already ended the watch
+                if let Err(e) = watcher.watcher.unwatch(directory) {
+                    debug!("Failed to unwatch {}: {e}", directory.display());
+                }
+            }
+        }
+    }
+
+    /// Adds the assemblies that changed to `assemblies_to_relink`, which maps
+    /// the old to the (potentially) new path of an assembly. Returns true if
+    /// the changed assemblies are ready to be relinked.
+    pub fn poll_changes(&mut self, assemblies_to_relink: &mut BTreeMap<PathBuf, PathBuf>) -> bool {
+        match self {
+            AssemblySource::FileSystem(watcher) => watcher.poll_changes(assemblies_to_relink),
+            AssemblySource::Provider(provider) => {
+                let changed = provider.get_mut().changed_assemblies();
+                let requires_relink = !changed.is_empty();
+                for path in changed {
+                    assemblies_to_relink.insert(path.clone(), path);
+                }
+                requires_relink
+            }
+            AssemblySource::Static(_) => false,
+            #[cfg(feature = "jit")]
+            AssemblySource::Jit(source) => source.poll_changes(assemblies_to_relink),
+        }
+    }
+}
+
+/// Watches the directories of loaded munlibs for changes.
+pub(crate) struct FileWatcher {
+    watcher: RecommendedWatcher,
+    watcher_rx: Mutex<Receiver<notify::Result<Event>>>,
+    renamed_files: HashMap<usize, PathBuf>,
+}
+
+impl FileWatcher {
+    fn new() -> Result<Self, notify::Error> {
+        let (tx, rx) = channel();
+        let watcher: RecommendedWatcher = notify::recommended_watcher(move |res| {
+            tx.send(res).expect("Failed to send filesystem event.");
+        })?;
+        Ok(Self {
+            watcher,
+            watcher_rx: Mutex::new(rx),
+            renamed_files: HashMap::new(),
+        })
+    }
+
+    /// Adds the munlibs that changed on disk to `assemblies_to_relink`.
+    /// Returns true once the compiler has released its lockfile.
+    fn poll_changes(&mut self, assemblies_to_relink: &mut BTreeMap<PathBuf, PathBuf>) -> bool {
+        fn is_lockfile(path: &Path) -> bool {
+            path.file_name().expect("Invalid file path.") == LOCKFILE_NAME
+        }
+
+        let mut requires_relink = false;
+        while let Ok(Ok(event)) = self.watcher_rx.get_mut().try_recv() {
+            for path in event.paths {
+                if is_lockfile(&path) {
+                    match event.kind {
+                        EventKind::Create(_) => debug!("Lockfile created"),
+                        EventKind::Remove(_) => {
+                            debug!("Lockfile deleted");
+
+                            requires_relink = true;
+                        }
+                        _ => (),
+                    }
+                } else {
+                    let path = path.canonicalize().unwrap_or_else(|_| {
+                        panic!("Failed to canonicalize path: {}.", path.to_string_lossy())
+                    });
+
+                    match event.kind {
+                        EventKind::Modify(ModifyKind::Name(_)) => {
+                            let tracker = event.attrs.tracker().expect("Invalid RENAME event.");
+                            if let Some(old_path) = self.renamed_files.remove(&tracker) {
+                                assemblies_to_relink.insert(old_path, path);
+                            } else {
+                                self.renamed_files.insert(tracker, path);
+                            }
+                        }
+                        EventKind::Modify(_) => {
+                            // TODO: don't overwrite existing
+                            assemblies_to_relink.insert(path.clone(), path);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+        requires_relink
+    }
+}
@@ -0,0 +1,93 @@
+//! Keeps track of the [`ExecutionState`] of the Mun code that executes on each
+//! thread. Every invocation through the runtime has its own state, so
+//! invocations on different threads don't interfere with each other.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt, ptr,
+};
+
+use mun_abi::{ExecutionState, Interrupt};
+
+use crate::UNLIMITED_BUDGET;
+
+thread_local! {
+    /// The state of Mun code that the host calls directly, e.g. through a function pointer,
+    /// instead of through one of the runtime's invoke functions.
+    static HOST_STATE: ExecutionState = const { ExecutionState::new(UNLIMITED_BUDGET) };
+
+    /// The state of the innermost invocation on this thread, or null if there is none.
+    static INVOCATION_STATE: Cell<*const ExecutionState> = const { Cell::new(ptr::null()) };
+
+    /// The location of the operation that overflowed, if Mun code on this thread was interrupted
+    /// because of an overflow.
+    static OVERFLOW_LOCATION: RefCell<Option<OverflowLocation>> = const { RefCell::new(None) };
+}
+
+/// The location in a source file of an integer operation that overflowed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverflowLocation {
+    /// The path of the source file
+    pub file: String,
+    /// The line of the operation, starting at 1
+    pub line: u32,
+    /// The column of the operation, starting at 1
+    pub column: u32,
+}
+
+impl fmt::Display for OverflowLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Calls `f` with the execution state of the current thread. That is the state
+/// of the innermost invocation, or the state of the host if there is none.
+pub(crate) fn with_current<R>(f: impl FnOnce(&ExecutionState) -> R) -> R {
+    let invocation_state = INVOCATION_STATE.with(Cell::get);
+    if invocation_state.is_null() {
+        HOST_STATE.with(f)
+    } else {
+        // Safety: `run_with` guarantees that the state outlives its invocation
+        f(unsafe { &*invocation_state })
+    }
+}
+
+/// Returns a pointer to the execution state of the current thread, which
+/// remains valid until the calling Mun function returns.
+pub(crate) fn current_ptr() -> *const ExecutionState {
+    with_current(|state| state as *const ExecutionState)
+}
+
+/// Calls `f` with `state` as the execution state of the current thread. The
+/// previous state is restored afterwards.
+pub(crate) fn run_with<R>(state: &ExecutionState, f: impl FnOnce() -> R) -> R {
+    /// Restores the previous state, even if `f` panics
+    struct Restore(*const ExecutionState);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            INVOCATION_STATE.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(INVOCATION_STATE.with(|current| current.replace(state)));
+    f()
+}
+
+/// Interrupts the Mun code that executes on the current thread because the
+/// operation at `location` overflowed, unless it was already interrupted for
+/// another reason.
+pub(crate) fn interrupt_overflow(location: OverflowLocation) {
+    if with_current(|state| state.interrupt(Interrupt::Overflow)) {
+        OVERFLOW_LOCATION.with(|current| current.replace(Some(location)));
+    }
+}
+
+/// Returns the location of the operation that overflowed, after the Mun code on
+/// the current thread was interrupted because of an overflow.
+pub(crate) fn take_overflow_location() -> OverflowLocation {
+    OVERFLOW_LOCATION
+        .with(RefCell::take)
+        .expect("the location of an overflow is recorded before execution is interrupted")
+}
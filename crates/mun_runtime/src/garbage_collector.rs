@@ -1,6 +1,97 @@
-use mun_memory::gc;
+use std::sync::{atomic::AtomicBool, Arc};
+
+use mun_abi::Interrupt;
+use mun_memory::{
+    gc::{self, Array, GcPtr, GcRuntime, HeapLimitExceeded},
+    Type,
+};
+
+use crate::execution_state;
 
 /// Defines the garbage collector used by the `Runtime`.
 pub type GarbageCollector = gc::MarkSweep<gc::NoopObserver<gc::Event>>;
 
 pub type GcRootPtr = gc::GcRootPtr<GarbageCollector>;
+
+/// The allocator that Mun assemblies use through their allocator handle.
+///
+/// Assemblies that were compiled with heap limit checks allocate through
+/// [`Allocator::try_alloc`]. When such an allocation would exceed the hard heap
+/// limit, it fails and the Mun code that executes on the current thread is
+/// interrupted. Other assemblies cannot handle a failed allocation, so their
+/// allocations are not limited.
+#[repr(C)]
+pub struct Allocator {
+    /// Points to the [`gc::MarkSweep::marking_flag`] of `gc`. Generated code
+    /// reads the flag through the allocator handle, to only call the write
+    /// barrier while an incremental cycle is marking objects. Therefore, this
+    /// has to be the first field.
+    marking: *const AtomicBool,
+    gc: Arc<GarbageCollector>,
+}
+
+// SAFETY: `marking` points into the garbage collector that is kept alive by
+// `gc`, which is `Send` and `Sync` itself.
+unsafe impl Send for Allocator {}
+unsafe impl Sync for Allocator {}
+
+impl Allocator {
+    /// Constructs a new allocator for the specified garbage collector.
+    pub fn new(gc: Arc<GarbageCollector>) -> Self {
+        Self {
+            marking: gc.marking_flag(),
+            gc,
+        }
+    }
+
+    /// Returns the garbage collector that is used to allocate memory.
+    pub fn gc(&self) -> &Arc<GarbageCollector> {
+        &self.gc
+    }
+
+    /// Allocates an object of the given type on behalf of Mun code, regardless
+    /// of the hard heap limit.
+    pub fn alloc(&self, ty: &Type) -> GcPtr {
+        self.gc.alloc(ty)
+    }
+
+    /// Allocates an array of the given type on behalf of Mun code, regardless
+    /// of the hard heap limit. `ty` must be an array type.
+    pub fn alloc_array(&self, ty: &Type, n: usize) -> GcPtr {
+        self.gc.alloc_array(ty, n).as_raw()
+    }
+
+    /// Allocates an object of the given type on behalf of Mun code.
+    ///
+    /// If the allocation would exceed the hard heap limit, nothing is allocated
+    /// and the Mun code that executes on the current thread is interrupted.
+    pub fn try_alloc(&self, ty: &Type) -> Option<GcPtr> {
+        match self.gc.try_alloc(ty) {
+            Ok(handle) => Some(handle),
+            Err(HeapLimitExceeded) => {
+                Self::interrupt();
+                None
+            }
+        }
+    }
+
+    /// Allocates an array of the given type on behalf of Mun code. `ty` must be
+    /// an array type.
+    ///
+    /// See [`Allocator::try_alloc`] for how the hard heap limit is handled.
+    pub fn try_alloc_array(&self, ty: &Type, n: usize) -> Option<GcPtr> {
+        match self.gc.try_alloc_array(ty, n) {
+            Ok(handle) => Some(handle.as_raw()),
+            Err(HeapLimitExceeded) => {
+                Self::interrupt();
+                None
+            }
+        }
+    }
+
+    /// Interrupts the Mun code that executes on the current thread because an
+    /// allocation exceeded the hard heap limit.
+    fn interrupt() {
+        execution_state::with_current(|state| state.interrupt(Interrupt::HeapLimitExceeded));
+    }
+}
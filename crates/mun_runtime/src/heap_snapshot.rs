@@ -0,0 +1,159 @@
+//! Writes [`HeapSnapshot`]s in the `.heapsnapshot` JSON format of the V8
+//! JavaScript engine. Snapshots in this format can be inspected with the memory
+//! tools of Chromium-based browsers, which show how much memory every type
+//! uses and through which references objects are retained.
+
+use std::{collections::HashMap, io};
+
+use mun_memory::gc::HeapSnapshot;
+use serde_json::json;
+
+/// The fields of every node, in order.
+const NODE_FIELDS: [&str; 7] = [
+    "type",
+    "name",
+    "id",
+    "self_size",
+    "edge_count",
+    "trace_node_id",
+    "detachedness",
+];
+
+/// The node types that the format defines. Objects use the `object` type and
+/// the root node uses the `synthetic` type.
+const NODE_TYPES: [&str; 15] = [
+    "hidden",
+    "array",
+    "string",
+    "object",
+    "code",
+    "closure",
+    "regexp",
+    "number",
+    "native",
+    "synthetic",
+    "concatenated string",
+    "sliced string",
+    "symbol",
+    "bigint",
+    "object shape",
+];
+const NODE_TYPE_OBJECT: usize = 3;
+const NODE_TYPE_SYNTHETIC: usize = 9;
+
+/// The edge types that the format defines. All references use the `element`
+/// type.
+const EDGE_TYPES: [&str; 7] = [
+    "context", "element", "property", "internal", "hidden", "shortcut", "weak",
+];
+const EDGE_TYPE_ELEMENT: usize = 1;
+
+/// Collects the strings of a snapshot, which nodes refer to by index.
+#[derive(Default)]
+struct StringTable {
+    strings: Vec<String>,
+    indices: HashMap<String, usize>,
+}
+
+impl StringTable {
+    fn index_of(&mut self, string: &str) -> usize {
+        if let Some(index) = self.indices.get(string) {
+            return *index;
+        }
+
+        let index = self.strings.len();
+        self.strings.push(string.to_owned());
+        self.indices.insert(string.to_owned(), index);
+        index
+    }
+}
+
+/// Writes `snapshot` to `writer` in the `.heapsnapshot` format.
+///
+/// The first node is a synthetic root node that references all rooted objects.
+/// Every other node corresponds to an object in the snapshot.
+pub(crate) fn write_heap_snapshot(
+    snapshot: &HeapSnapshot,
+    writer: impl io::Write,
+) -> io::Result<()> {
+    let objects = snapshot.objects();
+
+    // Edges refer to nodes by the index of their first field
+    let node_indices: HashMap<_, _> = objects
+        .iter()
+        .enumerate()
+        .map(|(index, object)| (object.handle, (index + 1) * NODE_FIELDS.len()))
+        .collect();
+
+    let mut strings = StringTable::default();
+    let mut nodes = Vec::with_capacity((objects.len() + 1) * NODE_FIELDS.len());
+    let mut edges = Vec::new();
+
+    let roots = objects
+        .iter()
+        .enumerate()
+        .filter(|(_, object)| object.roots > 0)
+        .map(|(index, _)| (index + 1) * NODE_FIELDS.len())
+        .collect::<Vec<_>>();
+    nodes.extend([
+        NODE_TYPE_SYNTHETIC,
+        strings.index_of("(GC roots)"),
+        0,
+        0,
+        roots.len(),
+        0,
+        0,
+    ]);
+    for (index, to_node) in roots.into_iter().enumerate() {
+        edges.extend([EDGE_TYPE_ELEMENT, index, to_node]);
+    }
+
+    for (index, object) in objects.iter().enumerate() {
+        let references = object
+            .references
+            .iter()
+            .filter_map(|reference| node_indices.get(reference))
+            .collect::<Vec<_>>();
+
+        nodes.extend([
+            NODE_TYPE_OBJECT,
+            strings.index_of(object.ty.name()),
+            // Node ids must be unique, the root node uses 0
+            index + 1,
+            object.size,
+            references.len(),
+            0,
+            0,
+        ]);
+        for (index, to_node) in references.into_iter().enumerate() {
+            edges.extend([EDGE_TYPE_ELEMENT, index, *to_node]);
+        }
+    }
+
+    let heap_snapshot = json!({
+        "snapshot": {
+            "meta": {
+                "node_fields": NODE_FIELDS,
+                "node_types": [NODE_TYPES, "string", "number", "number", "number", "number", "number"],
+                "edge_fields": ["type", "name_or_index", "to_node"],
+                "edge_types": [EDGE_TYPES, "string_or_number", "node"],
+                "trace_function_info_fields": [],
+                "trace_node_fields": [],
+                "sample_fields": [],
+                "location_fields": [],
+            },
+            "node_count": objects.len() + 1,
+            "edge_count": edges.len() / 3,
+            "trace_function_count": 0,
+        },
+        "nodes": nodes,
+        "edges": edges,
+        "trace_function_infos": [],
+        "trace_tree": [],
+        "samples": [],
+        "locations": [],
+        "strings": strings.strings,
+    });
+
+    serde_json::to_writer(writer, &heap_snapshot).map_err(io::Error::from)
+}
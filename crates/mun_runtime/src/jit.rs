@@ -0,0 +1,105 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use log::error;
+use mun_codegen::{AssemblyObject, JitEngine, JitError};
+use mun_compiler::{DisplayColor, Driver};
+use parking_lot::Mutex;
+
+use crate::{assembly::LoadError, garbage_collector::Allocator, Assembly};
+
+/// Compiles the assemblies of a compiler [`Driver`] into the running process,
+/// without writing munlibs to the filesystem.
+pub(crate) struct JitSource {
+    /// The driver is only accessed with exclusive access to the runtime, but
+    /// its database isn't `Sync` by itself.
+    driver: Mutex<Driver>,
+    engine: JitEngine,
+    /// The object files from which the loaded assemblies were linked, which are
+    /// used to detect the assemblies that changed.
+    loaded: HashMap<PathBuf, Arc<AssemblyObject>>,
+}
+
+impl JitSource {
+    /// Constructs a source that compiles the assemblies of `driver`.
+    pub fn new(driver: Driver) -> Result<Self, JitError> {
+        Ok(Self {
+            driver: Mutex::new(driver),
+            engine: JitEngine::new()?,
+            loaded: HashMap::new(),
+        })
+    }
+
+    /// Returns the driver, e.g. to change its source files.
+    pub fn driver_mut(&mut self) -> &mut Driver {
+        self.driver.get_mut()
+    }
+
+    /// Compiles the assembly at `library_path` into the running process.
+    ///
+    /// # Safety
+    ///
+    /// See [`Assembly::load_static`].
+    pub unsafe fn load(
+        &mut self,
+        library_path: &Path,
+        allocator: Arc<Allocator>,
+    ) -> Result<Assembly, LoadError> {
+        let driver = self.driver.get_mut();
+        if let Some(errors) = compiler_errors(driver) {
+            return Err(LoadError::CompileError(errors));
+        }
+
+        let object = driver.assembly_object(library_path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no assembly exists at `{}`", library_path.display()),
+            )
+        })?;
+
+        let name = library_path
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let jit_assembly = self.engine.add_assembly(&name, object.as_bytes())?;
+        let assembly = Assembly::load_jit(library_path, jit_assembly, allocator)?;
+
+        self.loaded.insert(library_path.to_path_buf(), object);
+        Ok(assembly)
+    }
+
+    /// Adds the loaded assemblies whose object file changed to
+    /// `assemblies_to_relink`. Returns true if there are any, and the driver
+    /// doesn't contain compiler errors.
+    pub fn poll_changes(&mut self, assemblies_to_relink: &mut BTreeMap<PathBuf, PathBuf>) -> bool {
+        let driver = self.driver.get_mut();
+        if let Some(errors) = compiler_errors(driver) {
+            error!("Failed to compile assemblies:\n{errors}");
+            return false;
+        }
+
+        let mut requires_relink = false;
+        for (library_path, loaded_object) in &self.loaded {
+            let changed = driver
+                .assembly_object(library_path)
+                .map_or(false, |object| object != *loaded_object);
+            if changed {
+                assemblies_to_relink.insert(library_path.clone(), library_path.clone());
+                requires_relink = true;
+            }
+        }
+        requires_relink
+    }
+}
+
+/// Returns the compiler errors of `driver`, if there are any.
+fn compiler_errors(driver: &Driver) -> Option<String> {
+    match driver.emit_diagnostics_to_string(DisplayColor::Disable) {
+        Ok(errors) => errors,
+        Err(error) => Some(error.to_string()),
+    }
+}
@@ -5,14 +5,21 @@
 #![warn(missing_docs)]
 
 mod assembly;
+mod asset;
 #[macro_use]
 mod garbage_collector;
 mod adt;
 mod array;
 mod dispatch_table;
+mod execution_state;
 mod function_info;
+mod heap_snapshot;
+#[cfg(feature = "jit")]
+mod jit;
 mod marshal;
 mod reflection;
+mod reload;
+mod shared;
 mod utils;
 
 use std::{
@@ -21,40 +28,51 @@ use std::{
     ffi,
     ffi::c_void,
     fmt::{Debug, Display, Formatter},
+    io,
     mem::ManuallyDrop,
     path::{Path, PathBuf},
-    ptr::NonNull,
-    sync::{
-        mpsc::{channel, Receiver},
-        Arc,
-    },
+    ptr::{self, NonNull},
+    sync::Arc,
 };
 
-use assembly::LoadError;
+use assembly::{LoadError, PendingMigration, Relinked};
+use asset::AssemblySource;
 use dispatch_table::DispatchTable;
-use garbage_collector::GarbageCollector;
+use garbage_collector::{Allocator, GarbageCollector};
 use log::{debug, error, info};
 use mun_abi as abi;
+pub use mun_abi::EntryPoints;
+use mun_abi::{ExecutionState, Interrupt};
 use mun_memory::{
     gc::{self, Array, GcRuntime},
     type_table::TypeTable,
 };
+use parking_lot::Mutex;
 // Re-export some useful types so crates dont have to depend on mun_memory as well.
-pub use mun_memory::{Field, FieldData, HasStaticType, PointerType, StructType, Type};
-use mun_project::LOCKFILE_NAME;
-use notify::{event::ModifyKind, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+pub use mun_memory::{
+    diff::FieldRenames,
+    gc::{GcPtr, HeapLimits, HeapSnapshot, LoadHeapError, ObjectSnapshot, TypeStats},
+    Field, FieldData, HasStaticType, PointerType, StructType, Type,
+};
 
 pub use crate::{
     adt::{RootedStruct, StructRef},
     array::{ArrayRef, RawArray, RootedArray},
-    assembly::{Assembly, LinkError, LinkFunctionsError},
+    assembly::{Assembly, LinkError, LinkFunctionsError, UnloadError},
+    asset::{AssetProvider, InMemoryAssets},
+    execution_state::OverflowLocation,
     function_info::{
         FunctionDefinition, FunctionPrototype, FunctionSignature, IntoFunctionDefinition,
     },
     marshal::Marshal,
     reflection::{ArgumentReflection, ReturnTypeReflection},
+    reload::{FailedMigration, MappedTypeReport, ReloadReport},
+    shared::{SharedRuntime, SharedRuntimeGuard},
 };
 
+/// A callback that is invoked with the report of every hot reload.
+pub type ReloadObserver = Box<dyn FnMut(&ReloadReport) + Send>;
+
 /// Options for the construction of a [`Runtime`].
 pub struct RuntimeOptions {
     /// Path to the entry point library
@@ -63,6 +81,19 @@ pub struct RuntimeOptions {
     pub type_table: TypeTable,
     /// Custom user injected functions
     pub user_functions: Vec<FunctionDefinition>,
+    /// Limits on the amount of memory that is allocated by Mun code
+    pub heap_limits: HeapLimits,
+    /// Fields of structs that were renamed, which are taken into account when
+    /// objects are converted during hot reloading
+    pub field_renames: FieldRenames,
+    /// Callback that is invoked after every hot reload
+    pub reload_observer: Option<ReloadObserver>,
+    /// Provider of the munlibs. If there is none, munlibs are loaded from the
+    /// filesystem and reloaded when they change on disk.
+    pub asset_provider: Option<Box<dyn AssetProvider>>,
+    /// Entry points of an assembly that is statically linked into the host.
+    /// If set, the assembly is not loaded from a munlib.
+    pub static_assembly: Option<EntryPoints>,
 }
 
 /// Retrieve the allocator using the provided handle.
@@ -71,8 +102,8 @@ pub struct RuntimeOptions {
 ///
 /// The allocator must have been set using the `set_allocator_handle` call -
 /// exposed by the Mun library.
-unsafe fn get_allocator(alloc_handle: *mut ffi::c_void) -> Arc<GarbageCollector> {
-    Arc::from_raw(alloc_handle as *const GarbageCollector)
+unsafe fn get_allocator(alloc_handle: *mut ffi::c_void) -> Arc<Allocator> {
+    Arc::from_raw(alloc_handle as *const Allocator)
 }
 
 /// Retrieve the `TypeInfo` using the provided handle.
@@ -121,14 +152,91 @@ extern "C" fn new_array(
     // of this function.
     let allocator = ManuallyDrop::new(unsafe { get_allocator(alloc_handle) });
 
-    let handle = allocator.as_ref().alloc_array(&type_info, length);
+    allocator.as_ref().alloc_array(&type_info, length).into()
+}
 
-    handle.as_raw().into()
+extern "C" fn try_new(
+    type_handle: *const ffi::c_void,
+    alloc_handle: *mut ffi::c_void,
+) -> *const *mut ffi::c_void {
+    // SAFETY: The runtime always constructs and uses `Arc<TypeInfo>::into_raw` to
+    // set the type type handles in the type LUT.
+    let type_info = ManuallyDrop::new(unsafe { get_type_info(type_handle) });
+
+    // Safety: `try_new` is only called from within Mun assemblies' core logic, so
+    // we are guaranteed that the `Runtime` and its `GarbageCollector` still exist
+    // if this function is called, and will continue to do so for the duration
+    // of this function.
+    let allocator = ManuallyDrop::new(unsafe { get_allocator(alloc_handle) });
+
+    // If the allocation fails, `ptr::null()` is returned and the Mun code returns
+    // because it was interrupted.
+    allocator
+        .as_ref()
+        .try_alloc(&type_info)
+        .map_or(ptr::null(), Into::into)
 }
 
+extern "C" fn try_new_array(
+    type_handle: *const ffi::c_void,
+    length: usize,
+    alloc_handle: *mut ffi::c_void,
+) -> *const *mut ffi::c_void {
+    // SAFETY: The runtime always constructs and uses `Arc<TypeInfo>::into_raw` to
+    // set the type type handles in the type LUT.
+    let type_info = ManuallyDrop::new(unsafe { get_type_info(type_handle) });
+
+    // Safety: `try_new_array` is only called from within Mun assemblies' core
+    // logic, so we are guaranteed that the `Runtime` and its `GarbageCollector`
+    // still exist if this function is called, and will continue to do so for
+    // the duration of this function.
+    let allocator = ManuallyDrop::new(unsafe { get_allocator(alloc_handle) });
+
+    // If the allocation fails, `ptr::null()` is returned and the Mun code returns
+    // because it was interrupted.
+    allocator
+        .as_ref()
+        .try_alloc_array(&type_info, length)
+        .map_or(ptr::null(), Into::into)
+}
+
+extern "C" fn gc_write_barrier(object: *const *mut ffi::c_void, alloc_handle: *mut ffi::c_void) {
+    // Safety: `gc_write_barrier` is only called from within Mun assemblies' core logic, so we are
+    // guaranteed that the `Runtime` and its `GarbageCollector` still exist if this function is
+    // called, and will continue to do so for the duration of this function.
+    let allocator = ManuallyDrop::new(unsafe { get_allocator(alloc_handle) });
+
+    allocator.gc().write_barrier(object.into());
+}
+
+extern "C" fn overflow_trap(file: *const u8, line: u32, column: u32) {
+    // Safety: The Mun Compiler guarantees that `file` always points to a null-terminated string
+    // that contains the path of a source file.
+    let file = unsafe { ffi::CStr::from_ptr(file.cast()) };
+
+    // Unwinding through Mun code is not supported, so the Mun code is interrupted instead. The
+    // generated code returns immediately after the trap.
+    execution_state::interrupt_overflow(OverflowLocation {
+        file: file.to_string_lossy().into_owned(),
+        line,
+        column,
+    });
+}
+
+extern "C" fn execution_state() -> *mut ffi::c_void {
+    execution_state::current_ptr().cast_mut().cast()
+}
+
+/// The budget that is used when no budget has been set. Even at a billion
+/// budget checks per second, it takes centuries to exhaust.
+const UNLIMITED_BUDGET: u64 = u64::MAX;
+
 /// A builder for the [`Runtime`].
 pub struct RuntimeBuilder {
     options: RuntimeOptions,
+    /// The compiler driver whose assemblies are compiled into the process
+    #[cfg(feature = "jit")]
+    driver: Option<mun_compiler::Driver>,
 }
 
 impl RuntimeBuilder {
@@ -140,7 +248,14 @@ impl RuntimeBuilder {
                 library_path: library_path.into(),
                 type_table: TypeTable::default(),
                 user_functions: Vec::default(),
+                heap_limits: HeapLimits::default(),
+                field_renames: FieldRenames::default(),
+                reload_observer: None,
+                asset_provider: None,
+                static_assembly: None,
             },
+            #[cfg(feature = "jit")]
+            driver: None,
         }
     }
 
@@ -154,6 +269,66 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Limits the amount of memory that is allocated by Mun code.
+    ///
+    /// When more memory is allocated than the soft limit allows, garbage is
+    /// collected during the next [`Runtime::update`]. The soft limit is not
+    /// checked when memory is allocated, so Mun code can exceed it in between
+    /// updates. An allocation by Mun code that would exceed the hard limit
+    /// fails and interrupts the invoked function, which results in an error.
+    /// The hard limit only applies to code that was compiled with heap limit
+    /// checks. Allocations made through the runtime's API are not limited.
+    pub fn heap_limits(mut self, limits: HeapLimits) -> Self {
+        self.options.heap_limits = limits;
+        self
+    }
+
+    /// Declares that the field `old_name` of the struct `struct_name` was
+    /// renamed to `new_name`.
+    ///
+    /// When the struct changes during a hot reload, the value of the old field
+    /// is moved to the renamed field, converting it if its type changed as
+    /// well. Without the declaration, the old field would be removed and the
+    /// renamed field would be zero-initialized, unless both fields have the
+    /// same type.
+    pub fn rename_field(
+        mut self,
+        struct_name: impl Into<String>,
+        old_name: impl Into<String>,
+        new_name: impl Into<String>,
+    ) -> Self {
+        self.options
+            .field_renames
+            .insert(struct_name, old_name, new_name);
+        self
+    }
+
+    /// Sets a callback that is invoked with a report of the changes of every hot
+    /// reload, from within [`Runtime::update`].
+    pub fn on_reload(mut self, observer: impl FnMut(&ReloadReport) + Send + 'static) -> Self {
+        self.options.reload_observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Loads munlibs from `provider` instead of the filesystem. The library
+    /// path of the builder then identifies the entry point library within
+    /// the provider, and assemblies are reloaded when the provider reports
+    /// that they changed.
+    pub fn asset_provider(mut self, provider: impl AssetProvider + 'static) -> Self {
+        self.options.asset_provider = Some(Box::new(provider));
+        self
+    }
+
+    /// Initializes the runtime from a static library that is linked into the
+    /// host, using the entry points returned by its registration function
+    /// (e.g. `mun_register_my_game`). The library path of the builder then
+    /// only identifies the assembly. Statically linked assemblies cannot be
+    /// hot reloaded.
+    pub fn static_assembly(mut self, entry_points: EntryPoints) -> Self {
+        self.options.static_assembly = Some(entry_points);
+        self
+    }
+
     /// Constructs a [`Runtime`] with the builder's options.
     ///
     /// # Safety
@@ -171,6 +346,12 @@ impl RuntimeBuilder {
     ///
     /// See [`Assembly::load`] for more information.
     pub unsafe fn finish(self) -> Result<Runtime, InitError> {
+        #[cfg(feature = "jit")]
+        if let Some(driver) = self.driver {
+            let source = AssemblySource::Jit(jit::JitSource::new(driver)?);
+            return Runtime::with_source(self.options, source);
+        }
+
         Runtime::new(self.options)
     }
 }
@@ -184,6 +365,10 @@ pub enum InitError {
     /// Failed to construct watcher
     #[error(transparent)]
     Watcher(#[from] notify::Error),
+    /// Failed to construct the JIT
+    #[cfg(feature = "jit")]
+    #[error(transparent)]
+    Jit(#[from] mun_codegen::JitError),
 }
 
 /// A runtime for the Mun language.
@@ -196,6 +381,21 @@ pub enum InitError {
 ///
 /// [log]: https://docs.rs/log
 /// [log-impl]: https://docs.rs/log/0.4.13/log/#available-logging-implementations
+///
+/// # Threading
+///
+/// The runtime is `Send` and `Sync`, so Mun functions can be invoked from
+/// multiple threads through a shared reference at the same time. Allocations
+/// made by Mun code are thread-safe. Updating the runtime requires exclusive
+/// access, so the borrow checker guarantees that no invocations are in flight
+/// during a hot reload. To share the runtime between threads that outlive a
+/// scope, use a [`SharedRuntime`].
+///
+/// Every invocation has its own execution budget, so an invocation that is
+/// interrupted doesn't affect invocations on other threads. The heap limits
+/// are shared by all threads. Garbage must not be collected with
+/// [`Runtime::gc_collect`] or [`Runtime::gc_step`] while other threads are
+/// executing Mun code.
 pub struct Runtime {
     assemblies: HashMap<PathBuf, Assembly>,
     /// Assemblies that have changed and thus need to be relinked. Maps the old
@@ -203,10 +403,14 @@ pub struct Runtime {
     assemblies_to_relink: BTreeMap<PathBuf, PathBuf>,
     dispatch_table: DispatchTable,
     type_table: TypeTable,
-    watcher: RecommendedWatcher,
-    watcher_rx: Receiver<notify::Result<Event>>,
-    renamed_files: HashMap<usize, PathBuf>,
+    source: AssemblySource,
     gc: Arc<GarbageCollector>,
+    /// The allocator that is shared with all loaded assemblies.
+    allocator: Arc<Allocator>,
+    field_renames: FieldRenames,
+    /// The observer is only invoked with exclusive access to the runtime, but
+    /// it isn't `Sync` by itself.
+    reload_observer: Option<Mutex<ReloadObserver>>,
 }
 
 impl Runtime {
@@ -216,9 +420,31 @@ impl Runtime {
         RuntimeBuilder::new(library_path)
     }
 
+    /// Constructs a new [`RuntimeBuilder`] for a runtime that compiles the
+    /// assemblies of `driver` into the running process, instead of loading
+    /// munlibs. Nothing is written to the filesystem. After changing source
+    /// files through [`Runtime::driver_mut`], [`Runtime::update`] reloads the
+    /// assemblies that changed. The driver has to compile for the host target.
+    #[cfg(feature = "jit")]
+    pub fn jit_builder(driver: mun_compiler::Driver) -> RuntimeBuilder {
+        let mut builder = RuntimeBuilder::new(driver.root_assembly_path());
+        builder.driver = Some(driver);
+        builder
+    }
+
+    /// Returns the compiler driver of a runtime that was constructed with
+    /// [`Runtime::jit_builder`].
+    #[cfg(feature = "jit")]
+    pub fn driver_mut(&mut self) -> Option<&mut mun_compiler::Driver> {
+        match &mut self.source {
+            AssemblySource::Jit(source) => Some(source.driver_mut()),
+            _ => None,
+        }
+    }
+
     /// Constructs a new `Runtime` that loads the library at `library_path` and
-    /// its dependencies. The `Runtime` contains a file watcher that is
-    /// triggered with an interval of `dur`.
+    /// its dependencies. Unless an [`AssetProvider`] is specified, the
+    /// `Runtime` contains a file watcher that detects changes to the library.
     ///
     /// # Safety
     ///
@@ -235,8 +461,20 @@ impl Runtime {
     ///
     /// See [`Assembly::load`] for more information.
     pub unsafe fn new(mut options: RuntimeOptions) -> Result<Runtime, InitError> {
-        let (tx, rx) = channel();
+        let source = AssemblySource::new(options.asset_provider.take(), options.static_assembly)?;
+        Self::with_source(options, source)
+    }
 
+    /// Constructs a new `Runtime` that loads the library at `library_path` and
+    /// its dependencies from `source`.
+    ///
+    /// # Safety
+    ///
+    /// See [`Runtime::new`].
+    unsafe fn with_source(
+        mut options: RuntimeOptions,
+        source: AssemblySource,
+    ) -> Result<Runtime, InitError> {
         let mut dispatch_table = DispatchTable::default();
         let type_table = options.type_table;
 
@@ -256,29 +494,60 @@ impl Runtime {
             "new_array",
         ));
 
+        options.user_functions.push(IntoFunctionDefinition::into(
+            try_new
+                as extern "C" fn(*const ffi::c_void, *mut ffi::c_void) -> *const *mut ffi::c_void,
+            "try_new",
+        ));
+
+        options.user_functions.push(IntoFunctionDefinition::into(
+            try_new_array
+                as extern "C" fn(
+                    *const ffi::c_void,
+                    usize,
+                    *mut ffi::c_void,
+                ) -> *const *mut ffi::c_void,
+            "try_new_array",
+        ));
+
+        options.user_functions.push(IntoFunctionDefinition::into(
+            overflow_trap as extern "C" fn(*const u8, u32, u32),
+            "overflow_trap",
+        ));
+
+        options.user_functions.push(IntoFunctionDefinition::into(
+            execution_state as extern "C" fn() -> *mut ffi::c_void,
+            "execution_state",
+        ));
+
+        options.user_functions.push(IntoFunctionDefinition::into(
+            gc_write_barrier as extern "C" fn(*const *mut ffi::c_void, *mut ffi::c_void),
+            "gc_write_barrier",
+        ));
+
         options.user_functions.into_iter().for_each(|fn_def| {
             dispatch_table.insert_fn(fn_def.prototype.name.clone(), Arc::new(fn_def));
         });
 
-        let watcher: RecommendedWatcher = notify::recommended_watcher(move |res| {
-            tx.send(res).expect("Failed to send filesystem event.");
-        })?;
+        let gc = Arc::new(GarbageCollector::default().with_limits(options.heap_limits));
         let mut runtime = Runtime {
             assemblies: HashMap::new(),
             assemblies_to_relink: BTreeMap::new(),
             dispatch_table,
             type_table,
-            watcher,
-            watcher_rx: rx,
-            renamed_files: HashMap::new(),
-            gc: Arc::new(self::garbage_collector::GarbageCollector::default()),
+            source,
+            allocator: Arc::new(Allocator::new(gc.clone())),
+            gc,
+            field_renames: options.field_renames,
+            reload_observer: options.reload_observer.map(Mutex::new),
         };
 
         runtime.add_assembly(&options.library_path)?;
         Ok(runtime)
     }
 
-    /// Adds an assembly corresponding to the library at `library_path`.
+    /// Adds an assembly corresponding to the library at `library_path`, and
+    /// its dependencies. Use [`Runtime::remove_assembly`] to remove it again.
     ///
     /// # Safety
     ///
@@ -294,9 +563,10 @@ impl Runtime {
     /// unloaded.
     ///
     /// See [`Assembly::load`] for more information.
-    unsafe fn add_assembly(&mut self, library_path: &Path) -> Result<(), LinkError> {
-        let library_path = library_path
-            .canonicalize()
+    pub unsafe fn add_assembly(&mut self, library_path: &Path) -> Result<(), LinkError> {
+        let library_path = self
+            .source
+            .identify(library_path)
             .map_err(|e| LinkError::LoadAssembly(LoadError::Other(e)))?;
 
         if self.assemblies.contains_key(&library_path) {
@@ -315,21 +585,14 @@ impl Runtime {
                 continue;
             }
 
-            let assembly = Assembly::load(&library_path, self.gc.clone())?;
-
-            let parent = library_path.parent().expect("Invalid library path");
-            let extension = library_path.extension();
+            let assembly = self.source.load(&library_path, self.allocator.clone())?;
 
             let dependencies: Vec<String> =
                 assembly.info().dependencies().map(From::from).collect();
             loaded.insert(library_path.clone(), assembly);
 
             for dependency in dependencies {
-                let mut library_path = parent.join(dependency);
-                if let Some(extension) = extension {
-                    library_path = library_path.with_extension(extension);
-                }
-
+                let library_path = self.source.resolve_dependency(&library_path, &dependency);
                 if !loaded.contains_key(&library_path) {
                     to_load.push_back(library_path);
                 }
@@ -340,16 +603,63 @@ impl Runtime {
             Assembly::link_all(loaded.values_mut(), &self.dispatch_table, &self.type_table)?;
 
         for (library_path, assembly) in loaded {
-            self.watcher
-                .watch(library_path.parent().unwrap(), RecursiveMode::NonRecursive)
-                .expect("Path must exist as we just loaded the library");
-
+            self.source.watch(&library_path);
             self.assemblies.insert(library_path, assembly);
         }
 
         Ok(())
     }
 
+    /// Removes the assembly corresponding to the library at `library_path`,
+    /// and unloads its library.
+    ///
+    /// Garbage is collected first. The assembly is only removed if no other
+    /// assembly depends on it, and if none of the remaining functions and live
+    /// objects use its types. Otherwise, the runtime is left unchanged.
+    ///
+    /// # Safety
+    ///
+    /// The callers of this function must ensure that execution of the
+    /// termination routines contained within the library is safe. An
+    /// [`Arc<FunctionDefinition>`] of the assembly that was obtained earlier,
+    /// e.g. through [`Runtime::get_function_definition`], still points at the
+    /// unloaded code afterwards, so its function pointer must no longer be
+    /// called.
+    pub unsafe fn remove_assembly(&mut self, library_path: &Path) -> Result<(), UnloadError> {
+        let library_path = self
+            .source
+            .identify(library_path)
+            .ok()
+            .filter(|library_path| self.assemblies.contains_key(library_path))
+            .ok_or_else(|| UnloadError::NotLoaded(library_path.to_path_buf()))?;
+
+        for (dependant_path, dependant) in self.assemblies.iter() {
+            let dependencies: Vec<String> =
+                dependant.info().dependencies().map(From::from).collect();
+            for dependency in dependencies {
+                if self.source.resolve_dependency(dependant_path, &dependency) == library_path {
+                    return Err(UnloadError::Dependency(dependant_path.clone()));
+                }
+            }
+        }
+
+        let assembly = &self.assemblies[&library_path];
+        (self.dispatch_table, self.type_table) =
+            assembly.unlink(&self.dispatch_table, &self.type_table)?;
+
+        self.assemblies.remove(&library_path);
+        self.assemblies_to_relink.remove(&library_path);
+        self.source.unwatch(&library_path, self.assemblies.keys());
+        Type::collect_unreferenced_type_data();
+
+        Ok(())
+    }
+
+    /// Returns the paths of the libraries of all loaded assemblies.
+    pub fn assemblies(&self) -> impl Iterator<Item = &Path> {
+        self.assemblies.keys().map(PathBuf::as_path)
+    }
+
     /// Retrieves the function definition corresponding to `function_name`, if
     /// available.
     pub fn get_function_definition(&self, function_name: &str) -> Option<Arc<FunctionDefinition>> {
@@ -378,7 +688,10 @@ impl Runtime {
                     None
                 }
             })
-            .min_by(|(_, dist1), (_, dist2)| dist1.cmp(dist2));
+            // Prefer the first name in alphabetical order if multiple names are
+            // equally similar, so the suggestion doesn't depend on the order of
+            // `fn_names`.
+            .min_by_key(|(name, dist)| (*dist, *name));
         found_match.map(|(closest_name, _)| closest_name)
     }
 
@@ -395,7 +708,9 @@ impl Runtime {
     }
 
     /// Updates the state of the runtime. This includes checking for file
-    /// changes, and reloading compiled assemblies.
+    /// changes, reloading compiled assemblies, and collecting garbage when the
+    /// soft heap limit has been exceeded.
+    ///
     /// # Safety
     ///
     /// A munlib is simply a shared object. When a library is loaded,
@@ -411,13 +726,19 @@ impl Runtime {
     ///
     /// See [`Assembly::load`] for more information.
     pub unsafe fn update(&mut self) -> bool {
-        fn is_lockfile(path: &Path) -> bool {
-            path.file_name().expect("Invalid file path.") == LOCKFILE_NAME
-        }
+        self.update_with_report().is_some()
+    }
 
+    /// Updates the state of the runtime like [`Runtime::update`], and returns a
+    /// report of the changes if the runtime was updated.
+    ///
+    /// # Safety
+    ///
+    /// See [`Runtime::update`].
+    pub unsafe fn update_with_report(&mut self) -> Option<ReloadReport> {
         unsafe fn relink_assemblies(
             runtime: &mut Runtime,
-        ) -> Result<(DispatchTable, TypeTable), LinkError> {
+        ) -> Result<(DispatchTable, TypeTable, Relinked), LinkError> {
             let mut loaded = HashMap::new();
             let to_load = &mut runtime.assemblies_to_relink;
 
@@ -438,21 +759,14 @@ impl Runtime {
                     continue;
                 }
 
-                let assembly = Assembly::load(&new_path, runtime.gc.clone())?;
-
-                let parent = new_path.parent().expect("Invalid library path");
-                let extension = new_path.extension();
+                let assembly = runtime.source.load(&new_path, runtime.allocator.clone())?;
 
                 let dependencies: Vec<String> =
                     assembly.info().dependencies().map(From::from).collect();
                 loaded.insert(old_path.clone(), assembly);
 
                 for dependency in dependencies {
-                    let mut library_path = parent.join(dependency);
-                    if let Some(extension) = extension {
-                        library_path = library_path.with_extension(extension);
-                    }
-
+                    let library_path = runtime.source.resolve_dependency(&new_path, &dependency);
                     if !loaded.contains_key(&library_path)
                         && !runtime.assemblies.contains_key(&library_path)
                     {
@@ -466,89 +780,276 @@ impl Runtime {
                 &mut runtime.assemblies,
                 &runtime.dispatch_table,
                 &runtime.type_table,
+                &runtime.field_renames,
             )
         }
 
-        let mut requires_relink = false;
-        while let Ok(Ok(event)) = self.watcher_rx.try_recv() {
-            for path in event.paths {
-                if is_lockfile(&path) {
-                    match event.kind {
-                        EventKind::Create(_) => debug!("Lockfile created"),
-                        EventKind::Remove(_) => {
-                            debug!("Lockfile deleted");
+        if self.gc.needs_collection() {
+            self.gc.collect();
+        }
 
-                            requires_relink = true;
-                        }
-                        _ => (),
-                    }
-                } else {
-                    let path = path.canonicalize().unwrap_or_else(|_| {
-                        panic!("Failed to canonicalize path: {}.", path.to_string_lossy())
-                    });
+        let requires_relink = self.source.poll_changes(&mut self.assemblies_to_relink);
 
-                    match event.kind {
-                        EventKind::Modify(ModifyKind::Name(_)) => {
-                            let tracker = event.attrs.tracker().expect("Invalid RENAME event.");
-                            if let Some(old_path) = self.renamed_files.remove(&tracker) {
-                                self.assemblies_to_relink.insert(old_path, path);
-                                // on_file_changed(self, &old_path, &path);
-                            } else {
-                                self.renamed_files.insert(tracker, path);
-                            }
-                        }
-                        EventKind::Modify(_) => {
-                            // TODO: don't overwrite existing
-                            self.assemblies_to_relink.insert(path.clone(), path);
-                        }
-                        _ => (),
-                    }
-                }
-            }
-        }
+        // Only reload assemblies that are still loaded
+        let assemblies = &self.assemblies;
+        self.assemblies_to_relink
+            .retain(|old_path, _| assemblies.contains_key(old_path));
 
         if requires_relink {
             if self.assemblies_to_relink.is_empty() {
                 debug!("The compiler didn't write a munlib.");
             } else {
                 match relink_assemblies(self) {
-                    Ok((dispatch_table, type_table)) => {
+                    Ok((dispatch_table, type_table, relinked)) => {
                         info!("Succesfully reloaded assemblies.");
 
+                        let mut report = ReloadReport {
+                            assemblies: relinked.assemblies,
+                            mapped_types: relinked.mapped_types,
+                            ..ReloadReport::default()
+                        };
+                        report.diff_functions(&self.dispatch_table, &dispatch_table);
+
                         self.dispatch_table = dispatch_table;
                         self.type_table = type_table;
                         self.assemblies_to_relink.clear();
 
-                        return true;
+                        report.failed_migrations = self.migrate(relinked.migrations);
+
+                        if let Some(observer) = self.reload_observer.as_mut() {
+                            (observer.get_mut())(&report);
+                        }
+                        return Some(report);
                     }
                     Err(e) => error!("Failed to relink assemblies: {e}"),
                 }
             }
         }
 
-        false
+        None
+    }
+
+    /// Invokes the user-defined migrations of objects whose struct type changed
+    /// during a hot reload. Returns the migrations that failed.
+    fn migrate(&self, migrations: Vec<PendingMigration>) -> Vec<FailedMigration> {
+        let mut failed_migrations = Vec::new();
+        for PendingMigration {
+            function_name,
+            object,
+            old_contents,
+        } in migrations
+        {
+            let arguments = (
+                StructRef::new(adt::RawStruct(object), self),
+                StructRef::new(adt::RawStruct(old_contents), self),
+            );
+            let result = self
+                .invoke::<(), _>(&function_name, arguments)
+                .map_err(|e| e.to_string());
+            if let Err(error) = result {
+                error!("Failed to migrate object with `{function_name}`: {error}");
+                failed_migrations.push(FailedMigration {
+                    function_name,
+                    error,
+                });
+            }
+
+            self.gc.unroot(old_contents);
+        }
+        failed_migrations
     }
 
     /// Returns a shared reference to the runtime's garbage collector.
     ///
-    /// We cannot return an `Arc` here, because the lifetime of data contained
-    /// in `GarbageCollector` is dependent on the `Runtime`.
-    pub fn gc(&self) -> &GarbageCollector {
+    /// This is not public, because the garbage collector allows collecting
+    /// garbage through a shared reference, while Mun code may be executing on
+    /// other threads.
+    pub(crate) fn gc(&self) -> &GarbageCollector {
         self.gc.as_ref()
     }
 
+    /// Allocates an object of type `ty` on the heap. The object is not rooted,
+    /// so it is collected unless it is rooted or referenced by a rooted object.
+    pub fn gc_alloc(&self, ty: &Type) -> GcPtr {
+        self.gc.alloc(ty)
+    }
+
+    /// Returns the type of the object that `obj` refers to.
+    pub fn gc_ptr_type(&self, obj: GcPtr) -> Type {
+        self.gc.ptr_type(obj)
+    }
+
+    /// Returns the array that `obj` refers to, or `None` if `obj` doesn't
+    /// refer to an array.
+    pub fn gc_array(&self, obj: GcPtr) -> Option<impl Array> {
+        self.gc.array(obj)
+    }
+
+    /// Roots `obj`, which keeps it and the objects it references alive. An
+    /// object can be rooted multiple times; it can only be collected after
+    /// [`Runtime::gc_unroot`] was called an equal number of times.
+    pub fn gc_root(&self, obj: GcPtr) {
+        self.gc.root(obj);
+    }
+
+    /// Unroots `obj`, potentially allowing it and the objects it references to
+    /// be collected.
+    pub fn gc_unroot(&self, obj: GcPtr) {
+        self.gc.unroot(obj);
+    }
+
+    /// Informs the garbage collector that a reference was stored in `obj`.
+    /// This must be called after storing references in an object from outside
+    /// of Mun code, so an incremental collection cycle does not miss them.
+    pub fn gc_write_barrier(&self, obj: GcPtr) {
+        self.gc.write_barrier(obj);
+    }
+
     /// Collects all memory that is no longer referenced by rooted objects.
     /// Returns `true` if memory was reclaimed, `false` otherwise. This
     /// behavior will likely change in the future.
-    pub fn gc_collect(&self) -> bool {
+    ///
+    /// Collecting requires exclusive access, so no Mun functions can be
+    /// executing on other threads. Use [`SharedRuntime::gc_collect`] to collect
+    /// garbage while the runtime is shared.
+    pub fn gc_collect(&mut self) -> bool {
         self.gc.collect()
     }
 
+    /// Performs a step of an incremental garbage collection cycle, starting a
+    /// new cycle if none is in progress. Every step processes roughly `budget`
+    /// objects, which bounds the time it takes. Returns `true` if the step
+    /// completed the cycle.
+    ///
+    /// Requires exclusive access, because references that are only held by
+    /// executing Mun code are not considered roots.
+    pub fn gc_step(&mut self, budget: usize) -> bool {
+        self.gc.collect_step(budget)
+    }
+
     /// Returns statistics about the garbage collector.
     pub fn gc_stats(&self) -> gc::Stats {
         self.gc.stats()
     }
 
+    /// Returns the number of objects and bytes that are currently allocated
+    /// for each type. This includes garbage that has not been collected yet.
+    pub fn gc_type_stats(&self) -> HashMap<Type, TypeStats> {
+        self.gc.type_stats()
+    }
+
+    /// Returns a snapshot of all objects that are currently allocated,
+    /// including garbage that has not been collected yet. Use
+    /// [`HeapSnapshot::path_to_root`] to find out why an object is kept alive.
+    pub fn gc_snapshot(&self) -> HeapSnapshot {
+        self.gc.snapshot()
+    }
+
+    /// Writes a snapshot of all objects that are currently allocated to
+    /// `writer`, in the `.heapsnapshot` JSON format of the V8 JavaScript
+    /// engine. The memory tools of Chromium-based browsers can load these
+    /// files, to show how much memory every type uses and which references
+    /// retain an object.
+    pub fn write_heap_snapshot(&self, writer: impl io::Write) -> io::Result<()> {
+        heap_snapshot::write_heap_snapshot(&self.gc.snapshot(), writer)
+    }
+
+    /// Writes the structs in `roots` and all objects that they reference to
+    /// `writer`. Fields are stored by name, so [`Runtime::load_heap`] can load
+    /// the objects even after their types have changed.
+    pub fn save_heap(&self, roots: &[StructRef<'_>], writer: impl io::Write) -> io::Result<()> {
+        let roots = roots
+            .iter()
+            .map(|root| root.clone().into_raw().0)
+            .collect::<Vec<_>>();
+        self.gc.save_heap(&roots, writer)
+    }
+
+    /// Loads the objects that were saved with [`Runtime::save_heap`] from
+    /// `reader`, and returns the saved roots in the same order.
+    ///
+    /// Structs that have changed since the objects were saved are converted to
+    /// their current version, in the same way as during a hot reload.
+    pub fn load_heap(&self, reader: impl io::Read) -> Result<Vec<RootedStruct>, LoadHeapError> {
+        let roots = self.gc.load_heap(&self.type_table, reader)?;
+
+        // Only structs can be saved as roots
+        let result = if roots.iter().all(|root| self.gc.ptr_type(*root).is_struct()) {
+            Ok(roots
+                .iter()
+                .map(|root| StructRef::new(adt::RawStruct(*root), self).root())
+                .collect())
+        } else {
+            Err(LoadHeapError::Corrupt)
+        };
+
+        for root in roots {
+            self.gc.unroot(root);
+        }
+        result
+    }
+
+    /// Returns true if Mun code that executes on the current thread was
+    /// interrupted because an allocation exceeded the hard heap limit, and
+    /// clears the interrupt.
+    ///
+    /// While Mun code is interrupted, all Mun functions on the stack return
+    /// immediately and the values they return are meaningless.
+    /// [`Runtime::invoke`] and [`Runtime::invoke_with_budget`] take care of
+    /// this; this function is only needed when Mun functions are called
+    /// directly.
+    pub fn take_heap_limit_exceeded(&self) -> bool {
+        execution_state::with_current(|state| {
+            let exceeded = state.interrupted() == Some(Interrupt::HeapLimitExceeded);
+            if exceeded {
+                state.take_interrupt();
+            }
+            exceeded
+        })
+    }
+
+    /// Limits the amount of work that Mun code on the current thread may
+    /// perform, and clears any interrupt. Every function call and loop
+    /// iteration consumes one unit of budget. When Mun code tries to consume
+    /// more budget than is left, all Mun functions on the stack return
+    /// immediately. Passing `None` removes the limit.
+    ///
+    /// Only assemblies that were compiled with budget checks consume budget.
+    /// The value returned by a function that ran out of budget is meaningless
+    /// and must be discarded; prefer [`Runtime::invoke_with_budget`], which
+    /// takes care of this. The budget applies to Mun functions that are called
+    /// directly and through [`Runtime::invoke`].
+    pub fn set_budget(&self, budget: Option<u64>) {
+        execution_state::with_current(|state| state.reset(budget.unwrap_or(UNLIMITED_BUDGET)));
+    }
+
+    /// Returns true if Mun code that executes on the current thread was
+    /// interrupted because it ran out of budget.
+    pub fn is_budget_exhausted(&self) -> bool {
+        execution_state::with_current(|state| {
+            state.interrupted() == Some(Interrupt::BudgetExhausted)
+        })
+    }
+
+    /// Returns the location of the integer operation that overflowed, if Mun
+    /// code that executes on the current thread was interrupted because of an
+    /// overflow, and clears the interrupt.
+    ///
+    /// Overflows are only detected in assemblies that were compiled with
+    /// overflow checks. This function is only needed when Mun functions are
+    /// called directly; [`Runtime::invoke_with_budget`] reports overflows
+    /// itself.
+    pub fn take_overflow(&self) -> Option<OverflowLocation> {
+        execution_state::with_current(|state| {
+            if state.interrupted() == Some(Interrupt::Overflow) {
+                state.take_interrupt();
+                Some(execution_state::take_overflow_location())
+            } else {
+                None
+            }
+        })
+    }
+
     /// Constructs an array with a predefined element type.
     pub fn construct_typed_array<
         't,
@@ -655,7 +1156,9 @@ impl Runtime {
 pub struct InvokeErr<'name, T> {
     msg: String,
     function_name: &'name str,
-    arguments: T,
+    /// The arguments of the invocation, if it can be retried. An invocation
+    /// that failed during execution consumed its arguments.
+    arguments: Option<T>,
 }
 
 impl<'name, T> Debug for InvokeErr<'name, T> {
@@ -671,8 +1174,16 @@ impl<'name, T> Display for InvokeErr<'name, T> {
 }
 
 impl<'name, T: InvokeArgs> InvokeErr<'name, T> {
+    /// Returns true if the invocation failed during the execution of the
+    /// function, e.g. because the hard heap limit was exceeded. Such an
+    /// invocation cannot be retried.
+    pub fn is_execution_error(&self) -> bool {
+        self.arguments.is_none()
+    }
+
     /// Retries a function invocation once, resulting in a potentially
-    /// successful invocation.
+    /// successful invocation. An invocation that failed during execution is
+    /// not retried.
     // FIXME: `unwrap_or_else` does not compile for `StructRef`, due to
     // https://doc.rust-lang.org/nomicon/lifetime-mismatch.html#improperly-reduced-borrows
     pub fn retry<'r, 'o, Output>(self, runtime: &'r mut Runtime) -> Result<Output, Self>
@@ -687,6 +1198,11 @@ impl<'name, T: InvokeArgs> InvokeErr<'name, T> {
 
     /// Retries the function invocation until it succeeds, resulting in an
     /// output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the invocation failed during execution, as it cannot be
+    /// retried.
     // FIXME: `unwrap_or_else` does not compile for `StructRef`, due to
     // https://doc.rust-lang.org/nomicon/lifetime-mismatch.html#improperly-reduced-borrows
     pub fn wait<'r, 'o, Output>(mut self, runtime: &'r mut Runtime) -> Output
@@ -694,6 +1210,12 @@ impl<'name, T: InvokeArgs> InvokeErr<'name, T> {
         Output: 'o + ReturnTypeReflection + Marshal<'o>,
         'r: 'o,
     {
+        assert!(
+            !self.is_execution_error(),
+            "cannot retry a failed invocation: {}",
+            self.msg
+        );
+
         loop {
             self = match unsafe { self.retry_impl(runtime) } {
                 Ok(output) => return output,
@@ -715,6 +1237,10 @@ impl<'name, T: InvokeArgs> InvokeErr<'name, T> {
     where
         Output: 'o + ReturnTypeReflection + Marshal<'o>,
     {
+        let Some(arguments) = self.arguments else {
+            return Err(self);
+        };
+
         // Safety: Guaranteed by the caller to be valid to dereference.
         let runtime = &mut *runtime;
 
@@ -723,7 +1249,76 @@ impl<'name, T: InvokeArgs> InvokeErr<'name, T> {
             // Wait until there has been an update that might fix the error
         }
 
-        runtime.invoke(self.function_name, self.arguments)
+        runtime.invoke(self.function_name, arguments)
+    }
+}
+
+/// An error that might occur when calling a Mun function from Rust with a
+/// limited execution budget.
+pub enum InvokeWithBudgetErr<'name, T> {
+    /// The function could not be invoked.
+    Invoke(InvokeErr<'name, T>),
+    /// The function ran out of budget before it finished.
+    BudgetExhausted {
+        /// The name of the function that was invoked
+        function_name: &'name str,
+    },
+    /// The function was interrupted because it exceeded the hard heap limit.
+    HeapLimitExceeded {
+        /// The name of the function that was invoked
+        function_name: &'name str,
+    },
+    /// The function was interrupted because an integer operation overflowed
+    /// while overflow checks were enabled.
+    Overflow {
+        /// The name of the function that was invoked
+        function_name: &'name str,
+        /// The location of the operation that overflowed
+        location: OverflowLocation,
+    },
+}
+
+impl<'name, T> InvokeWithBudgetErr<'name, T> {
+    /// Constructs the error for a function that was interrupted because of
+    /// `interrupt`.
+    fn interrupted(function_name: &'name str, interrupt: Interrupt) -> Self {
+        match interrupt {
+            Interrupt::BudgetExhausted => InvokeWithBudgetErr::BudgetExhausted { function_name },
+            Interrupt::HeapLimitExceeded => {
+                InvokeWithBudgetErr::HeapLimitExceeded { function_name }
+            }
+            Interrupt::Overflow => InvokeWithBudgetErr::Overflow {
+                function_name,
+                location: execution_state::take_overflow_location(),
+            },
+        }
+    }
+}
+
+impl<'name, T> Debug for InvokeWithBudgetErr<'name, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl<'name, T> Display for InvokeWithBudgetErr<'name, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvokeWithBudgetErr::Invoke(err) => Display::fmt(err, f),
+            InvokeWithBudgetErr::BudgetExhausted { function_name } => {
+                write!(f, "'{function_name}' exhausted its execution budget")
+            }
+            InvokeWithBudgetErr::HeapLimitExceeded { function_name } => {
+                write!(f, "'{function_name}' exceeded the hard heap limit")
+            }
+            InvokeWithBudgetErr::Overflow {
+                function_name,
+                location,
+            } => write!(
+                f,
+                "'{function_name}' attempted to compute a value that overflows its type at {location}"
+            ),
+        }
     }
 }
 
@@ -784,6 +1379,10 @@ seq_macro::seq!(I in 0..N {
 impl Runtime {
     /// Invokes the Mun function called `function_name` with the specified
     /// `arguments`.
+    ///
+    /// The function may consume the execution budget that is left on the
+    /// current thread (see [`Runtime::set_budget`]). An error is returned if
+    /// the function is interrupted, e.g. because that budget ran out.
     pub fn invoke<
         'runtime,
         'ret,
@@ -798,6 +1397,124 @@ impl Runtime {
     where
         'runtime: 'ret,
     {
+        let function_info = match self.find_invokable_fn::<ReturnType, _>(function_name, &arguments)
+        {
+            Ok(function_info) => function_info,
+            Err(msg) => {
+                return Err(InvokeErr {
+                    msg,
+                    function_name,
+                    arguments: Some(arguments),
+                })
+            }
+        };
+
+        // The function may consume the budget that is left on this thread
+        let budget = execution_state::with_current(ExecutionState::remaining_budget);
+        let result: ReturnType::MunType =
+            match unsafe { Self::invoke_fn_ptr(function_info.fn_ptr, arguments, budget) } {
+                Ok(result) => result,
+                Err(interrupt) => {
+                    // The value returned by an interrupted function is meaningless, so it must not
+                    // be marshalled
+                    return Err(InvokeErr {
+                        msg: InvokeWithBudgetErr::<()>::interrupted(function_name, interrupt)
+                            .to_string(),
+                        function_name,
+                        arguments: None,
+                    });
+                }
+            };
+
+        Ok(Marshal::marshal_from(result, self))
+    }
+
+    /// Invokes the Mun function called `function_name` with the specified
+    /// `arguments`, limiting the amount of work it may perform to `budget`.
+    /// Every function call and loop iteration consumes one unit of budget.
+    ///
+    /// If the budget runs out, the invocation is aborted and
+    /// [`InvokeWithBudgetErr::BudgetExhausted`] is returned. Similarly,
+    /// [`InvokeWithBudgetErr::HeapLimitExceeded`] is returned when the function
+    /// exceeds the hard heap limit, and [`InvokeWithBudgetErr::Overflow`] when
+    /// an integer operation overflows. The runtime remains usable afterwards, but
+    /// any side effects that the function already had are not undone.
+    ///
+    /// When this is called from a Rust function that Mun code called, the
+    /// budget that the function consumes is also consumed from the calling
+    /// invocation.
+    ///
+    /// Only assemblies that were compiled with budget checks consume budget;
+    /// functions in other assemblies run to completion.
+    pub fn invoke_with_budget<
+        'runtime,
+        'ret,
+        'name,
+        ReturnType: ReturnTypeReflection + Marshal<'ret> + 'ret,
+        ArgTypes: InvokeArgs,
+    >(
+        &'runtime self,
+        function_name: &'name str,
+        arguments: ArgTypes,
+        budget: u64,
+    ) -> Result<ReturnType, InvokeWithBudgetErr<'name, ArgTypes>>
+    where
+        'runtime: 'ret,
+    {
+        let function_info = match self.find_invokable_fn::<ReturnType, _>(function_name, &arguments)
+        {
+            Ok(function_info) => function_info,
+            Err(msg) => {
+                return Err(InvokeWithBudgetErr::Invoke(InvokeErr {
+                    msg,
+                    function_name,
+                    arguments: Some(arguments),
+                }))
+            }
+        };
+
+        match unsafe { Self::invoke_fn_ptr(function_info.fn_ptr, arguments, budget) } {
+            Ok(result) => Ok(Marshal::marshal_from(result, self)),
+            // The value returned by an interrupted function is meaningless, so it must not be
+            // marshalled
+            Err(interrupt) => Err(InvokeWithBudgetErr::interrupted(function_name, interrupt)),
+        }
+    }
+
+    /// Calls the Mun function at `fn_ptr` with `arguments` on the current
+    /// thread, limiting the amount of work it may perform to `budget`. The
+    /// budget that it consumes is also consumed from the enclosing invocation,
+    /// if any. Returns the reason why the function was interrupted, if it was.
+    ///
+    /// # Safety
+    ///
+    /// `fn_ptr` must point to a Mun function that accepts `arguments` and
+    /// returns `ReturnType`.
+    unsafe fn invoke_fn_ptr<ReturnType, ArgTypes: InvokeArgs>(
+        fn_ptr: *const c_void,
+        arguments: ArgTypes,
+        budget: u64,
+    ) -> Result<ReturnType, Interrupt> {
+        let state = ExecutionState::new(budget);
+        let result = execution_state::run_with(&state, || arguments.invoke(fn_ptr));
+
+        let consumed = budget - state.remaining_budget();
+        execution_state::with_current(|enclosing_state| enclosing_state.consume(consumed));
+
+        match state.interrupted() {
+            Some(interrupt) => Err(interrupt),
+            None => Ok(result),
+        }
+    }
+
+    /// Finds the function called `function_name` and validates that it can be
+    /// invoked with `arguments` and returns a `ReturnType`. Returns an error
+    /// message otherwise.
+    fn find_invokable_fn<ReturnType: ReturnTypeReflection, ArgTypes: InvokeArgs>(
+        &self,
+        function_name: &str,
+        arguments: &ArgTypes,
+    ) -> Result<Arc<FunctionDefinition>, String> {
         // Get the function information from the runtime
         let function_info = match self.get_function_definition(function_name).ok_or_else(|| {
             format!("failed to obtain function '{function_name}', no such function exists.")
@@ -808,45 +1525,25 @@ impl Runtime {
                 let suggested_name =
                     Self::find_best_match_for_fn_name(function_name, available_names, None);
 
-                let suggested_message = suggested_name.map_or_else(
+                return Err(suggested_name.map_or_else(
                     || msg.clone(),
                     |name| format!("{msg} There is a function with a similar name: {name}"),
-                );
-
-                return Err(InvokeErr {
-                    msg: suggested_message,
-                    function_name,
-                    arguments,
-                });
+                ));
             }
         };
 
         // Validate the arguments
-        match arguments.can_invoke(self, &function_info.prototype.signature) {
-            Ok(_) => {}
-            Err(msg) => {
-                return Err(InvokeErr {
-                    msg,
-                    function_name,
-                    arguments,
-                })
-            }
-        };
+        arguments.can_invoke(self, &function_info.prototype.signature)?;
 
         // Validate the return type
         if !ReturnType::accepts_type(&function_info.prototype.signature.return_type) {
-            return Err(InvokeErr {
-                msg: format!(
-                    "unexpected return type, got '{}', expected '{}",
-                    &function_info.prototype.signature.return_type.name(),
-                    ReturnType::type_hint()
-                ),
-                function_name,
-                arguments,
-            });
+            return Err(format!(
+                "unexpected return type, got '{}', expected '{}",
+                &function_info.prototype.signature.return_type.name(),
+                ReturnType::type_hint()
+            ));
         }
 
-        let result: ReturnType::MunType = unsafe { arguments.invoke(function_info.fn_ptr) };
-        Ok(Marshal::marshal_from(result, self))
+        Ok(function_info)
     }
 }
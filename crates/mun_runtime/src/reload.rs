@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+
+use mun_memory::{
+    mapping::{Action, StructMapping},
+    Type,
+};
+
+use crate::{function_info::FunctionSignature, DispatchTable};
+
+/// A report of the changes that were made when assemblies were hot reloaded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// The paths of the assemblies that were reloaded
+    pub assemblies: Vec<PathBuf>,
+    /// The names of the functions that were added
+    pub added_functions: Vec<String>,
+    /// The names of the functions that were removed
+    pub removed_functions: Vec<String>,
+    /// The names of the functions whose signature changed
+    pub changed_functions: Vec<String>,
+    /// The structs whose layout changed, and how their objects were converted
+    pub mapped_types: Vec<MappedTypeReport>,
+    /// The migrations that failed to convert an object
+    pub failed_migrations: Vec<FailedMigration>,
+}
+
+impl ReloadReport {
+    /// Returns true if the values of fields were dropped from existing objects.
+    pub fn has_dropped_fields(&self) -> bool {
+        self.mapped_types
+            .iter()
+            .any(|ty| ty.object_count > 0 && !ty.dropped_fields.is_empty())
+    }
+
+    /// Records the functions that were added, removed or changed between the
+    /// `old` and the `new` dispatch table.
+    pub(crate) fn diff_functions(&mut self, old: &DispatchTable, new: &DispatchTable) {
+        for name in new.get_fn_names() {
+            match old.get_fn(name) {
+                None => self.added_functions.push(name.to_owned()),
+                Some(old_fn) => {
+                    let new_fn = new.get_fn(name).expect("function must exist");
+                    if signature_names(&old_fn.prototype.signature)
+                        != signature_names(&new_fn.prototype.signature)
+                    {
+                        self.changed_functions.push(name.to_owned());
+                    }
+                }
+            }
+        }
+
+        self.removed_functions.extend(
+            old.get_fn_names()
+                .filter(|name| new.get_fn(name).is_none())
+                .map(ToOwned::to_owned),
+        );
+
+        self.added_functions.sort();
+        self.removed_functions.sort();
+        self.changed_functions.sort();
+    }
+}
+
+/// Returns the names of the argument and return types of a signature. Types
+/// are compared by name, because a struct whose layout changed is a different
+/// type, while the signature that uses it is still the same.
+fn signature_names(signature: &FunctionSignature) -> (Vec<&str>, &str) {
+    (
+        signature.arg_types.iter().map(Type::name).collect(),
+        signature.return_type.name(),
+    )
+}
+
+/// A migration that failed to convert an object. The object keeps the values
+/// that were mapped automatically.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailedMigration {
+    /// The name of the migration function
+    pub function_name: String,
+    /// A description of the error that occurred
+    pub error: String,
+}
+
+/// Describes how the objects of a struct whose layout changed were converted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MappedTypeReport {
+    /// The name of the struct
+    pub name: String,
+    /// The number of objects that were converted. Garbage is collected before
+    /// objects are converted, so only reachable objects are counted.
+    pub object_count: usize,
+    /// The number of objects that were passed to a migration defined by the
+    /// struct
+    pub migrated_count: usize,
+    /// The fields of the old struct whose values were dropped
+    pub dropped_fields: Vec<String>,
+    /// The fields of the new struct that were zero-initialized
+    pub zero_initialized_fields: Vec<String>,
+}
+
+impl MappedTypeReport {
+    /// Constructs the report of the conversion of `object_count` objects of
+    /// `old_ty` using `mapping`.
+    pub(crate) fn new(old_ty: &Type, mapping: &StructMapping, object_count: usize) -> Self {
+        let old_offsets = mapping
+            .field_mapping
+            .iter()
+            .filter_map(|field| old_offset(&field.action))
+            .collect::<Vec<_>>();
+
+        let dropped_fields = old_ty
+            .as_struct()
+            .into_iter()
+            .flat_map(|s| s.fields().iter())
+            .filter(|field| !old_offsets.contains(&field.offset()))
+            .map(|field| field.name().to_owned())
+            .collect();
+
+        let zero_initialized_fields = mapping
+            .new_ty
+            .as_struct()
+            .into_iter()
+            .flat_map(|s| s.fields().iter())
+            .zip(mapping.field_mapping.iter())
+            .filter(|(_, field)| {
+                matches!(
+                    field.action,
+                    Action::ArrayAlloc | Action::StructAlloc | Action::ZeroInitialize
+                )
+            })
+            .map(|(field, _)| field.name().to_owned())
+            .collect();
+
+        Self {
+            name: mapping.new_ty.name().to_owned(),
+            object_count,
+            migrated_count: 0,
+            dropped_fields,
+            zero_initialized_fields,
+        }
+    }
+}
+
+/// Returns the offset of the old field that is read by `action`, if any.
+fn old_offset(action: &Action) -> Option<usize> {
+    match action {
+        Action::ArrayFromValue { old_offset, .. }
+        | Action::ArrayMap { old_offset, .. }
+        | Action::Cast { old_offset, .. }
+        | Action::Copy { old_offset, .. }
+        | Action::ElementFromArray { old_offset, .. }
+        | Action::StructMapFromGc { old_offset, .. }
+        | Action::StructMapFromValue { old_offset, .. }
+        | Action::StructMapInPlace { old_offset, .. } => Some(*old_offset),
+        Action::ArrayAlloc | Action::StructAlloc | Action::ZeroInitialize => None,
+    }
+}
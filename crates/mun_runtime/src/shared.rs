@@ -0,0 +1,108 @@
+use std::{cell::Cell, ops::Deref, sync::Arc};
+
+use parking_lot::{RwLock, RwLockReadGuard};
+
+use crate::{ReloadReport, Runtime};
+
+/// A handle to a [`Runtime`] that can be shared between threads.
+///
+/// Any number of threads can invoke Mun functions through the runtime
+/// returned by [`SharedRuntime::read`] at the same time. Operations that
+/// cannot be performed while Mun code is executing, like hot reloading
+/// assemblies and collecting garbage, wait until all of these references have
+/// been released. New invocations wait in turn until the operation has
+/// finished, so no call ever observes a partially reloaded runtime.
+#[derive(Clone)]
+pub struct SharedRuntime {
+    runtime: Arc<RwLock<Runtime>>,
+}
+
+thread_local! {
+    /// The number of [`SharedRuntimeGuard`]s that the current thread holds.
+    static READ_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A reference to the runtime of a [`SharedRuntime`]. Hot reloads are blocked
+/// for as long as it exists, so it should not be held longer than necessary.
+pub struct SharedRuntimeGuard<'a> {
+    guard: RwLockReadGuard<'a, Runtime>,
+}
+
+impl Deref for SharedRuntimeGuard<'_> {
+    type Target = Runtime;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl Drop for SharedRuntimeGuard<'_> {
+    fn drop(&mut self) {
+        READ_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+impl SharedRuntime {
+    /// Constructs a new handle that shares `runtime` between threads.
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime: Arc::new(RwLock::new(runtime)),
+        }
+    }
+
+    /// Returns a reference to the runtime that can be used to invoke Mun
+    /// functions. Blocks while the runtime is being updated.
+    ///
+    /// Objects returned by an invocation borrow the guard, so they cannot
+    /// outlive it. Root them with [`crate::StructRef::root`] or
+    /// [`crate::ArrayRef::root`] to keep them alive across updates.
+    ///
+    /// A thread that already holds a reference can acquire another one, even
+    /// while an update is waiting. This allows host functions that are
+    /// called from Mun code to invoke Mun functions themselves.
+    pub fn read(&self) -> SharedRuntimeGuard<'_> {
+        let guard = if READ_DEPTH.with(Cell::get) > 0 {
+            // A fair read would wait for the pending update, which in turn waits
+            // for the reference that this thread already holds.
+            self.runtime.read_recursive()
+        } else {
+            self.runtime.read()
+        };
+        READ_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        SharedRuntimeGuard { guard }
+    }
+
+    /// Updates the state of the runtime, once all in-flight invocations have
+    /// finished. Returns `true` if assemblies were reloaded.
+    ///
+    /// # Safety
+    ///
+    /// See [`Runtime::update`].
+    pub unsafe fn update(&self) -> bool {
+        self.runtime.write().update()
+    }
+
+    /// Updates the state of the runtime like [`SharedRuntime::update`], and
+    /// returns a report of the changes if assemblies were reloaded.
+    ///
+    /// # Safety
+    ///
+    /// See [`Runtime::update`].
+    pub unsafe fn update_with_report(&self) -> Option<ReloadReport> {
+        self.runtime.write().update_with_report()
+    }
+
+    /// Collects all memory that is no longer referenced by rooted objects,
+    /// once all in-flight invocations have finished. Returns `true` if memory
+    /// was reclaimed.
+    pub fn gc_collect(&self) -> bool {
+        self.runtime.write().gc_collect()
+    }
+
+    /// Returns the runtime if this is the last handle to it.
+    pub fn try_unwrap(self) -> Result<Runtime, Self> {
+        Arc::try_unwrap(self.runtime)
+            .map(RwLock::into_inner)
+            .map_err(|runtime| Self { runtime })
+    }
+}
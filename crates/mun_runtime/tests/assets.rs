@@ -0,0 +1,100 @@
+use std::{fs, path::Path};
+
+use mun_runtime::{InMemoryAssets, Runtime};
+use mun_test::CompileTestDriver;
+
+/// Inserts all munlibs that `driver` generated into `assets`, at their path
+/// relative to the output directory.
+fn insert_munlibs(driver: &CompileTestDriver, assets: &InMemoryAssets) {
+    let out_dir = driver.lib_path().parent().unwrap();
+    for entry in fs::read_dir(out_dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map_or(false, |ext| ext == "munlib") {
+            let bytes = fs::read(&path).unwrap();
+            assets.insert(path.strip_prefix(out_dir).unwrap(), bytes);
+        }
+    }
+}
+
+#[test]
+fn load_from_memory() {
+    let mut driver = CompileTestDriver::from_file(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+
+    let assets = InMemoryAssets::default();
+    insert_munlibs(&driver, &assets);
+
+    // Safety: We compiled the library ourselves, therefore loading the munlib is safe.
+    let mut runtime = unsafe {
+        Runtime::builder("mod.munlib")
+            .asset_provider(assets.clone())
+            .finish()
+    }
+    .expect("Failed to build runtime");
+
+    let result: i32 = runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 5);
+
+    // Nothing changed, so there is nothing to reload
+    assert!(!unsafe { runtime.update() });
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub fn main() -> i32 { 10 }
+    ",
+    );
+    insert_munlibs(&driver, &assets);
+
+    // The provider reports the change, so no file watcher is involved
+    assert!(unsafe { runtime.update() });
+    let result: i32 = runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 10);
+}
+
+#[test]
+fn load_dependencies_from_memory() {
+    let driver = CompileTestDriver::from_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    //- /src/mod.mun
+    use package::foo::bar;
+    pub fn main() -> i32 { bar() }
+
+    //- /src/foo.mun
+    pub fn bar() -> i32 { 5 }
+    "#,
+    );
+
+    let assets = InMemoryAssets::default();
+    insert_munlibs(&driver, &assets);
+
+    // Safety: We compiled the library ourselves, therefore loading the munlib is safe.
+    let runtime = unsafe {
+        Runtime::builder(Path::new("mod.munlib"))
+            .asset_provider(assets)
+            .finish()
+    }
+    .expect("Failed to build runtime");
+
+    let result: i32 = runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn missing_asset() {
+    // Safety: No munlib is loaded.
+    let result = unsafe {
+        Runtime::builder("mod.munlib")
+            .asset_provider(InMemoryAssets::default())
+            .finish()
+    };
+    assert!(result.is_err());
+}
@@ -0,0 +1,217 @@
+use std::{cell::Cell, ptr};
+
+use mun_compiler::Config;
+use mun_runtime::{InvokeWithBudgetErr, Runtime, StructRef};
+use mun_test::CompileAndRunTestDriver;
+
+fn with_budget_checks() -> Config {
+    Config {
+        budget_checks: true,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn budget_exhausted() {
+    let driver = CompileAndRunTestDriver::with_compiler_config(
+        r"
+    pub struct Foo { count: i32 }
+
+    pub fn spin() -> i32 {
+        loop {}
+    }
+
+    fn recurse(n: i32) -> i32 {
+        recurse(n + 1)
+    }
+
+    pub fn recursion() -> i32 {
+        recurse(0) + 1
+    }
+
+    pub fn count(n: i32) -> i32 {
+        let i = 0;
+        while i < n {
+            i += 1;
+        }
+        i
+    }
+
+    pub fn make_foo(n: i32) -> Foo {
+        Foo { count: count(n) }
+    }
+    ",
+        with_budget_checks(),
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result: Result<i32, _> = driver.runtime.invoke_with_budget("spin", (), 1000);
+    assert!(matches!(
+        result,
+        Err(InvokeWithBudgetErr::BudgetExhausted {
+            function_name: "spin"
+        })
+    ));
+
+    let result: Result<i32, _> = driver.runtime.invoke_with_budget("recursion", (), 1000);
+    assert!(matches!(
+        result,
+        Err(InvokeWithBudgetErr::BudgetExhausted { .. })
+    ));
+
+    let result: Result<StructRef<'_>, _> =
+        driver
+            .runtime
+            .invoke_with_budget("make_foo", (1_000_000i32,), 1000);
+    assert!(matches!(
+        result,
+        Err(InvokeWithBudgetErr::BudgetExhausted { .. })
+    ));
+
+    // The runtime remains usable after the budget was exhausted
+    let result: i32 = driver
+        .runtime
+        .invoke_with_budget("count", (10i32,), 1000)
+        .unwrap();
+    assert_eq!(result, 10);
+    let result: StructRef<'_> = driver
+        .runtime
+        .invoke_with_budget("make_foo", (10i32,), 1000)
+        .unwrap();
+    assert_eq!(result.get::<i32>("count").unwrap(), 10);
+
+    // Without a budget, functions run to completion
+    let result: i32 = driver.runtime.invoke("count", (100_000i32,)).unwrap();
+    assert_eq!(result, 100_000);
+    assert!(!driver.runtime.is_budget_exhausted());
+}
+
+#[test]
+fn budget_without_budget_checks() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn count(n: i32) -> i32 {
+        let i = 0;
+        while i < n {
+            i += 1;
+        }
+        i
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    // Assemblies that were not compiled with budget checks don't consume budget
+    let result: i32 = driver
+        .runtime
+        .invoke_with_budget("count", (10_000i32,), 10)
+        .unwrap();
+    assert_eq!(result, 10_000);
+}
+
+#[test]
+fn budget_invoke_error() {
+    let driver = CompileAndRunTestDriver::with_compiler_config(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+        with_budget_checks(),
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result: Result<f32, _> = driver.runtime.invoke_with_budget("main", (), 10);
+    assert!(matches!(result, Err(InvokeWithBudgetErr::Invoke(_))));
+}
+
+#[test]
+fn budget_consumed_exactly() {
+    let driver = CompileAndRunTestDriver::with_compiler_config(
+        r"
+    pub fn one() -> i32 { 1 }
+    ",
+        with_budget_checks(),
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    // Calling `one` consumes a single unit of budget
+    let result: i32 = driver.runtime.invoke_with_budget("one", (), 1).unwrap();
+    assert_eq!(result, 1);
+
+    let result: Result<i32, _> = driver.runtime.invoke_with_budget("one", (), 0);
+    assert!(matches!(
+        result,
+        Err(InvokeWithBudgetErr::BudgetExhausted { .. })
+    ));
+}
+
+thread_local! {
+    static RUNTIME: Cell<*const Runtime> = Cell::new(ptr::null());
+}
+
+extern "C" fn nested_count(n: i32) -> i32 {
+    // Safety: the test stores a pointer to a runtime that outlives this call
+    let runtime = unsafe { &*RUNTIME.with(Cell::get) };
+    runtime.invoke_with_budget("count", (n,), u64::MAX).unwrap()
+}
+
+#[test]
+fn budget_nested_invocation() {
+    let driver = CompileAndRunTestDriver::with_compiler_config(
+        r"
+    extern fn nested_count(n: i32) -> i32;
+
+    pub fn one() -> i32 { 1 }
+
+    pub fn count(n: i32) -> i32 {
+        let i = 0;
+        while i < n {
+            i += 1;
+        }
+        i
+    }
+
+    pub fn outer(n: i32) -> i32 {
+        nested_count(n) + one()
+    }
+    ",
+        with_budget_checks(),
+        |builder| builder.insert_fn("nested_count", nested_count as extern "C" fn(i32) -> i32),
+    )
+    .expect("Failed to build test driver");
+    RUNTIME.with(|runtime| runtime.set(&driver.runtime));
+
+    // Find the budget that `count` requires on its own
+    let count_budget = (0..100)
+        .find(|&budget| {
+            driver
+                .runtime
+                .invoke_with_budget::<i32, _>("count", (10i32,), budget)
+                .is_ok()
+        })
+        .expect("`count` requires too much budget");
+
+    // `outer` and `one` both consume a unit next to the budget that the nested invocation
+    // consumes
+    let result: i32 = driver
+        .runtime
+        .invoke_with_budget("outer", (10i32,), count_budget + 2)
+        .unwrap();
+    assert_eq!(result, 11);
+
+    let result: Result<i32, _> =
+        driver
+            .runtime
+            .invoke_with_budget("outer", (10i32,), count_budget + 1);
+    assert!(matches!(
+        result,
+        Err(InvokeWithBudgetErr::BudgetExhausted {
+            function_name: "outer"
+        })
+    ));
+
+    RUNTIME.with(|runtime| runtime.set(ptr::null()));
+}
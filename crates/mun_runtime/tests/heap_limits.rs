@@ -0,0 +1,160 @@
+use mun_compiler::Config;
+use mun_runtime::{HeapLimits, InvokeWithBudgetErr, StructRef};
+use mun_test::CompileAndRunTestDriver;
+
+const TEST_CODE: &str = r"
+    pub struct Foo { value: i64 }
+
+    pub fn make_foo(value: i64) -> Foo {
+        Foo { value }
+    }
+
+    pub fn make_foos(n: i64) -> i64 {
+        let i = 0;
+        let sum = 0;
+        while i < n {
+            sum += make_foo(i).value;
+            i += 1;
+        }
+        sum
+    }
+";
+
+fn with_heap_limit_checks() -> Config {
+    Config {
+        heap_limit_checks: true,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn hard_heap_limit() {
+    let config = Config {
+        budget_checks: true,
+        ..with_heap_limit_checks()
+    };
+    let mut driver = CompileAndRunTestDriver::with_compiler_config(TEST_CODE, config, |builder| {
+        builder.heap_limits(HeapLimits {
+            soft_limit: None,
+            hard_limit: Some(1024),
+        })
+    })
+    .expect("Failed to build test driver");
+
+    let result: Result<i64, _> =
+        driver
+            .runtime
+            .invoke_with_budget("make_foos", (1_000i64,), 1_000_000);
+    assert!(matches!(
+        result,
+        Err(InvokeWithBudgetErr::HeapLimitExceeded {
+            function_name: "make_foos"
+        })
+    ));
+
+    // The error is reported through a regular invocation as well
+    driver.runtime.gc_collect();
+    let result: Result<i64, _> = driver.runtime.invoke("make_foos", (1_000i64,));
+    let err = result.unwrap_err();
+    assert!(err.is_execution_error());
+    assert!(!driver.runtime.take_heap_limit_exceeded());
+    assert!(!driver.runtime.is_budget_exhausted());
+
+    // After collecting garbage, the runtime is usable again
+    driver.runtime.gc_collect();
+    let result: i64 = driver.runtime.invoke("make_foos", (10i64,)).unwrap();
+    assert_eq!(result, 45);
+
+    // Allocations through the runtime's API are not limited
+    let array = driver.runtime.construct_array(0..1000i32);
+    assert_eq!(array.len(), 1000);
+}
+
+#[test]
+fn hard_heap_limit_without_budget_checks() {
+    let mut driver = CompileAndRunTestDriver::with_compiler_config(
+        TEST_CODE,
+        with_heap_limit_checks(),
+        |builder| {
+            builder.heap_limits(HeapLimits {
+                soft_limit: None,
+                hard_limit: Some(1024),
+            })
+        },
+    )
+    .expect("Failed to build test driver");
+
+    // Allocations never exceed the hard limit, even if Mun code doesn't check
+    // its budget
+    let result: Result<i64, _> =
+        driver
+            .runtime
+            .invoke_with_budget("make_foos", (1_000i64,), 1_000_000);
+    assert!(matches!(
+        result,
+        Err(InvokeWithBudgetErr::HeapLimitExceeded {
+            function_name: "make_foos"
+        })
+    ));
+    assert!(driver.runtime.gc_stats().allocated_memory <= 1024);
+
+    driver.runtime.gc_collect();
+    let result: i64 = driver.runtime.invoke("make_foos", (10i64,)).unwrap();
+    assert_eq!(result, 45);
+}
+
+#[test]
+fn hard_heap_limit_without_heap_limit_checks() {
+    let driver = CompileAndRunTestDriver::new(TEST_CODE, |builder| {
+        builder.heap_limits(HeapLimits {
+            soft_limit: None,
+            hard_limit: Some(1024),
+        })
+    })
+    .expect("Failed to build test driver");
+
+    // Code that cannot handle a failed allocation is not limited
+    let result: i64 = driver.runtime.invoke("make_foos", (1_000i64,)).unwrap();
+    assert_eq!(result, 499_500);
+    assert!(driver.runtime.gc_stats().allocated_memory > 1024);
+    assert!(!driver.runtime.take_heap_limit_exceeded());
+}
+
+#[test]
+fn soft_heap_limit() {
+    let mut driver = CompileAndRunTestDriver::new(TEST_CODE, |builder| {
+        builder.heap_limits(HeapLimits {
+            soft_limit: Some(64),
+            hard_limit: None,
+        })
+    })
+    .expect("Failed to build test driver");
+
+    let result: i64 = driver.runtime.invoke("make_foos", (100i64,)).unwrap();
+    assert_eq!(result, 4950);
+    assert!(driver.runtime.gc_stats().allocated_memory > 64);
+
+    // Garbage is collected during the next update
+    // Safety: the test driver ensures the loaded assembly is valid
+    unsafe { driver.runtime.update() };
+    assert_eq!(driver.runtime.gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn gc_type_stats() {
+    let mut driver = CompileAndRunTestDriver::new(TEST_CODE, |builder| builder)
+        .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver.runtime.invoke("make_foo", (5i64,)).unwrap();
+    let _foo_struct = foo_struct.root();
+    let _result: i64 = driver.runtime.invoke("make_foos", (3i64,)).unwrap();
+
+    let foo_type = driver.runtime.get_type_info_by_name("Foo").unwrap();
+    let stats = driver.runtime.gc_type_stats();
+    assert_eq!(stats[&foo_type].object_count, 4);
+    assert_eq!(stats[&foo_type].allocated_memory, 32);
+
+    driver.runtime.gc_collect();
+    let stats = driver.runtime.gc_type_stats();
+    assert_eq!(stats[&foo_type].object_count, 1);
+}
@@ -1,7 +1,10 @@
 #[macro_use]
 mod util;
 
-use mun_runtime::StructRef;
+use std::sync::{Arc, Mutex};
+
+use mun_compiler::Config;
+use mun_runtime::{FailedMigration, MappedTypeReport, ReloadReport, StructRef};
 use mun_test::CompileAndRunTestDriver;
 
 #[test]
@@ -190,3 +193,135 @@ fn reloadable_struct_decl_multi_file() {
         1
     );
 }
+
+#[test]
+fn reload_report() {
+    let reports = Arc::new(Mutex::new(Vec::<ReloadReport>::new()));
+    let observed_reports = reports.clone();
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        a: i32,
+        b: f32,
+    }
+
+    pub fn new_foo() -> Foo { Foo { a: 1, b: 2.0 } }
+    pub fn removed() -> i32 { 5 }
+    pub fn changed(a: i32) -> i32 { a }
+    "#,
+        move |builder| {
+            builder.on_reload(move |report| observed_reports.lock().unwrap().push(report.clone()))
+        },
+    )
+    .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver.runtime.invoke("new_foo", ()).unwrap();
+    let _foo_struct = foo_struct.root();
+
+    // Garbage isn't converted, nor reported
+    let _garbage: StructRef<'_> = driver.runtime.invoke("new_foo", ()).unwrap();
+
+    driver.update_file(
+        "mod.mun",
+        r#"
+    pub struct Foo {
+        a: i32,
+        c: bool,
+    }
+
+    pub fn new_foo() -> Foo { Foo { a: 1, c: true } }
+    pub fn added() -> i32 { 5 }
+    pub fn changed(a: f32) -> f32 { a }
+    "#,
+    );
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.assemblies.len(), 1);
+    assert_eq!(report.added_functions, vec!["added"]);
+    assert_eq!(report.removed_functions, vec!["removed"]);
+    assert_eq!(report.changed_functions, vec!["changed"]);
+    assert_eq!(
+        report.mapped_types,
+        vec![MappedTypeReport {
+            name: String::from("Foo"),
+            object_count: 1,
+            migrated_count: 0,
+            dropped_fields: vec![String::from("b")],
+            zero_initialized_fields: vec![String::from("c")],
+        }]
+    );
+    assert!(report.has_dropped_fields());
+    assert!(report.failed_migrations.is_empty());
+}
+
+#[test]
+fn reload_report_failed_migration() {
+    let reports = Arc::new(Mutex::new(Vec::<ReloadReport>::new()));
+    let observed_reports = reports.clone();
+    let mut driver = CompileAndRunTestDriver::with_compiler_config(
+        r#"
+    pub struct Player {
+        health: f32,
+    }
+
+    pub fn player_new(health: f32) -> Player { Player { health } }
+    "#,
+        Config {
+            budget_checks: true,
+            ..Config::default()
+        },
+        move |builder| {
+            builder.on_reload(move |report| observed_reports.lock().unwrap().push(report.clone()))
+        },
+    )
+    .expect("Failed to build test driver");
+
+    let player_struct: StructRef<'_> = driver.runtime.invoke("player_new", (40f32,)).unwrap();
+    let player_struct = player_struct.root();
+
+    // The migration never finishes, so it runs out of budget
+    driver.runtime.set_budget(Some(1000));
+    driver.update_file(
+        "mod.mun",
+        r#"
+    pub struct Player {
+        current: f32,
+        max: f32,
+    }
+
+    pub struct PlayerV1 {
+        health: f32,
+    }
+
+    impl Player {
+        pub fn migrate(self, old: PlayerV1) {
+            loop {}
+        }
+    }
+    "#,
+    );
+    driver.runtime.set_budget(None);
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    let [FailedMigration {
+        function_name,
+        error,
+    }] = reports[0].failed_migrations.as_slice()
+    else {
+        panic!("expected a single failed migration");
+    };
+    assert_eq!(function_name, "Player::migrate");
+    assert!(error.contains("budget"), "unexpected error: {error}");
+
+    // The object is still converted to the new layout
+    assert_eq!(
+        player_struct
+            .as_ref(&driver.runtime)
+            .get::<f32>("max")
+            .unwrap(),
+        0.0
+    );
+}
@@ -0,0 +1,47 @@
+use mun_test::{CompileAndRunTestDriver, InterpretTestDriver, Value};
+
+/// Functions that compute the same results whether they are compiled or
+/// interpreted.
+const SOURCE: &str = r"
+pub struct Vec2 { x: i64, y: i64 }
+
+pub fn fibonacci(n: i64) -> i64 {
+    if n <= 1 { n } else { fibonacci(n - 1) + fibonacci(n - 2) }
+}
+
+pub fn dot(n: i64) -> i64 {
+    let a = Vec2 { x: n, y: n * 2 };
+    let b = Vec2 { x: 3, y: -1 };
+    a.x * b.x + a.y * b.y
+}
+
+pub fn sum(n: i64) -> i64 {
+    let values = [1, 2, 3, 4, 5];
+    let i = 0;
+    let sum = 0;
+    while i < 5 {
+        sum += values[i] * n;
+        i += 1;
+    }
+    sum
+}
+";
+
+#[test]
+fn interpreted_matches_compiled() {
+    let compiled = CompileAndRunTestDriver::new(SOURCE, |builder| builder)
+        .expect("Failed to build test driver");
+    let interpreted = InterpretTestDriver::from_file(SOURCE);
+    let mut interpreter = interpreted.interpreter();
+
+    for function in ["fibonacci", "dot", "sum"] {
+        for n in [0i64, 1, 7, 15] {
+            let expected: i64 = compiled.runtime.invoke(function, (n,)).unwrap();
+            assert_eq!(
+                interpreter.invoke(function, vec![Value::Int(n.into())]),
+                Ok(Value::Int(expected.into())),
+                "{function}({n})"
+            );
+        }
+    }
+}
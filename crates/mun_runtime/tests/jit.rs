@@ -0,0 +1,101 @@
+use mun_compiler::{Config, Driver, PathOrInline, RelativePathBuf};
+use mun_runtime::{Runtime, StructRef};
+
+// Ensures the [`Runtime`] is still Send and Sync when it owns a compiler driver
+trait IsSendAndSync: Send + Sync {}
+impl IsSendAndSync for Runtime {}
+
+#[test]
+fn compile_and_reload_in_process() {
+    let out_dir = tempfile::TempDir::new().unwrap();
+    let config = Config {
+        out_dir: Some(out_dir.path().to_path_buf()),
+        ..Config::default()
+    };
+    let (driver, _) = Driver::with_file(
+        config,
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("mod.mun"),
+            contents: String::from("pub fn main() -> i32 { 5 }"),
+        },
+    )
+    .unwrap();
+
+    // Safety: The assembly is compiled from source that we control.
+    let mut runtime =
+        unsafe { Runtime::jit_builder(driver).finish() }.expect("Failed to build runtime");
+    let result: i32 = runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 5);
+
+    // Nothing changed, so nothing is reloaded
+    assert!(!unsafe { runtime.update() });
+
+    runtime
+        .driver_mut()
+        .unwrap()
+        .set_file_text("mod.mun", "pub fn main() -> i32 { 7 }")
+        .unwrap();
+    assert!(unsafe { runtime.update() });
+    let result: i32 = runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 7);
+
+    // Nothing was written to the filesystem
+    assert_eq!(std::fs::read_dir(out_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn invoke_on_another_thread() {
+    let (driver, _) = Driver::with_file(
+        Config::default(),
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("mod.mun"),
+            contents: String::from("pub fn main() -> i32 { 5 }"),
+        },
+    )
+    .unwrap();
+
+    // Safety: The assembly is compiled from source that we control.
+    let runtime =
+        unsafe { Runtime::jit_builder(driver).finish() }.expect("Failed to build runtime");
+
+    // A runtime that compiles its assemblies in process can be moved to another thread
+    let result = std::thread::spawn(move || runtime.invoke::<i32, ()>("main", ()).unwrap())
+        .join()
+        .unwrap();
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn reload_struct() {
+    let (driver, _) = Driver::with_file(
+        Config::default(),
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("mod.mun"),
+            contents: String::from(
+                "pub struct Foo { a: i32 }\npub fn new_foo(a: i32) -> Foo { Foo { a } }",
+            ),
+        },
+    )
+    .unwrap();
+
+    // Safety: The assembly is compiled from source that we control.
+    let mut runtime =
+        unsafe { Runtime::jit_builder(driver).finish() }.expect("Failed to build runtime");
+    let object: StructRef<'_> = runtime.invoke("new_foo", (5i32,)).unwrap();
+    let object = object.root();
+
+    runtime
+        .driver_mut()
+        .unwrap()
+        .set_file_text(
+            "mod.mun",
+            "pub struct Foo { a: i32, b: f64 }\npub fn new_foo(a: i32) -> Foo { Foo { a, b: 1.0 } }",
+        )
+        .unwrap();
+    assert!(unsafe { runtime.update() });
+
+    // The existing struct is migrated to the new definition
+    let object = object.as_ref(&runtime);
+    assert_eq!(object.get::<i32>("a").unwrap(), 5);
+    assert_eq!(object.get::<f64>("b").unwrap(), 0.0);
+}
@@ -25,7 +25,8 @@ fn return_value() {
     ",
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     assert_invoke_eq!(i32, 3, driver, "main");
 }
@@ -38,7 +39,8 @@ fn arguments() {
     ",
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     let a: i32 = 52;
     let b: i32 = 746;
@@ -54,7 +56,8 @@ fn dispatch_table() {
     ",
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     let a: i32 = 52;
     let b: i32 = 746;
@@ -84,7 +87,8 @@ fn booleans() {
     "#,
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     assert_invoke_eq!(bool, false, driver, "equal", 52i64, 764i64);
     assert_invoke_eq!(bool, true, driver, "equal", 64i64, 64i64);
@@ -126,7 +130,8 @@ fn fibonacci() {
     "#,
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     assert_invoke_eq!(i64, 5, driver, "fibonacci", 5i64);
     assert_invoke_eq!(i64, 89, driver, "fibonacci", 11i64);
@@ -154,7 +159,8 @@ fn fibonacci_loop() {
     "#,
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     assert_invoke_eq!(i64, 5, driver, "fibonacci", 5i64);
     assert_invoke_eq!(i64, 89, driver, "fibonacci", 11i64);
@@ -183,7 +189,8 @@ fn fibonacci_loop_break() {
     "#,
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     assert_invoke_eq!(i64, 5, driver, "fibonacci", 5i64);
     assert_invoke_eq!(i64, 89, driver, "fibonacci", 11i64);
@@ -210,7 +217,8 @@ fn fibonacci_while() {
     "#,
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     assert_invoke_eq!(i64, 5, driver, "fibonacci", 5i64);
     assert_invoke_eq!(i64, 89, driver, "fibonacci", 11i64);
@@ -232,7 +240,8 @@ fn true_is_true() {
     "#,
         |builder| builder,
     )
-    .expect("Failed to build test driver");
+    .expect("Failed to build test driver")
+    .with_interpreter();
 
     assert_invoke_eq!(bool, true, driver, "test_true");
     assert_invoke_eq!(bool, false, driver, "test_false");
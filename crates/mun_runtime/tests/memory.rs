@@ -1,4 +1,4 @@
-use mun_runtime::{ArrayRef, StructRef};
+use mun_runtime::{ArrayRef, Runtime, StructRef};
 use mun_test::CompileAndRunTestDriver;
 
 #[macro_use]
@@ -6,7 +6,7 @@ mod util;
 
 #[test]
 fn gc_trace() {
-    let driver = CompileAndRunTestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub struct Foo {
         quz: f64,
@@ -30,7 +30,7 @@ fn gc_trace() {
     )
     .expect("Failed to build test driver");
 
-    let runtime = &driver.runtime;
+    let runtime = &mut driver.runtime;
     let value: StructRef<'_> = runtime.invoke("new_foo", ()).unwrap();
     let value = value.root();
 
@@ -43,6 +43,180 @@ fn gc_trace() {
     assert_eq!(runtime.gc_stats().allocated_memory, 0);
 }
 
+#[test]
+fn gc_step() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: Bar,
+    }
+
+    pub struct Bar {
+        baz: i64
+    }
+
+    pub fn new_foo(baz: i64) -> Foo {
+        Foo {
+            bar: Bar { baz }
+        }
+    }
+
+    pub fn swap_bar(a: Foo, b: Foo) {
+        let bar = a.bar;
+        a.bar = b.bar;
+        b.bar = bar;
+    }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = &mut driver.runtime;
+    let bar_count = |runtime: &Runtime| {
+        runtime
+            .gc_type_stats()
+            .into_iter()
+            .find(|(ty, _)| ty.name() == "Bar")
+            .map_or(0, |(_, stats)| stats.object_count)
+    };
+
+    let a: StructRef<'_> = runtime.invoke("new_foo", (1i64,)).unwrap();
+    let a = a.root();
+    let b: StructRef<'_> = runtime.invoke("new_foo", (2i64,)).unwrap();
+    let b = b.root();
+
+    // Find the roots and trace one of the `Foo`s
+    assert!(!runtime.gc_step(1));
+    assert!(!runtime.gc_step(1));
+
+    // Swap the `Bar`s, which moves a `Bar` that has not been traced into the
+    // `Foo` that has been traced
+    let _: () = runtime
+        .invoke("swap_bar", (a.as_ref(runtime), b.as_ref(runtime)))
+        .unwrap();
+
+    // Both `Bar`s survive the cycle, because the moved `Bar` was shaded
+    while !runtime.gc_step(1) {}
+    assert_eq!(bar_count(runtime), 2);
+
+    let bar: StructRef<'_> = a.as_ref(runtime).get("bar").unwrap();
+    assert_eq!(bar.get::<i64>("baz").unwrap(), 2);
+
+    drop(b);
+    assert!(runtime.gc_collect());
+    assert_eq!(bar_count(runtime), 1);
+}
+
+#[test]
+fn gc_snapshot() {
+    let driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: Bar,
+    }
+
+    pub struct Bar {
+        baz: i64
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo {
+            bar: Bar { baz: 3 }
+        }
+    }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = &driver.runtime;
+    let foo_instance: StructRef<'_> = runtime.invoke("new_foo", ()).unwrap();
+    let _foo_instance = foo_instance.root();
+    let _: StructRef<'_> = runtime.invoke("new_foo", ()).unwrap();
+
+    let snapshot = runtime.gc_snapshot();
+    assert_eq!(snapshot.objects().len(), 4);
+    assert_eq!(snapshot.roots().count(), 1);
+
+    let find_bars = snapshot
+        .objects()
+        .iter()
+        .filter(|object| object.ty.name() == "Bar")
+        .map(|object| snapshot.path_to_root(object.handle))
+        .collect::<Vec<_>>();
+    assert_eq!(find_bars.len(), 2);
+    assert!(find_bars.iter().any(Option::is_none));
+
+    let path = find_bars.into_iter().flatten().next().unwrap();
+    let types = path
+        .iter()
+        .map(|handle| snapshot.get(*handle).unwrap().ty.name())
+        .collect::<Vec<_>>();
+    assert_eq!(types, ["Foo", "Bar"]);
+
+    let mut json = Vec::new();
+    runtime.write_heap_snapshot(&mut json).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json["snapshot"]["node_count"], 5);
+    // The root node references `Foo`, and both `Foo`s reference a `Bar`
+    assert_eq!(json["snapshot"]["edge_count"], 3);
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 5 * 7);
+    assert_eq!(json["edges"].as_array().unwrap().len(), 3 * 3);
+}
+
+#[test]
+fn save_and_load_heap() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+        pub struct Foo {
+            a: i32,
+            bar: Bar,
+        }
+
+        pub struct Bar {
+            b: f64,
+        }
+
+        pub fn foo_new(a: i32, b: f64) -> Foo {
+            Foo { a, bar: Bar { b } }
+        }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver.runtime.invoke("foo_new", (5i32, 3.0f64)).unwrap();
+    let mut file = Vec::new();
+    driver.runtime.save_heap(&[foo_struct], &mut file).unwrap();
+
+    driver.update_file(
+        "mod.mun",
+        r#"
+        pub struct Foo {
+            bar: Bar,
+            c: i64,
+            a: i64,
+        }
+
+        pub struct Bar {
+            b: f64,
+        }
+    "#,
+    );
+
+    let runtime = &driver.runtime;
+    let roots = runtime.load_heap(file.as_slice()).unwrap();
+    assert_eq!(roots.len(), 1);
+
+    let foo_struct = roots[0].as_ref(runtime);
+    assert_eq!(foo_struct.get::<i64>("a").unwrap(), 5);
+    assert_eq!(foo_struct.get::<i64>("c").unwrap(), 0);
+    let bar_struct: StructRef<'_> = foo_struct.get("bar").unwrap();
+    assert_eq!(bar_struct.get::<f64>("b").unwrap(), 3.0);
+
+    assert!(runtime.load_heap(b"MUNHEAP\0".as_slice()).is_err());
+}
+
 #[test]
 fn map_struct_insert_field1() {
     let mut driver = CompileAndRunTestDriver::new(
@@ -587,6 +761,111 @@ fn map_struct_rename_field2() {
     );
 }
 
+#[test]
+fn map_struct_declared_rename() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+        pub struct Foo {
+            hp: i32,
+            mana: f32,
+        }
+
+        pub fn foo_new(hp: i32, mana: f32) -> Foo {
+            Foo { hp, mana }
+        }
+    "#,
+        |builder| builder.rename_field("Foo", "hp", "health"),
+    )
+    .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver.runtime.invoke("foo_new", (80i32, 2.5f32)).unwrap();
+    let foo_struct = foo_struct.root();
+
+    // The field is renamed and its type is widened
+    driver.update_file(
+        "mod.mun",
+        r#"
+        struct Foo {
+            mana: f32,
+            health: i64,
+        }
+    "#,
+    );
+    assert_eq!(
+        foo_struct
+            .as_ref(&driver.runtime)
+            .get::<i64>("health")
+            .unwrap(),
+        80
+    );
+    assert_eq!(
+        foo_struct
+            .as_ref(&driver.runtime)
+            .get::<f32>("mana")
+            .unwrap(),
+        2.5
+    );
+}
+
+#[test]
+fn map_struct_migration() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+        pub struct Player {
+            health: f32,
+        }
+
+        pub fn player_new(health: f32) -> Player {
+            Player { health }
+        }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let player_struct: StructRef<'_> = driver.runtime.invoke("player_new", (40f32,)).unwrap();
+    let player_struct = player_struct.root();
+
+    // `health` is split into `current` and `max`
+    driver.update_file(
+        "mod.mun",
+        r#"
+        pub struct Player {
+            current: f32,
+            max: f32,
+        }
+
+        pub struct PlayerV1 {
+            health: f32,
+        }
+
+        impl Player {
+            pub fn migrate(self, old: PlayerV1) {
+                self.current = old.health;
+                self.max = 100.0;
+            }
+        }
+    "#,
+    );
+    assert_eq!(
+        player_struct
+            .as_ref(&driver.runtime)
+            .get::<f32>("current")
+            .unwrap(),
+        40.0
+    );
+    assert_eq!(
+        player_struct
+            .as_ref(&driver.runtime)
+            .get::<f32>("max")
+            .unwrap(),
+        100.0
+    );
+
+    // The copy of the old contents is no longer rooted
+    assert_eq!(driver.runtime.gc_snapshot().roots().count(), 1);
+}
+
 #[test]
 fn map_struct_all() {
     let mut driver = CompileAndRunTestDriver::new(
@@ -2267,6 +2546,18 @@ fn nested_structs() {
         Ok(b.into())
     );
 
+    // Fields that refer to structs whose memory kind changed are converted too
+    let wrapped_gc = gc_wrapper
+        .as_ref(&driver.runtime)
+        .get::<StructRef<'_>>("0")
+        .unwrap();
+    assert_eq!(wrapped_gc.get::<f64>("0"), Ok(a.into()));
+    let wrapped_value = gc_wrapper
+        .as_ref(&driver.runtime)
+        .get::<StructRef<'_>>("1")
+        .unwrap();
+    assert_eq!(wrapped_value.get::<f64>("1"), Ok(b.into()));
+
     // Tests mapping of different struct type, when `gc -> value`, `value -> gc`,
     // and retention of an old library (due to removal of `GcStruct` and
     // `ValueStruct`)
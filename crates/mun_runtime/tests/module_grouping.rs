@@ -0,0 +1,171 @@
+use std::{fs, path::Path};
+
+use mun_compiler::{Config, Driver};
+use mun_runtime::Runtime;
+use mun_test::CompileTestDriver;
+
+/// Returns the sorted names of the munlibs that `driver` generated.
+fn munlib_names(driver: &CompileTestDriver) -> Vec<String> {
+    let out_dir = driver.lib_path().parent().unwrap();
+    let mut names: Vec<String> = fs::read_dir(out_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "munlib"))
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Returns the error that occurs when writing the assemblies of a package with
+/// the specified manifest and source files.
+fn write_error(manifest: &str, source_files: &[(&str, &str)]) -> String {
+    let source_dir = tempfile::TempDir::new().unwrap();
+    let out_dir = tempfile::TempDir::new().unwrap();
+    fs::write(source_dir.path().join("mun.toml"), manifest).unwrap();
+    for (path, contents) in source_files {
+        let path = source_dir.path().join("src").join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    let config = Config {
+        out_dir: Some(out_dir.path().to_path_buf()),
+        ..Config::default()
+    };
+    let (_, mut driver) =
+        Driver::with_package_path(source_dir.path().join("mun.toml"), config).unwrap();
+    driver.write_all_assemblies(true).unwrap_err().to_string()
+}
+
+/// Loads the runtime from the root munlib and invokes `main`.
+fn invoke_main(lib_path: &Path) -> i32 {
+    // Safety: We compiled the library ourselves, therefore loading the munlib is safe.
+    let runtime = unsafe { Runtime::builder(lib_path).finish() }.expect("Failed to build runtime");
+    runtime.invoke("main", ()).unwrap()
+}
+
+#[test]
+fn per_package() {
+    let driver = CompileTestDriver::from_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    [build]
+    module-grouping = "per-package"
+
+    //- /src/mod.mun
+    use package::foo::bar;
+    pub fn main() -> i32 { bar() + package::foo::baz::baz() }
+
+    //- /src/foo.mun
+    pub fn bar() -> i32 { 5 }
+
+    //- /src/foo/baz.mun
+    pub fn baz() -> i32 { super::bar() * 2 }
+    "#,
+    );
+
+    assert_eq!(munlib_names(&driver), vec!["mod.munlib"]);
+    assert_eq!(invoke_main(driver.lib_path()), 15);
+}
+
+#[test]
+fn named_groups() {
+    let driver = CompileTestDriver::from_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    [build.module-groups]
+    math = ["math", "math::vector"]
+
+    //- /src/mod.mun
+    pub fn main() -> i32 { package::math::vector::length() + package::game::score() }
+
+    //- /src/math.mun
+    pub fn square(a: i32) -> i32 { a * a }
+
+    //- /src/math/vector.mun
+    pub fn length() -> i32 { super::square(3) }
+
+    //- /src/game.mun
+    pub fn score() -> i32 { package::math::square(2) }
+    "#,
+    );
+
+    assert_eq!(
+        munlib_names(&driver),
+        vec!["game.munlib", "math.munlib", "mod.munlib"]
+    );
+    assert_eq!(invoke_main(driver.lib_path()), 13);
+}
+
+#[test]
+fn group_with_name_of_ungrouped_module() {
+    let source_files = [
+        ("mod.mun", "pub fn main() -> i32 { 0 }"),
+        ("math.mun", "pub fn square(a: i32) -> i32 { a * a }"),
+        ("game.mun", "pub fn score() -> i32 { 2 }"),
+    ];
+
+    let error = write_error(
+        r#"
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    [build.module-groups]
+    game = ["math"]
+    "#,
+        &source_files,
+    );
+    assert_eq!(
+        error,
+        "module group 'game' has the same name as another assembly of the package"
+    );
+
+    // The root module is written to `mod.munlib`
+    let error = write_error(
+        r#"
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    [build.module-groups]
+    mod = ["math", "game"]
+    "#,
+        &source_files,
+    );
+    assert_eq!(
+        error,
+        "module group 'mod' has the same name as another assembly of the package"
+    );
+}
+
+#[test]
+fn group_with_unknown_module() {
+    let error = write_error(
+        r#"
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    [build.module-groups]
+    math = ["math", "math::matrix"]
+    "#,
+        &[
+            ("mod.mun", "pub fn main() -> i32 { 0 }"),
+            ("math.mun", "pub fn square(a: i32) -> i32 { a * a }"),
+        ],
+    );
+    assert_eq!(
+        error,
+        "module group 'math' refers to module 'package::math::matrix', which does not exist"
+    );
+}
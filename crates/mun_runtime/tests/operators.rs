@@ -0,0 +1,270 @@
+use mun_compiler::{Config, OptimizationLevel};
+use mun_runtime::InvokeWithBudgetErr;
+use mun_test::CompileAndRunTestDriver;
+
+#[test]
+fn overloaded_value_struct_operators() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    struct(value) Vec2 { x: f32, y: f32 }
+
+    impl Vec2 {
+        fn add(self, rhs: Self) -> Self { Vec2 { x: self.x + rhs.x, y: self.y + rhs.y } }
+        fn mul(self, rhs: f32) -> Self { Vec2 { x: self.x * rhs, y: self.y * rhs } }
+        fn neg(self) -> Self { Vec2 { x: -self.x, y: -self.y } }
+        fn eq(self, rhs: Self) -> bool { self.x == rhs.x && self.y == rhs.y }
+    }
+
+    pub fn sum_x(a: f32, b: f32) -> f32 {
+        let v = Vec2 { x: a, y: 0.0 } + Vec2 { x: b, y: 1.0 } * 2.0;
+        (-v).x
+    }
+
+    pub fn compound(n: i32) -> f32 {
+        let v = Vec2 { x: 0.0, y: 0.0 };
+        let i = 0;
+        while i < n {
+            v += Vec2 { x: 1.0, y: 2.0 };
+            i += 1;
+        }
+        v.y
+    }
+
+    pub fn equals(a: f32, b: f32) -> bool {
+        Vec2 { x: a, y: 1.0 } == Vec2 { x: b, y: 1.0 }
+    }
+
+    pub fn not_equals(a: f32, b: f32) -> bool {
+        Vec2 { x: a, y: 1.0 } != Vec2 { x: b, y: 1.0 }
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result: f32 = driver.runtime.invoke("sum_x", (1.0f32, 2.0f32)).unwrap();
+    assert_eq!(result, -5.0);
+
+    let result: f32 = driver.runtime.invoke("compound", (3i32,)).unwrap();
+    assert_eq!(result, 6.0);
+
+    let result: bool = driver.runtime.invoke("equals", (1.0f32, 1.0f32)).unwrap();
+    assert!(result);
+    let result: bool = driver
+        .runtime
+        .invoke("not_equals", (1.0f32, 1.0f32))
+        .unwrap();
+    assert!(!result);
+}
+
+#[test]
+fn overloaded_gc_struct_operators() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    struct Counter { count: i64 }
+
+    impl Counter {
+        fn add(self, rhs: i64) -> Self { Counter { count: self.count + rhs } }
+        fn sub(self, rhs: Self) -> Self { Counter { count: self.count - rhs.count } }
+    }
+
+    pub fn count(n: i64) -> i64 {
+        let c = Counter { count: 10 } + n;
+        c -= Counter { count: 3 };
+        c.count
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result: i64 = driver.runtime.invoke("count", (5i64,)).unwrap();
+    assert_eq!(result, 12);
+}
+
+#[test]
+fn compound_assignment_evaluates_place_once() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    struct Counter { calls: i64 }
+    struct Money { cents: i64 }
+
+    impl Money {
+        fn add(self, rhs: Self) -> Self { Money { cents: self.cents + rhs.cents } }
+    }
+
+    fn next(counter: Counter) -> usize {
+        counter.calls += 1;
+        0
+    }
+
+    pub fn overloaded() -> i64 {
+        let counter = Counter { calls: 0 };
+        let values = [Money { cents: 1 }];
+        values[next(counter)] += Money { cents: 2 };
+        values[0].cents * 10 + counter.calls
+    }
+
+    pub fn primitive() -> i64 {
+        let counter = Counter { calls: 0 };
+        let values = [1, 2];
+        values[next(counter)] += 5;
+        values[next(counter)] = values[0] * 2;
+        values[0] * 10 + counter.calls
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result: i64 = driver.runtime.invoke("overloaded", ()).unwrap();
+    assert_eq!(result, 31);
+
+    let result: i64 = driver.runtime.invoke("primitive", ()).unwrap();
+    assert_eq!(result, 122);
+}
+
+#[test]
+fn integer_overflow_methods() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn wrapping_add(a: u8, b: u8) -> u8 { u8::wrapping_add(a, b) }
+    pub fn wrapping_mul(a: i32, b: i32) -> i32 { i32::wrapping_mul(a, b) }
+    pub fn saturating_add(a: i8, b: i8) -> i8 { i8::saturating_add(a, b) }
+    pub fn saturating_sub(a: u16, b: u16) -> u16 { u16::saturating_sub(a, b) }
+    pub fn saturating_mul(a: i64, b: i64) -> i64 { i64::saturating_mul(a, b) }
+    pub fn strict_sub(a: u32, b: u32) -> u32 { u32::strict_sub(a, b) }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result: u8 = driver
+        .runtime
+        .invoke("wrapping_add", (250u8, 10u8))
+        .unwrap();
+    assert_eq!(result, 250u8.wrapping_add(10));
+    let result: i32 = driver
+        .runtime
+        .invoke("wrapping_mul", (i32::MAX, 3i32))
+        .unwrap();
+    assert_eq!(result, i32::MAX.wrapping_mul(3));
+
+    let result: i8 = driver
+        .runtime
+        .invoke("saturating_add", (100i8, 100i8))
+        .unwrap();
+    assert_eq!(result, i8::MAX);
+    let result: i8 = driver
+        .runtime
+        .invoke("saturating_add", (-100i8, -100i8))
+        .unwrap();
+    assert_eq!(result, i8::MIN);
+    let result: u16 = driver
+        .runtime
+        .invoke("saturating_sub", (5u16, 10u16))
+        .unwrap();
+    assert_eq!(result, 0);
+
+    let result: i64 = driver
+        .runtime
+        .invoke("saturating_mul", (i64::MAX / 2, 3i64))
+        .unwrap();
+    assert_eq!(result, i64::MAX);
+    let result: i64 = driver
+        .runtime
+        .invoke("saturating_mul", (i64::MIN, 2i64))
+        .unwrap();
+    assert_eq!(result, i64::MIN);
+    let result: i64 = driver
+        .runtime
+        .invoke("saturating_mul", (-7i64, 6i64))
+        .unwrap();
+    assert_eq!(result, -42);
+
+    let result: u32 = driver.runtime.invoke("strict_sub", (10u32, 3u32)).unwrap();
+    assert_eq!(result, 7);
+}
+
+#[test]
+fn strict_overflow_interrupts_invocation() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn strict_sub(a: u32, b: u32) -> u32 { u32::strict_sub(a, b) }
+    pub fn nested(a: u32) -> u32 { strict_sub(a, 10) + 1 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    // The overflow interrupts the calling function as well
+    let result = driver
+        .runtime
+        .invoke_with_budget::<u32, _>("nested", (3u32,), u64::MAX);
+    let Err(InvokeWithBudgetErr::Overflow {
+        function_name,
+        location,
+    }) = result
+    else {
+        panic!("expected an overflow, got {result:?}");
+    };
+    assert_eq!(function_name, "nested");
+    assert!(location.file.ends_with("mod.mun"), "{}", location.file);
+    assert_eq!((location.line, location.column), (2, 48));
+
+    let err = driver
+        .runtime
+        .invoke::<u32, _>("strict_sub", (3u32, 10u32))
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("'strict_sub' attempted to compute a value that overflows its type at"),
+        "{err}"
+    );
+
+    // The runtime remains usable after an overflow
+    let result: u32 = driver.runtime.invoke("nested", (30u32,)).unwrap();
+    assert_eq!(result, 21);
+    assert_eq!(driver.runtime.take_overflow(), None);
+}
+
+#[test]
+fn signed_division_overflow() {
+    const SOURCE: &str = r"
+    pub fn div(a: i32, b: i32) -> i32 { a / b }
+    pub fn rem(a: i32, b: i32) -> i32 { a % b }
+    ";
+
+    // Unoptimized builds trap, like Rust's debug builds
+    let driver = CompileAndRunTestDriver::with_compiler_config(
+        SOURCE,
+        Config {
+            optimization_lvl: OptimizationLevel::None,
+            ..Config::default()
+        },
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+    for function_name in ["div", "rem"] {
+        let result =
+            driver
+                .runtime
+                .invoke_with_budget::<i32, _>(function_name, (i32::MIN, -1i32), u64::MAX);
+        assert!(
+            matches!(result, Err(InvokeWithBudgetErr::Overflow { .. })),
+            "{result:?}"
+        );
+    }
+    let result: i32 = driver.runtime.invoke("div", (i32::MIN, 1i32)).unwrap();
+    assert_eq!(result, i32::MIN);
+
+    // Optimized builds wrap, like Rust's release builds
+    let driver = CompileAndRunTestDriver::new(SOURCE, |builder| builder)
+        .expect("Failed to build test driver");
+    let result: i32 = driver.runtime.invoke("div", (i32::MIN, -1i32)).unwrap();
+    assert_eq!(result, i32::MIN.wrapping_div(-1));
+    let result: i32 = driver.runtime.invoke("rem", (i32::MIN, -1i32)).unwrap();
+    assert_eq!(result, i32::MIN.wrapping_rem(-1));
+    let result: i32 = driver.runtime.invoke("rem", (-7i32, 3i32)).unwrap();
+    assert_eq!(result, -1);
+}
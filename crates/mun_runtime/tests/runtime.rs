@@ -20,7 +20,7 @@ fn invoke() {
 
 #[test]
 fn arrays_are_collected() {
-    let driver = CompileAndRunTestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub fn main() {
         let a = [1,2,3,4,5,6,7,8,9,1,2,3,4,5,6,7,8,9,1,2,3,4,5,6,7,8,9,1,2,3,4,5,6,7,8,9,]
@@ -0,0 +1,53 @@
+use mun_compiler::Config;
+use mun_libloader::MunLibrary;
+use mun_runtime::Runtime;
+use mun_test::CompileTestDriver;
+
+#[test]
+fn from_entry_points() {
+    let driver = CompileTestDriver::from_file(
+        r"
+    pub fn main() -> i32 { add(2, 3) }
+    fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefore loading the munlib is safe.
+    let library = unsafe { MunLibrary::new(driver.lib_path()) }.expect("Failed to load munlib");
+    let entry_points = unsafe { library.entry_points() };
+
+    // The path only identifies the assembly, it doesn't have to exist
+    let mut runtime = unsafe {
+        Runtime::builder("static")
+            .static_assembly(entry_points)
+            .finish()
+    }
+    .expect("Failed to build runtime");
+
+    let result: i32 = runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 5);
+
+    // Statically linked assemblies are never reloaded
+    assert!(!unsafe { runtime.update() });
+}
+
+#[test]
+fn emits_static_library() {
+    let driver = CompileTestDriver::from_file_with_config(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+        Config {
+            static_library: true,
+            ..Config::default()
+        },
+    );
+
+    let out_dir = driver.lib_path().parent().unwrap();
+    let file_name = if cfg!(windows) { "mod.lib" } else { "libmod.a" };
+    let archive = std::fs::read(out_dir.join(file_name)).expect("missing static library");
+    assert!(archive.starts_with(b"!<arch>\n"));
+
+    // No munlib is emitted
+    assert!(!driver.lib_path().exists());
+}
@@ -1,5 +1,196 @@
-use mun_runtime::Runtime;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, OnceLock,
+    },
+    thread::{self, sleep},
+    time::{Duration, Instant},
+};
+
+use mun_runtime::{Runtime, SharedRuntime, StructRef};
+use mun_test::CompileAndRunTestDriver;
 
 // Ensures the [`Runtime`] is Send
 trait IsSend: Send {}
 impl IsSend for Runtime {}
+impl IsSend for SharedRuntime {}
+
+// Ensures the [`Runtime`] is Sync
+trait IsSync: Sync {}
+impl IsSync for Runtime {}
+impl IsSync for SharedRuntime {}
+
+#[test]
+fn invoke_from_multiple_threads() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct Point { x: i32, y: i32 }
+
+    pub fn new_point(x: i32) -> Point {
+        let i = 0;
+        while i < 10 {
+            Point { x: i, y: i };
+            i += 1;
+        }
+        Point { x: x, y: x * 2 }
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = &driver.runtime;
+    thread::scope(|scope| {
+        for thread_idx in 0..8 {
+            scope.spawn(move || {
+                for i in 0..100 {
+                    let x = thread_idx * 100 + i;
+                    let point: StructRef<'_> = runtime
+                        .invoke("new_point", (x,))
+                        .expect("failed to invoke new_point");
+                    assert_eq!(point.get::<i32>("x").unwrap(), x);
+                    assert_eq!(point.get::<i32>("y").unwrap(), x * 2);
+                }
+            });
+        }
+    });
+
+    assert!(driver.runtime.gc_collect());
+}
+
+#[test]
+fn shared_runtime_reload_waits_for_invocations() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let (mut compiler, runtime) = driver.into_parts();
+    let runtime = SharedRuntime::new(runtime);
+
+    let readers = (0..4)
+        .map(|_| {
+            let runtime = runtime.clone();
+            thread::spawn(move || loop {
+                let result: i32 = runtime
+                    .read()
+                    .invoke("main", ())
+                    .expect("failed to invoke main");
+                match result {
+                    5 => thread::yield_now(),
+                    10 => break,
+                    _ => panic!("unexpected result: {result}"),
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    compiler.update_file(
+        "mod.mun",
+        r"
+    pub fn main() -> i32 { 10 }
+    ",
+    );
+
+    let start_time = Instant::now();
+    // Safety: We compiled the library ourselves, therefor updating the runtime is
+    // safe.
+    while !unsafe { runtime.update() } {
+        assert!(
+            start_time.elapsed() <= Duration::from_secs(10),
+            "runtime did not update after recompilation within 10 seconds"
+        );
+        sleep(Duration::from_millis(1));
+    }
+
+    for reader in readers {
+        reader.join().expect("reader thread panicked");
+    }
+
+    let result: i32 = runtime.read().invoke("main", ()).unwrap();
+    assert_eq!(result, 10);
+    assert!(runtime.try_unwrap().is_ok());
+}
+
+#[test]
+fn shared_runtime_reentrant_read() {
+    static RUNTIME: OnceLock<SharedRuntime> = OnceLock::new();
+
+    extern "C" fn call_inner() -> i32 {
+        let runtime = RUNTIME.get().expect("runtime is not initialized");
+        runtime
+            .read()
+            .invoke("inner", ())
+            .expect("failed to invoke inner")
+    }
+
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    extern fn call_inner() -> i32;
+
+    pub fn inner() -> i32 { 5 }
+    pub fn outer() -> i32 { call_inner() + 1 }
+    ",
+        |builder| builder.insert_fn("call_inner", call_inner as extern "C" fn() -> i32),
+    )
+    .expect("Failed to build test driver");
+
+    let (_compiler, runtime) = driver.into_parts();
+    let runtime = RUNTIME.get_or_init(|| SharedRuntime::new(runtime));
+
+    let guard = runtime.read();
+    // Queue up a writer behind the reference of this thread, which the host
+    // function must not wait for.
+    let writer = thread::spawn(|| runtime.gc_collect());
+    sleep(Duration::from_millis(100));
+
+    let result: i32 = guard.invoke("outer", ()).expect("failed to invoke outer");
+    assert_eq!(result, 6);
+
+    drop(guard);
+    writer.join().expect("writer thread panicked");
+}
+
+#[test]
+fn shared_runtime_writer_is_not_starved() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let (_compiler, runtime) = driver.into_parts();
+    let runtime = SharedRuntime::new(runtime);
+    let stop = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        // Keep the runtime read-locked at all times, with overlapping guards
+        for _ in 0..4 {
+            scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    let guard = runtime.read();
+                    let result: i32 = guard.invoke("main", ()).expect("failed to invoke main");
+                    assert_eq!(result, 5);
+                    sleep(Duration::from_millis(1));
+                }
+            });
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        scope.spawn(|| {
+            for _ in 0..10 {
+                runtime.gc_collect();
+            }
+            sender.send(()).unwrap();
+        });
+
+        let result = receiver.recv_timeout(Duration::from_secs(10));
+        stop.store(true, Ordering::Relaxed);
+        assert!(result.is_ok(), "writer was starved by readers");
+    });
+}
@@ -0,0 +1,241 @@
+use std::time::{Duration, Instant};
+
+use mun_runtime::{StructRef, UnloadError};
+use mun_test::{CompileAndRunTestDriver, CompileTestDriver};
+
+#[macro_use]
+mod util;
+
+#[test]
+fn add_and_remove_assembly() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let level = CompileTestDriver::from_file(
+        r"
+    pub fn level() -> i32 { 2 }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefore loading the munlib is safe.
+    unsafe { driver.runtime.add_assembly(level.lib_path()) }.expect("Failed to add assembly");
+    assert_eq!(driver.runtime.assemblies().count(), 2);
+    assert_invoke_eq!(i32, 1, driver, "main");
+    assert_invoke_eq!(i32, 2, driver, "level");
+
+    // Safety: We compiled the library ourselves, therefore unloading the munlib is safe.
+    unsafe { driver.runtime.remove_assembly(level.lib_path()) }.expect("Failed to remove assembly");
+    assert_eq!(driver.runtime.assemblies().count(), 1);
+    assert_invoke_eq!(i32, 1, driver, "main");
+    assert!(driver.runtime.invoke::<i32, ()>("level", ()).is_err());
+
+    // The assembly can be loaded again
+    unsafe { driver.runtime.add_assembly(level.lib_path()) }.expect("Failed to add assembly");
+    assert_invoke_eq!(i32, 2, driver, "level");
+}
+
+#[test]
+fn readded_assembly_is_reloaded() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let mut level = CompileTestDriver::from_file(
+        r"
+    pub fn level() -> i32 { 2 }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefore loading and unloading the munlib is
+    // safe.
+    unsafe {
+        driver.runtime.add_assembly(level.lib_path()).unwrap();
+        driver.runtime.remove_assembly(level.lib_path()).unwrap();
+        driver.runtime.add_assembly(level.lib_path()).unwrap();
+    }
+
+    // The directory of the assembly is watched again
+    level.update_file(
+        "mod.mun",
+        r"
+    pub fn level() -> i32 { 3 }
+    ",
+    );
+    let start_time = Instant::now();
+    // Safety: We compiled the library ourselves, therefore updating the runtime is safe.
+    while !unsafe { driver.runtime.update() } {
+        assert!(
+            start_time.elapsed() < Duration::from_secs(10),
+            "runtime did not update after recompilation within 10 seconds"
+        );
+    }
+    assert_invoke_eq!(i32, 3, driver, "level");
+}
+
+#[test]
+fn remove_assembly_with_live_objects() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let level = CompileTestDriver::from_file(
+        r"
+    pub struct Enemy { health: i32 }
+
+    pub fn spawn() -> Enemy { Enemy { health: 100 } }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefore loading the munlib is safe.
+    unsafe { driver.runtime.add_assembly(level.lib_path()) }.expect("Failed to add assembly");
+
+    let enemy: StructRef<'_> = driver.runtime.invoke("spawn", ()).unwrap();
+    let enemy = enemy.root();
+
+    // Safety: We compiled the library ourselves, therefore unloading the munlib is safe.
+    let result = unsafe { driver.runtime.remove_assembly(level.lib_path()) };
+    assert!(
+        matches!(&result, Err(UnloadError::LiveObjects(types)) if types == &["Enemy"]),
+        "unexpected result: {result:?}"
+    );
+
+    // The runtime is unchanged
+    assert_eq!(
+        enemy.as_ref(&driver.runtime).get::<i32>("health").unwrap(),
+        100
+    );
+    let _: StructRef<'_> = driver.runtime.invoke("spawn", ()).unwrap();
+
+    drop(enemy);
+    unsafe { driver.runtime.remove_assembly(level.lib_path()) }.expect("Failed to remove assembly");
+    assert!(driver.runtime.get_type_info_by_name("Enemy").is_none());
+}
+
+#[test]
+fn remove_dependency() {
+    let mut driver = CompileAndRunTestDriver::from_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    //- /src/mod.mun
+    use package::foo::bar;
+    pub fn main() -> i32 { bar() }
+
+    //- /src/foo.mun
+    pub fn bar() -> i32 { 5 }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let library_path = driver
+        .runtime
+        .assemblies()
+        .find(|path| path.ends_with("foo.munlib"))
+        .expect("dependency must be loaded")
+        .to_path_buf();
+
+    // Safety: We compiled the library ourselves, therefore unloading the munlib is safe.
+    let result = unsafe { driver.runtime.remove_assembly(&library_path) };
+    assert!(
+        matches!(result, Err(UnloadError::Dependency(ref path)) if path.ends_with("mod.munlib")),
+        "unexpected result: {result:?}"
+    );
+    assert_invoke_eq!(i32, 5, driver, "main");
+}
+
+#[test]
+fn remove_unknown_assembly() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let level = CompileTestDriver::from_file(
+        r"
+    pub fn level() -> i32 { 2 }
+    ",
+    );
+
+    // Safety: No munlib is unloaded.
+    let result = unsafe { driver.runtime.remove_assembly(level.lib_path()) };
+    assert!(matches!(result, Err(UnloadError::NotLoaded(_))));
+}
+
+#[test]
+fn isolated_runtimes() {
+    let mut first = CompileAndRunTestDriver::new(
+        r"
+    pub struct Foo { a: i32 }
+
+    pub fn new_foo() -> Foo { Foo { a: 1 } }
+    pub fn version() -> i32 { 1 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let second = CompileAndRunTestDriver::new(
+        r"
+    pub struct Foo { b: f64, a: i64 }
+
+    pub fn new_foo() -> Foo { Foo { b: 2.0, a: 2 } }
+    pub fn version() -> i32 { 2 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    // Both versions of the same munlib are loaded side by side
+    assert_invoke_eq!(i32, 1, first, "version");
+    assert_invoke_eq!(i32, 2, second, "version");
+
+    let first_foo: StructRef<'_> = first.runtime.invoke("new_foo", ()).unwrap();
+    let first_foo = first_foo.root();
+    let second_foo: StructRef<'_> = second.runtime.invoke("new_foo", ()).unwrap();
+    assert_eq!(first_foo.as_ref(&first.runtime).get::<i32>("a").unwrap(), 1);
+    assert_eq!(second_foo.get::<i64>("a").unwrap(), 2);
+
+    // Every runtime has its own garbage collector
+    assert_eq!(second.runtime.gc_type_stats().len(), 1);
+    // The object of the second runtime isn't rooted, so it is collected
+    assert!(second.runtime.gc_collect());
+    assert!(!first.runtime.gc_collect());
+    assert_eq!(first_foo.as_ref(&first.runtime).get::<i32>("a").unwrap(), 1);
+
+    // Hot reloading one runtime does not affect the other
+    first.update_file(
+        "mod.mun",
+        r"
+    pub struct Foo { a: i32, c: bool }
+
+    pub fn new_foo() -> Foo { Foo { a: 3, c: true } }
+    pub fn version() -> i32 { 3 }
+    ",
+    );
+    assert_invoke_eq!(i32, 3, first, "version");
+    assert_invoke_eq!(i32, 2, second, "version");
+    assert_eq!(first_foo.as_ref(&first.runtime).get::<i32>("a").unwrap(), 1);
+
+    let second_foo: StructRef<'_> = second.runtime.invoke("new_foo", ()).unwrap();
+    assert_eq!(second_foo.get::<f64>("b").unwrap(), 2.0);
+}
@@ -9,6 +9,15 @@ macro_rules! assert_invoke_eq {
                 stringify!(mun_runtime::invoke_fn!(runtime_ref, $($Arg)*).unwrap()),
                 $ExpectedResult
             );
+
+            // Drivers that opted in are interpreted as well
+            if let Some(interpreted) = $Driver.interpreted() {
+                interpreted.assert_invoke_eq(
+                    $Name,
+                    vec![$(mun_test::Value::from($Arg),)*],
+                    $ExpectedResult,
+                );
+            }
         }
     };
     ($ExpectedType:ty, $ExpectedResult:expr, $Driver:expr, $Name:expr) => {
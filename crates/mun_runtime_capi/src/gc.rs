@@ -3,8 +3,8 @@
 use std::mem::ManuallyDrop;
 
 use mun_capi_utils::{error::ErrorHandle, mun_error_try, try_deref_mut};
+use mun_memory::ffi::Type;
 pub use mun_memory::gc::GcPtr;
-use mun_memory::{ffi::Type, gc::GcRuntime};
 
 use crate::runtime::Runtime;
 
@@ -29,7 +29,7 @@ pub unsafe extern "C" fn mun_gc_alloc(runtime: Runtime, ty: Type, obj: *mut GcPt
         .map_err(|e| format!("invalid argument 'obj': {e}"))
         .map(ManuallyDrop::new));
     let obj = try_deref_mut!(obj);
-    *obj = runtime.gc().alloc(&ty);
+    *obj = runtime.gc_alloc(&ty);
     ErrorHandle::default()
 }
 
@@ -54,7 +54,7 @@ pub unsafe extern "C" fn mun_gc_ptr_type(
         .inner()
         .map_err(|e| format!("invalid argument 'runtime': {e}")));
     let ty = try_deref_mut!(ty);
-    *ty = runtime.gc().ptr_type(obj).into();
+    *ty = runtime.gc_ptr_type(obj).into();
     ErrorHandle::default()
 }
 
@@ -78,7 +78,7 @@ pub unsafe extern "C" fn mun_gc_root(runtime: Runtime, obj: GcPtr) -> ErrorHandl
     let runtime = mun_error_try!(runtime
         .inner()
         .map_err(|e| format!("invalid argument 'runtime': {e}")));
-    runtime.gc().root(obj);
+    runtime.gc_root(obj);
     ErrorHandle::default()
 }
 
@@ -101,7 +101,7 @@ pub unsafe extern "C" fn mun_gc_unroot(runtime: Runtime, obj: GcPtr) -> ErrorHan
     let runtime = mun_error_try!(runtime
         .inner()
         .map_err(|e| format!("invalid argument 'runtime': {e}")));
-    runtime.gc().unroot(obj);
+    runtime.gc_unroot(obj);
     ErrorHandle::default()
 }
 
@@ -110,6 +110,9 @@ pub unsafe extern "C" fn mun_gc_unroot(runtime: Runtime, obj: GcPtr) -> ErrorHan
 /// returned. If `reclaimed` is `true`, memory was reclaimed, otherwise nothing
 /// happend. This behavior will likely change in the future.
 ///
+/// This must not be called concurrently with any other function that uses the
+/// runtime.
+///
 /// If a non-zero error handle is returned, it must be manually destructed using
 /// [`mun_error_destroy`].
 ///
@@ -121,13 +124,65 @@ pub unsafe extern "C" fn mun_gc_unroot(runtime: Runtime, obj: GcPtr) -> ErrorHan
 #[no_mangle]
 pub unsafe extern "C" fn mun_gc_collect(runtime: Runtime, reclaimed: *mut bool) -> ErrorHandle {
     let runtime = mun_error_try!(runtime
-        .inner()
+        .inner_mut()
         .map_err(|e| format!("invalid argument 'runtime': {e}")));
     let reclaimed = try_deref_mut!(reclaimed);
     *reclaimed = runtime.gc_collect();
     ErrorHandle::default()
 }
 
+/// Performs a step of an incremental garbage collection cycle, starting a new
+/// cycle if none is in progress. Every step processes roughly `budget` objects.
+/// If successful, `completed` is set, otherwise a non-zero error handle is
+/// returned. If `completed` is `true`, the step completed the cycle.
+///
+/// This must not be called while Mun functions are executing, nor concurrently
+/// with any other function that uses the runtime.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments
+/// is a null pointer, an error will be returned. Passing pointers to invalid
+/// data, will lead to undefined behavior.
+#[no_mangle]
+pub unsafe extern "C" fn mun_gc_step(
+    runtime: Runtime,
+    budget: usize,
+    completed: *mut bool,
+) -> ErrorHandle {
+    let runtime = mun_error_try!(runtime
+        .inner_mut()
+        .map_err(|e| format!("invalid argument 'runtime': {e}")));
+    let completed = try_deref_mut!(completed);
+    *completed = runtime.gc_step(budget);
+    ErrorHandle::default()
+}
+
+/// Informs the garbage collector that references were stored in `obj`. This
+/// must be called after storing references in an object from outside of Mun
+/// code, so an incremental collection cycle does not miss them. If successful,
+/// a zero error handle is returned.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments
+/// is a null pointer, an error will be returned. Passing pointers to invalid
+/// data, will lead to undefined behavior.
+#[no_mangle]
+pub unsafe extern "C" fn mun_gc_write_barrier(runtime: Runtime, obj: GcPtr) -> ErrorHandle {
+    let runtime = mun_error_try!(runtime
+        .inner()
+        .map_err(|e| format!("invalid argument 'runtime': {e}")));
+    runtime.gc_write_barrier(obj);
+    ErrorHandle::default()
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -154,7 +209,9 @@ mod tests {
         gc_ptr_type(mem::zeroed::<GcPtr>(), ptr::null_mut()),
         gc_root(mem::zeroed::<GcPtr>()),
         gc_unroot(mem::zeroed::<GcPtr>()),
-        gc_collect(ptr::null_mut())
+        gc_collect(ptr::null_mut()),
+        gc_step(0, ptr::null_mut()),
+        gc_write_barrier(mem::zeroed::<GcPtr>())
     );
 
     #[test]
@@ -308,4 +365,42 @@ mod tests {
             @r#""invalid argument \'reclaimed\': null pointer""#
         );
     }
+
+    #[test]
+    fn test_gc_step_invalid_completed() {
+        let driver = TestDriver::new(
+            r#"
+        pub struct Foo;
+    "#,
+        );
+
+        assert_error_snapshot!(
+            unsafe { mun_gc_step(driver.runtime, 1, ptr::null_mut()) },
+            @r#""invalid argument \'completed\': null pointer""#
+        );
+    }
+
+    #[test]
+    fn test_gc_step() {
+        let driver = TestDriver::new(
+            r#"
+        pub struct Foo;
+    "#,
+        );
+
+        let type_name = CString::new("Foo").expect("Invalid type name.");
+        assert_getter2!(mun_runtime_get_type_info_by_name(
+            driver.runtime,
+            type_name.as_ptr(),
+            has_type,
+            ty,
+        ));
+        assert!(has_type);
+
+        assert_getter2!(mun_gc_alloc(driver.runtime, ty, obj));
+        assert!(unsafe { mun_gc_write_barrier(driver.runtime, obj) }.is_ok());
+
+        assert_getter2!(mun_gc_step(driver.runtime, usize::MAX, completed));
+        assert!(completed);
+    }
 }
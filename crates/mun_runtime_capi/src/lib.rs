@@ -6,6 +6,7 @@
 #![warn(missing_docs)]
 
 pub mod gc;
+pub mod reload;
 pub mod runtime;
 
 pub mod function;
@@ -0,0 +1,155 @@
+//! Exposes reports of hot reloads using the C ABI.
+
+use std::{
+    ffi::{c_void, CString},
+    os::raw::c_char,
+    ptr,
+};
+
+/// A callback that is invoked with a report of the changes of a hot reload.
+/// The report and all strings it references are only valid for the duration
+/// of the call.
+pub type ReloadCallback = unsafe extern "C" fn(report: *const ReloadReport, data: *mut c_void);
+
+/// A report of the changes that were made when assemblies were hot reloaded.
+#[repr(C)]
+pub struct ReloadReport {
+    /// The paths of the assemblies that were reloaded
+    pub assemblies: *const *const c_char,
+    /// The number of paths in [`assemblies`]
+    pub num_assemblies: usize,
+    /// The names of the functions that were added
+    pub added_functions: *const *const c_char,
+    /// The number of names in [`added_functions`]
+    pub num_added_functions: usize,
+    /// The names of the functions that were removed
+    pub removed_functions: *const *const c_char,
+    /// The number of names in [`removed_functions`]
+    pub num_removed_functions: usize,
+    /// The names of the functions whose signature changed
+    pub changed_functions: *const *const c_char,
+    /// The number of names in [`changed_functions`]
+    pub num_changed_functions: usize,
+    /// The structs whose layout changed
+    pub mapped_types: *const MappedTypeReport,
+    /// The number of structs in [`mapped_types`]
+    pub num_mapped_types: usize,
+}
+
+/// Describes how the objects of a struct whose layout changed were converted.
+#[repr(C)]
+pub struct MappedTypeReport {
+    /// The name of the struct
+    pub name: *const c_char,
+    /// The number of objects that were converted. Garbage is collected before
+    /// objects are converted, so only reachable objects are counted.
+    pub object_count: usize,
+    /// The number of objects that were passed to a migration defined by the
+    /// struct
+    pub migrated_count: usize,
+    /// The fields of the old struct whose values were dropped
+    pub dropped_fields: *const *const c_char,
+    /// The number of names in [`dropped_fields`]
+    pub num_dropped_fields: usize,
+    /// The fields of the new struct that were zero-initialized
+    pub zero_initialized_fields: *const *const c_char,
+    /// The number of names in [`zero_initialized_fields`]
+    pub num_zero_initialized_fields: usize,
+}
+
+/// Owns the strings that a [`ReloadReport`] references.
+#[derive(Default)]
+struct Strings {
+    strings: Vec<CString>,
+}
+
+impl Strings {
+    /// Stores `strings` and returns pointers to them.
+    fn add<'s>(&mut self, strings: impl IntoIterator<Item = &'s str>) -> Vec<*const c_char> {
+        strings
+            .into_iter()
+            .map(|string| {
+                // Strings cannot contain null characters in C
+                let string = CString::new(string.replace('\0', "")).expect("no null characters");
+                let ptr = string.as_ptr();
+                self.strings.push(string);
+                ptr
+            })
+            .collect()
+    }
+}
+
+/// Returns a pointer to the first element of `elements`, or null if it is
+/// empty.
+fn as_ptr<T>(elements: &[T]) -> *const T {
+    if elements.is_empty() {
+        ptr::null()
+    } else {
+        elements.as_ptr()
+    }
+}
+
+/// Invokes `callback` with a C representation of `report`.
+///
+/// # Safety
+///
+/// `callback` must be safe to call with any `data`.
+pub(crate) unsafe fn invoke_callback(
+    report: &mun_runtime::ReloadReport,
+    callback: ReloadCallback,
+    data: *mut c_void,
+) {
+    let mut strings = Strings::default();
+
+    let assemblies = report
+        .assemblies
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    let assemblies = strings.add(assemblies.iter().map(String::as_str));
+    let added_functions = strings.add(report.added_functions.iter().map(String::as_str));
+    let removed_functions = strings.add(report.removed_functions.iter().map(String::as_str));
+    let changed_functions = strings.add(report.changed_functions.iter().map(String::as_str));
+
+    let field_names = report
+        .mapped_types
+        .iter()
+        .map(|ty| {
+            (
+                strings.add(ty.dropped_fields.iter().map(String::as_str)),
+                strings.add(ty.zero_initialized_fields.iter().map(String::as_str)),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mapped_types = report
+        .mapped_types
+        .iter()
+        .zip(field_names.iter())
+        .map(
+            |(ty, (dropped_fields, zero_initialized_fields))| MappedTypeReport {
+                name: strings.add([ty.name.as_str()])[0],
+                object_count: ty.object_count,
+                migrated_count: ty.migrated_count,
+                dropped_fields: as_ptr(dropped_fields),
+                num_dropped_fields: dropped_fields.len(),
+                zero_initialized_fields: as_ptr(zero_initialized_fields),
+                num_zero_initialized_fields: zero_initialized_fields.len(),
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let report = ReloadReport {
+        assemblies: as_ptr(&assemblies),
+        num_assemblies: assemblies.len(),
+        added_functions: as_ptr(&added_functions),
+        num_added_functions: added_functions.len(),
+        removed_functions: as_ptr(&removed_functions),
+        num_removed_functions: removed_functions.len(),
+        changed_functions: as_ptr(&changed_functions),
+        num_changed_functions: changed_functions.len(),
+        mapped_types: as_ptr(&mapped_types),
+        num_mapped_types: mapped_types.len(),
+    };
+
+    callback(&report, data);
+}
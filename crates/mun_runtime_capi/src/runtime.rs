@@ -7,9 +7,12 @@ use mun_capi_utils::{
     error::ErrorHandle, mun_error_try, try_convert_c_string, try_deref, try_deref_mut,
 };
 use mun_memory::{ffi::Type, type_table::TypeTable, Type as RustType};
-use mun_runtime::{FunctionDefinition, FunctionPrototype, FunctionSignature};
+use mun_runtime::{
+    FieldRenames, FunctionDefinition, FunctionPrototype, FunctionSignature, HeapLimits,
+    ReloadObserver, ReloadReport,
+};
 
-use crate::function::Function;
+use crate::{function::Function, reload};
 
 /// A C-style handle to a runtime.
 #[repr(C)]
@@ -89,6 +92,33 @@ pub struct RuntimeOptions {
 
     /// The number of functions in the [`functions`] array.
     pub num_functions: u32,
+
+    /// When more than this number of bytes is allocated, garbage is collected
+    /// during the next [`mun_runtime_update`]. The limit is not checked when
+    /// memory is allocated. `0` means no limit.
+    pub heap_soft_limit: usize,
+
+    /// The maximum number of bytes that Mun code can allocate. An allocation
+    /// that would exceed this limit fails and interrupts the Mun code, see
+    /// [`mun_runtime_take_heap_limit_exceeded`]. Only applies to code that was
+    /// compiled with heap limit checks. `0` means no limit.
+    pub heap_hard_limit: usize,
+
+    /// A callback that is invoked with a report of the changes of every hot
+    /// reload, from within [`mun_runtime_update`]. Can be null.
+    // The type of `ReloadCallback` is spelled out, because cbindgen doesn't see
+    // through the alias inside an `Option`.
+    pub reload_callback:
+        Option<unsafe extern "C" fn(report: *const reload::ReloadReport, data: *mut c_void)>,
+
+    /// The data that is passed to [`reload_callback`].
+    pub reload_callback_data: *mut c_void,
+
+    /// The registration function of a statically linked Mun library, e.g.
+    /// `mun_register_mod`. If set, the assembly is loaded from the entry points
+    /// that it returns and `library_path` only identifies the assembly.
+    /// Statically linked assemblies are never reloaded. Can be null.
+    pub static_registration: Option<unsafe extern "C" fn() -> *const c_void>,
 }
 
 impl Default for RuntimeOptions {
@@ -96,10 +126,27 @@ impl Default for RuntimeOptions {
         RuntimeOptions {
             functions: std::ptr::null(),
             num_functions: 0,
+            heap_soft_limit: 0,
+            heap_hard_limit: 0,
+            reload_callback: None,
+            reload_callback_data: std::ptr::null_mut(),
+            static_registration: None,
         }
     }
 }
 
+/// The data of a reload callback. The user of the C API is responsible for
+/// making sure that it can be used from the thread that updates the runtime.
+struct CallbackData(*mut c_void);
+
+unsafe impl Send for CallbackData {}
+
+impl CallbackData {
+    fn get(&self) -> *mut c_void {
+        self.0
+    }
+}
+
 /// Constructs a new runtime that loads the library at `library_path` and its
 /// dependencies. If successful, the runtime `handle` is set, otherwise a
 /// non-zero error handle is returned.
@@ -128,6 +175,18 @@ pub unsafe extern "C" fn mun_runtime_create(
         return ErrorHandle::new("invalid argument: 'functions' is null pointer.");
     }
 
+    let static_assembly = match options.static_registration {
+        Some(register) => match register().cast::<abi::EntryPoints>().as_ref() {
+            Some(entry_points) => Some(*entry_points),
+            None => {
+                return ErrorHandle::new(
+                    "invalid argument: 'static_registration' returned a null pointer.",
+                )
+            }
+        },
+        None => None,
+    };
+
     let type_table = TypeTable::default();
     let user_functions = mun_error_try!(std::slice::from_raw_parts(
         options.functions,
@@ -183,6 +242,19 @@ pub unsafe extern "C" fn mun_runtime_create(
         library_path: library_path.into(),
         user_functions,
         type_table,
+        heap_limits: HeapLimits {
+            soft_limit: (options.heap_soft_limit != 0).then_some(options.heap_soft_limit),
+            hard_limit: (options.heap_hard_limit != 0).then_some(options.heap_hard_limit),
+        },
+        field_renames: FieldRenames::default(),
+        reload_observer: options.reload_callback.map(|callback| {
+            let data = CallbackData(options.reload_callback_data);
+            Box::new(move |report: &ReloadReport| {
+                reload::invoke_callback(report, callback, data.get());
+            }) as ReloadObserver
+        }),
+        asset_provider: None,
+        static_assembly,
     };
 
     let runtime = match mun_runtime::Runtime::new(runtime_options) {
@@ -344,9 +416,146 @@ pub unsafe extern "C" fn mun_runtime_update(runtime: Runtime, updated: *mut bool
     ErrorHandle::default()
 }
 
+/// Limits the amount of work that Mun code on the calling thread may perform
+/// to `budget`, and clears any interrupt. Every function call and loop
+/// iteration consumes one unit of budget. When Mun code tries to consume more
+/// budget than is left, all Mun functions on the stack return immediately.
+///
+/// Only assemblies that were compiled with budget checks consume budget. The
+/// value returned by a function that ran out of budget is meaningless and must
+/// be discarded. Use [`mun_runtime_is_budget_exhausted`] after a call to check
+/// whether this is the case, and [`mun_runtime_clear_budget`] to remove the
+/// limit again.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments
+/// is a null pointer, an error will be returned. Passing pointers to invalid
+/// data, will lead to undefined behavior.
+#[no_mangle]
+pub unsafe extern "C" fn mun_runtime_set_budget(runtime: Runtime, budget: u64) -> ErrorHandle {
+    let runtime = mun_error_try!(runtime
+        .inner()
+        .map_err(|e| format!("invalid argument 'runtime': {e}")));
+    runtime.set_budget(Some(budget));
+    ErrorHandle::default()
+}
+
+/// Removes the limit on the amount of work that Mun code on the calling thread
+/// may perform, and clears any interrupt.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments
+/// is a null pointer, an error will be returned. Passing pointers to invalid
+/// data, will lead to undefined behavior.
+#[no_mangle]
+pub unsafe extern "C" fn mun_runtime_clear_budget(runtime: Runtime) -> ErrorHandle {
+    let runtime = mun_error_try!(runtime
+        .inner()
+        .map_err(|e| format!("invalid argument 'runtime': {e}")));
+    runtime.set_budget(None);
+    ErrorHandle::default()
+}
+
+/// Checks whether Mun code on the calling thread was interrupted because it ran
+/// out of budget. If successful, `exhausted` is set, otherwise a non-zero error
+/// handle is returned.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments
+/// is a null pointer, an error will be returned. Passing pointers to invalid
+/// data, will lead to undefined behavior.
+#[no_mangle]
+pub unsafe extern "C" fn mun_runtime_is_budget_exhausted(
+    runtime: Runtime,
+    exhausted: *mut bool,
+) -> ErrorHandle {
+    let runtime = mun_error_try!(runtime
+        .inner()
+        .map_err(|e| format!("invalid argument 'runtime': {e}")));
+    let exhausted = try_deref_mut!(exhausted);
+    *exhausted = runtime.is_budget_exhausted();
+    ErrorHandle::default()
+}
+
+/// Checks whether Mun code on the calling thread was interrupted because an
+/// allocation exceeded the hard heap limit of the runtime corresponding to
+/// `handle`, and clears the interrupt. If successful, `exceeded` is set,
+/// otherwise a non-zero error handle is returned.
+///
+/// While Mun code is interrupted, all Mun functions on the stack return
+/// immediately. The value returned by the interrupted function is meaningless
+/// and must be discarded.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments
+/// is a null pointer, an error will be returned. Passing pointers to invalid
+/// data, will lead to undefined behavior.
+#[no_mangle]
+pub unsafe extern "C" fn mun_runtime_take_heap_limit_exceeded(
+    runtime: Runtime,
+    exceeded: *mut bool,
+) -> ErrorHandle {
+    let runtime = mun_error_try!(runtime
+        .inner()
+        .map_err(|e| format!("invalid argument 'runtime': {e}")));
+    let exceeded = try_deref_mut!(exceeded);
+    *exceeded = runtime.take_heap_limit_exceeded();
+    ErrorHandle::default()
+}
+
+/// Checks whether Mun code on the calling thread was interrupted because an
+/// integer operation overflowed, and clears the interrupt. If successful,
+/// `overflowed` is set, otherwise a non-zero error handle is returned.
+///
+/// Overflows are only detected in assemblies that were compiled with overflow
+/// checks. While Mun code is interrupted, all Mun functions on the stack return
+/// immediately. The value returned by the interrupted function is meaningless
+/// and must be discarded.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments
+/// is a null pointer, an error will be returned. Passing pointers to invalid
+/// data, will lead to undefined behavior.
+#[no_mangle]
+pub unsafe extern "C" fn mun_runtime_take_overflow(
+    runtime: Runtime,
+    overflowed: *mut bool,
+) -> ErrorHandle {
+    let runtime = mun_error_try!(runtime
+        .inner()
+        .map_err(|e| format!("invalid argument 'runtime': {e}")));
+    let overflowed = try_deref_mut!(overflowed);
+    *overflowed = runtime.take_overflow().is_some();
+    ErrorHandle::default()
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{ffi::CString, mem::MaybeUninit, ptr};
+    use std::{
+        ffi::{CStr, CString},
+        mem::MaybeUninit,
+        ptr,
+    };
 
     use mun_capi_utils::{
         assert_error_snapshot, assert_getter1, assert_getter2, assert_getter3,
@@ -355,13 +564,22 @@ mod tests {
     use mun_memory::HasStaticType;
 
     use super::*;
-    use crate::{test_invalid_runtime, test_util::TestDriver};
+    use crate::{
+        gc::{mun_gc_alloc, mun_gc_root},
+        test_invalid_runtime,
+        test_util::TestDriver,
+    };
 
     test_invalid_runtime!(
         runtime_find_function_definition(ptr::null(), 0, ptr::null_mut(), ptr::null_mut()),
         runtime_get_type_info_by_name(ptr::null(), ptr::null_mut(), ptr::null_mut()),
         runtime_get_type_info_by_id(ptr::null(), ptr::null_mut(), ptr::null_mut()),
-        runtime_update(ptr::null_mut())
+        runtime_update(ptr::null_mut()),
+        runtime_set_budget(0),
+        runtime_clear_budget(),
+        runtime_is_budget_exhausted(ptr::null_mut()),
+        runtime_take_heap_limit_exceeded(ptr::null_mut()),
+        runtime_take_overflow(ptr::null_mut())
     );
 
     #[test]
@@ -404,6 +622,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_runtime_create_invalid_static_registration() {
+        unsafe extern "C" fn mun_register_mod() -> *const c_void {
+            ptr::null()
+        }
+
+        let lib_path = CString::new("some/path").expect("Invalid library path");
+
+        let options = RuntimeOptions {
+            static_registration: Some(mun_register_mod),
+            ..Default::default()
+        };
+
+        let mut handle = MaybeUninit::uninit();
+        assert_error_snapshot!(
+            unsafe { mun_runtime_create(lib_path.into_raw(), options, handle.as_mut_ptr()) },
+            @r#""invalid argument: \'static_registration\' returned a null pointer.""#
+        );
+    }
+
     #[test]
     fn test_runtime_create_invalid_handle() {
         let lib_path = CString::new("some/path").expect("Invalid library path");
@@ -432,6 +670,7 @@ mod tests {
         let options = RuntimeOptions {
             functions: functions.as_ptr(),
             num_functions: 1,
+            ..Default::default()
         };
 
         let mut handle = MaybeUninit::uninit();
@@ -458,6 +697,7 @@ mod tests {
         let options = RuntimeOptions {
             functions: functions.as_ptr(),
             num_functions: 1,
+            ..Default::default()
         };
 
         let mut handle = MaybeUninit::uninit();
@@ -483,6 +723,7 @@ mod tests {
         let options = RuntimeOptions {
             functions: functions.as_ptr(),
             num_functions: 1,
+            ..Default::default()
         };
 
         let mut handle = MaybeUninit::uninit();
@@ -509,6 +750,7 @@ mod tests {
         let options = RuntimeOptions {
             functions: functions.as_ptr(),
             num_functions: 1,
+            ..Default::default()
         };
 
         let mut handle = MaybeUninit::uninit();
@@ -536,6 +778,7 @@ mod tests {
         let options = RuntimeOptions {
             functions: functions.as_ptr(),
             num_functions: 1,
+            ..Default::default()
         };
 
         let mut handle = MaybeUninit::uninit();
@@ -925,4 +1168,169 @@ mod tests {
 
         assert_getter1!(mun_runtime_update(driver.runtime, _updated));
     }
+
+    #[test]
+    fn test_runtime_is_budget_exhausted_invalid_exhausted() {
+        let driver = TestDriver::new(
+            r#"
+        pub fn main() -> i32 { 3 }
+    "#,
+        );
+
+        assert_error_snapshot!(
+            unsafe { mun_runtime_is_budget_exhausted(driver.runtime, ptr::null_mut()) },
+            @r#""invalid argument \'exhausted\': null pointer""#
+        );
+    }
+
+    #[test]
+    fn test_runtime_budget() {
+        let driver = TestDriver::new(
+            r#"
+        pub fn main() -> i32 { 3 }
+    "#,
+        );
+
+        assert_getter1!(mun_runtime_is_budget_exhausted(driver.runtime, exhausted));
+        assert!(!exhausted);
+
+        // A budget of zero is only exhausted once Mun code tries to consume it
+        assert!(unsafe { mun_runtime_set_budget(driver.runtime, 0) }.is_ok());
+        assert_getter1!(mun_runtime_is_budget_exhausted(driver.runtime, exhausted));
+        assert!(!exhausted);
+
+        assert!(unsafe { mun_runtime_clear_budget(driver.runtime) }.is_ok());
+        assert_getter1!(mun_runtime_is_budget_exhausted(driver.runtime, exhausted));
+        assert!(!exhausted);
+    }
+
+    #[test]
+    fn test_runtime_take_heap_limit_exceeded_invalid_exceeded() {
+        let driver = TestDriver::new(
+            r#"
+        pub fn main() -> i32 { 3 }
+    "#,
+        );
+
+        assert_error_snapshot!(
+            unsafe { mun_runtime_take_heap_limit_exceeded(driver.runtime, ptr::null_mut()) },
+            @r#""invalid argument \'exceeded\': null pointer""#
+        );
+    }
+
+    #[test]
+    fn test_runtime_take_heap_limit_exceeded() {
+        let driver = TestDriver::new(
+            r#"
+        pub fn main() -> i32 { 3 }
+    "#,
+        );
+
+        assert_getter1!(mun_runtime_take_heap_limit_exceeded(
+            driver.runtime,
+            exceeded
+        ));
+        assert!(!exceeded);
+    }
+
+    #[test]
+    fn test_runtime_take_overflow_invalid_overflowed() {
+        let driver = TestDriver::new(
+            r#"
+        pub fn main() -> i32 { 3 }
+    "#,
+        );
+
+        assert_error_snapshot!(
+            unsafe { mun_runtime_take_overflow(driver.runtime, ptr::null_mut()) },
+            @r#""invalid argument \'overflowed\': null pointer""#
+        );
+    }
+
+    #[test]
+    fn test_runtime_take_overflow() {
+        let driver = TestDriver::new(
+            r#"
+        pub fn main() -> i32 { 3 }
+    "#,
+        );
+
+        assert_getter1!(mun_runtime_take_overflow(driver.runtime, overflowed));
+        assert!(!overflowed);
+    }
+
+    /// Returns the strings referenced by a report as owned strings.
+    unsafe fn report_strings(strings: *const *const c_char, len: usize) -> Vec<String> {
+        if len == 0 {
+            return Vec::new();
+        }
+        slice::from_raw_parts(strings, len)
+            .iter()
+            .map(|string| CStr::from_ptr(*string).to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// Stores the changed functions and the mapped structs of the report in
+    /// the `Vec<String>` that `data` points to.
+    unsafe extern "C" fn record_reload(report: *const reload::ReloadReport, data: *mut c_void) {
+        let report = &*report;
+        let changes = &mut *data.cast::<Vec<String>>();
+
+        changes.extend(report_strings(
+            report.changed_functions,
+            report.num_changed_functions,
+        ));
+        if report.num_mapped_types > 0 {
+            for ty in slice::from_raw_parts(report.mapped_types, report.num_mapped_types) {
+                let dropped_fields = report_strings(ty.dropped_fields, ty.num_dropped_fields);
+                changes.push(format!(
+                    "{}: {} object(s), dropped {dropped_fields:?}",
+                    CStr::from_ptr(ty.name).to_string_lossy(),
+                    ty.object_count,
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_runtime_update_reload_callback() {
+        let mut changes = Vec::<String>::new();
+        let mut driver = TestDriver::with_options(
+            r#"
+        pub struct Foo { a: i32, b: f32 }
+        pub fn main() -> i32 { 3 }
+    "#,
+            RuntimeOptions {
+                reload_callback: Some(record_reload),
+                reload_callback_data: (&mut changes as *mut Vec<String>).cast(),
+                ..RuntimeOptions::default()
+            },
+        );
+
+        let type_name = CString::new("Foo").expect("Invalid type name");
+        assert_getter2!(mun_runtime_get_type_info_by_name(
+            driver.runtime,
+            type_name.as_ptr(),
+            has_type,
+            ty,
+        ));
+        assert!(has_type);
+        assert_getter2!(mun_gc_alloc(driver.runtime, ty, obj));
+        assert!(unsafe { mun_gc_root(driver.runtime, obj) }.is_ok());
+
+        driver.update_file(
+            r#"
+        pub struct Foo { a: i32 }
+        pub fn main() -> f32 { 3.0 }
+    "#,
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                String::from("main"),
+                String::from("Foo: 1 object(s), dropped [\"b\"]"),
+            ]
+        );
+    }
 }
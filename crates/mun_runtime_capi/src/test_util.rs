@@ -1,19 +1,35 @@
-use std::{ffi::CString, io::stderr, path::Path, ptr};
+use std::{
+    ffi::CString,
+    io::stderr,
+    path::Path,
+    ptr,
+    thread::sleep,
+    time::{Duration, Instant},
+};
 
 use mun_compiler::{Config, DisplayColor, Driver, PathOrInline, RelativePathBuf};
 
-use crate::runtime::{mun_runtime_create, mun_runtime_destroy, Runtime, RuntimeOptions};
+use crate::runtime::{
+    mun_runtime_create, mun_runtime_destroy, mun_runtime_update, Runtime, RuntimeOptions,
+};
 
 /// Combines a compiler and runtime in one. Use of the `TestDriver` allows for
 /// quick testing of Mun constructs in the runtime with hot-reloading support.
 pub(crate) struct TestDriver {
     _temp_dir: tempfile::TempDir,
+    driver: Driver,
     pub(crate) runtime: Runtime,
 }
 
 impl TestDriver {
     /// Constructs a new `TestDriver` from Mun source
     pub fn new(text: &str) -> Self {
+        Self::with_options(text, RuntimeOptions::default())
+    }
+
+    /// Constructs a new `TestDriver` from Mun source, with a runtime that is
+    /// created with the specified `options`.
+    pub fn with_options(text: &str, options: RuntimeOptions) -> Self {
         let temp_dir = tempfile::TempDir::new().unwrap();
         let config = Config {
             out_dir: Some(temp_dir.path().to_path_buf()),
@@ -32,12 +48,43 @@ impl TestDriver {
         }
         let out_path = driver.assembly_output_path_from_file(file_id);
         driver.write_all_assemblies(false).unwrap();
-        let runtime = make_runtime(&out_path);
+        let runtime = make_runtime(&out_path, options);
         TestDriver {
             _temp_dir: temp_dir,
+            driver,
             runtime,
         }
     }
+
+    /// Updates the text of the Mun source and ensures that the generated
+    /// assembly has been reloaded.
+    pub fn update_file(&mut self, text: &str) {
+        self.driver.set_file_text("mod.mun", text).unwrap();
+        if self
+            .driver
+            .emit_diagnostics(&mut stderr(), DisplayColor::Disable)
+            .unwrap()
+        {
+            panic!("compiler errors..")
+        }
+        self.driver.write_all_assemblies(false).unwrap();
+
+        let start_time = Instant::now();
+        loop {
+            let mut updated = false;
+            let error = unsafe { mun_runtime_update(self.runtime, &mut updated as *mut _) };
+            assert_eq!(error.0, ptr::null(), "Failed to update runtime");
+            if updated {
+                break;
+            }
+
+            assert!(
+                start_time.elapsed() <= Duration::from_secs(10),
+                "runtime did not update after recompilation within 10 seconds"
+            );
+            sleep(Duration::from_millis(1));
+        }
+    }
 }
 
 impl Drop for TestDriver {
@@ -46,18 +93,12 @@ impl Drop for TestDriver {
     }
 }
 
-fn make_runtime(lib_path: &Path) -> Runtime {
+fn make_runtime(lib_path: &Path, options: RuntimeOptions) -> Runtime {
     let lib_path = lib_path.to_str().expect("Invalid lib path");
     let lib_path = CString::new(lib_path).unwrap();
 
     let mut handle = Runtime(ptr::null_mut());
-    let error = unsafe {
-        mun_runtime_create(
-            lib_path.as_ptr(),
-            RuntimeOptions::default(),
-            &mut handle as *mut _,
-        )
-    };
+    let error = unsafe { mun_runtime_create(lib_path.as_ptr(), options, &mut handle as *mut _) };
     assert_eq!(error.0, ptr::null(), "Failed to create runtime");
     handle
 }
@@ -0,0 +1,70 @@
+#![cfg(unix)]
+
+use std::{io::stderr, path::Path, process::Command};
+
+use mun_compiler::{Config, DisplayColor, Driver, PathOrInline, RelativePathBuf};
+
+#[test]
+fn host_links_static_library() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config = Config {
+        out_dir: Some(temp_dir.path().to_path_buf()),
+        static_library: true,
+        ..Config::default()
+    };
+    let input = PathOrInline::Inline {
+        rel_path: RelativePathBuf::from("mod.mun"),
+        contents: r"
+    pub fn main() -> i32 { add(2, 3) }
+    fn add(a: i32, b: i32) -> i32 { a + b }
+    "
+        .to_owned(),
+    };
+    let (mut driver, file_id) = Driver::with_file(config, input).unwrap();
+    assert!(
+        !driver
+            .emit_diagnostics(&mut stderr(), DisplayColor::Disable)
+            .unwrap(),
+        "compiler errors"
+    );
+    driver.write_all_assemblies(false).unwrap();
+    let out_dir = driver.assembly_output_path_from_file(file_id);
+    let out_dir = out_dir.parent().unwrap();
+
+    // Cargo doesn't build the C API for integration tests, so build it
+    // ourselves. It ends up next to the dependencies of this test.
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--package", "mun_runtime_capi", "--lib"])
+        .status()
+        .expect("Failed to run cargo");
+    assert!(status.success(), "Failed to build the C API");
+    let exe_path = std::env::current_exe().unwrap();
+    let library_dir = exe_path.parent().unwrap();
+
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let host_path = temp_dir.path().join("host");
+    let status = Command::new("cc")
+        .arg(manifest_dir.join("tests/static_library/host.c"))
+        .arg(out_dir.join("libmod.a"))
+        .arg("-I")
+        .arg(manifest_dir.join("../../cpp/include"))
+        .arg("-L")
+        .arg(library_dir)
+        .arg("-lmun_runtime")
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .arg("-o")
+        .arg(&host_path)
+        .status()
+        .expect("Failed to run the C compiler");
+    assert!(status.success(), "Failed to link the host");
+
+    let output = Command::new(&host_path)
+        .output()
+        .expect("Failed to run the host");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "5\n");
+}
@@ -0,0 +1,50 @@
+// A host that links the static library of a Mun assembly, and prints the result of its `main`
+// function.
+
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "mun/runtime_capi.h"
+
+// The registration function of `libmod.a`
+const void* mun_register_mod(void);
+
+static bool succeeded(MunErrorHandle error) {
+    if (error.error_string != NULL) {
+        fprintf(stderr, "%s\n", error.error_string);
+        mun_error_destroy(error);
+        return false;
+    }
+    return true;
+}
+
+int main(void) {
+    MunRuntimeOptions options = {0};
+    options.static_registration = mun_register_mod;
+
+    // The path only identifies the assembly, it doesn't have to exist
+    MunRuntime runtime;
+    if (!succeeded(mun_runtime_create("mod", options, &runtime))) {
+        return 1;
+    }
+
+    bool has_fn_info = false;
+    MunFunction function;
+    if (!succeeded(mun_runtime_find_function_definition(runtime, "main", strlen("main"),
+                                                        &has_fn_info, &function)) ||
+        !has_fn_info) {
+        return 1;
+    }
+
+    const void* fn_ptr;
+    if (!succeeded(mun_function_fn_ptr(function, &fn_ptr))) {
+        return 1;
+    }
+    printf("%d\n", ((int32_t(*)(void))fn_ptr)());
+
+    mun_function_release(function);
+    mun_runtime_destroy(runtime);
+    return 0;
+}
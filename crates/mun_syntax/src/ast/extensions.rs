@@ -5,7 +5,7 @@ use rowan::{GreenNodeData, GreenTokenData, NodeOrToken};
 use text_size::TextRange;
 
 use crate::{
-    ast::{self, child_opt, AstNode, NameOwner},
+    ast::{self, child_opt, AstNode, NameOwner, TypeAscriptionOwner},
     SyntaxKind, SyntaxNode, TokenText, T,
 };
 
@@ -256,3 +256,32 @@ impl ast::TypeAliasDef {
         TextRange::new(start, end)
     }
 }
+
+impl ast::ConstDef {
+    /// Returns the signature range.
+    ///
+    /// ```rust, ignore
+    /// const FOO_BAR: i32 = 5;
+    /// ^^^^^^^^^^^^^^^^^^___ this part
+    /// ```
+    pub fn signature_range(&self) -> TextRange {
+        let const_kw = self
+            .syntax()
+            .children_with_tokens()
+            .find(|p| p.kind() == T![const])
+            .map(|kw| kw.text_range());
+        let name = self.name().map(|n| n.syntax.text_range());
+        let ascribed_type = self.ascribed_type().map(|t| t.syntax().text_range());
+
+        let start =
+            const_kw.map_or_else(|| self.syntax.text_range().start(), rowan::TextRange::start);
+
+        let end = ascribed_type
+            .map(rowan::TextRange::end)
+            .or_else(|| name.map(rowan::TextRange::end))
+            .or_else(|| const_kw.map(rowan::TextRange::end))
+            .unwrap_or_else(|| self.syntax().text_range().end());
+
+        TextRange::new(start, end)
+    }
+}
@@ -347,6 +347,38 @@ impl Condition {
     }
 }
 
+// ConstDef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstDef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ConstDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, CONST_DEF)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ConstDef { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::NameOwner for ConstDef {}
+impl ast::VisibilityOwner for ConstDef {}
+impl ast::DocCommentsOwner for ConstDef {}
+impl ast::TypeAscriptionOwner for ConstDef {}
+impl ConstDef {
+    pub fn body(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
 // Expr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -816,7 +848,7 @@ impl AstNode for ModuleItem {
     fn can_cast(kind: SyntaxKind) -> bool {
         matches!(
             kind,
-            USE | FUNCTION_DEF | STRUCT_DEF | TYPE_ALIAS_DEF | IMPL
+            USE | FUNCTION_DEF | STRUCT_DEF | TYPE_ALIAS_DEF | CONST_DEF | IMPL
         )
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
@@ -836,6 +868,7 @@ pub enum ModuleItemKind {
     FunctionDef(FunctionDef),
     StructDef(StructDef),
     TypeAliasDef(TypeAliasDef),
+    ConstDef(ConstDef),
     Impl(Impl),
 }
 impl From<Use> for ModuleItem {
@@ -858,6 +891,11 @@ impl From<TypeAliasDef> for ModuleItem {
         ModuleItem { syntax: n.syntax }
     }
 }
+impl From<ConstDef> for ModuleItem {
+    fn from(n: ConstDef) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
 impl From<Impl> for ModuleItem {
     fn from(n: Impl) -> ModuleItem {
         ModuleItem { syntax: n.syntax }
@@ -875,6 +913,7 @@ impl ModuleItem {
             TYPE_ALIAS_DEF => {
                 ModuleItemKind::TypeAliasDef(TypeAliasDef::cast(self.syntax.clone()).unwrap())
             }
+            CONST_DEF => ModuleItemKind::ConstDef(ConstDef::cast(self.syntax.clone()).unwrap()),
             IMPL => ModuleItemKind::Impl(Impl::cast(self.syntax.clone()).unwrap()),
             _ => unreachable!(),
         }
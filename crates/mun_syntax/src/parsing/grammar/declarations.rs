@@ -1,12 +1,19 @@
 use super::{
     adt, error_block, expressions, name, name_recovery, opt_visibility, params, paths, traits,
-    types, Marker, Parser, TokenSet, EOF, ERROR, EXTERN, FUNCTION_DEF, RENAME, RET_TYPE, USE,
-    USE_TREE, USE_TREE_LIST,
+    types, Marker, Parser, TokenSet, CONST_DEF, EOF, ERROR, EXTERN, FUNCTION_DEF, RENAME, RET_TYPE,
+    USE, USE_TREE, USE_TREE_LIST,
 };
 use crate::{parsing::grammar::paths::is_use_path_start, T};
 
-pub(super) const DECLARATION_RECOVERY_SET: TokenSet =
-    TokenSet::new(&[T![fn], T![pub], T![struct], T![use], T![;], T![impl]]);
+pub(super) const DECLARATION_RECOVERY_SET: TokenSet = TokenSet::new(&[
+    T![fn],
+    T![pub],
+    T![struct],
+    T![use],
+    T![;],
+    T![impl],
+    T![const],
+]);
 
 pub(super) fn mod_contents(p: &mut Parser<'_>) {
     while !p.at(EOF) {
@@ -78,11 +85,34 @@ fn declarations_without_modifiers(p: &mut Parser<'_>, m: Marker) -> Result<(), M
         T![impl] => {
             traits::impl_(p, m);
         }
+        T![const] => {
+            const_def(p, m);
+        }
         _ => return Err(m),
     };
     Ok(())
 }
 
+/// Parses a constant definition (e.g. `const FOO: i32 = 5;`)
+fn const_def(p: &mut Parser<'_>, m: Marker) {
+    assert!(p.at(T![const]));
+    p.bump(T![const]);
+    name_recovery(
+        p,
+        DECLARATION_RECOVERY_SET.union(TokenSet::new(&[T![:], T![=]])),
+    );
+    if p.at(T![:]) {
+        types::ascription(p);
+    } else {
+        p.error("missing type for `const`");
+    }
+    if p.expect(T![=]) {
+        expressions::expr(p);
+    }
+    p.expect(T![;]);
+    m.complete(p, CONST_DEF);
+}
+
 pub(super) fn fn_def(p: &mut Parser<'_>) {
     assert!(p.at(T![fn]));
     p.bump(T![fn]);
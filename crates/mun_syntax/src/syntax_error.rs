@@ -70,6 +70,11 @@ impl SyntaxError {
     pub fn location(&self) -> Location {
         self.location.clone()
     }
+
+    /// Returns the stable code that identifies syntax errors.
+    pub fn code(&self) -> &'static str {
+        mun_error_codes::SYNTAX_ERROR
+    }
 }
 
 impl fmt::Display for SyntaxError {
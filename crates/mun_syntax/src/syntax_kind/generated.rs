@@ -98,6 +98,7 @@ pub enum SyntaxKind {
     SELF_KW,
     EXTERN_KW,
     IMPL_KW,
+    CONST_KW,
     INT_NUMBER,
     FLOAT_NUMBER,
     STRING,
@@ -118,6 +119,7 @@ pub enum SyntaxKind {
     SELF_PARAM,
     STRUCT_DEF,
     TYPE_ALIAS_DEF,
+    CONST_DEF,
     MEMORY_TYPE_SPECIFIER,
     RECORD_FIELD_DEF_LIST,
     RECORD_FIELD_DEF,
@@ -391,6 +393,9 @@ macro_rules! T {
     (impl) => {
         $crate::SyntaxKind::IMPL_KW
     };
+    (const) => {
+        $crate::SyntaxKind::CONST_KW
+    };
 }
 
 impl From<u16> for SyntaxKind {
@@ -437,6 +442,7 @@ impl SyntaxKind {
         | SELF_KW
         | EXTERN_KW
         | IMPL_KW
+        | CONST_KW
         )
     }
 
@@ -579,6 +585,7 @@ impl SyntaxKind {
             SELF_KW => &SyntaxInfo { name: "SELF_KW" },
             EXTERN_KW => &SyntaxInfo { name: "EXTERN_KW" },
             IMPL_KW => &SyntaxInfo { name: "IMPL_KW" },
+            CONST_KW => &SyntaxInfo { name: "CONST_KW" },
             INT_NUMBER => &SyntaxInfo { name: "INT_NUMBER" },
             FLOAT_NUMBER => &SyntaxInfo { name: "FLOAT_NUMBER" },
             STRING => &SyntaxInfo { name: "STRING" },
@@ -599,6 +606,7 @@ impl SyntaxKind {
             SELF_PARAM => &SyntaxInfo { name: "SELF_PARAM" },
             STRUCT_DEF => &SyntaxInfo { name: "STRUCT_DEF" },
             TYPE_ALIAS_DEF => &SyntaxInfo { name: "TYPE_ALIAS_DEF" },
+            CONST_DEF => &SyntaxInfo { name: "CONST_DEF" },
             MEMORY_TYPE_SPECIFIER => &SyntaxInfo { name: "MEMORY_TYPE_SPECIFIER" },
             RECORD_FIELD_DEF_LIST => &SyntaxInfo { name: "RECORD_FIELD_DEF_LIST" },
             RECORD_FIELD_DEF => &SyntaxInfo { name: "RECORD_FIELD_DEF" },
@@ -677,6 +685,7 @@ impl SyntaxKind {
             "self" => SELF_KW,
             "extern" => EXTERN_KW,
             "impl" => IMPL_KW,
+            "const" => CONST_KW,
             _ => return None,
         };
         Some(kw)
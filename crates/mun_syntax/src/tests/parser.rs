@@ -3130,6 +3130,100 @@ fn extern_fn() {
     "#);
 }
 
+#[test]
+fn const_def() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    const FOO: i32 = 5;
+    pub const BAR: f64 = FOO * 2.0;
+    const BAZ = 3;
+    const QUX: bool;
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..105
+      WHITESPACE@0..5 "\n    "
+      CONST_DEF@5..24
+        CONST_KW@5..10 "const"
+        WHITESPACE@10..11 " "
+        NAME@11..14
+          IDENT@11..14 "FOO"
+        COLON@14..15 ":"
+        WHITESPACE@15..16 " "
+        PATH_TYPE@16..19
+          PATH@16..19
+            PATH_SEGMENT@16..19
+              NAME_REF@16..19
+                IDENT@16..19 "i32"
+        WHITESPACE@19..20 " "
+        EQ@20..21 "="
+        WHITESPACE@21..22 " "
+        LITERAL@22..23
+          INT_NUMBER@22..23 "5"
+        SEMI@23..24 ";"
+      WHITESPACE@24..29 "\n    "
+      CONST_DEF@29..60
+        VISIBILITY@29..32
+          PUB_KW@29..32 "pub"
+        WHITESPACE@32..33 " "
+        CONST_KW@33..38 "const"
+        WHITESPACE@38..39 " "
+        NAME@39..42
+          IDENT@39..42 "BAR"
+        COLON@42..43 ":"
+        WHITESPACE@43..44 " "
+        PATH_TYPE@44..47
+          PATH@44..47
+            PATH_SEGMENT@44..47
+              NAME_REF@44..47
+                IDENT@44..47 "f64"
+        WHITESPACE@47..48 " "
+        EQ@48..49 "="
+        WHITESPACE@49..50 " "
+        BIN_EXPR@50..59
+          PATH_EXPR@50..53
+            PATH@50..53
+              PATH_SEGMENT@50..53
+                NAME_REF@50..53
+                  IDENT@50..53 "FOO"
+          WHITESPACE@53..54 " "
+          STAR@54..55 "*"
+          WHITESPACE@55..56 " "
+          LITERAL@56..59
+            FLOAT_NUMBER@56..59 "2.0"
+        SEMI@59..60 ";"
+      WHITESPACE@60..65 "\n    "
+      CONST_DEF@65..79
+        CONST_KW@65..70 "const"
+        WHITESPACE@70..71 " "
+        NAME@71..74
+          IDENT@71..74 "BAZ"
+        WHITESPACE@74..75 " "
+        EQ@75..76 "="
+        WHITESPACE@76..77 " "
+        LITERAL@77..78
+          INT_NUMBER@77..78 "3"
+        SEMI@78..79 ";"
+      WHITESPACE@79..84 "\n    "
+      CONST_DEF@84..100
+        CONST_KW@84..89 "const"
+        WHITESPACE@89..90 " "
+        NAME@90..93
+          IDENT@90..93 "QUX"
+        COLON@93..94 ":"
+        WHITESPACE@94..95 " "
+        PATH_TYPE@95..99
+          PATH@95..99
+            PATH_SEGMENT@95..99
+              NAME_REF@95..99
+                IDENT@95..99 "bool"
+        SEMI@99..100 ";"
+      WHITESPACE@100..105 "\n    "
+    error Offset(74): missing type for `const`
+    error Offset(99): expected EQ
+
+    "#);
+}
+
 #[test]
 fn type_alias_def() {
     insta::assert_snapshot!(SourceFile::parse(
@@ -11,6 +11,7 @@ pub enum LinkerFlavor {
     Ld,
     Ld64,
     Msvc,
+    WasmLd,
 }
 
 /// Everything Mun knows about a target.
@@ -69,6 +70,10 @@ pub struct TargetOptions {
     /// to "generic".
     pub cpu: String,
 
+    /// The ABI that LLVM generates code for, e.g. `"lp64d"` on RISC-V. An empty
+    /// string uses LLVM's default for the target. Defaults to "".
+    pub llvm_abiname: String,
+
     /// Default target features to pass to LLVM. These features will *always* be
     /// passed, and cannot be disabled even via `-C`. Corresponds to `llc
     /// -mattr=$features`.
@@ -86,6 +91,9 @@ pub struct TargetOptions {
     /// against iOS/macOS, in particular running dsymutil and some other
     /// stuff like `-dead_strip`. Defaults to false.
     pub is_like_osx: bool,
+
+    /// Whether the target is a WebAssembly target. Defaults to false.
+    pub is_like_wasm: bool,
 }
 
 impl Default for TargetOptions {
@@ -102,10 +110,12 @@ impl Default for TargetOptions {
             pre_link_args: vec![],
             cpu: "generic".to_string(),
             features: "".to_string(),
+            llvm_abiname: "".to_string(),
             dll_prefix: "lib".to_string(),
             is_like_windows: false,
             is_like_msvc: false,
             is_like_osx: false,
+            is_like_wasm: false,
         }
     }
 }
@@ -138,9 +148,12 @@ supported_targets!(
     ("x86_64-apple-ios", x86_64_apple_ios),
     ("x86_64-pc-windows-msvc", x86_64_pc_windows_msvc),
     ("x86_64-unknown-linux-gnu", x86_64_unknown_linux_gnu),
+    ("aarch64-unknown-linux-gnu", aarch64_unknown_linux_gnu),
+    ("riscv64gc-unknown-linux-gnu", riscv64gc_unknown_linux_gnu),
     ("aarch64-apple-darwin", aarch64_apple_darwin),
     ("aarch64-apple-ios", aarch64_apple_ios),
     ("aarch64-apple-ios-sim", aarch64_apple_ios_sim),
+    ("wasm32-unknown-unknown", wasm32_unknown_unknown),
 );
 
 impl Target {
@@ -0,0 +1,18 @@
+use crate::spec::{Target, TargetOptions};
+
+pub fn target() -> Target {
+    Target {
+        llvm_target: "riscv64-unknown-linux-gnu".into(),
+        pointer_width: 64,
+        arch: "riscv64".into(),
+        data_layout: "e-m:e-p:64:64-i64:64-i128:128-n64-S128".into(),
+        options: TargetOptions {
+            cpu: "generic-rv64".into(),
+            // The bundled lld does not implement linker relaxation, so the object files
+            // must not depend on it.
+            features: "+m,+a,+f,+d,+c,-relax".into(),
+            llvm_abiname: "lp64d".into(),
+            ..super::linux_base::opts()
+        },
+    }
+}
@@ -0,0 +1,22 @@
+use crate::spec::{LinkerFlavor, Target, TargetOptions};
+
+/// Munlibs for this target can be generated, but not yet loaded by the runtime.
+/// That requires a wasm engine, a way to pass gc pointers of the 64-bit host
+/// heap to code that uses 32-bit linear memory, and a replacement for the
+/// host function pointers in the dispatch table.
+pub fn target() -> Target {
+    Target {
+        llvm_target: "wasm32-unknown-unknown".into(),
+        pointer_width: 32,
+        arch: "wasm32".into(),
+        data_layout: "e-m:e-p:32:32-i64:64-n32:64-S128".into(),
+        options: TargetOptions {
+            os: "unknown".into(),
+            vendor: "unknown".into(),
+            linker_flavor: LinkerFlavor::WasmLd,
+            dll_prefix: "".into(),
+            is_like_wasm: true,
+            ..Default::default()
+        },
+    }
+}
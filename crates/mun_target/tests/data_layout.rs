@@ -22,3 +22,27 @@ fn data_layout_linux() {
 
     insta::assert_debug_snapshot!(layout);
 }
+
+#[test]
+fn data_layout_linux_aarch64() {
+    let layout =
+        TargetDataLayout::parse(&Target::search("aarch64-unknown-linux-gnu").unwrap()).unwrap();
+
+    insta::assert_debug_snapshot!(layout);
+}
+
+#[test]
+fn data_layout_linux_riscv64() {
+    let layout =
+        TargetDataLayout::parse(&Target::search("riscv64gc-unknown-linux-gnu").unwrap()).unwrap();
+
+    insta::assert_debug_snapshot!(layout);
+}
+
+#[test]
+fn data_layout_wasm32() {
+    let layout =
+        TargetDataLayout::parse(&Target::search("wasm32-unknown-unknown").unwrap()).unwrap();
+
+    insta::assert_debug_snapshot!(layout);
+}
@@ -8,6 +8,8 @@ use mun_compiler::{Config, DisplayColor, Driver, PathOrInline, RelativePathBuf};
 use mun_hir_input::Fixture;
 use mun_runtime::{InitError, Runtime, RuntimeBuilder};
 
+use crate::InterpretTestDriver;
+
 /// Implements a compiler that generates and temporarily stores a `*.munlib`
 /// library corresponding to a single source file.
 pub struct CompileTestDriver {
@@ -65,10 +67,17 @@ impl CompileTestDriver {
 
     /// Constructs a new `CompileTestDriver` from a single Mun source.
     pub fn from_file(text: &str) -> Self {
+        Self::from_file_with_config(text, Config::default())
+    }
+
+    /// Constructs a new `CompileTestDriver` from a single Mun source that is
+    /// compiled with the specified `config`. The output directory of the
+    /// `config` is ignored.
+    pub fn from_file_with_config(text: &str, config: Config) -> Self {
         let temp_dir = tempfile::TempDir::new().unwrap();
         let config = Config {
             out_dir: Some(temp_dir.path().to_path_buf()),
-            ..Config::default()
+            ..config
         };
         let input = PathOrInline::Inline {
             rel_path: RelativePathBuf::from("mod.mun"),
@@ -130,6 +139,14 @@ impl std::fmt::Debug for CompileTestDriver {
 pub struct CompileAndRunTestDriver {
     driver: CompileTestDriver,
 
+    /// The text of the Mun source, unless the driver was constructed from a
+    /// fixture that consists of multiple files.
+    text: Option<String>,
+
+    /// Interprets the same Mun source, to check that the interpreter computes
+    /// the same results as the generated code.
+    interpreted: Option<InterpretTestDriver>,
+
     /// The runtime created by this instance.
     pub runtime: Runtime,
 }
@@ -157,7 +174,12 @@ impl CompileAndRunTestDriver {
         let build = config_fn(builder);
         let runtime = unsafe { build.finish() }?;
 
-        Ok(Self { driver, runtime })
+        Ok(Self {
+            driver,
+            text: None,
+            interpreted: None,
+            runtime,
+        })
     }
 
     /// Constructs a `CompileAndRunTestDriver` from a single Mun source file and
@@ -166,7 +188,18 @@ impl CompileAndRunTestDriver {
         text: &str,
         config_fn: impl FnOnce(RuntimeBuilder) -> RuntimeBuilder,
     ) -> Result<Self, InitError> {
-        let driver = CompileTestDriver::from_file(text);
+        Self::with_compiler_config(text, Config::default(), config_fn)
+    }
+
+    /// Constructs a `CompileAndRunTestDriver` from a single Mun source file
+    /// that is compiled with the specified `compiler_config`, and a
+    /// `config_fn` that allows modification of a [`RuntimeBuilder`].
+    pub fn with_compiler_config(
+        text: &str,
+        compiler_config: Config,
+        config_fn: impl FnOnce(RuntimeBuilder) -> RuntimeBuilder,
+    ) -> Result<Self, InitError> {
+        let driver = CompileTestDriver::from_file_with_config(text, compiler_config);
         let builder = Runtime::builder(driver.lib_path());
 
         // Safety: We compiled the library ourselves, therefor loading the munlib is
@@ -174,7 +207,37 @@ impl CompileAndRunTestDriver {
         let build = config_fn(builder);
         let runtime = unsafe { build.finish() }?;
 
-        Ok(Self { driver, runtime })
+        Ok(Self {
+            driver,
+            text: Some(text.to_owned()),
+            interpreted: None,
+            runtime,
+        })
+    }
+
+    /// Also interprets the Mun source, to check that the interpreter computes
+    /// the same results as the generated code. Only fixtures that consist of a
+    /// single file can be interpreted.
+    pub fn with_interpreter(mut self) -> Self {
+        let text = self
+            .text
+            .as_deref()
+            .expect("only a single Mun source can be interpreted");
+        self.interpreted = Some(InterpretTestDriver::from_file(text));
+        self
+    }
+
+    /// Returns the driver that interprets the same Mun source, if it was
+    /// enabled with [`CompileAndRunTestDriver::with_interpreter`].
+    pub fn interpreted(&self) -> Option<&InterpretTestDriver> {
+        self.interpreted.as_ref()
+    }
+
+    /// Splits the driver into its compiler and its runtime. This allows the
+    /// runtime to be shared between threads, while the compiler is used to
+    /// recompile the source.
+    pub fn into_parts(self) -> (CompileTestDriver, Runtime) {
+        (self.driver, self.runtime)
     }
 
     /// Updates the text of the Mun source and ensures that the generated
@@ -184,6 +247,12 @@ impl CompileAndRunTestDriver {
     /// moving of the existing borrow inside the update function. This
     /// obviates the necessity for `update` to use the `Runtime`.
     pub fn update_file(&mut self, path: impl AsRef<mun_paths::RelativePath>, text: &str) {
+        if self.text.is_some() {
+            self.text = Some(text.to_owned());
+        }
+        if let Some(interpreted) = &mut self.interpreted {
+            interpreted.update_file(text);
+        }
         self.driver.update_file(path, text);
 
         let start_time = Instant::now();
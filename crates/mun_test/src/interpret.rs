@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+use mun_hir::{AstDatabase, DiagnosticSink, Package};
+use mun_hir_input::{FileId, SourceDatabase, WithFixture};
+use mun_interpreter::{Interpreter, InterpreterDatabase, Value};
+
+/// Implements a driver that interprets a single Mun source file, without
+/// generating machine code. This allows fast execution of test fixtures that
+/// don't depend on hot-reloading.
+pub struct InterpretTestDriver {
+    db: InterpreterDatabase,
+    file_id: FileId,
+}
+
+impl InterpretTestDriver {
+    /// Constructs a new `InterpretTestDriver` from a single Mun source.
+    pub fn from_file(text: &str) -> Self {
+        let (db, file_id) = InterpreterDatabase::with_single_file(text);
+        let driver = InterpretTestDriver { db, file_id };
+        driver.assert_no_errors();
+        driver
+    }
+
+    /// Updates the text of the Mun source.
+    pub fn update_file(&mut self, text: &str) {
+        self.db.set_file_text(self.file_id, Arc::from(text));
+        self.assert_no_errors();
+    }
+
+    /// Returns an interpreter that executes the functions of the Mun source.
+    pub fn interpreter(&self) -> Interpreter<'_> {
+        Interpreter::new(&self.db)
+    }
+
+    /// Interprets the function called `name` with the specified `args`, and
+    /// panics unless it returns `expected`.
+    #[track_caller]
+    pub fn assert_invoke_eq(&self, name: &str, args: Vec<Value>, expected: impl Into<Value>) {
+        let result = self.interpreter().invoke(name, args);
+        assert_eq!(result, Ok(expected.into()), "interpreted {name}");
+    }
+
+    /// Panics if the Mun source contains any errors.
+    fn assert_no_errors(&self) {
+        let mut errors = self
+            .db
+            .parse(self.file_id)
+            .errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        for package in Package::all(&self.db) {
+            for module in package.modules(&self.db) {
+                module.diagnostics(
+                    &self.db,
+                    &mut DiagnosticSink::new(|d| errors.push(d.message())),
+                );
+            }
+        }
+
+        assert!(
+            errors.is_empty(),
+            "compiler errors:\n{}",
+            errors.iter().join("\n")
+        );
+    }
+}
+
+impl std::fmt::Debug for InterpretTestDriver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterpretTestDriver")
+            .field("file_id", &self.file_id)
+            .finish()
+    }
+}
@@ -3,6 +3,11 @@
 //! Mun Test contains shared functionality for testing Mun crates.
 #![warn(missing_docs)]
 
+#[cfg(feature = "compiler")]
 pub use driver::*;
+pub use interpret::InterpretTestDriver;
+pub use mun_interpreter::Value;
 
+#[cfg(feature = "compiler")]
 mod driver;
+mod interpret;